@@ -10,7 +10,10 @@ use image::codecs::png::PngEncoder;
 use image::{self, Pixel, Rgba, RgbaImage};
 use parley::layout::{Alignment, Glyph, GlyphRun, Layout, PositionedLayoutItem};
 use parley::style::{FontFamily, FontWeight, StyleProperty, TextStyle};
-use parley::{AlignmentOptions, FontContext, InlineBox, InlineBoxKind, LayoutContext, LineHeight};
+use parley::{
+    AlignmentOptions, FontContext, InlineBox, InlineBoxKind, LayoutContext, LineHeight,
+    VerticalAlign,
+};
 use std::fs::File;
 use swash::FontRef;
 use swash::scale::image::Content;
@@ -99,6 +102,7 @@ fn main() {
             width: 50.0,
             height: 50.0,
             baseline: None,
+            vertical_align: VerticalAlign::Baseline,
         });
 
         builder.push_text(&text[40..50]);
@@ -110,6 +114,7 @@ fn main() {
             width: 50.0,
             height: 30.0,
             baseline: None,
+            vertical_align: VerticalAlign::Baseline,
         });
 
         builder.push_text(&text[50..141]);
@@ -161,6 +166,7 @@ fn main() {
             width: 50.0,
             height: 50.0,
             baseline: None,
+            vertical_align: VerticalAlign::Baseline,
         });
         builder.push_inline_box(InlineBox {
             id: 1,
@@ -169,6 +175,7 @@ fn main() {
             width: 50.0,
             height: 30.0,
             baseline: None,
+            vertical_align: VerticalAlign::Baseline,
         });
 
         // Build the builder into a Layout
@@ -12,6 +12,7 @@
 use parley::{
     Alignment, AlignmentOptions, FontContext, FontWeight, GenericFamily, GlyphRun, InlineBox,
     InlineBoxKind, Layout, LayoutContext, LineHeight, PositionedLayoutItem, StyleProperty,
+    VerticalAlign,
 };
 use skrifa::{
     GlyphId, MetadataProvider, OutlineGlyph,
@@ -93,6 +94,7 @@ fn main() {
         width: 50.0,
         height: 50.0,
         baseline: None,
+        vertical_align: VerticalAlign::Baseline,
     });
 
     // Build the builder into a Layout
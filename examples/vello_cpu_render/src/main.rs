@@ -4,10 +4,13 @@
 //! A simple example that lays out some text using Parley and paints the glyphs using
 //! Vello CPU's built-in glyph rendering.
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
-use parley::{GlyphRun, Layout, PositionedLayoutItem};
+use parley::fontique::Blob;
+use parley::{FontData, GlyphRun, Layout, PaletteOverride, PositionedLayoutItem, Run};
 use parley_examples_common::{
     ColorBrush, ExampleConfig, FrameKind, FrameStats, frame_sequence, output_dir, prepare_layouts,
 };
@@ -33,6 +36,7 @@ fn main() {
     let mut renderer = RenderContext::new(width, height);
     stats.end("prepare_rendering");
     let output_path = output_dir(env!("CARGO_MANIFEST_DIR")).join("vello_cpu_render.png");
+    let mut palette_fonts = PaletteFonts::new();
 
     for frame in &frame_sequence() {
         println!("\n=== {} ===", frame.label);
@@ -44,6 +48,7 @@ fn main() {
             layout,
             config,
             &mut renderer,
+            &mut palette_fonts,
             width,
             height,
             &output_path,
@@ -61,6 +66,7 @@ fn render_frame(
     layout: &Layout<ColorBrush>,
     config: &ExampleConfig,
     renderer: &mut RenderContext,
+    palette_fonts: &mut PaletteFonts,
     width: u16,
     height: u16,
     output_path: &Path,
@@ -91,9 +97,10 @@ fn render_frame(
                     stats.start("fill_glyphs");
                     let normalized_coords =
                         &Vec::from_iter(run.normalized_coords().iter().map(|c| c.to_bits()));
+                    let font = font_with_palette(&run, palette_fonts);
                     renderer.set_paint(glyph_run.style().brush.color);
                     renderer
-                        .glyph_run(&run.font().font)
+                        .glyph_run(&font)
                         .font_size(run.font_size())
                         .hint(config.hint)
                         .normalized_coords(normalized_coords)
@@ -154,6 +161,95 @@ fn render_frame(
     stats.end("total_frame");
 }
 
+/// Copies of fonts with their first palette replaced, keyed by the font and the palette.
+type PaletteFonts = HashMap<(u64, u32, u16, Vec<PaletteOverride>), FontData>;
+
+/// Returns the font to paint the glyphs of `run` with.
+///
+/// Vello CPU paints COLR glyphs with the first palette of their font. To paint them with the
+/// palette selected for the run, this makes a copy of the font whose first palette is that
+/// palette, with the run's overrides applied.
+fn font_with_palette(run: &Run<'_, ColorBrush>, palette_fonts: &mut PaletteFonts) -> FontData {
+    let font = &run.font().font;
+    let overrides = run.palette_overrides();
+    if run.palette_index() == 0 && overrides.is_empty() {
+        return font.clone();
+    }
+    let key = (
+        font.data.id(),
+        font.index,
+        run.palette_index(),
+        overrides.to_vec(),
+    );
+    palette_fonts
+        .entry(key)
+        .or_insert_with(|| {
+            replace_first_palette(font, run.palette_index(), overrides)
+                .unwrap_or_else(|| font.clone())
+        })
+        .clone()
+}
+
+/// Copies `font`, pointing its first palette at the palette `index` and writing `overrides` over
+/// the colors of that palette.
+///
+/// Returns `None` if the font has no such palette.
+fn replace_first_palette(
+    font: &FontData,
+    index: u16,
+    overrides: &[PaletteOverride],
+) -> Option<FontData> {
+    let mut data = font.data.as_ref().to_vec();
+    let cpal = table_offset(&data, font.index, b"CPAL")?;
+    let num_entries = read_u16(&data, cpal + 2)?;
+    let num_palettes = read_u16(&data, cpal + 4)?;
+    let records = cpal + read_u32(&data, cpal + 8)? as usize;
+    if index >= num_palettes {
+        return None;
+    }
+    let first_record = read_u16(&data, cpal + 12 + 2 * usize::from(index))?;
+    data.get_mut(cpal + 12..cpal + 14)?
+        .copy_from_slice(&first_record.to_be_bytes());
+    for PaletteOverride { index, color } in overrides {
+        if *index >= num_entries {
+            continue;
+        }
+        let record = records + 4 * (usize::from(first_record) + usize::from(*index));
+        // Color records are stored as BGRA.
+        let [r, g, b, a] = *color;
+        data.get_mut(record..record + 4)?
+            .copy_from_slice(&[b, g, r, a]);
+    }
+    Some(FontData::new(Blob::new(Arc::new(data)), font.index))
+}
+
+/// Returns the offset of the table `tag` of the font at `index` in `data`.
+fn table_offset(data: &[u8], index: u32, tag: &[u8; 4]) -> Option<usize> {
+    let directory = if data.starts_with(b"ttcf") {
+        read_u32(data, 12 + 4 * index as usize)? as usize
+    } else {
+        0
+    };
+    let num_tables = read_u16(data, directory + 4)?;
+    (0..usize::from(num_tables))
+        .map(|table| directory + 12 + 16 * table)
+        .find(|&record| data.get(record..record + 4) == Some(tag))
+        .and_then(|record| read_u32(data, record + 8))
+        .map(|offset| offset as usize)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
 /// Reset the render context, clear the background, and set the transform for the frame.
 fn reset_renderer(
     renderer: &mut RenderContext,
@@ -14,6 +14,7 @@ use parlance::{FontStyle, FontWeight, FontWidth};
 /// [configure]: crate::Query::set_attributes
 /// [`Query`]: crate::Query
 #[derive(Copy, Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attributes {
     pub width: FontWidth,
     pub style: FontStyle,
@@ -16,7 +16,7 @@ use roxmltree::{Document, Node};
 
 use super::{
     FallbackKey, FamilyId, FamilyInfo, FamilyNameMap, GenericFamily, GenericFamilyMap, Language,
-    Script, scan,
+    Script, SharedScanCache, scan,
 };
 
 // TODO: Use actual generic families here, where available, when fonts.xml is properly parsed.
@@ -47,15 +47,22 @@ pub(crate) struct SystemFonts {
 }
 
 impl SystemFonts {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(scan_cache: Option<SharedScanCache>) -> Self {
         let android_root: String = std::env::var("ANDROID_ROOT").unwrap_or("/system".to_string());
+        let mut scan_cache = scan_cache
+            .as_ref()
+            .map(|scan_cache| scan_cache.lock().unwrap());
 
         let scan::ScannedCollection {
             family_names: mut name_map,
             families: family_map,
             postscript_names,
             ..
-        } = scan::ScannedCollection::from_paths(Path::new(&android_root).join("fonts").to_str(), 8);
+        } = scan::ScannedCollection::from_paths(
+            Path::new(&android_root).join("fonts").to_str(),
+            8,
+            scan_cache.as_deref_mut(),
+        );
         let mut generic_families = GenericFamilyMap::default();
         for (family, names) in DEFAULT_GENERIC_FAMILIES {
             generic_families.set(
@@ -69,6 +76,7 @@ impl SystemFonts {
 
         let mut locale_fallback = vec![];
         let mut script_fallback = vec![];
+        let mut lang_generics = vec![];
 
         // Try to get generic info from fonts.xml
         if let Ok(s) = std::fs::read_to_string(Path::new(&android_root).join("etc/fonts.xml"))
@@ -110,7 +118,7 @@ impl SystemFonts {
                                 .attribute("lang")
                                 .map(|s| s.split(',').collect::<Vec<&str>>())
                             {
-                                let (_has_for, hasnt_for): (Vec<Node<'_, '_>>, Vec<Node<'_, '_>>) =
+                                let (has_for, hasnt_for): (Vec<Node<'_, '_>>, Vec<Node<'_, '_>>) =
                                     child
                                         .children()
                                         .partition(|c| c.attribute("fallbackFor").is_some());
@@ -126,7 +134,7 @@ impl SystemFonts {
                                     if let Some(family) = ps_named.iter().find_map(|x| {
                                         postscript_names.get(x.attribute("postScriptName").unwrap())
                                     }) {
-                                        for lang in langs {
+                                        for lang in &langs {
                                             if let Some(scr) = lang.strip_prefix("und-") {
                                                 // Undefined lang for script-only fallbacks
                                                 script_fallback.push((
@@ -153,6 +161,12 @@ impl SystemFonts {
                                                     }
                                                 }
                                                 locale_fallback.push((locale, *family));
+                                                push_lang_generic(
+                                                    &mut lang_generics,
+                                                    GenericFamily::SansSerif,
+                                                    locale,
+                                                    *family,
+                                                );
                                             }
                                         }
                                     }
@@ -161,11 +175,28 @@ impl SystemFonts {
                                     //       when postScriptName is unavailable.
                                 }
 
-                                // family-specific fallback families, currently unimplemented
-                                // because it requires a GenericFamily to be plumbed through
-                                // the `RangedStyle` `font_stack` from `resolve` where it is
-                                // currently thrown away.
-                                {}
+                                // family-specific fallback families, used as the serif
+                                // family of their languages
+                                if let Some(family) = has_for
+                                    .iter()
+                                    .filter(|c| c.attribute("fallbackFor") == Some("serif"))
+                                    .find_map(|c| {
+                                        postscript_names.get(c.attribute("postScriptName")?)
+                                    })
+                                {
+                                    for lang in
+                                        langs.iter().filter(|lang| !lang.starts_with("und-"))
+                                    {
+                                        if let Ok(locale) = Language::parse(lang) {
+                                            push_lang_generic(
+                                                &mut lang_generics,
+                                                GenericFamily::Serif,
+                                                locale,
+                                                *family,
+                                            );
+                                        }
+                                    }
+                                }
                             }
                             // TODO: interpret variant="compact" without fallbackFor as a
                             //       fallback for system-ui, as falling back to a
@@ -178,6 +209,16 @@ impl SystemFonts {
             }
         }
 
+        // The families of a language come first, followed by the default
+        // families for the characters they don't cover.
+        for (generic, locale, family) in lang_generics {
+            let defaults = generic_families.get(generic).to_vec();
+            generic_families.set(
+                (generic, &locale),
+                core::iter::once(family).chain(defaults.into_iter().filter(|id| *id != family)),
+            );
+        }
+
         Self {
             name_map: Arc::new(name_map),
             generic_families: Arc::new(generic_families),
@@ -216,3 +257,19 @@ impl SystemFonts {
             })
     }
 }
+
+/// Records `family` as the `generic` family for `locale`, unless the locale
+/// already has one.
+fn push_lang_generic(
+    lang_generics: &mut Vec<(GenericFamily, Language, FamilyId)>,
+    generic: GenericFamily,
+    locale: Language,
+    family: FamilyId,
+) {
+    if !lang_generics
+        .iter()
+        .any(|(other, other_locale, _)| *other == generic && *other_locale == locale)
+    {
+        lang_generics.push((generic, locale, family));
+    }
+}
@@ -3,7 +3,7 @@
 
 use super::{
     FallbackKey, FamilyId, FamilyInfo, FamilyNameMap, GenericFamily, GenericFamilyMap, ScriptExt,
-    scan,
+    SharedScanCache, scan,
 };
 use alloc::format;
 use alloc::string::ToString;
@@ -41,8 +41,8 @@ pub(crate) struct SystemFonts {
 }
 
 impl SystemFonts {
-    pub(crate) fn new() -> Self {
-        let scanned = scan_system_fonts().unwrap_or_default();
+    pub(crate) fn new(scan_cache: Option<SharedScanCache>) -> Self {
+        let scanned = scan_system_fonts(scan_cache.as_ref()).unwrap_or_default();
         let name_map = scanned.family_names;
         let mut generic_families = GenericFamilyMap::default();
         for (family, names) in DEFAULT_GENERIC_FAMILIES {
@@ -89,7 +89,7 @@ impl SystemFonts {
 
 /// Discover system fonts by combining CoreText enumeration with a directory scan of all
 /// Library/Fonts paths, then index them through the shared scan pipeline.
-fn scan_system_fonts() -> Option<scan::ScannedCollection> {
+fn scan_system_fonts(scan_cache: Option<&SharedScanCache>) -> Option<scan::ScannedCollection> {
     // SAFETY: Calls into CoreText. If anything fails we return None and use the fallback scan.
     let collection = unsafe { CTFontCollection::from_available_fonts(None) };
     let descriptors = unsafe { collection.matching_font_descriptors()? };
@@ -135,7 +135,12 @@ fn scan_system_fonts() -> Option<scan::ScannedCollection> {
         return None;
     }
 
-    Some(scan::ScannedCollection::from_paths(paths.iter(), 0))
+    let mut scan_cache = scan_cache.map(|scan_cache| scan_cache.lock().unwrap());
+    Some(scan::ScannedCollection::from_paths(
+        paths.iter(),
+        0,
+        scan_cache.as_deref_mut(),
+    ))
 }
 
 fn library_font_files() -> Vec<PathBuf> {
@@ -25,7 +25,7 @@ use windows::{
 
 use super::{
     FallbackKey, FamilyId, FamilyInfo, FamilyNameMap, FontInfo, GenericFamily, GenericFamilyMap,
-    ScriptExt, SourcePathMap,
+    ScriptExt, SharedScanCache, SourcePathMap,
 };
 
 const DEFAULT_GENERIC_FAMILIES: &[(GenericFamily, &[&str])] = &[
@@ -50,7 +50,8 @@ pub(crate) struct SystemFonts {
 }
 
 impl SystemFonts {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(_scan_cache: Option<SharedScanCache>) -> Self {
+        // DirectWrite keeps its own cache of the system fonts' metadata.
         let dwrite_fonts = DWriteSystemFonts::new(false).unwrap();
         let mut name_map = FamilyNameMap::default();
         for family in dwrite_fonts.families() {
@@ -12,6 +12,7 @@ use std::{
     os::unix::ffi::OsStrExt,
     path::Path,
     sync::Arc,
+    vec::Vec,
 };
 
 #[cfg(feature = "fontconfig-dlopen")]
@@ -20,7 +21,11 @@ use fontconfig_sys::{
     FcChar8, FcCharSet, FcConfig, FcFontSet, FcLangSet, FcMatchKind, FcMatchPattern, FcPattern,
     FcResult, FcResultMatch, FcResultNoId, FcResultNoMatch, FcResultOutOfMemory,
     FcResultTypeMismatch, FcSetSystem,
-    constants::{FC_CHARSET, FC_FAMILY, FC_FILE, FC_INDEX, FC_LANG, FC_SLANT, FC_WEIGHT, FC_WIDTH},
+    constants::{
+        FC_ANTIALIAS, FC_AUTOHINT, FC_CHARSET, FC_EMBEDDED_BITMAP, FC_FAMILY, FC_FILE,
+        FC_HINT_STYLE, FC_HINTING, FC_INDEX, FC_LANG, FC_LCD_FILTER, FC_RGBA, FC_SLANT, FC_WEIGHT,
+        FC_WIDTH,
+    },
     ffi_dispatch,
 };
 #[cfg(not(feature = "fontconfig-dlopen"))]
@@ -29,18 +34,21 @@ use fontconfig_sys::{
     FcConfigDestroy, FcConfigGetFonts, FcConfigReference, FcConfigSubstitute, FcFontMatch,
     FcFontRenderPrepare, FcFontSetDestroy, FcFontSort, FcInitLoadConfig, FcLangSetAdd,
     FcLangSetCopy, FcLangSetCreate, FcLangSetDestroy, FcNameUnparse, FcPatternAddCharSet,
-    FcPatternAddLangSet, FcPatternAddString, FcPatternCreate, FcPatternDestroy,
+    FcPatternAddLangSet, FcPatternAddString, FcPatternCreate, FcPatternDestroy, FcPatternGetBool,
     FcPatternGetInteger, FcPatternGetString, FcPatternReference, FcStrFree,
 };
 
 use hashbrown::{HashMap, HashSet, hash_map::Entry};
 use smallvec::SmallVec;
 
+use super::LOCALIZED_GENERIC_LANGS;
 use crate::{
     FallbackKey, FamilyId, FamilyInfo, FontInfo, FontStyle, FontWeight, FontWidth,
-    FromFontconfig as _, GenericFamily, Script, ScriptExt,
+    FromFontconfig as _, GenericFamily, HintStyle, LcdFilter, RenderHints, Script, ScriptExt,
+    SourceCache, SubpixelOrder,
     family_name::{FamilyName, FamilyNameMap},
     generic::GenericFamilyMap,
+    scan_cache::SharedScanCache,
     source::SourcePathMap,
 };
 
@@ -192,6 +200,69 @@ impl Pattern {
         }
         Ok(dest)
     }
+
+    fn get_bool(&self, object: &CStr, n: u32) -> MatchResult<bool> {
+        let mut dest = 0;
+        let result = unsafe {
+            ffi_dispatch!(
+                feature = "fontconfig-dlopen",
+                LIB,
+                FcPatternGetBool,
+                self.inner.as_ptr(),
+                object.as_ptr(),
+                n.try_into().map_err(|_| MatchErr::Other)?,
+                &raw mut dest
+            )
+        };
+        if result != FcResultMatch {
+            return Err(MatchErr::from_raw(result));
+        }
+        Ok(dest != 0)
+    }
+
+    /// Reads the rendering properties of a pattern prepared by
+    /// `FcFontRenderPrepare`.
+    fn render_hints(&self) -> RenderHints {
+        // The constants are from `fontconfig.h`.
+        let hint_style = self.get_int(FC_HINT_STYLE, 0).ok().and_then(|value| {
+            Some(match value {
+                0 => HintStyle::None,
+                1 => HintStyle::Slight,
+                2 => HintStyle::Medium,
+                3 => HintStyle::Full,
+                _ => return None,
+            })
+        });
+        let subpixel_order = self.get_int(FC_RGBA, 0).ok().and_then(|value| {
+            Some(match value {
+                1 => SubpixelOrder::Rgb,
+                2 => SubpixelOrder::Bgr,
+                3 => SubpixelOrder::VerticalRgb,
+                4 => SubpixelOrder::VerticalBgr,
+                5 => SubpixelOrder::None,
+                // `FC_RGBA_UNKNOWN`
+                _ => return None,
+            })
+        });
+        let lcd_filter = self.get_int(FC_LCD_FILTER, 0).ok().and_then(|value| {
+            Some(match value {
+                0 => LcdFilter::None,
+                1 => LcdFilter::Default,
+                2 => LcdFilter::Light,
+                3 => LcdFilter::Legacy,
+                _ => return None,
+            })
+        });
+        RenderHints {
+            antialias: self.get_bool(FC_ANTIALIAS, 0).ok(),
+            hinting: self.get_bool(FC_HINTING, 0).ok(),
+            hint_style,
+            autohint: self.get_bool(FC_AUTOHINT, 0).ok(),
+            subpixel_order,
+            lcd_filter,
+            embedded_bitmaps: self.get_bool(FC_EMBEDDED_BITMAP, 0).ok(),
+        }
+    }
 }
 
 impl Clone for Pattern {
@@ -593,15 +664,19 @@ pub(crate) struct SystemFonts {
     pub(crate) name_map: Arc<FamilyNameMap>,
     pub(crate) generic_families: Arc<GenericFamilyMap>,
     source_cache: SourcePathMap,
+    /// Decoded WOFF and WOFF2 files, so each is decoded once for all of its
+    /// fonts.
+    woff_sources: SourceCache,
     family_map: HashMap<FamilyId, Option<FamilyInfo>>,
     config: Option<Config>,
     script_charsets: ScriptCharSetMap,
+    scan_cache: Option<SharedScanCache>,
 }
 
 unsafe impl Send for SystemFonts {}
 
 impl SystemFonts {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(scan_cache: Option<SharedScanCache>) -> Self {
         // We couldn't find the fontconfig library; maybe it doesn't exist. Just
         // return a `SystemFonts` with no `config`. All our methods will return
         // `None` and shouldn't attempt any FFI calls because the first thing we
@@ -675,54 +750,38 @@ impl SystemFonts {
         // Populate the generic family map.
         let mut generic_families = GenericFamilyMap::default();
         for (generic_family, name) in GENERIC_FAMILY_NAMES.iter().copied() {
-            let mut pattern = Pattern::new().unwrap();
-            pattern.add_string(FC_FAMILY, name);
-            // TODO: do we need FcConfigSetDefaultSubstitute?
-
-            config.substitute(&mut pattern, FcMatchPattern);
-
-            // We enable the "trim" option here which ignores later fonts if
-            // they provide no new Unicode coverage. On error this generic
-            // family simply has no members; in particular `font_sort` errors
-            // with `NoMatch` when there are no fonts to sort at all (e.g. a
-            // fontconfig configuration exposing an empty system font set).
-            let Ok(font_set) = config.font_sort(&pattern, true) else {
+            let families = sort_generic_family(&config, &name_map, name, None);
+            generic_families.append(generic_family, families.into_iter());
+        }
+        // Only keep the families of a language when its configuration
+        // actually differs from the default.
+        for lang in LOCALIZED_GENERIC_LANGS {
+            let Ok(fc_lang) = CString::new(*lang) else {
                 continue;
             };
-
-            // There are a lot of duplicate font families in the substituted
-            // pattern. Keep track of which ones have already been added to the
-            // list.
-            let mut added_families = HashSet::new();
-
-            generic_families.append(
-                generic_family,
-                font_set.iter().filter_map(|font| {
-                    // Not sure if FcFontRenderPrepare performs any substitutions
-                    // relevant to fallback family name matching, but it's a good
-                    // idea to call it just in case.
-                    let font = config.font_render_prepare(&pattern, &font)?;
-                    // Generic families can have more than one name, but the only
-                    // one we care about is the first one.
-                    let name = font.get_string(FC_FAMILY, 0).ok()?;
-                    let family_name = name_map.get(name.as_ref())?;
-
-                    if !added_families.insert(family_name.id()) {
-                        return None;
-                    }
-
-                    Some(family_name.id())
-                }),
-            );
+            for (generic_family, name) in GENERIC_FAMILY_NAMES.iter().copied() {
+                if !matches!(
+                    generic_family,
+                    GenericFamily::Serif | GenericFamily::SansSerif | GenericFamily::Monospace
+                ) {
+                    continue;
+                }
+                let families = sort_generic_family(&config, &name_map, name, Some(&fc_lang));
+                if !families.is_empty() && families != generic_families.get(generic_family) {
+                    generic_families.set((generic_family, *lang), families.into_iter());
+                }
+            }
         }
 
         Self {
             name_map: Arc::new(name_map),
             generic_families: Arc::new(generic_families),
             source_cache: SourcePathMap::default(),
+            woff_sources: SourceCache::default(),
             family_map: HashMap::default(),
             config: Some(config),
             script_charsets: ScriptCharSetMap::default(),
+            scan_cache,
         }
     }
 
@@ -815,9 +874,25 @@ impl SystemFonts {
                     .unwrap_or_default();
                 let index = font.get_int(FC_INDEX, 0).map_or(0, |idx| idx.max(0) as u32);
 
-                let mut font_info = FontInfo::from_source(source_info, index)?;
+                let mut font_info = match &self.scan_cache {
+                    // Fontconfig's own cache doesn't hold the character map
+                    // index or axes, so the font would otherwise be parsed.
+                    Some(scan_cache) => scan_cache
+                        .lock()
+                        .unwrap()
+                        .fonts(path)?
+                        .iter()
+                        .find(|font| font.index == index)?
+                        .font_info(source_info),
+                    None => FontInfo::from_cached_source(
+                        source_info,
+                        index,
+                        Some(&mut self.woff_sources),
+                    )?,
+                };
                 // TODO(valadaptive): does this do anything anymore?
                 font_info.maybe_override_attributes(width, style, weight);
+                font_info.set_render_hints(font.render_hints());
                 Some(font_info)
             })() {
                 font_infos.push(font_info);
@@ -832,6 +907,58 @@ impl SystemFonts {
     }
 }
 
+/// Returns the families fontconfig substitutes for the given generic family
+/// name, optionally for a language.
+fn sort_generic_family(
+    config: &Config,
+    name_map: &FamilyNameMap,
+    name: &CStr,
+    lang: Option<&CStr>,
+) -> Vec<FamilyId> {
+    let mut pattern = Pattern::new().unwrap();
+    pattern.add_string(FC_FAMILY, name);
+    if let Some(lang) = lang {
+        pattern.add_string(FC_LANG, lang);
+    }
+    // TODO: do we need FcConfigSetDefaultSubstitute?
+
+    config.substitute(&mut pattern, FcMatchPattern);
+
+    // We enable the "trim" option here which ignores later fonts if
+    // they provide no new Unicode coverage. On error this generic
+    // family simply has no members; in particular `font_sort` errors
+    // with `NoMatch` when there are no fonts to sort at all (e.g. a
+    // fontconfig configuration exposing an empty system font set).
+    let Ok(font_set) = config.font_sort(&pattern, true) else {
+        return Vec::new();
+    };
+
+    // There are a lot of duplicate font families in the substituted
+    // pattern. Keep track of which ones have already been added to the
+    // list.
+    let mut added_families = HashSet::new();
+
+    font_set
+        .iter()
+        .filter_map(|font| {
+            // Not sure if FcFontRenderPrepare performs any substitutions
+            // relevant to fallback family name matching, but it's a good
+            // idea to call it just in case.
+            let font = config.font_render_prepare(&pattern, &font)?;
+            // Generic families can have more than one name, but the only
+            // one we care about is the first one.
+            let name = font.get_string(FC_FAMILY, 0).ok()?;
+            let family_name = name_map.get(name.as_ref())?;
+
+            if !added_families.insert(family_name.id()) {
+                return None;
+            }
+
+            Some(family_name.id())
+        })
+        .collect()
+}
+
 const GENERIC_FAMILY_NAMES: &[(GenericFamily, &CStr)] = &[
     (GenericFamily::Serif, c"serif"),
     (GenericFamily::SansSerif, c"sans-serif"),
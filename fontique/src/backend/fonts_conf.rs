@@ -0,0 +1,900 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! System fonts configured by fontconfig's `fonts.conf`, without linking
+//! the fontconfig library.
+//!
+//! This reads the directories, aliases, family substitutions and font
+//! rejections of the configuration. Only the parts of `<match>` rules that
+//! test the family or language of a pattern and edit its family are
+//! understood, which covers the rules used to choose fonts for the generic
+//! families and for fallback.
+//!
+//! See <https://www.freedesktop.org/software/fontconfig/fontconfig-user.html>.
+
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+use hashbrown::{HashMap, HashSet};
+use roxmltree::{Document, Node, ParsingOptions};
+
+use super::{
+    FallbackKey, FamilyId, FamilyInfo, FamilyNameMap, GenericFamily, GenericFamilyMap,
+    LOCALIZED_GENERIC_LANGS, ScriptExt, SharedScanCache, SourceInfo, scan,
+};
+use crate::{
+    SourceId, SourceKind,
+    coverage::{ScriptCoverage, name_matches_locale},
+};
+
+/// The names fontconfig gives the generic families.
+const GENERIC_FAMILY_NAMES: &[(GenericFamily, &str)] = &[
+    (GenericFamily::Serif, "serif"),
+    (GenericFamily::SansSerif, "sans-serif"),
+    (GenericFamily::Monospace, "monospace"),
+    (GenericFamily::Cursive, "cursive"),
+    (GenericFamily::Fantasy, "fantasy"),
+    (GenericFamily::SystemUi, "system-ui"),
+    (GenericFamily::Emoji, "emoji"),
+    (GenericFamily::Math, "math"),
+];
+
+/// The family fontconfig substitutes into patterns that don't name one.
+const DEFAULT_FAMILY: &str = "sans-serif";
+
+/// Directories searched when the configuration doesn't list any.
+const DEFAULT_DIRS: &[&str] = &[
+    "/usr/share/fonts",
+    "/usr/local/share/fonts",
+    "~/.local/share/fonts",
+    "~/.fonts",
+];
+
+/// Limit on nested `<include>` elements, guarding against include cycles
+/// through symbolic links.
+const MAX_INCLUDE_DEPTH: u32 = 16;
+
+/// Raw access to the collection of local system fonts.
+pub(crate) struct SystemFonts {
+    pub(crate) name_map: Arc<FamilyNameMap>,
+    pub(crate) generic_families: Arc<GenericFamilyMap>,
+    family_map: HashMap<FamilyId, FamilyInfo>,
+    script_coverage: HashMap<(SourceId, u32), ScriptCoverage>,
+    config: FontsConf,
+    fallbacks: HashMap<FallbackKey, Option<FamilyId>>,
+}
+
+impl SystemFonts {
+    pub(crate) fn new(scan_cache: Option<SharedScanCache>) -> Self {
+        Self::from_config(FontsConf::load_default(), scan_cache)
+    }
+
+    fn from_config(config: FontsConf, scan_cache: Option<SharedScanCache>) -> Self {
+        let mut scan_cache = scan_cache
+            .as_ref()
+            .map(|scan_cache| scan_cache.lock().unwrap());
+        let scan::ScannedCollection {
+            family_names: name_map,
+            families: mut family_map,
+            script_coverage,
+            ..
+        } = scan::ScannedCollection::from_paths(&config.dirs, 8, scan_cache.as_deref_mut());
+
+        if !config.reject.is_empty() {
+            family_map.retain(|id, family| {
+                let Some(name) = name_map.get_by_id(*id) else {
+                    return false;
+                };
+                let fonts = family
+                    .fonts()
+                    .iter()
+                    .filter(|font| !config.is_rejected(font.source(), name.name()))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if fonts.len() != family.fonts().len() {
+                    *family = FamilyInfo::new(name.clone(), fonts);
+                }
+                !family.fonts().is_empty()
+            });
+        }
+
+        let mut fonts = Self {
+            name_map: Arc::new(name_map),
+            generic_families: Arc::default(),
+            family_map,
+            script_coverage,
+            config,
+            fallbacks: HashMap::default(),
+        };
+        let mut generic_families = GenericFamilyMap::default();
+        for (generic_family, name) in GENERIC_FAMILY_NAMES.iter().copied() {
+            generic_families.set(generic_family, fonts.substitute(name, None).into_iter());
+        }
+        for lang in LOCALIZED_GENERIC_LANGS {
+            for (generic_family, name) in GENERIC_FAMILY_NAMES.iter().copied() {
+                let families = fonts.substitute(name, Some(lang));
+                if families != generic_families.get(generic_family) {
+                    generic_families.set((generic_family, *lang), families.into_iter());
+                }
+            }
+        }
+        fonts.generic_families = Arc::new(generic_families);
+        fonts
+    }
+
+    pub(crate) fn family(&self, id: FamilyId) -> Option<FamilyInfo> {
+        self.family_map.get(&id).cloned()
+    }
+
+    pub(crate) fn fallback(&mut self, key: impl Into<FallbackKey>) -> Option<FamilyId> {
+        let key: FallbackKey = key.into();
+        if let Some(family) = self.fallbacks.get(&key) {
+            return *family;
+        }
+        let family = self.fallback_uncached(key);
+        self.fallbacks.insert(key, family);
+        family
+    }
+}
+
+impl SystemFonts {
+    /// Returns the families substituted for `family` that have fonts, in
+    /// order of preference.
+    fn substitute(&self, family: &str, lang: Option<&str>) -> Vec<FamilyId> {
+        let mut ids = Vec::new();
+        for name in self.config.substitute(family, lang) {
+            if let Some(name) = self.name_map.get(&name)
+                && self.family_map.contains_key(&name.id())
+                && !ids.contains(&name.id())
+            {
+                ids.push(name.id());
+            }
+        }
+        ids
+    }
+
+    /// Returns the script coverage of the default font of `family`, as
+    /// recorded when its file was scanned.
+    fn script_coverage(&self, family: &FamilyInfo) -> Option<&ScriptCoverage> {
+        let font = family.default_font()?;
+        self.script_coverage
+            .get(&(font.source().id(), font.index()))
+    }
+
+    fn fallback_uncached(&self, key: FallbackKey) -> Option<FamilyId> {
+        // Like fontconfig, prefer the families configured for the default
+        // family in the requested language. Other families are ranked by how
+        // much of the script they cover, then by whether they are named for
+        // the language, and then by name, so the order the fonts were
+        // scanned in doesn't decide.
+        let preferred = self.substitute(DEFAULT_FAMILY, key.locale_str());
+        let script = key.script();
+        if script.sample().is_none() {
+            return preferred.first().copied();
+        }
+        let coverage = |family: &FamilyInfo| -> Option<usize> {
+            self.script_coverage(family)?.covered(script)
+        };
+        preferred
+            .iter()
+            .copied()
+            .find(|id| coverage(&self.family_map[id]).is_some())
+            .or_else(|| {
+                self.family_map
+                    .values()
+                    .filter_map(|family| Some((family, coverage(family)?)))
+                    .min_by_key(|&(family, coverage)| {
+                        (
+                            Reverse(coverage),
+                            !name_matches_locale(family.name(), key.locale()),
+                            family.name(),
+                        )
+                    })
+                    .map(|(family, _)| family.id())
+            })
+            .or_else(|| preferred.first().copied())
+    }
+}
+
+/// The parts of a fontconfig configuration that affect font matching.
+#[derive(Default, Debug)]
+struct FontsConf {
+    /// Directories scanned for fonts.
+    dirs: Vec<PathBuf>,
+    /// Family substitutions from `<alias>` and `<match>` elements, in
+    /// document order.
+    rules: Vec<Rule>,
+    /// Fonts excluded by `<rejectfont>`.
+    reject: Vec<FontFilter>,
+    /// Fonts kept by `<acceptfont>` despite matching a rejection.
+    accept: Vec<FontFilter>,
+    /// Configuration files already loaded.
+    included: HashSet<PathBuf>,
+}
+
+impl FontsConf {
+    /// Loads the configuration the fontconfig library would load.
+    fn load_default() -> Self {
+        let path = std::env::var_os("FONTCONFIG_FILE")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("FONTCONFIG_PATH").map(|dir| Path::new(&dir).join("fonts.conf"))
+            })
+            .unwrap_or_else(|| PathBuf::from("/etc/fonts/fonts.conf"));
+        let mut config = Self::load(&path);
+        if config.dirs.is_empty() {
+            config.dirs = DEFAULT_DIRS
+                .iter()
+                .filter_map(|dir| expand_home(dir))
+                .collect();
+        }
+        config
+    }
+
+    /// Loads the configuration file at `path` and the files it includes.
+    fn load(path: &Path) -> Self {
+        let mut config = Self::default();
+        config.include(path, 0);
+        config
+    }
+
+    /// Loads a configuration file, or the numbered `.conf` files of a
+    /// directory in order.
+    fn include(&mut self, path: &Path, depth: u32) {
+        if depth > MAX_INCLUDE_DEPTH || !self.included.insert(path.to_path_buf()) {
+            return;
+        }
+        if path.is_dir() {
+            let Ok(entries) = std::fs::read_dir(path) else {
+                return;
+            };
+            let mut files: Vec<_> = entries
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .filter(|path| {
+                    path.extension().is_some_and(|ext| ext == "conf")
+                        && path
+                            .file_name()
+                            .and_then(|name| name.to_str())
+                            .is_some_and(|name| name.starts_with(|c: char| c.is_ascii_digit()))
+                })
+                .collect();
+            files.sort();
+            for file in files {
+                self.include(&file, depth + 1);
+            }
+        } else if let Ok(text) = std::fs::read_to_string(path) {
+            self.parse(&text, path.parent().unwrap_or(Path::new("")), depth);
+        }
+    }
+
+    /// Parses a configuration file, resolving relative paths against
+    /// `base`.
+    fn parse(&mut self, text: &str, base: &Path, depth: u32) {
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
+        let Ok(doc) = Document::parse_with_options(text, options) else {
+            return;
+        };
+        let root = doc.root_element();
+        if root.tag_name().name() != "fontconfig" {
+            return;
+        }
+        for node in root.children().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "dir" => {
+                    if let Some(dir) = resolve_path(node, base, XdgDir::Data) {
+                        self.dirs.push(dir);
+                    }
+                }
+                "include" => {
+                    if let Some(path) = resolve_path(node, base, XdgDir::Config) {
+                        self.include(&path, depth + 1);
+                    }
+                }
+                "alias" => self.rules.extend(Rule::from_alias(node)),
+                "match" => self.rules.extend(Rule::from_match(node)),
+                "selectfont" => {
+                    for list in node.children().filter(Node::is_element) {
+                        let filters = match list.tag_name().name() {
+                            "rejectfont" => &mut self.reject,
+                            "acceptfont" => &mut self.accept,
+                            _ => continue,
+                        };
+                        filters.extend(list.children().filter_map(FontFilter::new));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the families of a pattern naming `family` in language `lang`
+    /// after the configured substitutions.
+    fn substitute(&self, family: &str, lang: Option<&str>) -> Vec<String> {
+        let mut families = alloc::vec![family.to_string()];
+        for rule in &self.rules {
+            rule.apply(&mut families, lang);
+        }
+        families
+    }
+
+    /// Returns `true` if the font in `source` of the family `family` is
+    /// rejected.
+    fn is_rejected(&self, source: &SourceInfo, family: &str) -> bool {
+        let path = match source.kind() {
+            SourceKind::Path(path) => path.to_str(),
+            SourceKind::Memory(_) => None,
+        };
+        let matches = |filter: &FontFilter| filter.matches(path, family);
+        self.reject.iter().any(matches) && !self.accept.iter().any(matches)
+    }
+}
+
+/// A family substitution, applied when all of its tests pass.
+#[derive(Debug)]
+struct Rule {
+    tests: Vec<Test>,
+    edits: Vec<Edit>,
+}
+
+impl Rule {
+    /// Reads an `<alias>`, which prepends its preferred families to the
+    /// aliased family, appends its accepted families after it and its
+    /// default families at the end.
+    fn from_alias(node: Node<'_, '_>) -> Option<Self> {
+        let mut families = Vec::new();
+        let mut edits = Vec::new();
+        for child in node.children().filter(Node::is_element) {
+            let mode = match child.tag_name().name() {
+                "family" => {
+                    families.extend(element_text(child));
+                    continue;
+                }
+                "prefer" => EditMode::Prepend,
+                "accept" => EditMode::Append,
+                "default" => EditMode::AppendLast,
+                _ => continue,
+            };
+            let values = child
+                .children()
+                .filter(|node| node.has_tag_name("family"))
+                .filter_map(element_text)
+                .collect();
+            edits.push(Edit { mode, values });
+        }
+        (!families.is_empty() && !edits.is_empty()).then(|| Self {
+            tests: alloc::vec![Test {
+                property: Property::Family,
+                qual: Qual::Any,
+                compare: Compare::Eq,
+                values: families,
+            }],
+            edits,
+        })
+    }
+
+    /// Reads a `<match>` of the pattern editing its family.
+    ///
+    /// Returns `None` for rules targeting fonts, testing other properties
+    /// or using unsupported operators, which are ignored.
+    fn from_match(node: Node<'_, '_>) -> Option<Self> {
+        if node
+            .attribute("target")
+            .is_some_and(|target| target != "pattern")
+        {
+            return None;
+        }
+        let mut tests = Vec::new();
+        let mut edits = Vec::new();
+        for child in node.children().filter(Node::is_element) {
+            match child.tag_name().name() {
+                "test" => tests.push(Test::new(child)?),
+                "edit" if child.attribute("name") == Some("family") => {
+                    let mode = EditMode::new(child.attribute("mode").unwrap_or("assign"))?;
+                    let values = child
+                        .children()
+                        .filter(|node| node.has_tag_name("string"))
+                        .filter_map(element_text)
+                        .collect();
+                    edits.push(Edit { mode, values });
+                }
+                _ => {}
+            }
+        }
+        (!edits.is_empty()).then_some(Self { tests, edits })
+    }
+
+    fn apply(&self, families: &mut Vec<String>, lang: Option<&str>) {
+        // The position of the family matched by a family test, which edits
+        // are relative to.
+        let mut position = None;
+        for test in &self.tests {
+            match test.property {
+                Property::Family => match test.match_family(families) {
+                    Some(matched) => position = position.or(matched),
+                    None => return,
+                },
+                Property::Lang => {
+                    if !test.matches_lang(lang) {
+                        return;
+                    }
+                }
+            }
+        }
+        for edit in &self.edits {
+            edit.apply(families, &mut position);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Test {
+    property: Property,
+    qual: Qual,
+    compare: Compare,
+    values: Vec<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Property {
+    Family,
+    Lang,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Qual {
+    Any,
+    All,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Compare {
+    Eq,
+    NotEq,
+    Contains,
+    NotContains,
+}
+
+impl Test {
+    fn new(node: Node<'_, '_>) -> Option<Self> {
+        if node
+            .attribute("target")
+            .is_some_and(|target| target != "pattern" && target != "default")
+        {
+            return None;
+        }
+        let property = match node.attribute("name")? {
+            "family" => Property::Family,
+            "lang" => Property::Lang,
+            _ => return None,
+        };
+        let qual = match node.attribute("qual").unwrap_or("any") {
+            "any" => Qual::Any,
+            "all" => Qual::All,
+            _ => return None,
+        };
+        let compare = match node.attribute("compare").unwrap_or("eq") {
+            "eq" => Compare::Eq,
+            "not_eq" => Compare::NotEq,
+            "contains" => Compare::Contains,
+            "not_contains" => Compare::NotContains,
+            _ => return None,
+        };
+        let values: Vec<_> = node
+            .children()
+            .filter(|node| node.has_tag_name("string"))
+            .filter_map(element_text)
+            .collect();
+        (!values.is_empty()).then_some(Self {
+            property,
+            qual,
+            compare,
+            values,
+        })
+    }
+
+    /// Returns `None` if the test fails, or the position of the first
+    /// matching family if it passes.
+    fn match_family(&self, families: &[String]) -> Option<Option<usize>> {
+        let matches = |family: &String| {
+            let family = fold_family(family);
+            let any = self.values.iter().any(|value| {
+                let value = fold_family(value);
+                match self.compare {
+                    Compare::Eq | Compare::NotEq => family == value,
+                    Compare::Contains | Compare::NotContains => family.contains(&value),
+                }
+            });
+            any == matches!(self.compare, Compare::Eq | Compare::Contains)
+        };
+        match self.qual {
+            Qual::Any => families.iter().position(matches).map(Some),
+            Qual::All => families.iter().all(matches).then_some(None),
+        }
+    }
+
+    fn matches_lang(&self, lang: Option<&str>) -> bool {
+        let Some(lang) = lang else {
+            return false;
+        };
+        let any = self.values.iter().any(|value| lang_matches(lang, value));
+        any == matches!(self.compare, Compare::Eq | Compare::Contains)
+    }
+}
+
+#[derive(Debug)]
+struct Edit {
+    mode: EditMode,
+    values: Vec<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum EditMode {
+    Assign,
+    AssignReplace,
+    Prepend,
+    PrependFirst,
+    Append,
+    AppendLast,
+    Delete,
+    DeleteAll,
+}
+
+impl EditMode {
+    fn new(mode: &str) -> Option<Self> {
+        Some(match mode {
+            "assign" => Self::Assign,
+            "assign_replace" => Self::AssignReplace,
+            "prepend" => Self::Prepend,
+            "prepend_first" => Self::PrependFirst,
+            "append" => Self::Append,
+            "append_last" => Self::AppendLast,
+            "delete" => Self::Delete,
+            "delete_all" => Self::DeleteAll,
+            _ => return None,
+        })
+    }
+}
+
+impl Edit {
+    /// Edits `families` relative to the matched family at `position`, or
+    /// to the whole list if no family was matched.
+    fn apply(&self, families: &mut Vec<String>, position: &mut Option<usize>) {
+        let values = self.values.iter().cloned();
+        let len = self.values.len();
+        match (self.mode, *position) {
+            (EditMode::Assign, Some(idx)) => {
+                families.splice(idx..=idx, values);
+                if len == 0 {
+                    *position = None;
+                }
+            }
+            (EditMode::Assign | EditMode::AssignReplace, _) => {
+                families.clear();
+                families.extend(values);
+                *position = None;
+            }
+            (EditMode::Prepend, Some(idx)) => {
+                families.splice(idx..idx, values);
+                *position = Some(idx + len);
+            }
+            (EditMode::Prepend | EditMode::PrependFirst, _) => {
+                families.splice(0..0, values);
+                *position = position.map(|idx| idx + len);
+            }
+            (EditMode::Append, Some(idx)) => {
+                families.splice(idx + 1..idx + 1, values);
+            }
+            (EditMode::Append | EditMode::AppendLast, _) => families.extend(values),
+            (EditMode::Delete, Some(idx)) => {
+                families.remove(idx);
+                *position = None;
+            }
+            (EditMode::Delete | EditMode::DeleteAll, _) => {
+                families.clear();
+                *position = None;
+            }
+        }
+    }
+}
+
+/// A font selected by a `<glob>` on its file path or by a `<pattern>` on
+/// its family.
+#[derive(Debug)]
+enum FontFilter {
+    Glob(String),
+    Family(String),
+}
+
+impl FontFilter {
+    fn new(node: Node<'_, '_>) -> Option<Self> {
+        match node.tag_name().name() {
+            "glob" => element_text(node).map(Self::Glob),
+            "pattern" => {
+                // Only patterns of a single family are supported.
+                let mut elements = node.children().filter(Node::is_element);
+                let element = elements.next()?;
+                if elements.next().is_some() || element.attribute("name") != Some("family") {
+                    return None;
+                }
+                element
+                    .children()
+                    .find(|node| node.has_tag_name("string"))
+                    .and_then(element_text)
+                    .map(Self::Family)
+            }
+            _ => None,
+        }
+    }
+
+    fn matches(&self, path: Option<&str>, family: &str) -> bool {
+        match self {
+            Self::Glob(glob) => path.is_some_and(|path| glob_matches(glob, path)),
+            Self::Family(name) => fold_family(name) == fold_family(family),
+        }
+    }
+}
+
+/// The base directory of paths with `prefix="xdg"`.
+#[derive(Copy, Clone)]
+enum XdgDir {
+    Data,
+    Config,
+}
+
+/// Resolves the path in the text of `node` against `base`, expanding `~`
+/// and the `xdg` prefix.
+fn resolve_path(node: Node<'_, '_>, base: &Path, xdg: XdgDir) -> Option<PathBuf> {
+    let text = element_text(node)?;
+    if node.attribute("prefix") == Some("xdg") {
+        let (var, default) = match xdg {
+            XdgDir::Data => ("XDG_DATA_HOME", "~/.local/share"),
+            XdgDir::Config => ("XDG_CONFIG_HOME", "~/.config"),
+        };
+        let home = std::env::var_os(var)
+            .map(PathBuf::from)
+            .or_else(|| expand_home(default))?;
+        return Some(home.join(text));
+    }
+    expand_home(&text).map(|path| base.join(path))
+}
+
+/// Expands a leading `~` to the home directory.
+///
+/// Returns `None` if the path starts with `~` and there is no home
+/// directory.
+fn expand_home(path: &str) -> Option<PathBuf> {
+    match path.strip_prefix('~') {
+        Some(rest) => {
+            let home = std::env::var_os("HOME")?;
+            Some(Path::new(&home).join(rest.trim_start_matches('/')))
+        }
+        None => Some(PathBuf::from(path)),
+    }
+}
+
+/// Returns the trimmed, non-empty text of an element.
+fn element_text(node: Node<'_, '_>) -> Option<String> {
+    let text = node.text()?.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Folds a family name the way fontconfig compares them, ignoring case and
+/// blanks.
+fn fold_family(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Returns `true` if the language `lang` matches `value`, which matches
+/// any territory of its language when it doesn't name one.
+fn lang_matches(lang: &str, value: &str) -> bool {
+    let split = |tag: &str| {
+        let tag = tag.to_ascii_lowercase().replace('_', "-");
+        match tag.split_once('-') {
+            Some((language, territory)) => (language.to_string(), Some(territory.to_string())),
+            None => (tag, None),
+        }
+    };
+    let (lang, lang_territory) = split(lang);
+    let (value, value_territory) = split(value);
+    lang == value && (value_territory.is_none() || value_territory == lang_territory)
+}
+
+/// Matches `text` against a glob with `*` and `?` wildcards.
+fn glob_matches(glob: &str, text: &str) -> bool {
+    let (glob, text) = (glob.as_bytes(), text.as_bytes());
+    let (mut g, mut t) = (0, 0);
+    // The position of the last `*` and of the text it was matched at, to
+    // backtrack to.
+    let mut star = None;
+    while t < text.len() {
+        match glob.get(g) {
+            Some(b'*') => {
+                star = Some((g, t));
+                g += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => {
+                let Some((star_g, star_t)) = star else {
+                    return false;
+                };
+                g = star_g + 1;
+                t = star_t + 1;
+                star = Some((star_g, t));
+            }
+        }
+    }
+    glob[g..].iter().all(|c| *c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FontsConf, GenericFamily, SystemFonts, glob_matches};
+    use crate::{FallbackKey, GenericFamilyKey, Script};
+    use alloc::{string::String, vec::Vec};
+    use std::path::{Path, PathBuf};
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../parley_dev/assets/fonts_conf")
+            .join(name)
+    }
+
+    fn parse(text: &str) -> FontsConf {
+        let mut config = FontsConf::default();
+        config.parse(text, Path::new("/etc/fonts"), 0);
+        config
+    }
+
+    fn families(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| String::from(*name)).collect()
+    }
+
+    #[test]
+    fn alias_positions() {
+        let config = parse(
+            r#"<?xml version="1.0"?>
+            <!DOCTYPE fontconfig SYSTEM "urn:fontconfig:fonts.dtd">
+            <fontconfig>
+                <alias>
+                    <family>serif</family>
+                    <prefer><family>A</family><family>B</family></prefer>
+                    <accept><family>C</family></accept>
+                    <default><family>D</family></default>
+                </alias>
+                <alias>
+                    <family>B</family>
+                    <accept><family>E</family></accept>
+                </alias>
+            </fontconfig>"#,
+        );
+        assert_eq!(
+            config.substitute("serif", None),
+            families(&["A", "B", "E", "serif", "C", "D"])
+        );
+        assert_eq!(config.substitute("Other", None), families(&["Other"]));
+    }
+
+    #[test]
+    fn match_edits() {
+        let config = parse(
+            r#"<fontconfig>
+                <match target="pattern">
+                    <test name="family"><string>Sans Serif</string></test>
+                    <edit name="family" mode="assign"><string>sans-serif</string></edit>
+                </match>
+                <match>
+                    <test name="lang" compare="contains"><string>ja</string></test>
+                    <test name="family"><string>sans-serif</string></test>
+                    <edit name="family" mode="prepend"><string>Noto Sans CJK JP</string></edit>
+                </match>
+                <match target="pattern">
+                    <test name="prgname"><string>app</string></test>
+                    <edit name="family" mode="assign_replace"><string>Ignored</string></edit>
+                </match>
+                <match target="font">
+                    <edit name="family" mode="assign_replace"><string>Ignored</string></edit>
+                </match>
+                <match>
+                    <edit name="family" mode="append_last"><string>Fallback</string></edit>
+                </match>
+            </fontconfig>"#,
+        );
+        assert_eq!(
+            config.substitute("sansserif", None),
+            families(&["sans-serif", "Fallback"])
+        );
+        assert_eq!(
+            config.substitute("Sans Serif", Some("ja-JP")),
+            families(&["Noto Sans CJK JP", "sans-serif", "Fallback"])
+        );
+        assert_eq!(
+            config.substitute("sans-serif", Some("ko")),
+            families(&["sans-serif", "Fallback"])
+        );
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_matches(
+            "/usr/share/fonts/*",
+            "/usr/share/fonts/a/b.ttf"
+        ));
+        assert!(glob_matches("*.pcf.gz", "/fonts/misc/6x13.pcf.gz"));
+        assert!(glob_matches("/fonts/?.ttf", "/fonts/a.ttf"));
+        assert!(!glob_matches("/fonts/?.ttf", "/fonts/ab.ttf"));
+        assert!(!glob_matches("*.otf", "/fonts/a.ttf"));
+    }
+
+    #[test]
+    fn fixture_config() {
+        let config = FontsConf::load(&fixture("fonts.conf"));
+        assert_eq!(config.dirs, [fixture("../fonts")]);
+        // `conf.d/README` isn't a numbered configuration file.
+        assert!(!config.included.contains(&fixture("conf.d/README")));
+
+        let fonts = SystemFonts::from_config(config, None);
+        let family = |name: &str| fonts.name_map.get(name).unwrap().id();
+        let generic = |generic: GenericFamily| fonts.generic_families.get(generic).to_vec();
+
+        assert_eq!(
+            generic(GenericFamily::SansSerif),
+            [family("Arimo"), family("Roboto")]
+        );
+        assert_eq!(generic(GenericFamily::Monospace), [family("Roboto")]);
+        assert_eq!(generic(GenericFamily::SystemUi), [family("Roboto Flex")]);
+        assert!(generic(GenericFamily::Serif).is_empty());
+
+        // Generic families configured per language.
+        let japanese = crate::Language::parse("ja-JP").unwrap();
+        let serif_ja = GenericFamilyKey::new(GenericFamily::Serif, Some(&japanese));
+        assert_eq!(fonts.generic_families.get(serif_ja), [family("Roboto")]);
+        assert_eq!(
+            fonts.generic_families.get((GenericFamily::SansSerif, "ja")),
+            [family("Arimo"), family("Roboto")]
+        );
+
+        // Families whose files are all rejected are removed.
+        assert!(fonts.family(family("Noto Color Emoji CBTF")).is_none());
+        assert!(fonts.family(family("Noto Color Emoji")).is_some());
+        assert_eq!(generic(GenericFamily::Emoji), [family("Noto Color Emoji")]);
+    }
+
+    #[test]
+    fn fixture_fallbacks() {
+        let mut fonts = SystemFonts::from_config(FontsConf::load(&fixture("fonts.conf")), None);
+        let family = |fonts: &SystemFonts, name: &str| fonts.name_map.get(name).unwrap().id();
+
+        let latin = FallbackKey::new(Script::from_bytes(*b"Latn"), None);
+        assert_eq!(fonts.fallback(latin), Some(family(&fonts, "Arimo")));
+
+        // Not among the families configured for sans-serif, but found by
+        // coverage.
+        let arabic = FallbackKey::new(Script::from_bytes(*b"Arab"), None);
+        assert_eq!(
+            fonts.fallback(arabic),
+            Some(family(&fonts, "Noto Kufi Arabic"))
+        );
+
+        // The configuration prefers another family for German.
+        let german = crate::Language::parse("de").unwrap();
+        let latin_de = FallbackKey::new(Script::from_bytes(*b"Latn"), Some(&german));
+        assert_eq!(
+            fonts.fallback(latin_de),
+            Some(family(&fonts, "Roboto Flex"))
+        );
+    }
+}
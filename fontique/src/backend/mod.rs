@@ -11,10 +11,21 @@ mod system;
 #[path = "coretext.rs"]
 mod system;
 
-#[cfg(all(feature = "system", any(target_os = "linux", target_os = "freebsd")))]
+#[cfg(all(
+    feature = "fontconfig",
+    not(feature = "fonts-conf"),
+    any(target_os = "linux", target_os = "freebsd")
+))]
 #[path = "fontconfig.rs"]
 mod system;
 
+#[cfg(all(
+    feature = "fonts-conf",
+    any(target_os = "linux", target_os = "freebsd")
+))]
+#[path = "fonts_conf.rs"]
+mod system;
+
 #[cfg(all(feature = "system", target_os = "android"))]
 #[path = "android.rs"]
 mod system;
@@ -30,13 +41,26 @@ use super::{
 
 #[cfg(feature = "std")]
 #[allow(unused_imports)]
-use super::source::SourcePathMap;
+use super::{scan_cache::SharedScanCache, source::SourcePathMap};
 
 pub(crate) use system::SystemFonts;
 
-// Dummy system font backend for targets like wasm32-unknown-unknown
+/// Languages whose generic families commonly resolve to other families than
+/// the default ones, such as Han fonts with regional glyph forms.
+#[cfg(all(
+    any(feature = "fontconfig", feature = "fonts-conf"),
+    any(target_os = "linux", target_os = "freebsd")
+))]
+const LOCALIZED_GENERIC_LANGS: &[&str] = &["ja", "ko", "zh-CN", "zh-TW", "zh-HK"];
+
+// Dummy system font backend for targets like wasm32-unknown-unknown, and for
+// Linux and FreeBSD without a backend feature
 #[cfg(any(
     not(feature = "system"),
+    all(
+        not(any(feature = "fontconfig", feature = "fonts-conf")),
+        any(target_os = "linux", target_os = "freebsd")
+    ),
     not(any(
         target_os = "windows",
         target_os = "linux",
@@ -46,6 +70,8 @@ pub(crate) use system::SystemFonts;
     ))
 ))]
 mod system {
+    #[cfg(feature = "std")]
+    use super::SharedScanCache;
     #[cfg(feature = "system")]
     use super::{FallbackKey, FamilyId, FamilyInfo};
     use super::{FamilyNameMap, GenericFamilyMap};
@@ -58,7 +84,7 @@ mod system {
     }
 
     impl SystemFonts {
-        pub(crate) fn new() -> Self {
+        pub(crate) fn new(#[cfg(feature = "std")] _scan_cache: Option<SharedScanCache>) -> Self {
             Self::default()
         }
 
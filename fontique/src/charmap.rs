@@ -6,6 +6,8 @@
 // TODO(dfrg): move this code to read-fonts so it can be shared among other
 // crates.
 
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
 use read_fonts::{
     FontData, FontRead, FontRef, TableProvider, TopLevelTable,
     tables::cmap::{Cmap, CmapSubtable},
@@ -15,9 +17,9 @@ use read_fonts::{
 /// Metadata for constructing a character map from font data.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct CharmapIndex {
-    subtable_offset: u32,
-    is_symbol: bool,
-    is_mac_roman: bool,
+    pub(crate) subtable_offset: u32,
+    pub(crate) is_symbol: bool,
+    pub(crate) is_mac_roman: bool,
 }
 
 impl CharmapIndex {
@@ -46,6 +48,7 @@ impl CharmapIndex {
             subtable,
             is_symbol: self.is_symbol,
             is_mac_roman: self.is_mac_roman,
+            unicode_range: &[],
         })
     }
 }
@@ -56,13 +59,36 @@ pub struct Charmap<'a> {
     subtable: CmapSubtable<'a>,
     is_symbol: bool,
     is_mac_roman: bool,
+    unicode_range: &'a [RangeInclusive<u32>],
 }
 
-impl Charmap<'_> {
+impl<'a> Charmap<'a> {
+    /// Restricts the character map to the given ranges of codepoints.
+    ///
+    /// Codepoints outside of the ranges are not mapped. An empty slice leaves
+    /// the character map unrestricted.
+    ///
+    /// The ranges must be sorted and must not overlap, as the ranges of
+    /// [`FontInfo::unicode_range`](crate::FontInfo::unicode_range) are.
+    pub fn with_unicode_range(mut self, unicode_range: &'a [RangeInclusive<u32>]) -> Self {
+        self.unicode_range = unicode_range;
+        self
+    }
+
     /// Returns the glyph identifier for the given codepoint.
     pub fn map(&self, codepoint: impl Into<u32>) -> Option<u32> {
         const ASCII_MAX: u32 = 0x7F;
         let mut c = codepoint.into();
+        if !self.unicode_range.is_empty() {
+            let index = self.unicode_range.partition_point(|range| *range.end() < c);
+            if !self
+                .unicode_range
+                .get(index)
+                .is_some_and(|range| range.contains(&c))
+            {
+                return None;
+            }
+        }
         // The Mac Roman encoding requires special processing for codepoints
         // above the ASCII range.
         if self.is_mac_roman && c > ASCII_MAX {
@@ -98,6 +124,32 @@ impl Charmap<'_> {
     }
 }
 
+/// Sorts `ranges` and merges the ones that overlap or are adjacent, so that
+/// they can be searched with a binary search.
+///
+/// Empty ranges are dropped.
+pub(crate) fn normalize_unicode_range(ranges: &[RangeInclusive<u32>]) -> Vec<RangeInclusive<u32>> {
+    let mut sorted: Vec<_> = ranges
+        .iter()
+        .filter(|range| !range.is_empty())
+        .cloned()
+        .collect();
+    sorted.sort_unstable_by_key(|range| *range.start());
+    let mut merged: Vec<RangeInclusive<u32>> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        if let Some(last) = merged.last_mut()
+            && *range.start() <= last.end().saturating_add(1)
+        {
+            if range.end() > last.end() {
+                *last = *last.start()..=*range.end();
+            }
+            continue;
+        }
+        merged.push(range);
+    }
+    merged
+}
+
 #[rustfmt::skip]
 static UNICODE_TO_MAC_ROMAN: &[u16] = &[
     0x00C4, 0x00C5, 0x00C7, 0x00C9, 0x00D1, 0x00D6, 0x00DC, 0x00E1,
@@ -125,3 +177,17 @@ fn unicode_to_mac_roman(c: u32) -> u32 {
     };
     (0x80 + index) as u32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_unicode_range;
+
+    #[test]
+    fn unicode_range_is_sorted_and_merged() {
+        assert_eq!(
+            normalize_unicode_range(&[0x60..=0x7A, 0x30..=0x39, 0x3A..=0x40, 0x35..=0x38, 5..=4]),
+            [0x30..=0x40, 0x60..=0x7A]
+        );
+        assert!(normalize_unicode_range(&[]).is_empty());
+    }
+}
@@ -7,18 +7,19 @@ mod query;
 
 pub use query::{Query, QueryFamily, QueryFont, QueryStatus};
 
-use crate::font::FontInfoOverride;
+use crate::font::{FontFaceDescriptor, FontInfoOverride};
 
 use super::SourceCache;
 
 use super::{
-    Blob, FontStyle, FontWeight, FontWidth, GenericFamily, Language, Script,
+    Blob, FontStyle, FontWeight, FontWidth, Language, Script,
     backend::SystemFonts,
+    coverage::CoverageMap,
     fallback::{FallbackKey, FallbackMap},
     family::{FamilyId, FamilyInfo},
     family_name::{FamilyName, FamilyNameMap},
     font::FontInfo,
-    generic::GenericFamilyMap,
+    generic::{GenericFamilyKey, GenericFamilyMap},
     source::{SourceId, SourceInfo, SourceKind},
 };
 use crate::{AtomicCounter, CounterInt};
@@ -26,14 +27,18 @@ use alloc::{string::String, sync::Arc, vec::Vec};
 use hashbrown::HashMap;
 use read_fonts::types::NameId;
 #[cfg(feature = "std")]
-use std::path::Path;
-#[cfg(feature = "std")]
-use std::sync::{Mutex, atomic::Ordering};
+use {
+    crate::scan::{ScannedFontInfo, scan_file_info},
+    crate::scan_cache::{FileStamp, ScanCache, SharedScanCache},
+    hashbrown::HashSet,
+    std::path::{Path, PathBuf},
+    std::sync::{Mutex, atomic::Ordering},
+};
 
 type FamilyMap = HashMap<FamilyId, Option<FamilyInfo>>;
 
 /// Options for a [font collection](Collection).
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct CollectionOptions {
     /// If true, the font collection will use a secondary shared store
     /// guaranteeing that any changes to the collection will be
@@ -50,6 +55,19 @@ pub struct CollectionOptions {
     ///
     /// The default value is `true`.
     pub system_fonts: bool,
+
+    /// Path of a file caching the metadata of the font files that fontique
+    /// scans itself, both system fonts and fonts loaded with
+    /// [`Collection::load_fonts_from_paths`].
+    ///
+    /// Files that haven't changed since they were cached aren't parsed
+    /// again, which speeds up loading large font directories. The cache is
+    /// written with [`Collection::save_scan_cache`], and otherwise when the
+    /// collection and all its clones are dropped, ignoring any error.
+    ///
+    /// The default value is `None`.
+    #[cfg(feature = "std")]
+    pub scan_cache: Option<PathBuf>,
 }
 
 impl Default for CollectionOptions {
@@ -57,6 +75,8 @@ impl Default for CollectionOptions {
         Self {
             shared: false,
             system_fonts: true,
+            #[cfg(feature = "std")]
+            scan_cache: None,
         }
     }
 }
@@ -104,6 +124,38 @@ impl Collection {
         self.inner.load_fonts_from_paths(paths);
     }
 
+    /// Rescans the specified directory(s) for fonts that were added, changed
+    /// or removed since they were loaded.
+    ///
+    /// Files are compared with those loaded by earlier calls to this method or
+    /// to [`Self::load_fonts_from_paths`] under the same paths, by path,
+    /// modification time and size. The fonts of removed and changed files are
+    /// taken out of their families, and the fonts of new and changed files are
+    /// loaded.
+    ///
+    /// Returns true if any file was added, changed or removed, in which case
+    /// the [generation](Self::generation) of the collection changes.
+    #[cfg(feature = "std")]
+    pub fn rescan_paths(&mut self, paths: impl IntoIterator<Item = impl AsRef<Path>>) -> bool {
+        self.inner.rescan_paths(paths)
+    }
+
+    /// Returns the generation of the collection.
+    ///
+    /// The generation changes whenever fonts are loaded, registered or
+    /// removed, or the generic families or fallbacks are changed, including
+    /// through other clones of a shared collection. Caches of data derived
+    /// from the collection, such as resolved font families, can compare it
+    /// to know when they are stale.
+    ///
+    /// Generations are unique across collections, so a cache can't mistake
+    /// another collection for the one it was filled from. Clones of a shared
+    /// collection report the same generation once they see the same changes,
+    /// unless system fonts were loaded into one of them.
+    pub fn generation(&mut self) -> u64 {
+        self.inner.generation()
+    }
+
     /// Returns an iterator over all available family names in the collection.
     ///
     /// If `fontique` was compiled with the `"system"` feature, then it will
@@ -142,34 +194,43 @@ impl Collection {
 
     /// Returns an iterator over the family identifiers for the given
     /// generic family.
+    ///
+    /// The key may be a bare [`GenericFamily`](crate::GenericFamily) or paired with a locale, in
+    /// which case the families set for the best matching locale are returned,
+    /// falling back to those set without a locale.
     pub fn generic_families(
         &mut self,
-        family: GenericFamily,
+        key: impl Into<GenericFamilyKey>,
     ) -> impl Iterator<Item = FamilyId> + '_ + Clone {
-        self.inner.generic_families(family)
+        self.inner.generic_families(key)
     }
 
     /// Replaces the set of family identifiers associated with the given generic
-    /// family.
+    /// family, optionally only for a locale.
     pub fn set_generic_families(
         &mut self,
-        generic: GenericFamily,
+        key: impl Into<GenericFamilyKey>,
         families: impl Iterator<Item = FamilyId>,
     ) {
-        self.inner.set_generic_families(generic, families);
+        self.inner.set_generic_families(key, families);
     }
 
-    /// Appends the set of family identifiers to the given generic family.
+    /// Appends the set of family identifiers to the given generic family,
+    /// optionally only for a locale.
     pub fn append_generic_families(
         &mut self,
-        generic: GenericFamily,
+        key: impl Into<GenericFamilyKey>,
         families: impl Iterator<Item = FamilyId>,
     ) {
-        self.inner.append_generic_families(generic, families);
+        self.inner.append_generic_families(key, families);
     }
 
     /// Returns an iterator over the fallback families for the given
     /// key.
+    ///
+    /// If no fallbacks were set for the key and the system doesn't provide
+    /// any, these are the registered families covering the key's script,
+    /// best first.
     pub fn fallback_families(
         &mut self,
         key: impl Into<FallbackKey>,
@@ -177,6 +238,18 @@ impl Collection {
         self.inner.fallback_families(key)
     }
 
+    /// Returns an iterator over the registered families that map the given
+    /// codepoint to a glyph, in order of registration.
+    ///
+    /// This can be used to find a font for characters that aren't covered by
+    /// the [fallback families](Self::fallback_families) of their script.
+    pub fn codepoint_fallbacks(
+        &mut self,
+        codepoint: char,
+    ) -> impl Iterator<Item = FamilyId> + '_ + Clone {
+        self.inner.codepoint_fallbacks(codepoint)
+    }
+
     /// Replaces the set of family identifiers associated with the fallback
     /// key.
     pub fn set_fallbacks(
@@ -215,6 +288,24 @@ impl Collection {
         self.inner.register_fonts(data, info_override)
     }
 
+    /// Registers all fonts that exist in the given data as described by a
+    /// `@font-face`-like descriptor.
+    ///
+    /// Like [`Self::register_fonts`], this returns a list of pairs each
+    /// containing the family identifier and fonts added to that family.
+    ///
+    /// A family can be assembled from several subset files by registering each
+    /// of them with the same family name and attributes, and with the unicode
+    /// range it covers. Queries then yield all of the subsets, and each subset's
+    /// [character map](QueryFont::charmap) only maps its own range.
+    pub fn register_font_face(
+        &mut self,
+        data: Blob<u8>,
+        descriptor: &FontFaceDescriptor<'_>,
+    ) -> Vec<(FamilyId, Vec<FontInfo>)> {
+        self.inner.register_font_face(data, descriptor)
+    }
+
     /// Unregisters the font with the given attributes from the given family.
     ///
     /// Returns true if a font was removed from the family, false otherwise.
@@ -251,12 +342,22 @@ struct Inner {
     shared: Option<Arc<Shared>>,
     #[allow(unused)]
     shared_version: CounterInt,
+    generation: u64,
     fallback_cache: FallbackCache,
+    #[cfg(feature = "std")]
+    scan_cache: Option<SharedScanCache>,
 }
 
 impl Inner {
     /// Creates a new collection with the given options.
     pub fn new(options: CollectionOptions) -> Self {
+        #[cfg(feature = "std")]
+        let scan_cache = options.scan_cache.as_deref().map(ScanCache::open_shared);
+        #[cfg(feature = "std")]
+        let system = options
+            .system_fonts
+            .then(|| System::new(scan_cache.clone()));
+        #[cfg(not(feature = "std"))]
         let system = options.system_fonts.then(System::new);
         let shared = options.shared.then(|| Arc::new(Shared::default()));
         Self {
@@ -264,7 +365,10 @@ impl Inner {
             data: CommonData::default(),
             shared,
             shared_version: 0,
+            generation: next_generation(),
             fallback_cache: FallbackCache::default(),
+            #[cfg(feature = "std")]
+            scan_cache,
         }
     }
 
@@ -274,13 +378,22 @@ impl Inner {
             self.shared = Some(Arc::new(Shared {
                 data: Mutex::new(self.data.clone()),
                 version: AtomicCounter::new(self.shared_version),
+                generation: Mutex::new(self.generation),
             }));
         }
     }
 
     /// Load system fonts. If system fonts are already loaded then they will be reloaded.
     pub fn load_system_fonts(&mut self) {
-        self.system = Some(System::new());
+        self.generation = next_generation();
+        #[cfg(feature = "std")]
+        {
+            self.system = Some(System::new(self.scan_cache.clone()));
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.system = Some(System::new());
+        }
     }
 
     /// Returns an iterator over all available family names in the collection.
@@ -371,15 +484,16 @@ impl Inner {
     /// generic family.
     pub fn generic_families(
         &mut self,
-        family: GenericFamily,
+        key: impl Into<GenericFamilyKey>,
     ) -> impl Iterator<Item = FamilyId> + '_ + Clone {
+        let key = key.into();
         self.sync_shared();
         GenericFamilies {
-            ours: self.data.generic_families.get(family).iter().copied(),
+            ours: self.data.generic_families.get(key).iter().copied(),
             system: self
                 .system
                 .as_ref()
-                .map(|sys| sys.generic_families.get(family).iter().copied()),
+                .map(|sys| sys.generic_families.get(key).iter().copied()),
         }
     }
 
@@ -387,10 +501,12 @@ impl Inner {
     /// family.
     pub fn set_generic_families(
         &mut self,
-        generic: GenericFamily,
+        key: impl Into<GenericFamilyKey>,
         families: impl Iterator<Item = FamilyId>,
     ) {
+        let key = key.into();
         self.sync_shared();
+        self.generation = next_generation();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
             shared
@@ -398,22 +514,24 @@ impl Inner {
                 .lock()
                 .unwrap()
                 .generic_families
-                .set(generic, families);
+                .set(key, families);
             shared.bump_version();
         } else {
-            self.data.generic_families.set(generic, families);
+            self.data.generic_families.set(key, families);
         }
         #[cfg(not(feature = "std"))]
-        self.data.generic_families.set(generic, families);
+        self.data.generic_families.set(key, families);
     }
 
     /// Appends the set of family identifiers to the given generic family.
     pub fn append_generic_families(
         &mut self,
-        generic: GenericFamily,
+        key: impl Into<GenericFamilyKey>,
         families: impl Iterator<Item = FamilyId>,
     ) {
+        let key = key.into();
         self.sync_shared();
+        self.generation = next_generation();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
             shared
@@ -421,13 +539,13 @@ impl Inner {
                 .lock()
                 .unwrap()
                 .generic_families
-                .append(generic, families);
+                .append(key, families);
             shared.bump_version();
         } else {
-            self.data.generic_families.append(generic, families);
+            self.data.generic_families.append(key, families);
         }
         #[cfg(not(feature = "std"))]
-        self.data.generic_families.append(generic, families);
+        self.data.generic_families.append(key, families);
     }
 
     /// Returns an iterator over the fallback families for the given
@@ -442,26 +560,38 @@ impl Inner {
         if self.fallback_cache.script != Some(script) || self.fallback_cache.language != lang_key {
             self.sync_shared();
             self.fallback_cache.reset();
-            #[cfg(feature = "system")]
             if let Some(families) = self.data.fallbacks.get(selector) {
                 self.fallback_cache.set(script, lang_key, families);
-            } else if let Some(system) = self.system.as_ref() {
-                // Some platforms don't need mut System
-                #[allow(unused_mut)]
-                let mut system = system.fonts.lock().unwrap();
-                if let Some(family) = system.fallback(selector) {
-                    self.data.fallbacks.set(selector, core::iter::once(family));
-                    self.fallback_cache.set(script, lang_key, &[family]);
+            } else {
+                #[cfg(feature = "system")]
+                if let Some(system) = self.system.as_ref() {
+                    // Some platforms don't need mut System
+                    #[allow(unused_mut)]
+                    let mut system = system.fonts.lock().unwrap();
+                    if let Some(family) = system.fallback(selector) {
+                        self.data.fallbacks.set(selector, core::iter::once(family));
+                        self.fallback_cache.set(script, lang_key, &[family]);
+                    }
+                }
+                if self.fallback_cache.script.is_none() {
+                    let families = self.data.coverage_fallbacks(selector);
+                    self.fallback_cache.set(script, lang_key, families);
                 }
-            }
-            #[cfg(not(feature = "system"))]
-            if let Some(families) = self.data.fallbacks.get(selector) {
-                self.fallback_cache.set(script, lang_key, families);
             }
         }
         self.fallback_cache.families.iter().copied()
     }
 
+    /// Returns an iterator over the registered families that map the given
+    /// codepoint to a glyph.
+    pub fn codepoint_fallbacks(
+        &mut self,
+        codepoint: char,
+    ) -> impl Iterator<Item = FamilyId> + '_ + Clone {
+        self.sync_shared();
+        self.data.codepoint_fallbacks(codepoint).iter().copied()
+    }
+
     /// Replaces the set of family identifiers associated with the fallback
     /// key.
     pub fn set_fallbacks(
@@ -470,6 +600,7 @@ impl Inner {
         families: impl Iterator<Item = FamilyId>,
     ) -> bool {
         self.sync_shared();
+        self.generation = next_generation();
         self.fallback_cache.reset();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
@@ -490,6 +621,7 @@ impl Inner {
         families: impl Iterator<Item = FamilyId>,
     ) -> bool {
         self.sync_shared();
+        self.generation = next_generation();
         self.fallback_cache.reset();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
@@ -506,12 +638,19 @@ impl Inner {
     /// Loads all fonts that exist in the specified directory(s)
     #[cfg(feature = "std")]
     pub fn load_fonts_from_paths(&mut self, paths: impl IntoIterator<Item = impl AsRef<Path>>) {
+        self.fallback_cache.reset();
+        self.generation = next_generation();
+        let scan_cache = self.scan_cache.as_ref();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
-            shared.data.lock().unwrap().load_fonts_from_paths(paths);
+            shared
+                .data
+                .lock()
+                .unwrap()
+                .load_fonts_from_paths(paths, scan_cache);
             shared.bump_version();
         } else {
-            self.data.load_fonts_from_paths(paths);
+            self.data.load_fonts_from_paths(paths, scan_cache);
         }
         #[cfg(not(feature = "std"))]
         self.data.register_fonts(paths)
@@ -526,6 +665,8 @@ impl Inner {
         data: Blob<u8>,
         info_override: Option<FontInfoOverride<'_>>,
     ) -> Vec<(FamilyId, Vec<FontInfo>)> {
+        self.fallback_cache.reset();
+        self.generation = next_generation();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
             let result = shared
@@ -542,6 +683,31 @@ impl Inner {
         self.data.register_fonts(data, info_override)
     }
 
+    /// Registers all fonts that exist in the given data as described by the
+    /// descriptor.
+    pub fn register_font_face(
+        &mut self,
+        data: Blob<u8>,
+        descriptor: &FontFaceDescriptor<'_>,
+    ) -> Vec<(FamilyId, Vec<FontInfo>)> {
+        self.fallback_cache.reset();
+        self.generation = next_generation();
+        #[cfg(feature = "std")]
+        if let Some(shared) = &self.shared {
+            let result = shared
+                .data
+                .lock()
+                .unwrap()
+                .register_font_face(data, descriptor);
+            shared.bump_version();
+            result
+        } else {
+            self.data.register_font_face(data, descriptor)
+        }
+        #[cfg(not(feature = "std"))]
+        self.data.register_font_face(data, descriptor)
+    }
+
     /// Unregisters the font with the given attributes from the given family.
     ///
     /// Returns true if a font was removed from the family, false otherwise.
@@ -552,6 +718,8 @@ impl Inner {
         style: FontStyle,
         weight: FontWeight,
     ) -> bool {
+        self.fallback_cache.reset();
+        self.generation = next_generation();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
             let result = shared
@@ -576,6 +744,8 @@ impl Inner {
     /// [`Self::register_fonts`], and unsets all previously-set generic families
     /// and fallbacks. This will not remove any system fonts.
     pub fn clear(&mut self) {
+        self.fallback_cache.reset();
+        self.generation = next_generation();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
             shared.data.lock().unwrap().clear();
@@ -586,17 +756,64 @@ impl Inner {
         self.data.clear();
     }
 
+    /// Writes the [scan cache](CollectionOptions::scan_cache) to its file, if
+    /// any file was scanned since it was last written.
+    ///
+    /// The cache is also written when the collection and all its clones are
+    /// dropped, but errors are ignored then, and it isn't written at all if
+    /// the process exits before. Does nothing if there is no scan cache.
+    #[cfg(feature = "std")]
+    pub fn save_scan_cache(&self) -> std::io::Result<()> {
+        match &self.scan_cache {
+            Some(scan_cache) => scan_cache.lock().unwrap().flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Rescans the specified directory(s), returning whether any file
+    /// changed.
+    #[cfg(feature = "std")]
+    pub fn rescan_paths(&mut self, paths: impl IntoIterator<Item = impl AsRef<Path>>) -> bool {
+        self.sync_shared();
+        let scan_cache = self.scan_cache.as_ref();
+        let changed = if let Some(shared) = &self.shared {
+            let changed = shared.data.lock().unwrap().rescan_paths(paths, scan_cache);
+            if changed {
+                shared.bump_version();
+            }
+            changed
+        } else {
+            self.data.rescan_paths(paths, scan_cache)
+        };
+        if changed {
+            self.fallback_cache.reset();
+            self.generation = next_generation();
+        }
+        changed
+    }
+
+    pub fn generation(&mut self) -> u64 {
+        self.sync_shared();
+        self.generation
+    }
+
     fn sync_shared(&mut self) {
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
-            let version = shared.version.load(Ordering::Acquire);
-            if self.shared_version != version {
+            if self.shared_version != shared.version.load(Ordering::Acquire) {
+                // Read the version and generation together, so clones synced
+                // to the same version report the same generation.
+                let (version, generation) = {
+                    let generation = shared.generation.lock().unwrap();
+                    (shared.version.load(Ordering::Acquire), *generation)
+                };
                 // This is an ugly deep copy, but the assumption is that
                 // modifications to font collections are fairly rare.
                 // If this becomes a problem, do more fine grained tracking
                 // of changes.
                 self.data = shared.data.lock().unwrap().clone();
                 self.shared_version = version;
+                self.generation = generation;
                 self.fallback_cache.reset();
             }
         }
@@ -675,7 +892,10 @@ struct System {
 }
 
 impl System {
-    fn new() -> Self {
+    fn new(#[cfg(feature = "std")] scan_cache: Option<SharedScanCache>) -> Self {
+        #[cfg(feature = "std")]
+        let fonts = SystemFonts::new(scan_cache);
+        #[cfg(not(feature = "std"))]
         let fonts = SystemFonts::new();
         let family_names = fonts.name_map.clone();
         let generic_families = fonts.generic_families.clone();
@@ -697,38 +917,225 @@ struct CommonData {
     families: FamilyMap,
     generic_families: GenericFamilyMap,
     fallbacks: FallbackMap,
+    coverage: CoverageMap,
+    /// Files loaded from paths, to find the fonts to replace when rescanning.
+    #[cfg(feature = "std")]
+    loaded_files: HashMap<Arc<Path>, LoadedFile>,
+}
+
+/// A font file loaded from a path.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+struct LoadedFile {
+    /// The modification time and size of the file when it was loaded.
+    stamp: Option<FileStamp>,
+    /// The source of the fonts of the file.
+    source: SourceId,
+    /// The families the fonts of the file were added to.
+    families: Vec<FamilyId>,
 }
 
 impl CommonData {
+    /// Returns the registered families covering the script of `key`.
+    fn coverage_fallbacks(&mut self, key: FallbackKey) -> &[FamilyId] {
+        let Self {
+            family_names,
+            families,
+            coverage,
+            ..
+        } = self;
+        coverage.script_fallbacks(registered_families(family_names, families), key)
+    }
+
+    /// Returns the registered families that map `codepoint` to a glyph.
+    fn codepoint_fallbacks(&mut self, codepoint: char) -> &[FamilyId] {
+        let Self {
+            family_names,
+            families,
+            coverage,
+            ..
+        } = self;
+        coverage.codepoint_fallbacks(
+            registered_families(family_names, families),
+            codepoint.into(),
+        )
+    }
+
     #[cfg(feature = "std")]
-    fn load_fonts_from_paths(&mut self, paths: impl IntoIterator<Item = impl AsRef<Path>>) {
+    fn load_fonts_from_paths(
+        &mut self,
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        scan_cache: Option<&SharedScanCache>,
+    ) {
+        let mut files = Vec::new();
+        crate::scan::visit_paths(paths, 16, |path| files.push(PathBuf::from(path)));
+        self.load_files(&files, scan_cache);
+    }
+
+    /// Reloads the files under `paths` that were added, changed or removed
+    /// since they were loaded, returning whether there were any.
+    #[cfg(feature = "std")]
+    fn rescan_paths(
+        &mut self,
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        scan_cache: Option<&SharedScanCache>,
+    ) -> bool {
+        let roots: Vec<PathBuf> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect();
+        let mut seen = HashSet::new();
+        let mut changed = Vec::new();
+        crate::scan::visit_paths(&roots, 16, |path| {
+            let is_fresh = self
+                .loaded_files
+                .get(path)
+                .is_some_and(|file| file.stamp.is_some() && file.stamp == FileStamp::of(path));
+            if !is_fresh {
+                changed.push(PathBuf::from(path));
+            }
+            seen.insert(PathBuf::from(path));
+        });
+        let removed: Vec<Arc<Path>> = self
+            .loaded_files
+            .keys()
+            .filter(|path| roots.iter().any(|root| path.starts_with(root)))
+            .filter(|path| !seen.contains(&***path))
+            .cloned()
+            .collect();
+        if changed.is_empty() && removed.is_empty() {
+            return false;
+        }
+        for path in removed
+            .iter()
+            .map(|path| &**path)
+            .chain(changed.iter().map(PathBuf::as_path))
+        {
+            self.unload_file(path);
+        }
+        self.load_files(&changed, scan_cache);
+        true
+    }
+
+    /// Loads the fonts of each file, recording the file so it can be
+    /// rescanned.
+    #[cfg(feature = "std")]
+    fn load_files(&mut self, files: &[PathBuf], scan_cache: Option<&SharedScanCache>) {
+        let mut scan_cache = scan_cache.map(|scan_cache| scan_cache.lock().unwrap());
         let mut families: HashMap<FamilyId, (FamilyName, Vec<FontInfo>)> = HashMap::default();
-        let mut scratch_family_name = String::default();
-        crate::scan::scan_paths(paths, 16, |scanned_font| {
+        for path in files {
+            let path: Arc<Path> = Arc::from(path.as_path());
             let source = SourceInfo {
                 id: SourceId::new(),
-                kind: SourceKind::Path(Arc::from(scanned_font.path.unwrap())),
+                kind: SourceKind::Path(path.clone()),
             };
-
-            let font_data = scanned_font.font.data().as_bytes();
-            self.register_font_impl(
-                font_data,
-                source,
-                None,
-                &mut scratch_family_name,
-                &mut families,
+            // Stamp the file before scanning it, so a change made while it's
+            // scanned is picked up by the next rescan.
+            let stamp = FileStamp::of(&path);
+            let file_families = match scan_cache.as_deref_mut() {
+                Some(scan_cache) => scan_cache
+                    .fonts(&path)
+                    .map(|fonts| self.add_scanned_fonts(&source, fonts, &mut families)),
+                None => scan_file_info(&path)
+                    .map(|fonts| self.add_scanned_fonts(&source, &fonts, &mut families)),
+            };
+            self.loaded_files.insert(
+                path,
+                LoadedFile {
+                    stamp,
+                    source: source.id,
+                    families: file_families.unwrap_or_default(),
+                },
             );
-        });
+        }
+        self.add_families(&families);
+    }
+
+    /// Adds the scanned fonts of a file to `families`, returning the
+    /// identifiers of the families they belong to.
+    #[cfg(feature = "std")]
+    fn add_scanned_fonts(
+        &mut self,
+        source: &SourceInfo,
+        fonts: &[ScannedFontInfo],
+        families: &mut HashMap<FamilyId, (FamilyName, Vec<FontInfo>)>,
+    ) -> Vec<FamilyId> {
+        let mut ids = Vec::new();
+        for scanned_font in fonts {
+            let Some(family_name) = scanned_font.family_names.first() else {
+                continue;
+            };
+            let name = self.family_names.get_or_insert(family_name);
+            if !ids.contains(&name.id()) {
+                ids.push(name.id());
+            }
+            families
+                .entry(name.id())
+                .or_insert_with(|| (name, Vec::default()))
+                .1
+                .push(scanned_font.font_info(source.clone()));
+        }
+        ids
+    }
+
+    /// Removes the fonts of a file loaded from `path` from their families.
+    #[cfg(feature = "std")]
+    fn unload_file(&mut self, path: &Path) {
+        let Some(file) = self.loaded_files.remove(path) else {
+            return;
+        };
+        self.coverage.clear();
+        for id in file.families {
+            let Some(name) = self.family_names.get_by_id(id) else {
+                continue;
+            };
+            if let Some(Some(family)) = self.families.get_mut(&id) {
+                let fonts = family
+                    .fonts()
+                    .iter()
+                    .filter(|font| font.source().id() != file.source)
+                    .cloned();
+                *family = FamilyInfo::new(name.clone(), fonts);
+            }
+        }
     }
 
     fn register_fonts(
         &mut self,
         data: Blob<u8>,
         info_override: Option<FontInfoOverride<'_>>,
+    ) -> Vec<(FamilyId, Vec<FontInfo>)> {
+        self.register_blob(data, info_override.and_then(|o| o.family_name), |font| {
+            if let Some(info_override) = info_override.as_ref() {
+                font.apply_override(info_override);
+            }
+        })
+    }
+
+    fn register_font_face(
+        &mut self,
+        data: Blob<u8>,
+        descriptor: &FontFaceDescriptor<'_>,
+    ) -> Vec<(FamilyId, Vec<FontInfo>)> {
+        self.register_blob(data, descriptor.family_name, |font| {
+            font.apply_descriptor(descriptor);
+        })
+    }
+
+    fn register_blob(
+        &mut self,
+        data: Blob<u8>,
+        family_name: Option<&str>,
+        customize: impl FnMut(&mut FontInfo),
     ) -> Vec<(FamilyId, Vec<FontInfo>)> {
         let mut families: HashMap<FamilyId, (FamilyName, Vec<FontInfo>)> = HashMap::default();
         let mut scratch_family_name = String::default();
 
+        // WOFF and WOFF2 fonts are stored decoded so they can be used directly.
+        let data = match crate::woff::decompress(data.as_ref()) {
+            Some(sfnt) => Blob::new(Arc::new(sfnt)),
+            None => data,
+        };
         let source = SourceInfo {
             id: SourceId::new(),
             kind: SourceKind::Memory(data.clone()),
@@ -737,7 +1144,8 @@ impl CommonData {
         self.register_font_impl(
             data.as_ref(),
             source,
-            info_override,
+            family_name,
+            customize,
             &mut scratch_family_name,
             &mut families,
         );
@@ -748,33 +1156,35 @@ impl CommonData {
             .collect()
     }
 
+    /// Registers the fonts in `font_data`, calling `customize` on each to apply
+    /// overrides before it's added to its family.
     fn register_font_impl(
         &mut self,
         font_data: &[u8],
         source: SourceInfo,
-        info_override: Option<FontInfoOverride<'_>>,
+        override_family_name: Option<&str>,
+        mut customize: impl FnMut(&mut FontInfo),
         scratch_family_name: &mut String,
         families: &mut HashMap<FamilyId, (FamilyName, Vec<FontInfo>)>,
     ) {
         super::scan::scan_memory(font_data, |scanned_font| {
             scratch_family_name.clear();
 
-            let family_name =
-                if let Some(override_family_name) = info_override.and_then(|o| o.family_name) {
-                    override_family_name
-                } else {
-                    let family_chars = scanned_font
-                        .english_or_first_name(NameId::TYPOGRAPHIC_FAMILY_NAME)
-                        .or_else(|| scanned_font.english_or_first_name(NameId::FAMILY_NAME))
-                        .map(|name| name.chars());
-                    let Some(family_chars) = family_chars else {
-                        return;
-                    };
-                    scratch_family_name.extend(family_chars);
-
-                    #[allow(clippy::needless_borrow)] // false positive
-                    &scratch_family_name
+            let family_name = if let Some(override_family_name) = override_family_name {
+                override_family_name
+            } else {
+                let family_chars = scanned_font
+                    .english_or_first_name(NameId::TYPOGRAPHIC_FAMILY_NAME)
+                    .or_else(|| scanned_font.english_or_first_name(NameId::FAMILY_NAME))
+                    .map(|name| name.chars());
+                let Some(family_chars) = family_chars else {
+                    return;
                 };
+                scratch_family_name.extend(family_chars);
+
+                #[allow(clippy::needless_borrow)] // false positive
+                &scratch_family_name
+            };
 
             if family_name.is_empty() {
                 return;
@@ -786,9 +1196,7 @@ impl CommonData {
                 return;
             };
 
-            if let Some(info_override) = info_override.as_ref() {
-                font.apply_override(info_override);
-            }
+            customize(&mut font);
 
             let name = self.family_names.get_or_insert(family_name);
             families
@@ -797,7 +1205,14 @@ impl CommonData {
                 .1
                 .push(font);
         });
-        for (id, (name, fonts)) in families.iter() {
+        self.add_families(&families);
+    }
+
+    /// Adds fonts to their families, creating the families that don't exist
+    /// yet.
+    fn add_families(&mut self, families: &HashMap<FamilyId, (FamilyName, Vec<FontInfo>)>) {
+        self.coverage.clear();
+        for (id, (name, fonts)) in families {
             if let Some(Some(family)) = self.families.get_mut(id) {
                 let new_fonts = family.fonts().iter().chain(fonts).cloned();
                 *family = FamilyInfo::new(name.clone(), new_fonts);
@@ -817,6 +1232,7 @@ impl CommonData {
     ) -> Option<()> {
         let family_name = self.family_names.get_by_id(family)?;
         let family = self.families.get_mut(&family)?.as_mut()?;
+        self.coverage.clear();
 
         let new_fonts = family
             .fonts()
@@ -833,6 +1249,27 @@ impl CommonData {
     }
 }
 
+/// Returns the families registered with the collection, leaving out the system
+/// families that were looked up.
+fn registered_families<'a>(
+    family_names: &'a FamilyNameMap,
+    families: &'a FamilyMap,
+) -> impl Iterator<Item = &'a FamilyInfo> {
+    families
+        .iter()
+        .filter(|(id, _)| family_names.get_by_id(**id).is_some())
+        .filter_map(|(_, family)| family.as_ref())
+}
+
+/// Returns a collection generation that was never returned before.
+fn next_generation() -> u64 {
+    static GENERATION_COUNTER: AtomicCounter = AtomicCounter::new(1);
+    #[allow(clippy::useless_conversion, reason = "Not useless on 32-bit platforms")]
+    GENERATION_COUNTER
+        .fetch_add(1, core::sync::atomic::Ordering::Relaxed)
+        .into()
+}
+
 /// Synchronized shared collection data.
 #[derive(Default)]
 struct Shared {
@@ -841,11 +1278,17 @@ struct Shared {
     #[cfg(feature = "std")]
     #[allow(unused)]
     data: Mutex<CommonData>,
+    /// The generation of the shared data, replaced whenever the version is
+    /// bumped.
+    #[cfg(feature = "std")]
+    generation: Mutex<u64>,
 }
 
 impl Shared {
     #[cfg(feature = "std")]
     fn bump_version(&self) {
+        let mut generation = self.generation.lock().unwrap();
+        *generation = next_generation();
         self.version.fetch_add(1, Ordering::Release);
     }
 }
@@ -866,6 +1309,7 @@ fn make_shared_matches_local() {
     let mut collection = Collection::new(CollectionOptions {
         shared: false,
         system_fonts: false,
+        ..CollectionOptions::default()
     });
 
     let font_dirs: Vec<std::path::PathBuf> = [
@@ -897,3 +1341,139 @@ fn make_shared_matches_local() {
 
     assert_eq!(names_before.len(), names_after.len());
 }
+
+#[test]
+#[cfg(feature = "std")]
+fn rescan_paths_reloads_changed_files() {
+    use crate::{Collection, CollectionOptions};
+
+    const ROBOTO: &[u8] =
+        include_bytes!("../../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+    const ARIMO: &[u8] =
+        include_bytes!("../../../parley_dev/assets/fonts/arimo_fonts/Arimo-VariableFont_wght.ttf");
+
+    let dir = std::env::temp_dir().join(alloc::format!("fontique-rescan-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("Roboto-Regular.ttf"), ROBOTO).unwrap();
+
+    let mut collection = Collection::new(CollectionOptions {
+        shared: true,
+        system_fonts: false,
+        ..CollectionOptions::default()
+    });
+    let mut clone = collection.clone();
+    collection.load_fonts_from_paths([&dir]);
+    let roboto = collection.family_id("Roboto").unwrap();
+    let generation = clone.generation();
+    assert!(!collection.rescan_paths([&dir]));
+    assert_eq!(clone.generation(), generation);
+
+    // Other clones of a shared collection see the new generation.
+    std::fs::write(dir.join("Arimo.ttf"), ARIMO).unwrap();
+    assert!(collection.rescan_paths([&dir]));
+    assert_ne!(clone.generation(), generation);
+    assert_eq!(clone.generation(), collection.generation());
+    assert!(clone.family_by_name("Arimo").is_some());
+
+    std::fs::remove_file(dir.join("Roboto-Regular.ttf")).unwrap();
+    assert!(collection.rescan_paths([&dir]));
+    assert!(collection.family(roboto).unwrap().fonts().is_empty());
+    assert_eq!(collection.family_by_name("Arimo").unwrap().fonts().len(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn shared_clones_report_equal_generations() {
+    use crate::{Blob, Collection, CollectionOptions};
+
+    const ROBOTO: &[u8] =
+        include_bytes!("../../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+
+    let mut collection = Collection::new(CollectionOptions {
+        shared: true,
+        system_fonts: false,
+        ..CollectionOptions::default()
+    });
+    let mut first = collection.clone();
+    let mut second = collection.clone();
+    let generation = first.generation();
+    collection.register_fonts(Blob::new(Arc::new(ROBOTO)), None);
+
+    assert_ne!(first.generation(), generation);
+    assert_eq!(first.generation(), second.generation());
+    assert_eq!(first.generation(), collection.generation());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn save_scan_cache_writes_file() {
+    use crate::{Collection, CollectionOptions};
+
+    const ROBOTO: &[u8] =
+        include_bytes!("../../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+
+    let dir =
+        std::env::temp_dir().join(alloc::format!("fontique-save-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let fonts_dir = dir.join("fonts");
+    std::fs::create_dir_all(&fonts_dir).unwrap();
+    std::fs::write(fonts_dir.join("Roboto-Regular.ttf"), ROBOTO).unwrap();
+    let cache_path = dir.join("fonts.cache");
+
+    let mut collection = Collection::new(CollectionOptions {
+        shared: false,
+        system_fonts: false,
+        scan_cache: Some(cache_path.clone()),
+    });
+    collection.save_scan_cache().unwrap();
+    assert!(!cache_path.exists(), "nothing was scanned yet");
+
+    collection.load_fonts_from_paths([&fonts_dir]);
+    collection.save_scan_cache().unwrap();
+    assert!(
+        cache_path.exists(),
+        "the cache should be written before the collection is dropped"
+    );
+
+    // Saving fails visibly when the cache can't be written.
+    std::fs::remove_file(&cache_path).unwrap();
+    std::fs::create_dir(&cache_path).unwrap();
+    std::fs::write(fonts_dir.join("Roboto-Copy.ttf"), ROBOTO).unwrap();
+    collection.load_fonts_from_paths([&fonts_dir]);
+    assert!(collection.save_scan_cache().is_err());
+
+    drop(collection);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn codepoint_fallback_reports_new_families() {
+    use crate::{Blob, Collection, CollectionOptions, SourceCache};
+
+    const ROBOTO: &[u8] =
+        include_bytes!("../../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+    const KUFI: &[u8] =
+        include_bytes!("../../../parley_dev/assets/fonts/noto_fonts/NotoKufiArabic-Regular.otf");
+
+    let mut collection = Collection::new(CollectionOptions {
+        shared: false,
+        system_fonts: false,
+        ..CollectionOptions::default()
+    });
+    collection.register_fonts(Blob::new(Arc::new(ROBOTO)), None);
+    collection.register_fonts(Blob::new(Arc::new(KUFI)), None);
+    let mut source_cache = SourceCache::default();
+    let mut query = collection.query(&mut source_cache);
+
+    query.set_families(["Roboto"]);
+    assert!(query.set_codepoint_fallback(Some('ب')));
+    // No registered family maps a private use character.
+    assert!(!query.set_codepoint_fallback(Some('\u{E000}')));
+
+    // The only family covering the character is already queried.
+    query.set_families(["Roboto", "Noto Kufi Arabic"]);
+    assert!(!query.set_codepoint_fallback(Some('ب')));
+}
@@ -3,16 +3,20 @@
 
 //! Query support.
 
-use crate::{Charmap, CharmapIndex};
+use crate::{Charmap, CharmapIndex, RenderHints};
 
 use super::super::{Collection, SourceCache, matching::match_fonts};
 
-use alloc::vec::Vec;
-use parlance::Script;
+use alloc::{sync::Arc, vec::Vec};
+use core::ops::RangeInclusive;
+use parlance::{FontFeature, Script};
 use smallvec::SmallVec;
 
 use super::{
-    super::{Attributes, Blob, FallbackKey, FamilyId, FamilyInfo, GenericFamily, Synthesis},
+    super::{
+        Attributes, Blob, FallbackKey, FamilyId, FamilyInfo, GenericFamily, GenericFamilyKey,
+        Language, Synthesis,
+    },
     Inner,
 };
 
@@ -20,12 +24,14 @@ use super::{
 pub(super) struct QueryState {
     families: Vec<CachedFamily>,
     fallback_families: Vec<CachedFamily>,
+    codepoint_families: Vec<CachedFamily>,
 }
 
 impl QueryState {
     fn clear(&mut self) {
         self.families.clear();
         self.fallback_families.clear();
+        self.codepoint_families.clear();
     }
 }
 
@@ -38,6 +44,8 @@ pub struct Query<'a> {
     source_cache: &'a mut SourceCache,
     attributes: Attributes,
     fallbacks: Option<FallbackKey>,
+    codepoint_fallback: Option<char>,
+    locale: Option<Language>,
 }
 
 impl<'a> Query<'a> {
@@ -49,15 +57,29 @@ impl<'a> Query<'a> {
             source_cache,
             attributes: Attributes::default(),
             fallbacks: None,
+            codepoint_fallback: None,
+            locale: None,
         }
     }
 
+    /// Sets the locale used to resolve generic families.
+    ///
+    /// This applies to the families given to subsequent calls to
+    /// [`set_families`](Self::set_families).
+    pub fn set_locale(&mut self, locale: Option<&Language>) {
+        self.locale = locale.copied();
+    }
+
     /// Sets the ordered sequence of families to match against.
+    ///
+    /// Generic families are resolved for the locale given to
+    /// [`set_locale`](Self::set_locale).
     pub fn set_families<'f, I>(&mut self, families: I)
     where
         I: IntoIterator,
         I::Item: Into<QueryFamily<'f>>,
     {
+        self.set_codepoint_fallback(None);
         self.state.families.clear();
         for family in families {
             let family = family.into();
@@ -71,7 +93,8 @@ impl<'a> Query<'a> {
                     self.state.families.push(CachedFamily::new(id));
                 }
                 QueryFamily::Generic(generic) => {
-                    for id in self.collection.generic_families(generic) {
+                    let key = GenericFamilyKey::new(generic, self.locale.as_ref());
+                    for id in self.collection.generic_families(key) {
                         self.state.families.push(CachedFamily::new(id));
                     }
                 }
@@ -79,6 +102,16 @@ impl<'a> Query<'a> {
         }
     }
 
+    /// Returns the families of a generic family, resolved for the locale given to
+    /// [`set_locale`](Self::set_locale).
+    pub fn generic_families(
+        &mut self,
+        family: GenericFamily,
+    ) -> impl Iterator<Item = FamilyId> + '_ + Clone {
+        self.collection
+            .generic_families(GenericFamilyKey::new(family, self.locale.as_ref()))
+    }
+
     /// Sets the primary attributes to match against.
     pub fn set_attributes(&mut self, attributes: Attributes) {
         if self.attributes != attributes {
@@ -88,6 +121,9 @@ impl<'a> Query<'a> {
             for family in &mut self.state.fallback_families {
                 family.clear_fonts();
             }
+            for family in &mut self.state.codepoint_families {
+                family.clear_fonts();
+            }
             self.attributes = attributes;
         }
     }
@@ -96,6 +132,7 @@ impl<'a> Query<'a> {
     pub fn set_fallbacks(&mut self, key: impl Into<FallbackKey>) {
         let key = key.into();
         if self.fallbacks != Some(key) {
+            self.set_codepoint_fallback(None);
             self.state.fallback_families.clear();
             self.state.fallback_families.extend(
                 self.collection
@@ -114,6 +151,39 @@ impl<'a> Query<'a> {
         }
     }
 
+    /// Sets a codepoint to find fonts for after the fallback families.
+    ///
+    /// The registered families that map the codepoint and aren't already
+    /// queried are matched last, so characters outside of the coverage of
+    /// the fallback families for their script still find a font.
+    ///
+    /// This is reset when the families or fallbacks of the query change.
+    ///
+    /// Returns true if any families are matched for the codepoint, so the
+    /// query may find fonts it didn't find before.
+    ///
+    /// See [`Collection::codepoint_fallbacks`].
+    pub fn set_codepoint_fallback(&mut self, codepoint: Option<char>) -> bool {
+        if self.codepoint_fallback != codepoint {
+            self.state.codepoint_families.clear();
+            if let Some(codepoint) = codepoint {
+                for id in self.collection.codepoint_fallbacks(codepoint) {
+                    let is_queried = self
+                        .state
+                        .families
+                        .iter()
+                        .chain(&self.state.fallback_families)
+                        .any(|family| family.id == id);
+                    if !is_queried {
+                        self.state.codepoint_families.push(CachedFamily::new(id));
+                    }
+                }
+            }
+            self.codepoint_fallback = codepoint;
+        }
+        !self.state.codepoint_families.is_empty()
+    }
+
     /// Invokes the given callback with all fonts that match the current
     /// settings.
     ///
@@ -125,6 +195,7 @@ impl<'a> Query<'a> {
             .families
             .iter_mut()
             .chain(self.state.fallback_families.iter_mut())
+            .chain(self.state.codepoint_families.iter_mut())
         {
             match &mut family.family {
                 Entry::Error => continue,
@@ -234,12 +305,48 @@ pub struct QueryFont {
     pub synthesis: Synthesis,
     /// Data used for constructing a character map for this font.
     pub charmap_index: CharmapIndex,
+    /// Ranges of codepoints the font may be used for, if it was registered
+    /// with a unicode range.
+    ///
+    /// See [`FontFaceDescriptor::unicode_range`](crate::FontFaceDescriptor::unicode_range).
+    pub unicode_range: Option<Arc<[RangeInclusive<u32>]>>,
+    /// Font features to apply by default when shaping with this font.
+    ///
+    /// See [`FontFaceDescriptor::features`](crate::FontFaceDescriptor::features).
+    pub features: Option<Arc<[FontFeature]>>,
+    /// Whether the font has an `opsz` axis.
+    ///
+    /// See [`FontInfo::has_optical_size_axis`](crate::FontInfo::has_optical_size_axis).
+    pub has_optical_size_axis: bool,
+    /// Rendering settings the system configures for the font.
+    ///
+    /// See [`FontInfo::render_hints`](crate::FontInfo::render_hints).
+    pub render_hints: RenderHints,
 }
 
 impl QueryFont {
+    fn new(family: &FamilyInfo, index: usize, blob: Blob<u8>, attributes: Attributes) -> Self {
+        let font_info = &family.fonts()[index];
+        Self {
+            family: (family.id(), index),
+            blob,
+            index: font_info.index(),
+            synthesis: font_info.synthesis(attributes.width, attributes.style, attributes.weight),
+            charmap_index: font_info.charmap_index(),
+            unicode_range: font_info.unicode_range.clone(),
+            features: font_info.features.clone(),
+            has_optical_size_axis: font_info.has_optical_size_axis(),
+            render_hints: font_info.render_hints(),
+        }
+    }
+
     /// Attempts to construct a [Charmap] for this font.
+    ///
+    /// Codepoints outside of the font's [unicode range](Self::unicode_range)
+    /// are not mapped.
     pub fn charmap(&self) -> Option<Charmap<'_>> {
-        self.charmap_index.charmap(self.blob.as_ref())
+        let charmap = self.charmap_index.charmap(self.blob.as_ref())?;
+        Some(charmap.with_unicode_range(self.unicode_range.as_deref().unwrap_or_default()))
     }
 }
 
@@ -270,17 +377,7 @@ fn load_bucket<'a>(
                 let Some(blob) = font_info.load(Some(source_cache)) else {
                     continue;
                 };
-                fonts.push(QueryFont {
-                    family: (family.id(), index),
-                    blob: blob.clone(),
-                    index: font_info.index(),
-                    synthesis: font_info.synthesis(
-                        attributes.width,
-                        attributes.style,
-                        attributes.weight,
-                    ),
-                    charmap_index: font_info.charmap_index(),
-                });
+                fonts.push(QueryFont::new(family, index, blob, attributes));
             }
             if fonts.is_empty() {
                 return &[];
@@ -310,16 +407,7 @@ fn load_font<'a>(
             let family_index = family.default_font_index();
             let font_info = family.fonts().get(family_index)?;
             let blob = font_info.load(Some(source_cache))?;
-            let blob_index = font_info.index();
-            let synthesis =
-                font_info.synthesis(attributes.width, attributes.style, attributes.weight);
-            *status = Entry::Ok(QueryFont {
-                family: (family.id(), family_index),
-                blob: blob.clone(),
-                index: blob_index,
-                synthesis,
-                charmap_index: font_info.charmap_index(),
-            });
+            *status = Entry::Ok(QueryFont::new(family, family_index, blob, attributes));
             if let Entry::Ok(font) = status {
                 Some(font)
             } else {
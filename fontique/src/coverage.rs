@@ -0,0 +1,296 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Fallback based on the coverage of registered fonts.
+
+use super::{
+    Blob, CharmapIndex, FallbackKey, Language, ScriptExt,
+    family::{FamilyId, FamilyInfo},
+};
+#[cfg(feature = "std")]
+use super::{Charmap, Script};
+use alloc::{sync::Arc, vec::Vec};
+use core::{cmp::Reverse, ops::RangeInclusive};
+use hashbrown::HashMap;
+use smallvec::SmallVec;
+
+type FamilyList = SmallVec<[FamilyId; 4]>;
+
+/// Fallback families chosen by the scripts and codepoints the registered
+/// families cover.
+///
+/// This is used when neither the application nor the system backend provide
+/// fallback families.
+#[derive(Clone, Default)]
+pub(crate) struct CoverageMap {
+    /// The default font of each registered family, built on first use.
+    families: Option<Arc<[CoveredFamily]>>,
+    scripts: HashMap<FallbackKey, FamilyList>,
+    codepoints: HashMap<u32, FamilyList>,
+}
+
+#[derive(Clone)]
+struct CoveredFamily {
+    id: FamilyId,
+    name: Arc<str>,
+    data: Blob<u8>,
+    charmap_index: CharmapIndex,
+    unicode_range: Option<Arc<[RangeInclusive<u32>]>>,
+}
+
+impl CoveredFamily {
+    fn new(family: &FamilyInfo) -> Option<Self> {
+        let font = family.default_font()?;
+        Some(Self {
+            id: family.id(),
+            name: family.name().into(),
+            data: font.load(None)?,
+            charmap_index: font.charmap_index(),
+            unicode_range: font.unicode_range.clone(),
+        })
+    }
+
+    /// Returns the number of the given codepoints the family maps to a glyph.
+    fn coverage(&self, codepoints: impl Iterator<Item = u32>) -> usize {
+        let Some(charmap) = self.charmap_index.charmap(self.data.as_ref()) else {
+            return 0;
+        };
+        let charmap = charmap.with_unicode_range(self.unicode_range.as_deref().unwrap_or_default());
+        codepoints
+            .filter(|ch| charmap.map(*ch).is_some_and(|glyph_id| glyph_id != 0))
+            .count()
+    }
+}
+
+/// The number of sample characters of each script that a font maps to a
+/// glyph, for the scripts it maps any of.
+///
+/// This is computed when a font file is scanned and kept in the scan cache,
+/// so fallback can rank system fonts without loading them.
+#[cfg(feature = "std")]
+#[derive(Clone, Default, PartialEq, Debug)]
+pub(crate) struct ScriptCoverage(Vec<(Script, u8)>);
+
+#[cfg(feature = "std")]
+impl ScriptCoverage {
+    /// Counts the sample characters of each script that `charmap` maps.
+    pub(crate) fn new(charmap: &Charmap<'_>) -> Self {
+        Self(
+            Script::all_samples()
+                .iter()
+                .filter_map(|(script, sample)| {
+                    let count = sample
+                        .chars()
+                        .filter(|ch| charmap.map(*ch).is_some_and(|glyph_id| glyph_id != 0))
+                        .count();
+                    let count = u8::try_from(count).unwrap_or(u8::MAX);
+                    (count != 0).then_some((*script, count))
+                })
+                .collect(),
+        )
+    }
+
+    /// Creates the coverage from counts sorted by script.
+    pub(crate) fn from_counts(counts: Vec<(Script, u8)>) -> Self {
+        Self(counts)
+    }
+
+    /// Returns the number of sample characters of each script, sorted by
+    /// script.
+    pub(crate) fn counts(&self) -> &[(Script, u8)] {
+        &self.0
+    }
+
+    /// Returns the number of sample characters of `script` that the font
+    /// maps, if that covers the script.
+    #[cfg_attr(
+        not(all(
+            feature = "fonts-conf",
+            any(target_os = "linux", target_os = "freebsd")
+        )),
+        allow(unused)
+    )]
+    pub(crate) fn covered(&self, script: Script) -> Option<usize> {
+        let sample = script.sample()?;
+        let ix = self.0.binary_search_by_key(&script, |entry| entry.0).ok()?;
+        let coverage = usize::from(self.0[ix].1);
+        covers_sample(coverage, sample.chars().count()).then_some(coverage)
+    }
+}
+
+impl CoverageMap {
+    /// Forgets the coverage of the registered families, which must be called
+    /// whenever they change.
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Returns the registered families covering the script of `key`, best
+    /// first.
+    ///
+    /// A family covers a script when it maps at least half of the script's
+    /// sample characters. Families are ranked by how many sample characters
+    /// they map, then by whether their name marks them as designed for the
+    /// locale of `key`.
+    pub(crate) fn script_fallbacks<'a>(
+        &mut self,
+        families: impl Iterator<Item = &'a FamilyInfo>,
+        key: FallbackKey,
+    ) -> &[FamilyId] {
+        let covered = self
+            .families
+            .get_or_insert_with(|| covered_families(families));
+        self.scripts.entry(key).or_insert_with(|| {
+            let Some(sample) = key.script().sample() else {
+                return FamilyList::new();
+            };
+            let len = sample.chars().count();
+            let mut ranked: Vec<_> = covered
+                .iter()
+                .map(|family| (family, family.coverage(sample.chars().map(u32::from))))
+                .filter(|(_, coverage)| covers_sample(*coverage, len))
+                .collect();
+            ranked.sort_by_key(|(family, coverage)| {
+                (
+                    Reverse(*coverage),
+                    !name_matches_locale(&family.name, key.locale()),
+                )
+            });
+            ranked.into_iter().map(|(family, _)| family.id).collect()
+        })
+    }
+
+    /// Returns the registered families that map `codepoint` to a glyph, in
+    /// order of registration.
+    pub(crate) fn codepoint_fallbacks<'a>(
+        &mut self,
+        families: impl Iterator<Item = &'a FamilyInfo>,
+        codepoint: u32,
+    ) -> &[FamilyId] {
+        let covered = self
+            .families
+            .get_or_insert_with(|| covered_families(families));
+        self.codepoints.entry(codepoint).or_insert_with(|| {
+            covered
+                .iter()
+                .filter(|family| family.coverage(core::iter::once(codepoint)) != 0)
+                .map(|family| family.id)
+                .collect()
+        })
+    }
+}
+
+/// Returns `true` if a font mapping `coverage` of the `len` sample
+/// characters of a script covers it, which takes at least half of them.
+fn covers_sample(coverage: usize, len: usize) -> bool {
+    coverage > 0 && coverage * 2 >= len
+}
+
+/// Collects the default fonts of `families` in order of registration.
+fn covered_families<'a>(families: impl Iterator<Item = &'a FamilyInfo>) -> Arc<[CoveredFamily]> {
+    let mut covered: Vec<_> = families.filter_map(CoveredFamily::new).collect();
+    // Family identifiers are allocated in order, so this makes ties resolve
+    // to the family registered first.
+    covered.sort_by_key(|family| family.id);
+    covered.into()
+}
+
+/// Returns `true` if a family name marks the family as designed for
+/// `locale`, as in "Noto Sans JP" or "Noto Nastaliq Urdu".
+pub(crate) fn name_matches_locale(name: &str, locale: Option<Language>) -> bool {
+    let hints = locale.map_or(&[][..], locale_name_hints);
+    name.split_whitespace().any(|word| hints.contains(&word))
+}
+
+/// Returns the words that mark a family name as designed for `locale`.
+fn locale_name_hints(locale: Language) -> &'static [&'static str] {
+    match (locale.language(), locale.region().unwrap_or_default()) {
+        ("ja", _) => &["JP", "Japanese"],
+        ("ko", _) => &["KR", "Korean"],
+        ("zh", "HK" | "MO") => &["HK"],
+        ("zh", "TW") => &["TC", "TW"],
+        ("zh", _) => &["SC", "CN"],
+        ("ur", _) => &["Urdu", "Nastaliq"],
+        ("fa", _) => &["Persian"],
+        ("yi", _) => &["Yiddish"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blob, Collection, CollectionOptions, FallbackKey, Script};
+    use alloc::{sync::Arc, vec::Vec};
+
+    fn collection(fonts: &[&'static [u8]]) -> Collection {
+        let mut collection = Collection::new(CollectionOptions {
+            system_fonts: false,
+            ..CollectionOptions::default()
+        });
+        for font in fonts {
+            collection.register_fonts(Blob::new(Arc::new(*font)), None);
+        }
+        collection
+    }
+
+    const ROBOTO: &[u8] =
+        include_bytes!("../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+    const ARABIC: &[u8] =
+        include_bytes!("../../parley_dev/assets/fonts/noto_fonts/NotoKufiArabic-Regular.otf");
+
+    #[test]
+    fn script_fallbacks_from_coverage() {
+        let mut collection = collection(&[ROBOTO, ARABIC]);
+        let arabic = collection.family_id("Noto Kufi Arabic").unwrap();
+        let roboto = collection.family_id("Roboto").unwrap();
+
+        let families: Vec<_> = collection
+            .fallback_families(Script::from_bytes(*b"Arab"))
+            .collect();
+        assert_eq!(families, [arabic]);
+        let families: Vec<_> = collection
+            .fallback_families(Script::from_bytes(*b"Cyrl"))
+            .collect();
+        assert_eq!(families, [roboto]);
+        assert_eq!(
+            collection
+                .fallback_families(Script::from_bytes(*b"Thai"))
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn explicit_fallbacks_take_precedence() {
+        let mut collection = collection(&[ROBOTO, ARABIC]);
+        let roboto = collection.family_id("Roboto").unwrap();
+        let key = FallbackKey::new(Script::from_bytes(*b"Arab"), None);
+        collection.set_fallbacks(key, core::iter::once(roboto));
+        let families: Vec<_> = collection.fallback_families(key).collect();
+        assert_eq!(families, [roboto]);
+    }
+
+    #[test]
+    fn codepoint_fallbacks() {
+        let mut collection = collection(&[ROBOTO, ARABIC]);
+        let arabic = collection.family_id("Noto Kufi Arabic").unwrap();
+        let roboto = collection.family_id("Roboto").unwrap();
+
+        let families: Vec<_> = collection.codepoint_fallbacks('ب').collect();
+        assert_eq!(families, [arabic]);
+        let families: Vec<_> = collection.codepoint_fallbacks('Ж').collect();
+        assert_eq!(families, [roboto]);
+        assert_eq!(collection.codepoint_fallbacks('\u{E01}').count(), 0);
+    }
+
+    #[test]
+    fn registering_fonts_updates_fallbacks() {
+        let mut collection = collection(&[ROBOTO]);
+        let arab = Script::from_bytes(*b"Arab");
+        assert_eq!(collection.fallback_families(arab).count(), 0);
+        collection.register_fonts(Blob::new(Arc::new(ARABIC)), None);
+        let arabic = collection.family_id("Noto Kufi Arabic").unwrap();
+        let families: Vec<_> = collection.fallback_families(arab).collect();
+        assert_eq!(families, [arabic]);
+    }
+}
@@ -4,8 +4,9 @@
 //! Model for font families.
 
 use super::{FontStyle, FontWeight, FontWidth, family_name::FamilyName, font::FontInfo};
-use crate::AtomicCounter;
+use crate::{AtomicCounter, NamedInstance, SourceCache};
 use alloc::sync::Arc;
+use alloc::vec::Vec;
 use core::sync::atomic::Ordering;
 use smallvec::SmallVec;
 
@@ -69,6 +70,32 @@ impl FamilyInfo {
         &self.0.fonts
     }
 
+    /// Returns the named instances of the variable fonts in the family, each
+    /// with the index of the font it belongs to.
+    ///
+    /// This reads the data of each font, optionally loading it from a source
+    /// cache.
+    pub fn named_instances(
+        &self,
+        mut source_cache: Option<&mut SourceCache>,
+    ) -> Vec<(usize, NamedInstance)> {
+        let mut instances = Vec::new();
+        for (index, font) in self.fonts().iter().enumerate() {
+            if font.axes().is_empty() {
+                continue;
+            }
+            if let Some(metadata) = font.metadata(source_cache.as_deref_mut()) {
+                instances.extend(
+                    metadata
+                        .named_instances()
+                        .iter()
+                        .map(|instance| (index, instance.clone())),
+                );
+            }
+        }
+        instances
+    }
+
     /// Returns index of the default font of the family.
     pub fn default_font_index(&self) -> usize {
         self.0.default_font
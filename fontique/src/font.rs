@@ -4,15 +4,21 @@
 //! Model for a font.
 
 use crate::CharmapIndex;
+use crate::FontMetadata;
+use crate::charmap::normalize_unicode_range;
+use crate::matching::DEFAULT_OBLIQUE_ANGLE;
 
 use super::source::{SourceInfo, SourceKind};
 use super::{Blob, source_cache::SourceCache};
-use crate::{FontStyle, FontWeight, FontWidth};
+use crate::{FontStyle, FontWeight, FontWidth, RenderHints};
+use alloc::sync::Arc;
 use core::fmt;
+use core::ops::RangeInclusive;
+use parlance::{FontFeature, FontSynthesis};
 use read_fonts::{FontRef, TableProvider as _, types::Tag};
 use smallvec::SmallVec;
 
-type AxisVec = SmallVec<[AxisInfo; 1]>;
+pub(crate) type AxisVec = SmallVec<[AxisInfo; 1]>;
 
 /// Representation of a single font in a family.
 #[derive(Clone, Debug)]
@@ -25,17 +31,52 @@ pub struct FontInfo {
     axes: AxisVec,
     attr_axes: u8,
     charmap_index: CharmapIndex,
+    ranges: Option<Arc<AttributeRanges>>,
+    pub(crate) unicode_range: Option<Arc<[RangeInclusive<u32>]>>,
+    pub(crate) features: Option<Arc<[FontFeature]>>,
+    render_hints: RenderHints,
+}
+
+/// Attribute ranges of a font registered with a [`FontFaceDescriptor`].
+#[derive(Clone, Debug, PartialEq)]
+struct AttributeRanges {
+    width: RangeInclusive<FontWidth>,
+    style: RangeInclusive<FontStyle>,
+    weight: RangeInclusive<FontWeight>,
 }
 
 impl FontInfo {
     /// Creates a new font object from the given source and index.
+    ///
+    /// WOFF and WOFF2 files are decoded on each call.
     pub fn from_source(source: SourceInfo, index: u32) -> Option<Self> {
+        Self::from_cached_source(source, index, None)
+    }
+
+    /// Creates a new font object from the given source and index, decoding
+    /// WOFF and WOFF2 files through a source cache.
+    ///
+    /// Other files are read directly, without being added to the cache.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub(crate) fn from_cached_source(
+        source: SourceInfo,
+        index: u32,
+        source_cache: Option<&mut SourceCache>,
+    ) -> Option<Self> {
         match &source.kind {
             #[cfg(feature = "std")]
             SourceKind::Path(path) => {
                 let file = std::fs::File::open(&**path).ok()?;
                 let mapped = unsafe { memmap2::Mmap::map(&file).ok()? };
-                let font = FontRef::from_index(&mapped, index).ok()?;
+                if !crate::woff::is_compressed(&mapped) {
+                    let font = FontRef::from_index(&mapped, index).ok()?;
+                    return Self::from_font_ref(&font, source.clone(), index);
+                }
+                let data = match source_cache {
+                    Some(source_cache) => source_cache.get(&source)?,
+                    None => super::source_cache::load_blob(path)?,
+                };
+                let font = FontRef::from_index(data.as_ref(), index).ok()?;
                 Self::from_font_ref(&font, source.clone(), index)
             }
             SourceKind::Memory(memory) => {
@@ -69,6 +110,17 @@ impl FontInfo {
         }
     }
 
+    /// Reads the localized names and named instances of the font, optionally
+    /// loading it from a source cache.
+    ///
+    /// This parses the font data on each call, so it's best suited to
+    /// infrequent uses such as populating a font picker.
+    pub fn metadata(&self, source_cache: Option<&mut SourceCache>) -> Option<FontMetadata> {
+        let data = self.load(source_cache)?;
+        let font = FontRef::from_index(data.as_ref(), self.index).ok()?;
+        Some(FontMetadata::from_font_ref(&font))
+    }
+
     /// Returns the visual width of the font-- a relative change from the normal
     /// aspect ratio, typically in the range `0.5` to `2.0`.
     pub fn width(&self) -> FontWidth {
@@ -86,10 +138,74 @@ impl FontInfo {
         self.weight
     }
 
+    /// Returns the range of widths supported by the font.
+    ///
+    /// This is only wider than [`Self::width`] for fonts registered with a
+    /// [`FontFaceDescriptor`] giving a width range.
+    pub fn width_range(&self) -> RangeInclusive<FontWidth> {
+        match &self.ranges {
+            Some(ranges) => ranges.width.clone(),
+            None => self.width..=self.width,
+        }
+    }
+
+    /// Returns the range of styles supported by the font.
+    ///
+    /// This is only wider than [`Self::style`] for fonts registered with a
+    /// [`FontFaceDescriptor`] giving a range of oblique angles.
+    pub fn style_range(&self) -> RangeInclusive<FontStyle> {
+        match &self.ranges {
+            Some(ranges) => ranges.style.clone(),
+            None => self.style..=self.style,
+        }
+    }
+
+    /// Returns the range of weights supported by the font.
+    ///
+    /// This is only wider than [`Self::weight`] for fonts registered with a
+    /// [`FontFaceDescriptor`] giving a weight range.
+    pub fn weight_range(&self) -> RangeInclusive<FontWeight> {
+        match &self.ranges {
+            Some(ranges) => ranges.weight.clone(),
+            None => self.weight..=self.weight,
+        }
+    }
+
+    /// Returns the ranges of codepoints the font may be used for, or `None` if
+    /// it may be used for all codepoints in its character map.
+    ///
+    /// The ranges are sorted, and overlapping ranges of the descriptor are
+    /// merged.
+    ///
+    /// See [`FontFaceDescriptor::unicode_range`].
+    pub fn unicode_range(&self) -> Option<&[RangeInclusive<u32>]> {
+        self.unicode_range.as_deref()
+    }
+
+    /// Returns the font features to apply by default when shaping with this
+    /// font.
+    ///
+    /// See [`FontFaceDescriptor::features`].
+    pub fn features(&self) -> &[FontFeature] {
+        self.features.as_deref().unwrap_or_default()
+    }
+
     /// Returns synthesis suggestions for this font with the given attributes.
+    ///
+    /// For fonts registered with attribute ranges, the requested attributes are
+    /// first clamped to those ranges.
     pub fn synthesis(&self, width: FontWidth, style: FontStyle, weight: FontWeight) -> Synthesis {
         let mut synth = Synthesis::default();
         let mut len = 0_usize;
+        let requested_weight = weight;
+        let (width, weight) = match &self.ranges {
+            Some(ranges) => (
+                clamp_to_range(width, &ranges.width),
+                clamp_to_range(weight, &ranges.weight),
+            ),
+            None => (width, weight),
+        };
+        let face_style = self.matched_style(style);
         if self.has_width_axis() && self.width != width {
             synth.vars[len] = (Tag::new(b"wdth"), width.percentage());
             len += 1;
@@ -108,14 +224,14 @@ impl FontInfo {
                 synth.vars[len] = (Tag::new(b"wght"), weight.value());
                 len += 1;
             }
-        } else if weight.value() > self.weight.value() + 200.0 {
+        } else if requested_weight.value() > self.matched_weight(requested_weight).value() + 200.0 {
             synth.embolden = true;
         }
-        if self.style != style {
+        if face_style != style {
             match style {
                 FontStyle::Normal => {}
                 FontStyle::Italic => {
-                    if self.style == FontStyle::Normal {
+                    if face_style == FontStyle::Normal {
                         if self.has_italic_axis() {
                             synth.vars[len] = (Tag::new(b"ital"), 1.0);
                             len += 1;
@@ -128,7 +244,7 @@ impl FontInfo {
                     }
                 }
                 FontStyle::Oblique(angle) => {
-                    if self.style == FontStyle::Normal {
+                    if face_style == FontStyle::Normal {
                         let degrees = angle.unwrap_or(14.0);
                         if self.has_slant_axis() {
                             synth.vars[len] = (Tag::new(b"slnt"), degrees);
@@ -142,6 +258,14 @@ impl FontInfo {
                     }
                 }
             }
+        } else if let FontStyle::Oblique(Some(degrees)) = face_style
+            && self.has_slant_axis()
+            && self.ranges.is_some()
+        {
+            // The angle was matched within the registered range of a variable
+            // font, so select it on the `slnt` axis.
+            synth.vars[len] = (Tag::new(b"slnt"), degrees);
+            len += 1;
         }
         synth.len = len as u8;
         synth
@@ -210,6 +334,14 @@ impl FontInfo {
     pub fn charmap_index(&self) -> CharmapIndex {
         self.charmap_index
     }
+
+    /// Returns the rendering settings the system configures for this font.
+    ///
+    /// These are only known for system fonts matched by fontconfig, and are
+    /// empty otherwise.
+    pub fn render_hints(&self) -> RenderHints {
+        self.render_hints
+    }
 }
 
 impl FontInfo {
@@ -221,32 +353,36 @@ impl FontInfo {
         // It's probably not useful to retain fonts that don't have
         // a valid cmap so just bail here if we fail.
         let charmap_index = CharmapIndex::new(font)?;
-        let (width, style, weight) = read_attributes(font);
-        let (axes, attr_axes) = if let Ok(fvar_axes) = font.fvar().and_then(|fvar| fvar.axes()) {
-            let mut axes = SmallVec::<[AxisInfo; 1]>::with_capacity(fvar_axes.len());
-            let mut attrs_axes = 0_u8;
-            for fvar_axis in fvar_axes {
-                let axis = AxisInfo {
-                    tag: fvar_axis.axis_tag(),
-                    min: fvar_axis.min_value().to_f32(),
-                    max: fvar_axis.max_value().to_f32(),
-                    default: fvar_axis.default_value().to_f32(),
-                };
-                axes.push(axis);
-                match &axis.tag.to_be_bytes() {
-                    b"wght" => attrs_axes |= WEIGHT_AXIS,
-                    b"wdth" => attrs_axes |= WIDTH_AXIS,
-                    b"slnt" => attrs_axes |= SLANT_AXIS,
-                    b"ital" => attrs_axes |= ITALIC_AXIS,
-                    b"opsz" => attrs_axes |= OPTICAL_SIZE_AXIS,
-                    _ => {}
-                }
+        Some(Self::from_parts(
+            source,
+            index,
+            read_attributes(font),
+            read_axes(font),
+            charmap_index,
+        ))
+    }
+
+    /// Creates a font from previously extracted attributes, axes and
+    /// character map index.
+    pub(crate) fn from_parts(
+        source: SourceInfo,
+        index: u32,
+        (width, style, weight): (FontWidth, FontStyle, FontWeight),
+        axes: AxisVec,
+        charmap_index: CharmapIndex,
+    ) -> Self {
+        let mut attr_axes = 0_u8;
+        for axis in &axes {
+            match &axis.tag.to_be_bytes() {
+                b"wght" => attr_axes |= WEIGHT_AXIS,
+                b"wdth" => attr_axes |= WIDTH_AXIS,
+                b"slnt" => attr_axes |= SLANT_AXIS,
+                b"ital" => attr_axes |= ITALIC_AXIS,
+                b"opsz" => attr_axes |= OPTICAL_SIZE_AXIS,
+                _ => {}
             }
-            (axes, attrs_axes)
-        } else {
-            (SmallVec::default(), 0)
-        };
-        Some(Self {
+        }
+        Self {
             source,
             index,
             width,
@@ -255,7 +391,61 @@ impl FontInfo {
             axes,
             attr_axes,
             charmap_index,
-        })
+            ranges: None,
+            unicode_range: None,
+            features: None,
+            render_hints: RenderHints::default(),
+        }
+    }
+
+    #[cfg_attr(
+        not(all(
+            feature = "fontconfig",
+            not(feature = "fonts-conf"),
+            any(target_os = "linux", target_os = "freebsd")
+        )),
+        allow(unused)
+    )]
+    pub(crate) fn set_render_hints(&mut self, render_hints: RenderHints) {
+        self.render_hints = render_hints;
+    }
+
+    /// Returns the width this font is matched with when `width` is requested.
+    pub(crate) fn matched_width(&self, width: FontWidth) -> FontWidth {
+        match &self.ranges {
+            Some(ranges) => clamp_to_range(width, &ranges.width),
+            None => self.width,
+        }
+    }
+
+    /// Returns the style this font is matched with when `style` is requested.
+    ///
+    /// Only ranges of oblique angles are matched against; other style ranges
+    /// behave as their start.
+    pub(crate) fn matched_style(&self, style: FontStyle) -> FontStyle {
+        let Some(ranges) = &self.ranges else {
+            return self.style;
+        };
+        let (FontStyle::Oblique(Some(min)), FontStyle::Oblique(Some(max))) =
+            (*ranges.style.start(), *ranges.style.end())
+        else {
+            return self.style;
+        };
+        let angle = match style {
+            FontStyle::Normal if min <= 0.0 && max >= 0.0 => return FontStyle::Normal,
+            FontStyle::Normal => 0.0,
+            FontStyle::Italic => DEFAULT_OBLIQUE_ANGLE,
+            FontStyle::Oblique(angle) => angle.unwrap_or(DEFAULT_OBLIQUE_ANGLE),
+        };
+        FontStyle::Oblique(Some(clamp_to_range(angle, &(min..=max))))
+    }
+
+    /// Returns the weight this font is matched with when `weight` is requested.
+    pub(crate) fn matched_weight(&self, weight: FontWeight) -> FontWeight {
+        match &self.ranges {
+            Some(ranges) => clamp_to_range(weight, &ranges.weight),
+            None => self.weight,
+        }
     }
 
     #[allow(unused)]
@@ -276,6 +466,32 @@ impl FontInfo {
         }
     }
 
+    pub(crate) fn apply_descriptor(&mut self, descriptor: &FontFaceDescriptor<'_>) {
+        if descriptor.width.is_some() || descriptor.style.is_some() || descriptor.weight.is_some() {
+            let ranges = AttributeRanges {
+                width: descriptor.width.clone().unwrap_or(self.width..=self.width),
+                style: descriptor.style.clone().unwrap_or(self.style..=self.style),
+                weight: descriptor
+                    .weight
+                    .clone()
+                    .unwrap_or(self.weight..=self.weight),
+            };
+            self.width = *ranges.width.start();
+            self.style = *ranges.style.start();
+            self.weight = *ranges.weight.start();
+            self.ranges = Some(Arc::new(ranges));
+        }
+        if !descriptor.unicode_range.is_empty() {
+            self.unicode_range = Some(normalize_unicode_range(descriptor.unicode_range).into());
+        }
+        if !descriptor.features.is_empty() {
+            self.features = Some(descriptor.features.into());
+        }
+        if let Some(axes) = descriptor.axes {
+            self.apply_axis_defaults(axes);
+        }
+    }
+
     pub(crate) fn apply_override(&mut self, info_override: &FontInfoOverride<'_>) {
         if let Some(width) = info_override.width {
             self.width = width;
@@ -287,17 +503,32 @@ impl FontInfo {
             self.weight = weight;
         }
         if let Some(axes) = info_override.axes {
-            // This is O(n^2) but no font should have enough axes for that to
-            // matter (read-fonts does the same thing)
-            for (tag, value) in axes {
-                if let Some(axis) = self.axes.iter_mut().find(|axis| axis.tag == *tag) {
-                    axis.default = *value;
-                }
+            self.apply_axis_defaults(axes);
+        }
+    }
+
+    fn apply_axis_defaults(&mut self, axes: &[(Tag, f32)]) {
+        // This is O(n^2) but no font should have enough axes for that to
+        // matter (read-fonts does the same thing)
+        for (tag, value) in axes {
+            if let Some(axis) = self.axes.iter_mut().find(|axis| axis.tag == *tag) {
+                axis.default = *value;
             }
         }
     }
 }
 
+/// Clamps `value` to `range`, leaving it unchanged if it's incomparable.
+fn clamp_to_range<T: PartialOrd + Copy>(value: T, range: &RangeInclusive<T>) -> T {
+    if value < *range.start() {
+        *range.start()
+    } else if value > *range.end() {
+        *range.end()
+    } else {
+        value
+    }
+}
+
 const WEIGHT_AXIS: u8 = 0x01;
 const WIDTH_AXIS: u8 = 0x02;
 const SLANT_AXIS: u8 = 0x04;
@@ -323,7 +554,7 @@ const OPTICAL_SIZE_AXIS: u8 = 0x10;
 /// For a broader explanation of this, see
 /// [Axis in Variable Fonts](https://fonts.google.com/knowledge/glossary/axis_in_variable_fonts)
 /// from Google Fonts.
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
 pub struct AxisInfo {
     /// The tag that identifies the axis.
     pub tag: Tag,
@@ -343,6 +574,7 @@ pub struct AxisInfo {
 ///
 /// [`QueryFont::synthesis`]: crate::QueryFont::synthesis
 #[derive(Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Synthesis {
     vars: [(Tag, f32); 3],
     len: u8,
@@ -378,6 +610,37 @@ impl Synthesis {
             None
         }
     }
+
+    /// Returns the amount a faux bold widens glyphs at the given font size,
+    /// or zero if no faux bold is requested.
+    ///
+    /// Glyph outlines should be expanded by half of this on each side, and
+    /// glyph advances are widened by all of it.
+    pub fn embolden_strength(&self, font_size: f32) -> f32 {
+        if self.embolden {
+            // This matches the default strength of FreeType's emboldening.
+            font_size / 24.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns these suggestions without the kinds of synthesis that
+    /// `allowed` disallows.
+    ///
+    /// Variation settings are kept, as they select an instance of the font
+    /// rather than synthesize one. Small capitals and subscript and superscript
+    /// glyphs are never suggested, so those kinds are ignored.
+    #[must_use]
+    pub fn restrict(mut self, allowed: FontSynthesis) -> Self {
+        if !allowed.weight {
+            self.embolden = false;
+        }
+        if !allowed.style {
+            self.skew = 0;
+        }
+        self
+    }
 }
 
 #[allow(clippy::missing_fields_in_debug)]
@@ -391,7 +654,23 @@ impl fmt::Debug for Synthesis {
     }
 }
 
-fn read_attributes(font: &FontRef<'_>) -> (FontWidth, FontStyle, FontWeight) {
+/// Reads the variation axes of a font from its `fvar` table.
+pub(crate) fn read_axes(font: &FontRef<'_>) -> AxisVec {
+    match font.fvar().and_then(|fvar| fvar.axes()) {
+        Ok(fvar_axes) => fvar_axes
+            .iter()
+            .map(|fvar_axis| AxisInfo {
+                tag: fvar_axis.axis_tag(),
+                min: fvar_axis.min_value().to_f32(),
+                max: fvar_axis.max_value().to_f32(),
+                default: fvar_axis.default_value().to_f32(),
+            })
+            .collect(),
+        Err(_) => AxisVec::default(),
+    }
+}
+
+pub(crate) fn read_attributes(font: &FontRef<'_>) -> (FontWidth, FontStyle, FontWeight) {
     use read_fonts::{
         TableProvider,
         tables::{
@@ -488,6 +767,50 @@ pub struct FontInfoOverride<'a> {
     pub axes: Option<&'a [(Tag, f32)]>,
 }
 
+/// Description of a font face registered with
+/// [`Collection::register_font_face`], modelled after the CSS `@font-face`
+/// rule.
+///
+/// Unlike [`FontInfoOverride`], the attributes may be given as ranges, which
+/// the font matches for any value inside of them. This is how a variable font
+/// declares `font-weight: 100 900`, for example. The `unicode_range` restricts
+/// the codepoints the font is used for, which allows a family to be split over
+/// several subset files, each registered with its own range.
+///
+/// [`Collection::register_font_face`]: crate::Collection::register_font_face
+#[derive(Clone, Debug, Default)]
+pub struct FontFaceDescriptor<'a> {
+    /// Font family name to be used instead of the one specified in the font
+    /// itself.
+    pub family_name: Option<&'a str>,
+    /// Range of font widths to be used instead of the width specified in the
+    /// font itself.
+    pub width: Option<RangeInclusive<FontWidth>>,
+    /// Range of font styles to be used instead of the style specified in the
+    /// font itself.
+    ///
+    /// Only a range between two [oblique](FontStyle::Oblique) angles is
+    /// matched as a range; other ranges behave as their start.
+    pub style: Option<RangeInclusive<FontStyle>>,
+    /// Range of font weights to be used instead of the weight specified in the
+    /// font itself.
+    pub weight: Option<RangeInclusive<FontWeight>>,
+    /// Ranges of codepoints the font is used for, as in the CSS
+    /// `unicode-range` descriptor.
+    ///
+    /// Codepoints outside of these ranges are treated as missing from the
+    /// font's [character map](crate::QueryFont::charmap). If empty, the font
+    /// is used for all codepoints it supports. The ranges may be given in any
+    /// order and may overlap; empty ranges are ignored.
+    pub unicode_range: &'a [RangeInclusive<u32>],
+    /// Default values for the font's variation axes. Axes not included within
+    /// the font will be ignored.
+    pub axes: Option<&'a [(Tag, f32)]>,
+    /// Font features applied when shaping with the font, before the features
+    /// of the text's style.
+    pub features: &'a [FontFeature],
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::sync::Arc;
@@ -559,4 +882,106 @@ mod tests {
         assert!(synthesis.embolden());
         assert!(synthesis.variation_settings().is_empty());
     }
+
+    #[test]
+    fn restricted_synthesis_keeps_variations() {
+        let font = font_info(ROBOTO);
+        let synthesis = font.synthesis(font.width(), FontStyle::Italic, FontWeight::BOLD);
+        assert!(synthesis.embolden());
+        assert!(synthesis.skew().is_some());
+        assert_eq!(synthesis.restrict(FontSynthesis::ALL), synthesis);
+        assert_eq!(synthesis.embolden_strength(24.0), 1.0);
+
+        let restricted = synthesis.restrict(FontSynthesis {
+            weight: false,
+            ..FontSynthesis::ALL
+        });
+        assert!(!restricted.embolden());
+        assert_eq!(restricted.embolden_strength(24.0), 0.0);
+        assert_eq!(restricted.skew(), synthesis.skew());
+        assert!(!synthesis.restrict(FontSynthesis::NONE).any());
+
+        let flex = font_info(ROBOTO_FLEX);
+        let synthesis = flex.synthesis(flex.width(), flex.style(), FontWeight::BOLD);
+        let restricted = synthesis.restrict(FontSynthesis::NONE);
+        assert_eq!(
+            restricted.variation_settings(),
+            synthesis.variation_settings()
+        );
+        assert!(restricted.any());
+    }
+    #[test]
+    fn weight_range_clamps_requested_weight() {
+        let mut font = font_info(ROBOTO);
+        font.apply_descriptor(&FontFaceDescriptor {
+            weight: Some(FontWeight::NORMAL..=FontWeight::BOLD),
+            ..Default::default()
+        });
+
+        assert_eq!(font.weight(), FontWeight::NORMAL);
+        assert_eq!(font.weight_range(), FontWeight::NORMAL..=FontWeight::BOLD);
+        assert_eq!(
+            font.matched_weight(FontWeight::SEMI_BOLD),
+            FontWeight::SEMI_BOLD
+        );
+        assert_eq!(font.matched_weight(FontWeight::BLACK), FontWeight::BOLD);
+        assert_eq!(font.matched_weight(FontWeight::THIN), FontWeight::NORMAL);
+
+        // The weights in the range don't need faux bold, even for a static font.
+        let synthesis = font.synthesis(font.width(), font.style(), FontWeight::BOLD);
+        assert!(!synthesis.any());
+        let synthesis = font.synthesis(font.width(), font.style(), FontWeight::new(950.0));
+        assert!(synthesis.embolden());
+    }
+
+    #[test]
+    fn oblique_range_matches_requested_angle() {
+        let mut font = font_info(ROBOTO_FLEX);
+        font.apply_descriptor(&FontFaceDescriptor {
+            style: Some(FontStyle::Oblique(Some(0.0))..=FontStyle::Oblique(Some(10.0))),
+            ..Default::default()
+        });
+
+        assert_eq!(font.matched_style(FontStyle::Normal), FontStyle::Normal);
+        assert_eq!(
+            font.matched_style(FontStyle::Oblique(Some(5.0))),
+            FontStyle::Oblique(Some(5.0))
+        );
+        assert_eq!(
+            font.matched_style(FontStyle::Italic),
+            FontStyle::Oblique(Some(10.0))
+        );
+
+        let synthesis = font.synthesis(
+            font.width(),
+            FontStyle::Oblique(Some(5.0)),
+            FontWeight::NORMAL,
+        );
+        assert_eq!(synthesis.variation_settings(), &[(Tag::new(b"slnt"), 5.0)]);
+        assert_eq!(synthesis.skew(), None);
+    }
+
+    #[test]
+    fn descriptor_sets_unicode_range_and_features() {
+        let mut font = font_info(ROBOTO);
+        assert_eq!(font.unicode_range(), None);
+        assert!(font.features().is_empty());
+
+        let features = [FontFeature::new(parlance::Tag::new(b"tnum"), 1)];
+        font.apply_descriptor(&FontFaceDescriptor {
+            unicode_range: &[0x30..=0x39],
+            features: &features,
+            ..Default::default()
+        });
+        assert_eq!(font.unicode_range(), Some(&[0x30..=0x39][..]));
+        assert_eq!(font.features(), &features);
+
+        font.apply_descriptor(&FontFaceDescriptor {
+            unicode_range: &[0x41..=0x5A, 0x30..=0x39, 0x38..=0x40],
+            ..Default::default()
+        });
+        assert_eq!(font.unicode_range(), Some(&[0x30..=0x5A][..]));
+        // Ranges aren't recorded when no attributes are described.
+        assert_eq!(font.weight_range(), font.weight()..=font.weight());
+    }
 }
@@ -4,33 +4,199 @@
 //! Generic font families.
 
 use super::FamilyId;
-use crate::GenericFamily;
+use crate::{GenericFamily, Language};
+use alloc::vec::Vec;
 use smallvec::SmallVec;
 
 type FamilyVec = SmallVec<[FamilyId; 2]>;
 const COUNT: usize = GenericFamily::MAX_VALUE as usize + 1;
 
+/// Describes a selector for the families of a generic family.
+///
+/// This is a [`GenericFamily`] and optionally, a `locale`, represented as a
+/// [`Language`]. Generic families may resolve to different families in
+/// different locales, such as `serif` for Japanese and Chinese text.
+///
+/// It can be constructed directly via [`GenericFamilyKey::new`] or any of
+/// a variety of `From` implementations to improve the ease of use.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct GenericFamilyKey {
+    family: GenericFamily,
+    locale: Option<Language>,
+}
+
+impl GenericFamilyKey {
+    /// Creates a new key from the given generic family and locale.
+    pub fn new(family: GenericFamily, locale: Option<&Language>) -> Self {
+        Self {
+            family,
+            locale: locale.copied(),
+        }
+    }
+
+    /// Returns the requested generic family.
+    pub fn family(&self) -> GenericFamily {
+        self.family
+    }
+
+    /// Returns the requested locale.
+    pub fn locale(&self) -> Option<Language> {
+        self.locale
+    }
+}
+
+impl From<GenericFamily> for GenericFamilyKey {
+    fn from(value: GenericFamily) -> Self {
+        Self::new(value, None)
+    }
+}
+
+impl From<(GenericFamily, &str)> for GenericFamilyKey {
+    fn from(value: (GenericFamily, &str)) -> Self {
+        let locale = Language::parse(value.1).ok();
+        Self::new(value.0, locale.as_ref())
+    }
+}
+
+impl From<(GenericFamily, &Language)> for GenericFamilyKey {
+    fn from(value: (GenericFamily, &Language)) -> Self {
+        Self::new(value.0, Some(value.1))
+    }
+}
+
 /// Maps generic families to family identifiers.
 #[derive(Clone, Default, Debug)]
 pub struct GenericFamilyMap {
     map: [FamilyVec; COUNT],
+    /// Families of generic families in specific locales.
+    localized: Vec<(GenericFamily, Language, FamilyVec)>,
 }
 
 impl GenericFamilyMap {
     /// Returns the associated family identifiers for the given generic family.
-    pub fn get(&self, generic: GenericFamily) -> &[FamilyId] {
-        &self.map[generic as usize]
+    ///
+    /// With a locale, these are the families set for the locale that best
+    /// matches it, or the families set without a locale if none does.
+    pub fn get(&self, key: impl Into<GenericFamilyKey>) -> &[FamilyId] {
+        let key = key.into();
+        if let Some(locale) = key.locale
+            && let Some(families) = self.get_localized(key.family, locale)
+        {
+            return families;
+        }
+        &self.map[key.family as usize]
     }
 
     /// Sets the associated family identifiers for the given generic family.
-    pub fn set(&mut self, generic: GenericFamily, families: impl Iterator<Item = FamilyId>) {
-        let map = &mut self.map[generic as usize];
+    pub fn set(
+        &mut self,
+        key: impl Into<GenericFamilyKey>,
+        families: impl Iterator<Item = FamilyId>,
+    ) {
+        let map = self.entry(key.into());
         map.clear();
         map.extend(families);
     }
 
     /// Appends the family identifiers to the list for the given generic family.
-    pub fn append(&mut self, generic: GenericFamily, families: impl Iterator<Item = FamilyId>) {
-        self.map[generic as usize].extend(families);
+    pub fn append(
+        &mut self,
+        key: impl Into<GenericFamilyKey>,
+        families: impl Iterator<Item = FamilyId>,
+    ) {
+        self.entry(key.into()).extend(families);
+    }
+
+    fn entry(&mut self, key: GenericFamilyKey) -> &mut FamilyVec {
+        let Some(locale) = key.locale else {
+            return &mut self.map[key.family as usize];
+        };
+        let idx = match self
+            .localized
+            .iter()
+            .position(|(family, other, _)| *family == key.family && *other == locale)
+        {
+            Some(idx) => idx,
+            None => {
+                self.localized
+                    .push((key.family, locale, FamilyVec::default()));
+                self.localized.len() - 1
+            }
+        };
+        &mut self.localized[idx].2
+    }
+
+    fn get_localized(&self, generic: GenericFamily, locale: Language) -> Option<&[FamilyId]> {
+        self.localized
+            .iter()
+            .filter(|(family, _, _)| *family == generic)
+            .filter_map(|(_, tag, families)| Some((locale_match(*tag, locale)?, families)))
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, families)| families.as_slice())
+    }
+}
+
+/// Returns how specifically `tag` matches `locale`, or `None` if it doesn't.
+///
+/// A tag matches when its language is that of the locale, and its script
+/// and region, if given, are those of the locale as well.
+fn locale_match(tag: Language, locale: Language) -> Option<u8> {
+    if tag.language() != locale.language() {
+        return None;
+    }
+    let mut score = 1;
+    if let Some(script) = tag.script() {
+        if Some(script) != locale.script().or_else(|| implied_script(locale)) {
+            return None;
+        }
+        score += 1;
+    }
+    if let Some(region) = tag.region() {
+        if Some(region) != locale.region() {
+            return None;
+        }
+        score += 1;
+    }
+    Some(score)
+}
+
+/// Returns the script of Chinese written in the region of `locale`.
+fn implied_script(locale: Language) -> Option<&'static str> {
+    match (locale.language(), locale.region()) {
+        ("zh", Some("TW" | "HK" | "MO")) => Some("Hant"),
+        ("zh", _) => Some("Hans"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GenericFamilyKey, GenericFamilyMap};
+    use crate::{FamilyId, GenericFamily, Language};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn localized_families() {
+        let [default, ja, hans, hant, hk] = core::array::from_fn(|_| FamilyId::new());
+        let mut map = GenericFamilyMap::default();
+        map.set(GenericFamily::Serif, [default].into_iter());
+        map.set((GenericFamily::Serif, "ja"), [ja].into_iter());
+        map.set((GenericFamily::Serif, "zh-Hans"), [hans].into_iter());
+        map.set((GenericFamily::Serif, "zh-Hant"), [hant].into_iter());
+        map.append((GenericFamily::Serif, "zh-HK"), [hk, hant].into_iter());
+
+        let get = |locale: Option<&str>| {
+            let locale = locale.map(|locale| Language::parse(locale).unwrap());
+            let key = GenericFamilyKey::new(GenericFamily::Serif, locale.as_ref());
+            map.get(key).to_vec()
+        };
+        assert_eq!(get(None), [default]);
+        assert_eq!(get(Some("ja-JP")), [ja]);
+        assert_eq!(get(Some("zh")), [hans]);
+        assert_eq!(get(Some("zh-CN")), [hans]);
+        assert_eq!(get(Some("zh-TW")), [hant]);
+        assert_eq!(get(Some("zh-HK")), [hk, hant]);
+        assert_eq!(get(Some("ko")), [default]);
+        assert_eq!(map.get(GenericFamily::SansSerif), Vec::<FamilyId>::new());
     }
 }
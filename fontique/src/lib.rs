@@ -41,6 +41,7 @@ mod attributes;
 mod backend;
 mod charmap;
 mod collection;
+mod coverage;
 mod fallback;
 mod family;
 mod family_name;
@@ -48,9 +49,15 @@ mod font;
 mod generic;
 mod impl_fontconfig;
 mod matching;
+mod metadata;
+mod palette;
+mod render_hints;
 mod scan;
+#[cfg(feature = "std")]
+mod scan_cache;
 mod script;
 mod source;
+mod woff;
 
 mod source_cache;
 
@@ -62,14 +69,33 @@ pub use charmap::{Charmap, CharmapIndex};
 pub use collection::{Collection, CollectionOptions, Query, QueryFamily, QueryFont, QueryStatus};
 pub use fallback::FallbackKey;
 pub use family::{FamilyId, FamilyInfo};
-pub use font::{AxisInfo, FontInfo, FontInfoOverride, Synthesis};
+pub use font::{AxisInfo, FontFaceDescriptor, FontInfo, FontInfoOverride, Synthesis};
+pub use generic::GenericFamilyKey;
 pub use impl_fontconfig::FromFontconfig;
-pub use parlance::{FontStyle, FontWeight, FontWidth, GenericFamily, Language, Script};
+pub use metadata::{AxisValueName, FontMetadata, LocalizedName, NamedInstance};
+pub use palette::ColorPalettes;
+pub use parlance::{
+    FontFeature, FontStyle, FontSynthesis, FontVariation, FontWeight, FontWidth, GenericFamily,
+    Language, Script,
+};
+pub use render_hints::{HintStyle, LcdFilter, RenderHints, SubpixelOrder};
 pub use source::{SourceId, SourceInfo, SourceKind};
 
 #[cfg(all(feature = "system", target_vendor = "apple"))]
 use objc2 as _;
+#[cfg(all(
+    feature = "system",
+    not(feature = "fonts-conf"),
+    any(target_os = "linux", target_os = "freebsd")
+))]
+use roxmltree as _;
 pub use source_cache::{SourceCache, SourceCacheOptions};
+#[cfg(all(
+    feature = "fontconfig",
+    feature = "fonts-conf",
+    any(target_os = "linux", target_os = "freebsd")
+))]
+use yeslogic_fontconfig_sys as _;
 
 #[cfg(not(target_has_atomic = "64"))]
 use core::sync::atomic::AtomicU32 as AtomicCounter;
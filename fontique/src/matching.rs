@@ -7,7 +7,7 @@ use super::font::FontInfo;
 use crate::{FontStyle, FontWeight, FontWidth};
 use smallvec::SmallVec;
 
-const DEFAULT_OBLIQUE_ANGLE: f32 = 14.0;
+pub(crate) const DEFAULT_OBLIQUE_ANGLE: f32 = 14.0;
 
 pub(crate) fn match_fonts(
     set: &[FontInfo],
@@ -16,15 +16,18 @@ pub(crate) fn match_fonts(
     weight: FontWeight,
     synthesize_style: bool,
 ) -> impl Iterator<Item = usize> + '_ {
-    let best = match_font(set, width, style, weight, synthesize_style).map(|index| {
-        let font = &set[index];
-        (font.width(), font.style(), font.weight())
-    });
+    let matched = move |font: &FontInfo| {
+        (
+            font.matched_width(width),
+            font.matched_style(style),
+            font.matched_weight(weight),
+        )
+    };
+    let best =
+        match_font(set, width, style, weight, synthesize_style).map(|index| matched(&set[index]));
     set.iter().enumerate().filter_map(move |(index, font)| {
-        let (best_width, best_style, best_weight) = best?;
         // We need to return each font with the best attributes, as there might be multiple subsets.
-        (font.width() == best_width && font.style() == best_style && font.weight() == best_weight)
-            .then_some(index)
+        (matched(font) == best?).then_some(index)
     })
 }
 
@@ -54,9 +57,9 @@ pub fn match_font(
         .enumerate()
         .map(|(i, font)| Candidate {
             index: i,
-            width: (font.width().ratio() * 100.0) as i32,
-            style: font.style(),
-            weight: font.weight().value(),
+            width: (font.matched_width(width).ratio() * 100.0) as i32,
+            style: font.matched_style(style),
+            weight: font.matched_weight(weight).value(),
             has_slnt: font.has_slant_axis(),
         })
         .collect();
@@ -0,0 +1,445 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Localized names, named instances and axis value names of a font.
+
+use crate::{FontStyle, FontVariation, FontWeight, FontWidth, Language};
+use alloc::string::String;
+use alloc::vec::Vec;
+use parlance::Tag;
+use read_fonts::{
+    FontRef, TableProvider as _,
+    tables::{
+        name::Name,
+        stat::{AxisValue, AxisValueTableFlags},
+    },
+    types::NameId,
+};
+
+/// Names and variable font instances of a font, read from its `name`,
+/// `fvar` and `STAT` tables.
+///
+/// Instances of this can be obtained from [`FontInfo::metadata`].
+///
+/// [`FontInfo::metadata`]: crate::FontInfo::metadata
+#[derive(Clone, Default, Debug)]
+pub struct FontMetadata {
+    family_names: Vec<LocalizedName>,
+    subfamily_names: Vec<LocalizedName>,
+    named_instances: Vec<NamedInstance>,
+    axis_values: Vec<AxisValueName>,
+}
+
+impl FontMetadata {
+    /// Returns the names of the font's family in each language the font gives
+    /// them in.
+    ///
+    /// These are the typographic family names if the font has them, and the
+    /// legacy family names otherwise.
+    pub fn family_names(&self) -> &[LocalizedName] {
+        &self.family_names
+    }
+
+    /// Returns the names of the font within its family, such as "Bold
+    /// Italic", in each language the font gives them in.
+    ///
+    /// These are the typographic subfamily names if the font has them, and
+    /// the legacy subfamily names otherwise.
+    pub fn subfamily_names(&self) -> &[LocalizedName] {
+        &self.subfamily_names
+    }
+
+    /// Returns the named instances of a variable font.
+    pub fn named_instances(&self) -> &[NamedInstance] {
+        &self.named_instances
+    }
+
+    /// Returns the names of values on the variation axes of the font, from
+    /// its style attributes table.
+    ///
+    /// Names for combinations of values on several axes are not included.
+    pub fn axis_values(&self) -> &[AxisValueName] {
+        &self.axis_values
+    }
+}
+
+impl FontMetadata {
+    pub(crate) fn from_font_ref(font: &FontRef<'_>) -> Self {
+        let Ok(name) = font.name() else {
+            return Self::default();
+        };
+        let names_or = |id: NameId, fallback: NameId| {
+            let names = localized_names(&name, id);
+            if names.is_empty() {
+                localized_names(&name, fallback)
+            } else {
+                names
+            }
+        };
+        let mut metadata = Self {
+            family_names: names_or(NameId::TYPOGRAPHIC_FAMILY_NAME, NameId::FAMILY_NAME),
+            subfamily_names: names_or(NameId::TYPOGRAPHIC_SUBFAMILY_NAME, NameId::SUBFAMILY_NAME),
+            named_instances: Vec::new(),
+            axis_values: Vec::new(),
+        };
+        let Ok(fvar) = font.fvar() else {
+            return metadata;
+        };
+        let Ok(axes) = fvar.axes() else {
+            return metadata;
+        };
+        let tags: Vec<Tag> = axes
+            .iter()
+            .map(|axis| Tag::from_bytes(axis.axis_tag().to_be_bytes()))
+            .collect();
+        if let Ok(instances) = fvar.instances() {
+            for instance in instances.iter().flatten() {
+                let postscript_name = instance
+                    .post_script_name_id
+                    .and_then(|id| localized_names(&name, id).into_iter().next())
+                    .map(|postscript_name| postscript_name.name);
+                metadata.named_instances.push(NamedInstance {
+                    names: localized_names(&name, instance.subfamily_name_id),
+                    postscript_name,
+                    coords: tags
+                        .iter()
+                        .zip(instance.coordinates)
+                        .map(|(tag, coord)| FontVariation::new(*tag, coord.get().to_f32()))
+                        .collect(),
+                });
+            }
+        }
+        if let Ok(stat) = font.stat()
+            && let Ok(design_axes) = stat.design_axes()
+            && let Some(Ok(values)) = stat.offset_to_axis_values()
+        {
+            for value in values.axis_values().iter().flatten() {
+                let (axis_index, flags, name_id, value) = match &value {
+                    AxisValue::Format1(value) => (
+                        value.axis_index(),
+                        value.flags(),
+                        value.value_name_id(),
+                        value.value(),
+                    ),
+                    AxisValue::Format2(value) => (
+                        value.axis_index(),
+                        value.flags(),
+                        value.value_name_id(),
+                        value.nominal_value(),
+                    ),
+                    AxisValue::Format3(value) => (
+                        value.axis_index(),
+                        value.flags(),
+                        value.value_name_id(),
+                        value.value(),
+                    ),
+                    AxisValue::Format4(_) => continue,
+                };
+                let Some(axis) = design_axes.get(usize::from(axis_index)) else {
+                    continue;
+                };
+                metadata.axis_values.push(AxisValueName {
+                    tag: Tag::from_bytes(axis.axis_tag().to_be_bytes()),
+                    value: value.to_f32(),
+                    names: localized_names(&name, name_id),
+                    elidable: flags.contains(AxisValueTableFlags::ELIDABLE_AXIS_VALUE_NAME),
+                });
+            }
+        }
+        metadata
+    }
+}
+
+/// A named instance of a variable font, such as "SemiBold Condensed".
+#[derive(Clone, PartialEq, Debug)]
+pub struct NamedInstance {
+    /// The names of the instance in each language the font gives them in.
+    pub names: Vec<LocalizedName>,
+    /// The PostScript name of the instance, if the font gives one.
+    pub postscript_name: Option<String>,
+    /// The coordinates of the instance on each axis of the font.
+    pub coords: Vec<FontVariation>,
+}
+
+impl NamedInstance {
+    /// Returns the English name of the instance, or its first name if it has
+    /// no English one.
+    pub fn name(&self) -> Option<&str> {
+        english_or_first(&self.names)
+    }
+
+    /// Returns the weight of the instance, if the font has a `wght` axis.
+    pub fn weight(&self) -> Option<FontWeight> {
+        self.coord(b"wght").map(FontWeight::new)
+    }
+
+    /// Returns the width of the instance, if the font has a `wdth` axis.
+    pub fn width(&self) -> Option<FontWidth> {
+        self.coord(b"wdth").map(FontWidth::from_percentage)
+    }
+
+    /// Returns the style of the instance, if the font has an `ital` or a
+    /// `slnt` axis.
+    pub fn style(&self) -> Option<FontStyle> {
+        if let Some(ital) = self.coord(b"ital")
+            && ital >= 0.5
+        {
+            return Some(FontStyle::Italic);
+        }
+        match self.coord(b"slnt") {
+            // Negative `slnt` values lean forward, which are positive oblique
+            // angles in CSS.
+            Some(slnt) if slnt != 0.0 => Some(FontStyle::Oblique(Some(-slnt))),
+            Some(_) => Some(FontStyle::Normal),
+            None => self.coord(b"ital").map(|_| FontStyle::Normal),
+        }
+    }
+
+    fn coord(&self, tag: &[u8; 4]) -> Option<f32> {
+        let tag = Tag::new(tag);
+        self.coords
+            .iter()
+            .find(|coord| coord.tag == tag)
+            .map(|coord| coord.value)
+    }
+}
+
+/// The name of a value on a variation axis, such as "Bold" for a `wght`
+/// value of 700.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AxisValueName {
+    /// The tag of the axis.
+    pub tag: Tag,
+    /// The value on the axis.
+    ///
+    /// For names of a range of values, this is the nominal value of the range.
+    pub value: f32,
+    /// The names of the value in each language the font gives them in.
+    pub names: Vec<LocalizedName>,
+    /// Whether the name can be omitted when composing the name of a
+    /// combination of axis values, such as "Regular".
+    pub elidable: bool,
+}
+
+impl AxisValueName {
+    /// Returns the English name of the value, or its first name if it has
+    /// no English one.
+    pub fn name(&self) -> Option<&str> {
+        english_or_first(&self.names)
+    }
+}
+
+/// A name from a font's naming table in one language.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LocalizedName {
+    /// The language of the name, or `None` if the font doesn't give it or it
+    /// isn't known.
+    pub language: Option<Language>,
+    /// The name.
+    pub name: String,
+}
+
+fn english_or_first(names: &[LocalizedName]) -> Option<&str> {
+    names
+        .iter()
+        .find(|name| name.language.is_some_and(|lang| lang.language() == "en"))
+        .or(names.first())
+        .map(|name| name.name.as_str())
+}
+
+/// Reads the names with the given identifier, skipping duplicates.
+fn localized_names(name: &Name<'_>, id: NameId) -> Vec<LocalizedName> {
+    let mut names: Vec<LocalizedName> = Vec::new();
+    for record in name.name_record().iter().filter(|rec| rec.name_id() == id) {
+        let Ok(string) = record.string(name.string_data()) else {
+            continue;
+        };
+        let language = record_language(name, record.platform_id(), record.language_id());
+        let string: String = string.chars().collect();
+        if string.is_empty()
+            || names
+                .iter()
+                .any(|existing| existing.language == language && existing.name == string)
+        {
+            continue;
+        }
+        names.push(LocalizedName {
+            language,
+            name: string,
+        });
+    }
+    names
+}
+
+/// Returns the language of a name record.
+fn record_language(name: &Name<'_>, platform_id: u16, language_id: u16) -> Option<Language> {
+    const PLATFORM_MACINTOSH: u16 = 1;
+    const PLATFORM_WINDOWS: u16 = 3;
+    const MACINTOSH_ENGLISH: u16 = 0;
+    const FIRST_LANG_TAG: u16 = 0x8000;
+
+    if language_id >= FIRST_LANG_TAG {
+        // Version 1 naming tables give languages as BCP 47 tags.
+        let record = name
+            .lang_tag_record()?
+            .get(usize::from(language_id - FIRST_LANG_TAG))?;
+        let tag: String = record.lang_tag(name.string_data()).ok()?.chars().collect();
+        return Language::parse(&tag).ok();
+    }
+    let tag = match platform_id {
+        PLATFORM_MACINTOSH if language_id == MACINTOSH_ENGLISH => "en",
+        PLATFORM_WINDOWS => {
+            let idx = WINDOWS_LANGUAGES
+                .binary_search_by_key(&language_id, |(id, _)| *id)
+                .ok()?;
+            WINDOWS_LANGUAGES[idx].1
+        }
+        _ => return None,
+    };
+    Language::parse(tag).ok()
+}
+
+/// Windows language identifiers of common languages and their BCP 47 tags,
+/// sorted by identifier.
+const WINDOWS_LANGUAGES: &[(u16, &str)] = &[
+    (0x0401, "ar-SA"),
+    (0x0402, "bg-BG"),
+    (0x0403, "ca-ES"),
+    (0x0404, "zh-TW"),
+    (0x0405, "cs-CZ"),
+    (0x0406, "da-DK"),
+    (0x0407, "de-DE"),
+    (0x0408, "el-GR"),
+    (0x0409, "en-US"),
+    (0x040A, "es-ES"),
+    (0x040B, "fi-FI"),
+    (0x040C, "fr-FR"),
+    (0x040D, "he-IL"),
+    (0x040E, "hu-HU"),
+    (0x040F, "is-IS"),
+    (0x0410, "it-IT"),
+    (0x0411, "ja-JP"),
+    (0x0412, "ko-KR"),
+    (0x0413, "nl-NL"),
+    (0x0414, "nb-NO"),
+    (0x0415, "pl-PL"),
+    (0x0416, "pt-BR"),
+    (0x0418, "ro-RO"),
+    (0x0419, "ru-RU"),
+    (0x041A, "hr-HR"),
+    (0x041B, "sk-SK"),
+    (0x041C, "sq-AL"),
+    (0x041D, "sv-SE"),
+    (0x041E, "th-TH"),
+    (0x041F, "tr-TR"),
+    (0x0420, "ur-PK"),
+    (0x0421, "id-ID"),
+    (0x0422, "uk-UA"),
+    (0x0423, "be-BY"),
+    (0x0424, "sl-SI"),
+    (0x0425, "et-EE"),
+    (0x0426, "lv-LV"),
+    (0x0427, "lt-LT"),
+    (0x0429, "fa-IR"),
+    (0x042A, "vi-VN"),
+    (0x042B, "hy-AM"),
+    (0x042D, "eu-ES"),
+    (0x042F, "mk-MK"),
+    (0x0436, "af-ZA"),
+    (0x0437, "ka-GE"),
+    (0x0439, "hi-IN"),
+    (0x043E, "ms-MY"),
+    (0x0441, "sw-KE"),
+    (0x0445, "bn-IN"),
+    (0x0449, "ta-IN"),
+    (0x044A, "te-IN"),
+    (0x0456, "gl-ES"),
+    (0x0804, "zh-CN"),
+    (0x0807, "de-CH"),
+    (0x0809, "en-GB"),
+    (0x080A, "es-MX"),
+    (0x080C, "fr-BE"),
+    (0x0816, "pt-PT"),
+    (0x0C04, "zh-HK"),
+    (0x0C07, "de-AT"),
+    (0x0C09, "en-AU"),
+    (0x0C0A, "es-ES"),
+    (0x0C0C, "fr-CA"),
+    (0x1004, "zh-SG"),
+    (0x1009, "en-CA"),
+    (0x100C, "fr-CH"),
+    (0x1404, "zh-MO"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{FontMetadata, WINDOWS_LANGUAGES};
+    use crate::{FontStyle, FontWeight, FontWidth};
+    use parlance::Tag;
+    use read_fonts::FontRef;
+
+    #[test]
+    fn windows_languages_are_sorted() {
+        assert!(
+            WINDOWS_LANGUAGES
+                .windows(2)
+                .all(|pair| pair[0].0 < pair[1].0)
+        );
+    }
+
+    #[test]
+    fn named_instances_of_variable_font() {
+        let data = include_bytes!(
+            "../../parley_dev/assets/fonts/roboto_fonts/RobotoFlex-VariableFont.ttf"
+        );
+        let font = FontRef::new(data).unwrap();
+        let metadata = FontMetadata::from_font_ref(&font);
+        assert_eq!(
+            metadata.family_names()[0].name,
+            "Roboto Flex",
+            "the family name should be read"
+        );
+        let instances = metadata.named_instances();
+        assert!(!instances.is_empty(), "Roboto Flex has named instances");
+        let bold = instances
+            .iter()
+            .find(|instance| instance.name() == Some("Bold"))
+            .expect("Roboto Flex has a bold instance");
+        assert_eq!(bold.weight(), Some(FontWeight::BOLD));
+        assert_eq!(bold.width(), Some(FontWidth::NORMAL));
+        assert_eq!(bold.style(), Some(FontStyle::Normal));
+        assert_eq!(
+            bold.coords.len(),
+            font_axis_count(&font),
+            "an instance has a coordinate for each axis"
+        );
+        let bold_italic = instances
+            .iter()
+            .find(|instance| instance.name() == Some("Bold Italic"))
+            .expect("Roboto Flex has a bold italic instance");
+        assert_eq!(bold_italic.style(), Some(FontStyle::Oblique(Some(10.0))));
+
+        let regular = metadata
+            .axis_values()
+            .iter()
+            .find(|value| value.tag == Tag::new(b"wght") && value.value == 400.0)
+            .expect("Roboto Flex names its weights");
+        assert_eq!(regular.name(), Some("Regular"));
+        assert!(regular.elidable);
+    }
+
+    #[test]
+    fn static_font_has_no_instances() {
+        let data = include_bytes!("../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+        let font = FontRef::new(data).unwrap();
+        let metadata = FontMetadata::from_font_ref(&font);
+        assert_eq!(metadata.family_names()[0].name, "Roboto");
+        assert!(metadata.named_instances().is_empty());
+        assert!(metadata.axis_values().is_empty());
+    }
+
+    fn font_axis_count(font: &FontRef<'_>) -> usize {
+        use read_fonts::TableProvider as _;
+        font.fvar().unwrap().axes().unwrap().len()
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Summary of the color palettes of a font.
+
+use read_fonts::{FontRef, TableProvider};
+
+/// Flag of the `CPAL` palette types marking a palette as usable on light backgrounds.
+const USABLE_WITH_LIGHT_BACKGROUND: u32 = 0x0001;
+/// Flag of the `CPAL` palette types marking a palette as usable on dark backgrounds.
+const USABLE_WITH_DARK_BACKGROUND: u32 = 0x0002;
+
+/// The color palettes of a font, as defined by its `CPAL` table.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ColorPalettes {
+    len: u16,
+    entries: u16,
+    light: Option<u16>,
+    dark: Option<u16>,
+}
+
+impl ColorPalettes {
+    /// Reads the palettes of the font at `index` in `font_data`.
+    ///
+    /// Returns `None` if the font has no `CPAL` table.
+    pub fn new(font_data: &[u8], index: u32) -> Option<Self> {
+        let font = FontRef::from_index(font_data, index).ok()?;
+        let cpal = font.cpal().ok()?;
+        let mut palettes = Self {
+            len: cpal.num_palettes(),
+            entries: cpal.num_palette_entries(),
+            light: None,
+            dark: None,
+        };
+        // Palette types were added in version 1 of the table.
+        if let Some(Ok(types)) = cpal.palette_types_array() {
+            let first_with = |flag: u32| {
+                types
+                    .iter()
+                    .position(|ty| ty.get() & flag != 0)
+                    .and_then(|idx| u16::try_from(idx).ok())
+            };
+            palettes.light = first_with(USABLE_WITH_LIGHT_BACKGROUND);
+            palettes.dark = first_with(USABLE_WITH_DARK_BACKGROUND);
+        }
+        Some(palettes)
+    }
+
+    /// Returns the number of palettes.
+    pub fn len(&self) -> u16 {
+        self.len
+    }
+
+    /// Returns `true` if the font has no palettes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of colors in each palette.
+    pub fn num_entries(&self) -> u16 {
+        self.entries
+    }
+
+    /// Returns the index of the first palette usable on light backgrounds, if any.
+    pub fn light(&self) -> Option<u16> {
+        self.light
+    }
+
+    /// Returns the index of the first palette usable on dark backgrounds, if any.
+    pub fn dark(&self) -> Option<u16> {
+        self.dark
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorPalettes;
+    use alloc::vec::Vec;
+
+    /// Builds a font containing only a version 1 `CPAL` table with the given palette types.
+    fn font_with_palettes(types: &[u32]) -> Vec<u8> {
+        let num_palettes = u16::try_from(types.len()).unwrap();
+        let mut cpal = Vec::new();
+        cpal.extend_from_slice(&1_u16.to_be_bytes());
+        // One entry per palette, each with its own color record.
+        cpal.extend_from_slice(&1_u16.to_be_bytes());
+        cpal.extend_from_slice(&num_palettes.to_be_bytes());
+        cpal.extend_from_slice(&num_palettes.to_be_bytes());
+        let records_offset = 12 + 2 * u32::from(num_palettes) + 12;
+        let types_offset = records_offset + 4 * u32::from(num_palettes);
+        cpal.extend_from_slice(&records_offset.to_be_bytes());
+        for idx in 0..num_palettes {
+            cpal.extend_from_slice(&idx.to_be_bytes());
+        }
+        cpal.extend_from_slice(&types_offset.to_be_bytes());
+        cpal.extend_from_slice(&0_u32.to_be_bytes());
+        cpal.extend_from_slice(&0_u32.to_be_bytes());
+        for _ in 0..num_palettes {
+            cpal.extend_from_slice(&[0, 0, 0, 0xFF]);
+        }
+        for ty in types {
+            cpal.extend_from_slice(&ty.to_be_bytes());
+        }
+
+        let mut font = Vec::new();
+        font.extend_from_slice(&0x0001_0000_u32.to_be_bytes());
+        font.extend_from_slice(&1_u16.to_be_bytes());
+        font.extend_from_slice(&[0; 6]);
+        font.extend_from_slice(b"CPAL");
+        font.extend_from_slice(&0_u32.to_be_bytes());
+        font.extend_from_slice(&28_u32.to_be_bytes());
+        font.extend_from_slice(&u32::try_from(cpal.len()).unwrap().to_be_bytes());
+        font.extend_from_slice(&cpal);
+        font
+    }
+
+    #[test]
+    fn palette_types() {
+        let font = font_with_palettes(&[0, 0x2, 0x1, 0x3]);
+        let palettes = ColorPalettes::new(&font, 0).unwrap();
+        assert_eq!(palettes.len(), 4);
+        assert_eq!(palettes.num_entries(), 1);
+        assert_eq!(palettes.light(), Some(2));
+        assert_eq!(palettes.dark(), Some(1));
+    }
+
+    #[test]
+    fn palettes_without_types() {
+        let font = font_with_palettes(&[0, 0]);
+        let palettes = ColorPalettes::new(&font, 0).unwrap();
+        assert_eq!(palettes.len(), 2);
+        assert_eq!(palettes.light(), None);
+        assert_eq!(palettes.dark(), None);
+    }
+
+    #[test]
+    fn font_without_palettes() {
+        let font = include_bytes!("../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+        assert_eq!(ColorPalettes::new(font, 0), None);
+    }
+}
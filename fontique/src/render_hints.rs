@@ -0,0 +1,76 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering settings configured for fonts by the system.
+
+/// How a font should be rasterized, as configured by the user's desktop.
+///
+/// These come from the rendering properties fontconfig resolves for each
+/// font on Linux and FreeBSD, so that renderers can follow the user's
+/// settings. Each setting is `None` when the system doesn't configure it,
+/// which leaves it to the renderer.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RenderHints {
+    /// Whether glyphs are antialiased.
+    pub antialias: Option<bool>,
+    /// Whether glyph outlines are hinted.
+    pub hinting: Option<bool>,
+    /// How strongly glyph outlines are hinted.
+    pub hint_style: Option<HintStyle>,
+    /// Whether the automatic hinter is used instead of the font's own
+    /// instructions.
+    pub autohint: Option<bool>,
+    /// The order of the subpixels of the display, for subpixel
+    /// antialiasing.
+    pub subpixel_order: Option<SubpixelOrder>,
+    /// The filter applied to reduce color fringes of subpixel antialiasing.
+    pub lcd_filter: Option<LcdFilter>,
+    /// Whether bitmaps embedded in outline fonts are used instead of the
+    /// outlines.
+    pub embedded_bitmaps: Option<bool>,
+}
+
+/// How strongly glyph outlines are hinted.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HintStyle {
+    /// No hinting.
+    None,
+    /// Hinting in the vertical direction only.
+    Slight,
+    /// Moderate hinting.
+    Medium,
+    /// Hinting for maximum contrast.
+    Full,
+}
+
+/// The layout of the subpixels of a display.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubpixelOrder {
+    /// Subpixel antialiasing is disabled.
+    None,
+    /// Red, green and blue subpixels from left to right.
+    Rgb,
+    /// Blue, green and red subpixels from left to right.
+    Bgr,
+    /// Red, green and blue subpixels from top to bottom.
+    VerticalRgb,
+    /// Blue, green and red subpixels from top to bottom.
+    VerticalBgr,
+}
+
+/// The filter applied to subpixel antialiased glyphs.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LcdFilter {
+    /// No filtering.
+    None,
+    /// The renderer's default filter.
+    Default,
+    /// A lighter filter, keeping glyphs sharper.
+    Light,
+    /// The filter of older `FreeType` versions.
+    Legacy,
+}
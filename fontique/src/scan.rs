@@ -6,9 +6,11 @@
 #![allow(dead_code, unused_imports)]
 
 use super::{
+    CharmapIndex, FontStyle, FontWeight, FontWidth,
     family::{FamilyId, FamilyInfo},
     family_name::{FamilyName, FamilyNameMap},
-    font::FontInfo,
+    font::{AxisVec, FontInfo},
+    source::SourceInfo,
 };
 use alloc::string::String;
 use alloc::vec;
@@ -20,7 +22,14 @@ use read_fonts::{
 };
 use smallvec::SmallVec;
 #[cfg(feature = "std")]
-use {super::source::SourcePathMap, std::path::Path};
+use {
+    super::{
+        coverage::ScriptCoverage,
+        scan_cache::ScanCache,
+        source::{SourceId, SourcePathMap},
+    },
+    std::path::Path,
+};
 
 use alloc::vec::Vec;
 
@@ -32,14 +41,51 @@ pub struct ScannedCollection {
     pub postscript_names: HashMap<String, FamilyId>,
     pub data_paths: SourcePathMap,
     pub families: HashMap<FamilyId, FamilyInfo>,
+    /// The script coverage of each font, by source and index.
+    pub(crate) script_coverage: HashMap<(SourceId, u32), ScriptCoverage>,
 }
 
 #[cfg(feature = "std")]
 impl ScannedCollection {
     /// Creates a new collection by scanning the given paths for
     /// font files.
-    pub fn from_paths(paths: impl IntoIterator<Item = impl AsRef<Path>>, max_depth: u32) -> Self {
-        scan_collection(paths, max_depth)
+    ///
+    /// Files that are unchanged since they were stored in `scan_cache` are
+    /// not parsed again.
+    pub fn from_paths(
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        max_depth: u32,
+        scan_cache: Option<&mut ScanCache>,
+    ) -> Self {
+        scan_collection(paths, max_depth, scan_cache)
+    }
+}
+
+#[cfg(feature = "std")]
+/// Metadata of a font discovered in a file, as stored in a [`ScanCache`].
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct ScannedFontInfo {
+    pub(crate) index: u32,
+    /// All family names of the font, starting with the English or first one.
+    pub(crate) family_names: Vec<String>,
+    pub(crate) postscript_name: Option<String>,
+    pub(crate) attributes: (FontWidth, FontStyle, FontWeight),
+    pub(crate) axes: AxisVec,
+    pub(crate) charmap_index: CharmapIndex,
+    pub(crate) script_coverage: ScriptCoverage,
+}
+
+#[cfg(feature = "std")]
+impl ScannedFontInfo {
+    /// Creates the font info for this font loaded from `source`.
+    pub(crate) fn font_info(&self, source: SourceInfo) -> FontInfo {
+        FontInfo::from_parts(
+            source,
+            self.index,
+            self.attributes,
+            self.axes.clone(),
+            self.charmap_index,
+        )
     }
 }
 
@@ -66,9 +112,9 @@ pub fn scan_paths(
     max_depth: u32,
     mut f: impl FnMut(&ScannedFont<'_>),
 ) {
-    for path in paths {
-        scan_path_impl(path.as_ref(), max_depth, &mut f, 0);
-    }
+    visit_paths(paths, max_depth, |path| {
+        scan_file(path, &mut f);
+    });
 }
 
 /// Scans a memory buffer and invokes the given function for each font
@@ -79,63 +125,123 @@ pub fn scan_memory<'a>(buf: &'a [u8], mut f: impl FnMut(&ScannedFont<'a>)) {
 }
 
 #[cfg(feature = "std")]
-fn scan_collection(
+/// Invokes the given function for each file in the given paths.
+pub(crate) fn visit_paths(
     paths: impl IntoIterator<Item = impl AsRef<Path>>,
     max_depth: u32,
-) -> ScannedCollection {
-    let mut collection = ScannedCollection::default();
-    let mut families: HashMap<FamilyId, (FamilyName, SmallVec<[FontInfo; 4]>)> = HashMap::default();
-    let mut postscript_name = String::default();
+    mut f: impl FnMut(&Path),
+) {
+    for path in paths {
+        visit_path_impl(path.as_ref(), max_depth, &mut f, 0);
+    }
+}
+
+#[cfg(feature = "std")]
+/// Scans a single font file, returning the metadata of each font it contains.
+///
+/// Returns `None` if the file could not be read.
+pub(crate) fn scan_file_info(path: &Path) -> Option<Vec<ScannedFontInfo>> {
+    let mut fonts = vec![];
     let mut name_pool = vec![];
-    let mut names = vec![];
-    scan_paths(paths, max_depth, |scanned_font| {
-        let Some(path) = &scanned_font.path else {
-            return;
-        };
-        name_pool.append(&mut names);
-        postscript_name.clear();
+    scan_file(path, &mut |scanned_font| {
+        let mut family_names = vec![];
         if !all_names(
             &scanned_font.name_table,
             NameId::TYPOGRAPHIC_FAMILY_NAME,
             &mut name_pool,
-            &mut names,
+            &mut family_names,
         ) && !all_names(
             &scanned_font.name_table,
             NameId::FAMILY_NAME,
             &mut name_pool,
-            &mut names,
+            &mut family_names,
         ) {
             return;
         }
-        let postscript_chars = scanned_font
-            .english_or_first_name(NameId::POSTSCRIPT_NAME)
-            .map(|name| name.chars());
-        if let Some(chars) = postscript_chars {
-            postscript_name.extend(chars);
-        } else {
-            return;
-        }
-        let data = collection.data_paths.get_or_insert(path);
-        let Some(font) = FontInfo::from_font_ref(&scanned_font.font, data, scanned_font.index)
-        else {
+        // It's probably not useful to retain fonts that don't have
+        // a valid cmap so skip them here.
+        let Some(charmap_index) = CharmapIndex::new(&scanned_font.font) else {
             return;
         };
-        let [first_name, other_names @ ..] = names.as_slice() else {
-            return;
-        };
-        let name = collection.family_names.get_or_insert(first_name);
-        for other_name in other_names {
-            collection.family_names.add_alias(name.id(), other_name);
+        let script_coverage = charmap_index
+            .charmap(scanned_font.font.data().as_bytes())
+            .map(|charmap| ScriptCoverage::new(&charmap))
+            .unwrap_or_default();
+        let postscript_name = scanned_font
+            .english_or_first_name(NameId::POSTSCRIPT_NAME)
+            .map(|name| name.chars().collect());
+        fonts.push(ScannedFontInfo {
+            index: scanned_font.index,
+            family_names,
+            postscript_name,
+            attributes: crate::font::read_attributes(&scanned_font.font),
+            axes: crate::font::read_axes(&scanned_font.font),
+            charmap_index,
+            script_coverage,
+        });
+    })?;
+    Some(fonts)
+}
+
+#[cfg(feature = "std")]
+/// Invokes the given function with the fonts of each file in the given paths,
+/// taking them from `scan_cache` for files that haven't changed.
+pub(crate) fn scan_paths_info(
+    paths: impl IntoIterator<Item = impl AsRef<Path>>,
+    max_depth: u32,
+    mut scan_cache: Option<&mut ScanCache>,
+    mut f: impl FnMut(&Path, &[ScannedFontInfo]),
+) {
+    visit_paths(paths, max_depth, |path| match scan_cache.as_deref_mut() {
+        Some(scan_cache) => {
+            if let Some(fonts) = scan_cache.fonts(path) {
+                f(path, fonts);
+            }
+        }
+        None => {
+            if let Some(fonts) = scan_file_info(path) {
+                f(path, &fonts);
+            }
         }
-        collection
-            .postscript_names
-            .insert(postscript_name.clone(), name.id());
-        families
-            .entry(name.id())
-            .or_insert_with(|| (name.clone(), SmallVec::default()))
-            .1
-            .push(font);
     });
+}
+
+#[cfg(feature = "std")]
+fn scan_collection(
+    paths: impl IntoIterator<Item = impl AsRef<Path>>,
+    max_depth: u32,
+    scan_cache: Option<&mut ScanCache>,
+) -> ScannedCollection {
+    let mut collection = ScannedCollection::default();
+    let mut families: HashMap<FamilyId, (FamilyName, SmallVec<[FontInfo; 4]>)> = HashMap::default();
+    let add_fonts = |path: &Path, fonts: &[ScannedFontInfo]| {
+        for scanned_font in fonts {
+            let Some(postscript_name) = &scanned_font.postscript_name else {
+                continue;
+            };
+            let [first_name, other_names @ ..] = scanned_font.family_names.as_slice() else {
+                continue;
+            };
+            let font = scanned_font.font_info(collection.data_paths.get_or_insert(path));
+            collection.script_coverage.insert(
+                (font.source().id(), font.index()),
+                scanned_font.script_coverage.clone(),
+            );
+            let name = collection.family_names.get_or_insert(first_name);
+            for other_name in other_names {
+                collection.family_names.add_alias(name.id(), other_name);
+            }
+            collection
+                .postscript_names
+                .insert(postscript_name.clone(), name.id());
+            families
+                .entry(name.id())
+                .or_insert_with(|| (name.clone(), SmallVec::default()))
+                .1
+                .push(font);
+        }
+    };
+    scan_paths_info(paths, max_depth, scan_cache, add_fonts);
     collection.families.extend(
         families
             .drain()
@@ -145,10 +251,10 @@ fn scan_collection(
 }
 
 #[cfg(feature = "std")]
-fn scan_path_impl(
+fn visit_path_impl(
     path: &Path,
     max_depth: u32,
-    f: &mut impl FnMut(&ScannedFont<'_>),
+    f: &mut impl FnMut(&Path),
     depth: u32,
 ) -> Option<()> {
     let metadata = path.metadata().ok()?;
@@ -157,16 +263,25 @@ fn scan_path_impl(
             return None;
         }
         for entry in std::fs::read_dir(path).ok()?.filter_map(|entry| entry.ok()) {
-            scan_path_impl(entry.path().as_path(), max_depth, f, depth + 1);
+            visit_path_impl(entry.path().as_path(), max_depth, f, depth + 1);
         }
     } else {
-        let file = std::fs::File::open(path).ok()?;
-        let mapped = unsafe { memmap2::Mmap::map(&file) }.ok()?;
-        scan_memory_impl(&mapped, Some(path), f);
+        f(path);
     }
     Some(())
 }
 
+#[cfg(feature = "std")]
+fn scan_file(path: &Path, f: &mut impl FnMut(&ScannedFont<'_>)) -> Option<()> {
+    let file = std::fs::File::open(path).ok()?;
+    let mapped = unsafe { memmap2::Mmap::map(&file) }.ok()?;
+    match crate::woff::decompress(&mapped) {
+        Some(sfnt) => scan_memory_impl(&sfnt, Some(path), f),
+        None => scan_memory_impl(&mapped, Some(path), f),
+    };
+    Some(())
+}
+
 #[cfg(feature = "std")]
 type ScanMemoryPathType<'a> = Option<&'a Path>;
 
@@ -0,0 +1,472 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Persistent cache of the metadata of scanned font files.
+
+use super::{
+    CharmapIndex, FontStyle, FontWeight, FontWidth, Script,
+    coverage::ScriptCoverage,
+    font::{AxisInfo, AxisVec},
+    scan::{ScannedFontInfo, scan_file_info},
+};
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+use hashbrown::HashMap;
+use read_fonts::types::Tag;
+use std::{
+    fs::Metadata,
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::UNIX_EPOCH,
+};
+
+/// Identifies the cache file format.
+const MAGIC: [u8; 4] = *b"FQSC";
+/// Version of the cache file format, bumped whenever the layout changes.
+const VERSION: u32 = 2;
+
+/// Scan cache shared between a collection and its system font backend.
+pub(crate) type SharedScanCache = Arc<Mutex<ScanCache>>;
+
+/// Cache of the fonts found in each scanned file, stored on disk.
+///
+/// Files are rescanned when their modification time or size changes. The
+/// cache is written with [`Self::flush`], and as a fallback when dropped, if
+/// any file was scanned.
+pub(crate) struct ScanCache {
+    path: PathBuf,
+    files: HashMap<Arc<Path>, CachedFile>,
+    dirty: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct CachedFile {
+    stamp: Option<FileStamp>,
+    fonts: Vec<ScannedFontInfo>,
+}
+
+/// Modification time and size of a file, used to detect changes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct FileStamp {
+    secs: u64,
+    nanos: u32,
+    len: u64,
+}
+
+impl FileStamp {
+    /// Returns the stamp of the file at `path`, if its metadata can be read.
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        Self::new(&path.metadata().ok()?)
+    }
+
+    fn new(metadata: &Metadata) -> Option<Self> {
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            secs: modified.as_secs(),
+            nanos: modified.subsec_nanos(),
+            len: metadata.len(),
+        })
+    }
+}
+
+impl ScanCache {
+    /// Opens the cache stored at `path`.
+    ///
+    /// A missing, unreadable or outdated cache file results in an empty cache
+    /// that replaces the file when written.
+    pub(crate) fn open(path: &Path) -> Self {
+        let files = std::fs::read(path)
+            .ok()
+            .and_then(|data| decode(&data))
+            .unwrap_or_default();
+        Self {
+            path: path.into(),
+            files,
+            dirty: false,
+        }
+    }
+
+    /// Opens the cache stored at `path`, to be shared.
+    pub(crate) fn open_shared(path: &Path) -> SharedScanCache {
+        Arc::new(Mutex::new(Self::open(path)))
+    }
+
+    /// Returns the fonts contained in the file at `path`, scanning it if it
+    /// isn't cached or has changed since it was cached.
+    ///
+    /// Returns `None` if the file could not be read.
+    pub(crate) fn fonts(&mut self, path: &Path) -> Option<&[ScannedFontInfo]> {
+        let Some(stamp) = path
+            .metadata()
+            .ok()
+            .map(|metadata| FileStamp::new(&metadata))
+        else {
+            if self.files.remove(path).is_some() {
+                self.dirty = true;
+            }
+            return None;
+        };
+        let is_fresh = self
+            .files
+            .get(path)
+            .is_some_and(|file| file.stamp.is_some() && file.stamp == stamp);
+        if !is_fresh {
+            let Some(fonts) = scan_file_info(path) else {
+                if self.files.remove(path).is_some() {
+                    self.dirty = true;
+                }
+                return None;
+            };
+            self.files.insert(path.into(), CachedFile { stamp, fonts });
+            self.dirty = true;
+        }
+        self.files.get(path).map(|file| file.fonts.as_slice())
+    }
+
+    /// Writes the cache to its file.
+    ///
+    /// The cache is written to a temporary file first, so concurrent readers
+    /// never observe a partially written cache.
+    pub(crate) fn save(&mut self) -> io::Result<()> {
+        // Forget files that were removed since they were scanned.
+        self.files.retain(|path, _| path.exists());
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(format!(".{}.tmp", std::process::id()));
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&tmp_path, encode(&self.files))?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Writes the cache to its file if any file was scanned since it was last
+    /// written.
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        if self.dirty { self.save() } else { Ok(()) }
+    }
+}
+
+impl Drop for ScanCache {
+    fn drop(&mut self) {
+        // Failing to write the cache only means the next start is slower.
+        let _ = self.flush();
+    }
+}
+
+fn encode(files: &HashMap<Arc<Path>, CachedFile>) -> Vec<u8> {
+    let mut w = Writer::default();
+    w.bytes(&MAGIC);
+    w.u32(VERSION);
+    let files: Vec<_> = files
+        .iter()
+        .filter_map(|(path, file)| Some((path_to_bytes(path)?, file.stamp?, &file.fonts)))
+        .collect();
+    w.count(files.len());
+    for (path, stamp, fonts) in files {
+        w.count(path.len());
+        w.bytes(path);
+        w.u64(stamp.secs);
+        w.u32(stamp.nanos);
+        w.u64(stamp.len);
+        w.count(fonts.len());
+        for font in fonts {
+            w.u32(font.index);
+            w.count(font.family_names.len());
+            for name in &font.family_names {
+                w.str(name);
+            }
+            match &font.postscript_name {
+                Some(name) => {
+                    w.u8(1);
+                    w.str(name);
+                }
+                None => w.u8(0),
+            }
+            let (width, style, weight) = font.attributes;
+            w.f32(width.ratio());
+            match style {
+                FontStyle::Normal => w.u8(0),
+                FontStyle::Italic => w.u8(1),
+                FontStyle::Oblique(None) => w.u8(2),
+                FontStyle::Oblique(Some(angle)) => {
+                    w.u8(3);
+                    w.f32(angle);
+                }
+            }
+            w.f32(weight.value());
+            w.count(font.axes.len());
+            for axis in &font.axes {
+                w.bytes(&axis.tag.to_be_bytes());
+                w.f32(axis.min);
+                w.f32(axis.max);
+                w.f32(axis.default);
+            }
+            let charmap = font.charmap_index;
+            w.u32(charmap.subtable_offset);
+            w.u8(u8::from(charmap.is_symbol) | (u8::from(charmap.is_mac_roman) << 1));
+            let counts = font.script_coverage.counts();
+            w.count(counts.len());
+            for (script, count) in counts {
+                w.bytes(&script.to_bytes());
+                w.u8(*count);
+            }
+        }
+    }
+    w.0
+}
+
+fn decode(data: &[u8]) -> Option<HashMap<Arc<Path>, CachedFile>> {
+    let mut r = Reader(data);
+    if r.bytes(MAGIC.len())? != MAGIC || r.u32()? != VERSION {
+        return None;
+    }
+    let num_files = r.count()?;
+    let mut files = HashMap::with_capacity(num_files.min(r.0.len()));
+    for _ in 0..num_files {
+        let path_len = r.count()?;
+        let path = path_from_bytes(r.bytes(path_len)?)?;
+        let stamp = FileStamp {
+            secs: r.u64()?,
+            nanos: r.u32()?,
+            len: r.u64()?,
+        };
+        let num_fonts = r.count()?;
+        let mut fonts = Vec::new();
+        for _ in 0..num_fonts {
+            let index = r.u32()?;
+            let num_names = r.count()?;
+            let family_names = (0..num_names)
+                .map(|_| r.str())
+                .collect::<Option<Vec<_>>>()?;
+            let postscript_name = match r.u8()? {
+                0 => None,
+                _ => Some(r.str()?),
+            };
+            let width = FontWidth::from_ratio(r.f32()?);
+            let style = match r.u8()? {
+                0 => FontStyle::Normal,
+                1 => FontStyle::Italic,
+                2 => FontStyle::Oblique(None),
+                _ => FontStyle::Oblique(Some(r.f32()?)),
+            };
+            let weight = FontWeight::new(r.f32()?);
+            let num_axes = r.count()?;
+            let axes = (0..num_axes)
+                .map(|_| {
+                    Some(AxisInfo {
+                        tag: Tag::from_be_bytes(r.array()?),
+                        min: r.f32()?,
+                        max: r.f32()?,
+                        default: r.f32()?,
+                    })
+                })
+                .collect::<Option<AxisVec>>()?;
+            let subtable_offset = r.u32()?;
+            let flags = r.u8()?;
+            let num_scripts = r.count()?;
+            let counts = (0..num_scripts)
+                .map(|_| Some((Script::from_bytes(r.array()?), r.u8()?)))
+                .collect::<Option<Vec<_>>>()?;
+            fonts.push(ScannedFontInfo {
+                index,
+                family_names,
+                postscript_name,
+                attributes: (width, style, weight),
+                axes,
+                charmap_index: CharmapIndex {
+                    subtable_offset,
+                    is_symbol: flags & 1 != 0,
+                    is_mac_roman: flags & 2 != 0,
+                },
+                script_coverage: ScriptCoverage::from_counts(counts),
+            });
+        }
+        files.insert(
+            path,
+            CachedFile {
+                stamp: Some(stamp),
+                fonts,
+            },
+        );
+    }
+    Some(files)
+}
+
+#[cfg(unix)]
+fn path_to_bytes(path: &Path) -> Option<&[u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn path_to_bytes(path: &Path) -> Option<&[u8]> {
+    // Paths that aren't valid UTF-8 are simply not cached.
+    path.to_str().map(str::as_bytes)
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> Option<Arc<Path>> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(Path::new(std::ffi::OsStr::from_bytes(bytes)).into())
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> Option<Arc<Path>> {
+    Some(Path::new(core::str::from_utf8(bytes).ok()?).into())
+}
+
+/// Little endian encoder for the cache file.
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn count(&mut self, count: usize) {
+        self.u32(u32::try_from(count).unwrap_or(u32::MAX));
+    }
+
+    fn str(&mut self, value: &str) {
+        self.count(value.len());
+        self.bytes(value.as_bytes());
+    }
+}
+
+/// Little endian decoder for the cache file.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let (bytes, rest) = self.0.split_at_checked(len)?;
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.bytes(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.array::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.array().map(f32::from_le_bytes)
+    }
+
+    fn count(&mut self) -> Option<usize> {
+        usize::try_from(self.u32()?).ok()
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let len = self.count()?;
+        let bytes = self.bytes(len)?;
+        Some(core::str::from_utf8(bytes).ok()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScanCache, decode, encode};
+    use crate::Script;
+    use alloc::format;
+    use std::path::PathBuf;
+
+    const ROBOTO: &[u8] =
+        include_bytes!("../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+    const ROBOTO_FLEX: &[u8] =
+        include_bytes!("../../parley_dev/assets/fonts/roboto_fonts/RobotoFlex-VariableFont.ttf");
+
+    /// Creates an empty directory for a test, removing any leftovers of a
+    /// previous run.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("fontique-scan-cache-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn cache_round_trip() {
+        let dir = test_dir("round-trip");
+        let font_path = dir.join("Roboto-Regular.ttf");
+        let cache_path = dir.join("fonts.cache");
+        std::fs::write(&font_path, ROBOTO).unwrap();
+
+        let mut cache = ScanCache::open(&cache_path);
+        let fonts = cache.fonts(&font_path).unwrap().to_vec();
+        assert_eq!(fonts.len(), 1);
+        assert_eq!(fonts[0].family_names[0], "Roboto");
+        assert_eq!(fonts[0].postscript_name.as_deref(), Some("Roboto-Regular"));
+        let coverage = &fonts[0].script_coverage;
+        assert!(coverage.covered(Script::from_bytes(*b"Cyrl")).is_some());
+        assert!(coverage.covered(Script::from_bytes(*b"Arab")).is_none());
+        assert_eq!(decode(&encode(&cache.files)), Some(cache.files.clone()));
+        drop(cache);
+
+        let mut cache = ScanCache::open(&cache_path);
+        assert_eq!(cache.fonts(&font_path).unwrap(), fonts);
+        assert!(!cache.dirty, "an unchanged file shouldn't be rescanned");
+
+        drop(cache);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn changed_file_is_rescanned() {
+        let dir = test_dir("changed");
+        let font_path = dir.join("font.ttf");
+        let cache_path = dir.join("fonts.cache");
+        std::fs::write(&font_path, ROBOTO).unwrap();
+        ScanCache::open(&cache_path).fonts(&font_path).unwrap();
+
+        std::fs::write(&font_path, ROBOTO_FLEX).unwrap();
+        let mut cache = ScanCache::open(&cache_path);
+        let fonts = cache.fonts(&font_path).unwrap();
+        assert_eq!(fonts[0].family_names[0], "Roboto Flex");
+        assert!(!fonts[0].axes.is_empty());
+        assert!(cache.dirty);
+
+        std::fs::remove_file(&font_path).unwrap();
+        assert!(cache.fonts(&font_path).is_none());
+        assert!(cache.files.is_empty());
+
+        drop(cache);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_cache_is_ignored() {
+        assert_eq!(decode(b"FQSC"), None);
+        assert_eq!(decode(b"not a cache file"), None);
+    }
+}
@@ -250,5 +250,8 @@ impl<T> From<&Entry<Blob<T>>> for Entry<WeakBlob<T>> {
 pub(crate) fn load_blob(path: &Path) -> Option<Blob<u8>> {
     let file = std::fs::File::open(path).ok()?;
     let mapped = unsafe { memmap2::Mmap::map(&file).ok()? };
+    if let Some(sfnt) = crate::woff::decompress(&mapped) {
+        return Some(Blob::new(Arc::new(sfnt)));
+    }
     Some(Blob::new(Arc::new(mapped)))
 }
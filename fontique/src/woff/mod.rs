@@ -0,0 +1,59 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Decoding of WOFF and WOFF2 font files.
+//!
+//! Both formats are compressed wrappers around sfnt (TrueType or OpenType)
+//! data. Fonts are decoded to sfnt data when they are registered or loaded,
+//! so the rest of the crate only deals with sfnt data.
+
+#[cfg(any(feature = "woff", feature = "woff2"))]
+mod sfnt;
+#[cfg(feature = "woff")]
+mod woff1;
+#[cfg(feature = "woff2")]
+mod woff2;
+
+use alloc::vec::Vec;
+
+#[cfg(all(test, feature = "std"))]
+std::thread_local! {
+    /// The number of WOFF and WOFF2 files decoded on this thread, for tests.
+    pub(crate) static DECODES: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+/// Returns true if `data` is a WOFF or WOFF2 file that can be decoded with
+/// the enabled features.
+pub(crate) fn is_compressed(data: &[u8]) -> bool {
+    (cfg!(feature = "woff") && data.starts_with(b"wOFF"))
+        || (cfg!(feature = "woff2") && data.starts_with(b"wOF2"))
+}
+
+/// Decodes `data` to sfnt data if it's a WOFF or WOFF2 file.
+///
+/// Returns `None` if `data` isn't in a format supported by the enabled
+/// features, which includes sfnt data itself, or if it fails to decode.
+#[cfg(any(feature = "woff", feature = "woff2"))]
+pub(crate) fn decompress(data: &[u8]) -> Option<Vec<u8>> {
+    #[cfg(all(test, feature = "std"))]
+    if is_compressed(data) {
+        DECODES.with(|decodes| decodes.set(decodes.get() + 1));
+    }
+    #[cfg(feature = "woff")]
+    if data.starts_with(b"wOFF") {
+        return woff1::decompress(data);
+    }
+    #[cfg(feature = "woff2")]
+    if data.starts_with(b"wOF2") {
+        return woff2::decompress(data);
+    }
+    None
+}
+
+/// Decodes `data` to sfnt data if it's a WOFF or WOFF2 file.
+///
+/// Without the `woff` and `woff2` features, nothing is decoded.
+#[cfg(not(any(feature = "woff", feature = "woff2")))]
+pub(crate) fn decompress(_data: &[u8]) -> Option<Vec<u8>> {
+    None
+}
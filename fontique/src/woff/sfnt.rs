@@ -0,0 +1,125 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Reading of WOFF data and writing of the sfnt data it decodes to.
+
+use alloc::vec::Vec;
+
+/// Upper bound on the size of decoded font data, to guard against
+/// decompression bombs.
+pub(super) const MAX_SFNT_SIZE: usize = 256 * 1024 * 1024;
+
+/// Big-endian reader over a byte slice.
+#[derive(Clone)]
+pub(super) struct Reader<'a> {
+    pub(super) data: &'a [u8],
+    pub(super) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(super) fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    pub(super) fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    pub(super) fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    pub(super) fn i16(&mut self) -> Option<i16> {
+        Some(i16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    pub(super) fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+}
+
+/// A table of the sfnt being reconstructed.
+pub(super) struct SfntTable {
+    pub(super) tag: [u8; 4],
+    pub(super) data: Vec<u8>,
+}
+
+/// Assembles sfnt data from its flavor (`0x00010000` or `OTTO`) and tables.
+pub(super) fn write_sfnt(flavor: u32, mut tables: Vec<SfntTable>) -> Option<Vec<u8>> {
+    // The table directory must be sorted by tag for binary search.
+    tables.sort_unstable_by_key(|table| table.tag);
+    let num_tables = u16::try_from(tables.len()).ok()?;
+    let entry_selector = num_tables.checked_ilog2().unwrap_or(0) as u16;
+    let search_range = (1_u16 << entry_selector) * 16;
+    let range_shift = num_tables * 16 - search_range;
+
+    let header_len = 12 + 16 * tables.len();
+    let total_len = tables.iter().try_fold(header_len, |len, table| {
+        len.checked_add(padded_len(table.data.len()))
+    })?;
+    if total_len > MAX_SFNT_SIZE {
+        return None;
+    }
+    let mut sfnt = Vec::with_capacity(total_len);
+    sfnt.extend_from_slice(&flavor.to_be_bytes());
+    sfnt.extend_from_slice(&num_tables.to_be_bytes());
+    sfnt.extend_from_slice(&search_range.to_be_bytes());
+    sfnt.extend_from_slice(&entry_selector.to_be_bytes());
+    sfnt.extend_from_slice(&range_shift.to_be_bytes());
+    let mut offset = header_len;
+    for table in &tables {
+        sfnt.extend_from_slice(&table.tag);
+        sfnt.extend_from_slice(&table_checksum(table).to_be_bytes());
+        sfnt.extend_from_slice(&u32::try_from(offset).ok()?.to_be_bytes());
+        sfnt.extend_from_slice(&u32::try_from(table.data.len()).ok()?.to_be_bytes());
+        offset += padded_len(table.data.len());
+    }
+    for table in &tables {
+        sfnt.extend_from_slice(&table.data);
+        sfnt.resize(padded_len(sfnt.len()), 0);
+    }
+    Some(sfnt)
+}
+
+/// Returns `len` rounded up to a multiple of four, the alignment of sfnt tables.
+pub(super) fn padded_len(len: usize) -> usize {
+    len.next_multiple_of(4)
+}
+
+/// Computes the checksum of a table for the table directory.
+///
+/// The checksum of the head table is computed with its `checkSumAdjustment`
+/// field set to zero.
+fn table_checksum(table: &SfntTable) -> u32 {
+    let sum = checksum(&table.data);
+    if &table.tag == b"head"
+        && let Some(adjustment) = table.data.get(8..12)
+    {
+        let adjustment =
+            u32::from_be_bytes([adjustment[0], adjustment[1], adjustment[2], adjustment[3]]);
+        sum.wrapping_sub(adjustment)
+    } else {
+        sum
+    }
+}
+
+/// Computes the sfnt checksum of data.
+pub(super) fn checksum(data: &[u8]) -> u32 {
+    let mut chunks = data.chunks_exact(4);
+    let mut sum = chunks.by_ref().fold(0_u32, |sum, chunk| {
+        sum.wrapping_add(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+    });
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        let mut last = [0; 4];
+        last[..rest.len()].copy_from_slice(rest);
+        sum = sum.wrapping_add(u32::from_be_bytes(last));
+    }
+    sum
+}
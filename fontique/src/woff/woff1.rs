@@ -0,0 +1,143 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Decoding of [WOFF 1.0](https://www.w3.org/TR/WOFF/) files.
+
+use alloc::vec::Vec;
+
+use super::sfnt::{MAX_SFNT_SIZE, Reader, SfntTable, write_sfnt};
+
+/// Size of the WOFF header.
+const HEADER_LEN: usize = 44;
+
+/// Decodes a WOFF file to sfnt data.
+pub(super) fn decompress(data: &[u8]) -> Option<Vec<u8>> {
+    let mut header = Reader::new(data);
+    let _signature = header.u32()?;
+    let flavor = header.u32()?;
+    let length = header.u32()?;
+    let num_tables = header.u16()?;
+    if length as usize != data.len() || num_tables == 0 {
+        return None;
+    }
+
+    let mut directory = Reader::new(data.get(HEADER_LEN..)?);
+    let mut tables = Vec::with_capacity(num_tables as usize);
+    let mut total_len = 0_usize;
+    for _ in 0..num_tables {
+        let tag = directory.bytes(4)?.try_into().ok()?;
+        let offset = directory.u32()? as usize;
+        let comp_length = directory.u32()? as usize;
+        let orig_length = directory.u32()? as usize;
+        let _orig_checksum = directory.u32()?;
+
+        total_len = total_len.checked_add(orig_length)?;
+        if total_len > MAX_SFNT_SIZE || comp_length > orig_length {
+            return None;
+        }
+        let compressed = data.get(offset..offset.checked_add(comp_length)?)?;
+        let table_data = if comp_length < orig_length {
+            let table_data =
+                miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(compressed, orig_length)
+                    .ok()?;
+            if table_data.len() != orig_length {
+                return None;
+            }
+            table_data
+        } else {
+            compressed.to_vec()
+        };
+        tables.push(SfntTable {
+            tag,
+            data: table_data,
+        });
+    }
+    write_sfnt(flavor, tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use read_fonts::FontRef;
+
+    use super::super::sfnt::padded_len;
+    use super::decompress;
+
+    const ROBOTO: &[u8] =
+        include_bytes!("../../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+
+    /// Wraps sfnt data in a WOFF file, compressing every other table.
+    fn encode_woff(sfnt: &[u8]) -> Vec<u8> {
+        let font = FontRef::new(sfnt).unwrap();
+        let records = font.table_directory().table_records();
+        let mut tables = Vec::new();
+        for (i, record) in records.iter().enumerate() {
+            let start = record.offset() as usize;
+            let orig = &sfnt[start..start + record.length() as usize];
+            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(orig, 6);
+            let data = if i % 2 == 0 && compressed.len() < orig.len() {
+                compressed
+            } else {
+                orig.to_vec()
+            };
+            tables.push((record, orig.len(), data));
+        }
+
+        let mut offset = super::HEADER_LEN + 20 * tables.len();
+        let mut directory = Vec::new();
+        let mut table_data = Vec::new();
+        for (record, orig_len, data) in &tables {
+            directory.extend_from_slice(&record.tag().to_be_bytes());
+            directory.extend_from_slice(&(offset as u32).to_be_bytes());
+            directory.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            directory.extend_from_slice(&(*orig_len as u32).to_be_bytes());
+            directory.extend_from_slice(&record.checksum().to_be_bytes());
+            table_data.extend_from_slice(data);
+            table_data.resize(padded_len(table_data.len()), 0);
+            offset = padded_len(offset + data.len());
+        }
+
+        let length = super::HEADER_LEN + directory.len() + table_data.len();
+        let mut woff = Vec::new();
+        woff.extend_from_slice(b"wOFF");
+        woff.extend_from_slice(&sfnt[..4]);
+        woff.extend_from_slice(&(length as u32).to_be_bytes());
+        woff.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+        woff.extend_from_slice(&[0; 2]);
+        woff.extend_from_slice(&(sfnt.len() as u32).to_be_bytes());
+        woff.extend_from_slice(&[0; 24]);
+        woff.extend_from_slice(&directory);
+        woff.extend_from_slice(&table_data);
+        woff
+    }
+
+    #[test]
+    fn woff_round_trip() {
+        let woff = encode_woff(ROBOTO);
+        let sfnt = decompress(&woff).unwrap();
+
+        let original = FontRef::new(ROBOTO).unwrap();
+        let decoded = FontRef::new(&sfnt).unwrap();
+        let records = original.table_directory().table_records();
+        assert_eq!(
+            decoded.table_directory().table_records().len(),
+            records.len()
+        );
+        for record in records {
+            assert_eq!(
+                decoded.table_data(record.tag()).unwrap().as_bytes(),
+                original.table_data(record.tag()).unwrap().as_bytes(),
+                "table {} should be decoded unchanged",
+                record.tag()
+            );
+        }
+    }
+
+    #[test]
+    fn woff_rejects_truncated_data() {
+        let woff = encode_woff(ROBOTO);
+        assert!(decompress(&woff[..woff.len() - 1]).is_none());
+        assert!(decompress(&woff[..40]).is_none());
+    }
+}
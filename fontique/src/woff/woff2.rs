@@ -0,0 +1,981 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Decoding of [WOFF2](https://www.w3.org/TR/WOFF2/) files.
+//!
+//! Font collections in WOFF2 files aren't supported.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::sfnt::{MAX_SFNT_SIZE, Reader, SfntTable, checksum, padded_len, write_sfnt};
+
+/// Size of the WOFF2 header.
+const HEADER_LEN: usize = 48;
+
+/// The tags of the table directory's known tag indices.
+///
+/// See <https://www.w3.org/TR/WOFF2/#table_dir_format>.
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+const GLYF: [u8; 4] = *b"glyf";
+const LOCA: [u8; 4] = *b"loca";
+const HMTX: [u8; 4] = *b"hmtx";
+const HHEA: [u8; 4] = *b"hhea";
+const HEAD: [u8; 4] = *b"head";
+
+/// An entry of the WOFF2 table directory.
+struct TableEntry {
+    tag: [u8; 4],
+    /// The length of the table in the decompressed stream.
+    stream_len: usize,
+    is_transformed: bool,
+}
+
+/// Decodes a WOFF2 file to sfnt data.
+pub(super) fn decompress(data: &[u8]) -> Option<Vec<u8>> {
+    let mut header = Reader::new(data);
+    let _signature = header.u32()?;
+    let flavor = header.u32()?;
+    let length = header.u32()?;
+    let num_tables = header.u16()?;
+    let _reserved = header.u16()?;
+    let _total_sfnt_size = header.u32()?;
+    let total_compressed_size = header.u32()? as usize;
+    if length as usize != data.len() || num_tables == 0 || &flavor.to_be_bytes() == b"ttcf" {
+        return None;
+    }
+
+    let mut directory = Reader::new(data.get(HEADER_LEN..)?);
+    let mut entries = Vec::with_capacity(num_tables as usize);
+    let mut stream_len = 0_usize;
+    for _ in 0..num_tables {
+        let flags = directory.u8()?;
+        let tag = match flags & 0x3F {
+            0x3F => directory.bytes(4)?.try_into().ok()?,
+            index => *KNOWN_TAGS[index as usize],
+        };
+        let transform_version = flags >> 6;
+        let orig_len = base_128(&mut directory)? as usize;
+        // The glyf and loca tables use transform 0 and the null transform 3,
+        // while other tables use the null transform 0.
+        let is_transformed = if tag == GLYF || tag == LOCA {
+            transform_version == 0
+        } else {
+            transform_version != 0
+        };
+        let table_len = if is_transformed {
+            base_128(&mut directory)? as usize
+        } else {
+            orig_len
+        };
+        if is_transformed && tag == LOCA && table_len != 0 {
+            return None;
+        }
+        stream_len = stream_len.checked_add(table_len)?;
+        if stream_len > MAX_SFNT_SIZE {
+            return None;
+        }
+        entries.push(TableEntry {
+            tag,
+            stream_len: table_len,
+            is_transformed,
+        });
+    }
+
+    let compressed_start = HEADER_LEN + directory.pos;
+    let compressed =
+        data.get(compressed_start..compressed_start.checked_add(total_compressed_size)?)?;
+    let mut stream = LimitedWriter {
+        data: Vec::with_capacity(stream_len),
+        limit: stream_len,
+    };
+    brotli_decompressor::BrotliDecompress(&mut &compressed[..], &mut stream).ok()?;
+    let stream = stream.data;
+    if stream.len() != stream_len {
+        return None;
+    }
+
+    // Slice the decompressed stream into the tables.
+    let mut offset = 0;
+    let table_data: Vec<&[u8]> = entries
+        .iter()
+        .map(|entry| {
+            let table = &stream[offset..offset + entry.stream_len];
+            offset += entry.stream_len;
+            table
+        })
+        .collect();
+    let find = |tag: [u8; 4]| entries.iter().position(|entry| entry.tag == tag);
+
+    let mut tables = Vec::with_capacity(entries.len());
+    let mut glyph_x_mins = None;
+    if let Some(glyf_index) = find(GLYF)
+        && entries[glyf_index].is_transformed
+    {
+        // The transformed glyf table always comes with a loca table to replace.
+        find(LOCA)?;
+        let glyf = reconstruct_glyf(table_data[glyf_index])?;
+        glyph_x_mins = Some(glyf.x_mins);
+        tables.push(SfntTable {
+            tag: GLYF,
+            data: glyf.glyf,
+        });
+        tables.push(SfntTable {
+            tag: LOCA,
+            data: glyf.loca,
+        });
+    }
+    for (entry, data) in entries.iter().zip(&table_data) {
+        if tables.iter().any(|table| table.tag == entry.tag) {
+            continue;
+        }
+        let data = if entry.is_transformed {
+            if entry.tag != HMTX {
+                // There are no other transforms defined.
+                return None;
+            }
+            let hhea = table_data[find(HHEA)?];
+            let num_h_metrics = u16::from_be_bytes(hhea.get(34..36)?.try_into().ok()?);
+            reconstruct_hmtx(data, num_h_metrics, glyph_x_mins.as_deref()?)?
+        } else {
+            data.to_vec()
+        };
+        tables.push(SfntTable {
+            tag: entry.tag,
+            data,
+        });
+    }
+
+    // The head table's checkSumAdjustment is computed over the whole font,
+    // with the field itself set to zero.
+    let head = tables.iter_mut().find(|table| table.tag == HEAD)?;
+    head.data.get_mut(8..12)?.fill(0);
+    let mut sfnt = write_sfnt(flavor, tables)?;
+    let adjustment = 0xB1B0_AFBA_u32.wrapping_sub(checksum(&sfnt));
+    let head_offset = head_offset(&sfnt)?;
+    sfnt.get_mut(head_offset + 8..head_offset + 12)?
+        .copy_from_slice(&adjustment.to_be_bytes());
+    Some(sfnt)
+}
+
+/// Writer that fails when more than `limit` bytes are written to it.
+struct LimitedWriter {
+    data: Vec<u8>,
+    limit: usize,
+}
+
+impl std::io::Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.data.len() + buf.len() > self.limit {
+            return Err(std::io::ErrorKind::InvalidData.into());
+        }
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the offset of the head table in sfnt data.
+fn head_offset(sfnt: &[u8]) -> Option<usize> {
+    let mut reader = Reader::new(sfnt);
+    reader.bytes(4)?;
+    let num_tables = reader.u16()?;
+    reader.bytes(6)?;
+    for _ in 0..num_tables {
+        let tag = reader.bytes(4)?;
+        let _checksum = reader.u32()?;
+        let offset = reader.u32()? as usize;
+        let _length = reader.u32()?;
+        if tag == HEAD {
+            return Some(offset);
+        }
+    }
+    None
+}
+
+/// Reads a `UIntBase128` value.
+fn base_128(reader: &mut Reader<'_>) -> Option<u32> {
+    let mut value = 0_u32;
+    for i in 0..5 {
+        let byte = reader.u8()?;
+        // Leading zeros are invalid.
+        if i == 0 && byte == 0x80 {
+            return None;
+        }
+        // The value must fit in 32 bits.
+        if value & 0xFE00_0000 != 0 {
+            return None;
+        }
+        value = (value << 7) | u32::from(byte & 0x7F);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Reads a `255UInt16` value.
+fn u255_16(reader: &mut Reader<'_>) -> Option<u16> {
+    const ONE_MORE_BYTE_CODE_1: u8 = 255;
+    const ONE_MORE_BYTE_CODE_2: u8 = 254;
+    const WORD_CODE: u8 = 253;
+    const LOWEST_U_CODE: u16 = 253;
+    match reader.u8()? {
+        WORD_CODE => reader.u16(),
+        ONE_MORE_BYTE_CODE_1 => Some(u16::from(reader.u8()?) + LOWEST_U_CODE),
+        ONE_MORE_BYTE_CODE_2 => Some(u16::from(reader.u8()?) + LOWEST_U_CODE * 2),
+        code => Some(u16::from(code)),
+    }
+}
+
+/// The glyf and loca tables reconstructed from a transformed glyf table.
+struct Glyf {
+    glyf: Vec<u8>,
+    loca: Vec<u8>,
+    /// The `xMin` of each glyph, used to reconstruct left side bearings.
+    x_mins: Vec<i16>,
+}
+
+/// Reconstructs the glyf and loca tables from transform version 0.
+///
+/// See <https://www.w3.org/TR/WOFF2/#glyf_table_format>.
+fn reconstruct_glyf(data: &[u8]) -> Option<Glyf> {
+    const HAVE_OVERLAP_SIMPLE_BITMAP: u16 = 1;
+
+    let mut header = Reader::new(data);
+    let _reserved = header.u16()?;
+    let option_flags = header.u16()?;
+    let num_glyphs = header.u16()? as usize;
+    let index_format = header.u16()?;
+    let mut streams = [0_usize; 7];
+    for len in &mut streams {
+        *len = header.u32()? as usize;
+    }
+    let mut rest = Reader::new(data.get(header.pos..)?);
+    let [
+        n_contours_len,
+        n_points_len,
+        flags_len,
+        glyphs_len,
+        composites_len,
+        bbox_len,
+        instructions_len,
+    ] = streams;
+    let mut n_contours = Reader::new(rest.bytes(n_contours_len)?);
+    let mut n_points = Reader::new(rest.bytes(n_points_len)?);
+    let mut flags = Reader::new(rest.bytes(flags_len)?);
+    let mut glyphs = Reader::new(rest.bytes(glyphs_len)?);
+    let mut composites = Reader::new(rest.bytes(composites_len)?);
+    let bbox_stream = rest.bytes(bbox_len)?;
+    let mut instructions = Reader::new(rest.bytes(instructions_len)?);
+    let bitmap_len = num_glyphs.div_ceil(32) * 4;
+    let bbox_bitmap = bbox_stream.get(..bitmap_len)?;
+    let mut bboxes = Reader::new(&bbox_stream[bitmap_len..]);
+    let overlap_bitmap = if option_flags & HAVE_OVERLAP_SIMPLE_BITMAP != 0 {
+        Some(rest.bytes(num_glyphs.div_ceil(8))?)
+    } else {
+        None
+    };
+    let is_set = |bitmap: &[u8], index: usize| bitmap[index >> 3] & (0x80 >> (index & 7)) != 0;
+
+    let mut glyf = Vec::new();
+    let mut offsets = Vec::with_capacity(num_glyphs + 1);
+    let mut x_mins = Vec::with_capacity(num_glyphs);
+    let mut points = Vec::new();
+    for index in 0..num_glyphs {
+        offsets.push(glyf.len());
+        let contour_count = n_contours.i16()?;
+        let explicit_bbox = if is_set(bbox_bitmap, index) {
+            Some([bboxes.i16()?, bboxes.i16()?, bboxes.i16()?, bboxes.i16()?])
+        } else {
+            None
+        };
+        match contour_count {
+            0 => {
+                // Empty glyphs can't have a bounding box.
+                if explicit_bbox.is_some() {
+                    return None;
+                }
+                x_mins.push(0);
+                continue;
+            }
+            -1 => {
+                // Composite glyphs always have an explicit bounding box.
+                let bbox = explicit_bbox?;
+                let start = composites.pos;
+                let have_instructions = skip_composite(&mut composites)?;
+                glyf.extend_from_slice(&(-1_i16).to_be_bytes());
+                for value in bbox {
+                    glyf.extend_from_slice(&value.to_be_bytes());
+                }
+                glyf.extend_from_slice(&composites.data[start..composites.pos]);
+                if have_instructions {
+                    let len = u255_16(&mut glyphs)?;
+                    glyf.extend_from_slice(&len.to_be_bytes());
+                    glyf.extend_from_slice(instructions.bytes(len as usize)?);
+                }
+                x_mins.push(bbox[0]);
+            }
+            contour_count if contour_count > 0 => {
+                let mut end_points = Vec::with_capacity(contour_count as usize);
+                let mut point_count = 0_usize;
+                for _ in 0..contour_count {
+                    point_count = point_count.checked_add(u255_16(&mut n_points)? as usize)?;
+                    end_points.push(u16::try_from(point_count.checked_sub(1)?).ok()?);
+                }
+                points.clear();
+                decode_triplets(flags.bytes(point_count)?, &mut glyphs, &mut points)?;
+                let instruction_len = u255_16(&mut glyphs)?;
+                let instruction_data = instructions.bytes(instruction_len as usize)?;
+
+                let bbox = match explicit_bbox {
+                    Some(bbox) => bbox,
+                    None => bounding_box(&points)?,
+                };
+                glyf.extend_from_slice(&contour_count.to_be_bytes());
+                for value in bbox {
+                    glyf.extend_from_slice(&value.to_be_bytes());
+                }
+                for end_point in end_points {
+                    glyf.extend_from_slice(&end_point.to_be_bytes());
+                }
+                glyf.extend_from_slice(&instruction_len.to_be_bytes());
+                glyf.extend_from_slice(instruction_data);
+                let overlaps = overlap_bitmap.is_some_and(|bitmap| is_set(bitmap, index));
+                write_points(&points, overlaps, &mut glyf)?;
+                x_mins.push(bbox[0]);
+            }
+            _ => return None,
+        }
+        glyf.resize(padded_len(glyf.len()), 0);
+        if glyf.len() > MAX_SFNT_SIZE {
+            return None;
+        }
+    }
+    offsets.push(glyf.len());
+
+    let loca = if index_format == 0 {
+        let mut loca = Vec::with_capacity(offsets.len() * 2);
+        for offset in offsets {
+            loca.extend_from_slice(&u16::try_from(offset / 2).ok()?.to_be_bytes());
+        }
+        loca
+    } else {
+        let mut loca = Vec::with_capacity(offsets.len() * 4);
+        for offset in offsets {
+            loca.extend_from_slice(&u32::try_from(offset).ok()?.to_be_bytes());
+        }
+        loca
+    };
+    Some(Glyf { glyf, loca, x_mins })
+}
+
+/// Skips over the components of a composite glyph, returning whether it has
+/// instructions.
+fn skip_composite(reader: &mut Reader<'_>) -> Option<bool> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+    const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+    let mut have_instructions = false;
+    loop {
+        let flags = reader.u16()?;
+        have_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+        let mut len = 2;
+        len += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            len += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            len += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            len += 8;
+        }
+        reader.bytes(len)?;
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(have_instructions);
+        }
+    }
+}
+
+/// A point of a simple glyph outline.
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+    on_curve: bool,
+}
+
+/// Decodes the coordinates of a simple glyph from its flags and triplets.
+///
+/// See <https://www.w3.org/TR/WOFF2/#triplet_decoding>.
+fn decode_triplets(flags: &[u8], reader: &mut Reader<'_>, points: &mut Vec<Point>) -> Option<()> {
+    fn with_sign(flag: u8, value: i32) -> i32 {
+        if flag & 1 != 0 { value } else { -value }
+    }
+
+    let (mut x, mut y) = (0_i32, 0_i32);
+    for &flag in flags {
+        let on_curve = flag >> 7 == 0;
+        let flag = flag & 0x7F;
+        let (dx, dy) = if flag < 10 {
+            let b0 = i32::from(reader.u8()?);
+            (0, with_sign(flag, (i32::from(flag & 14) << 7) + b0))
+        } else if flag < 20 {
+            let b0 = i32::from(reader.u8()?);
+            (with_sign(flag, (i32::from((flag - 10) & 14) << 7) + b0), 0)
+        } else if flag < 84 {
+            let b0 = i32::from(flag - 20);
+            let b1 = i32::from(reader.u8()?);
+            (
+                with_sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+                with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)),
+            )
+        } else if flag < 120 {
+            let b0 = i32::from(flag - 84);
+            let (b1, b2) = (i32::from(reader.u8()?), i32::from(reader.u8()?));
+            (
+                with_sign(flag, 1 + ((b0 / 12) << 8) + b1),
+                with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b2),
+            )
+        } else if flag < 124 {
+            let (b1, b2, b3) = (
+                i32::from(reader.u8()?),
+                i32::from(reader.u8()?),
+                i32::from(reader.u8()?),
+            );
+            (
+                with_sign(flag, (b1 << 4) + (b2 >> 4)),
+                with_sign(flag >> 1, ((b2 & 0x0F) << 8) + b3),
+            )
+        } else {
+            let (dx, dy) = (i32::from(reader.u16()?), i32::from(reader.u16()?));
+            (with_sign(flag, dx), with_sign(flag >> 1, dy))
+        };
+        x = x.checked_add(dx)?;
+        y = y.checked_add(dy)?;
+        points.push(Point { x, y, on_curve });
+    }
+    Some(())
+}
+
+/// Computes the bounding box of a simple glyph's points.
+fn bounding_box(points: &[Point]) -> Option<[i16; 4]> {
+    let Some(first) = points.first() else {
+        return Some([0; 4]);
+    };
+    let mut bbox = [first.x, first.y, first.x, first.y];
+    for point in points {
+        bbox[0] = bbox[0].min(point.x);
+        bbox[1] = bbox[1].min(point.y);
+        bbox[2] = bbox[2].max(point.x);
+        bbox[3] = bbox[3].max(point.y);
+    }
+    let [x_min, y_min, x_max, y_max] = bbox.map(i16::try_from);
+    Some([x_min.ok()?, y_min.ok()?, x_max.ok()?, y_max.ok()?])
+}
+
+/// Writes the flags and coordinates of a simple glyph's points.
+fn write_points(points: &[Point], overlaps: bool, glyf: &mut Vec<u8>) -> Option<()> {
+    const ON_CURVE_POINT: u8 = 0x01;
+    const X_SHORT_VECTOR: u8 = 0x02;
+    const Y_SHORT_VECTOR: u8 = 0x04;
+    const X_IS_SAME_OR_POSITIVE: u8 = 0x10;
+    const Y_IS_SAME_OR_POSITIVE: u8 = 0x20;
+    const OVERLAP_SIMPLE: u8 = 0x40;
+
+    let mut flags = Vec::with_capacity(points.len());
+    let mut x_coordinates = Vec::new();
+    let mut y_coordinates = Vec::new();
+    let (mut last_x, mut last_y) = (0, 0);
+    for (index, point) in points.iter().enumerate() {
+        let mut flag = if point.on_curve { ON_CURVE_POINT } else { 0 };
+        if overlaps && index == 0 {
+            flag |= OVERLAP_SIMPLE;
+        }
+        for (delta, coordinates, short, same_or_positive) in [
+            (
+                point.x - last_x,
+                &mut x_coordinates,
+                X_SHORT_VECTOR,
+                X_IS_SAME_OR_POSITIVE,
+            ),
+            (
+                point.y - last_y,
+                &mut y_coordinates,
+                Y_SHORT_VECTOR,
+                Y_IS_SAME_OR_POSITIVE,
+            ),
+        ] {
+            if delta == 0 {
+                flag |= same_or_positive;
+            } else if delta.unsigned_abs() < 256 {
+                flag |= short;
+                if delta > 0 {
+                    flag |= same_or_positive;
+                }
+                coordinates.push(delta.unsigned_abs() as u8);
+            } else {
+                coordinates.extend_from_slice(&i16::try_from(delta).ok()?.to_be_bytes());
+            }
+        }
+        flags.push(flag);
+        (last_x, last_y) = (point.x, point.y);
+    }
+    glyf.extend_from_slice(&flags);
+    glyf.extend_from_slice(&x_coordinates);
+    glyf.extend_from_slice(&y_coordinates);
+    Some(())
+}
+
+/// Reconstructs the hmtx table from transform version 1.
+///
+/// Omitted left side bearings are the `xMin` of their glyphs. See
+/// <https://www.w3.org/TR/WOFF2/#hmtx_table_format>.
+fn reconstruct_hmtx(data: &[u8], num_h_metrics: u16, x_mins: &[i16]) -> Option<Vec<u8>> {
+    const NO_PROPORTIONAL_LSBS: u8 = 0x01;
+    const NO_MONOSPACE_LSBS: u8 = 0x02;
+
+    let num_h_metrics = num_h_metrics as usize;
+    let num_glyphs = x_mins.len();
+    if num_h_metrics == 0 || num_h_metrics > num_glyphs {
+        return None;
+    }
+    let mut reader = Reader::new(data);
+    let flags = reader.u8()?;
+    let advances = (0..num_h_metrics)
+        .map(|_| reader.u16())
+        .collect::<Option<Vec<_>>>()?;
+    let mut lsbs = vec![0_i16; num_glyphs];
+    for (index, lsb) in lsbs.iter_mut().enumerate() {
+        let omitted = if index < num_h_metrics {
+            flags & NO_PROPORTIONAL_LSBS != 0
+        } else {
+            flags & NO_MONOSPACE_LSBS != 0
+        };
+        *lsb = if omitted {
+            x_mins[index]
+        } else {
+            reader.i16()?
+        };
+    }
+
+    let mut hmtx = Vec::with_capacity(num_h_metrics * 4 + (num_glyphs - num_h_metrics) * 2);
+    for (index, lsb) in lsbs.iter().enumerate() {
+        if let Some(advance) = advances.get(index) {
+            hmtx.extend_from_slice(&advance.to_be_bytes());
+        }
+        hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+    Some(hmtx)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use read_fonts::tables::glyf::{Glyph, SimpleGlyph};
+    use read_fonts::types::{GlyphId, Tag};
+    use read_fonts::{FontData, FontRead, FontRef, TableProvider};
+
+    use super::super::sfnt::{Reader, checksum};
+    use super::{
+        GLYF, HEADER_LEN, KNOWN_TAGS, LOCA, base_128, decompress, reconstruct_hmtx, skip_composite,
+        u255_16,
+    };
+
+    const ROBOTO: &[u8] =
+        include_bytes!("../../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+
+    /// Writes bits to a byte stream, least significant bit first.
+    #[derive(Default)]
+    struct BitWriter {
+        data: Vec<u8>,
+        bit: u32,
+    }
+
+    impl BitWriter {
+        fn write(&mut self, value: u32, count: u32) {
+            for i in 0..count {
+                if self.bit == 0 {
+                    self.data.push(0);
+                }
+                *self.data.last_mut().unwrap() |= (((value >> i) & 1) as u8) << self.bit;
+                self.bit = (self.bit + 1) % 8;
+            }
+        }
+
+        fn align(&mut self) {
+            self.bit = 0;
+        }
+    }
+
+    /// Wraps `data` in a Brotli stream of uncompressed meta-blocks.
+    fn brotli_store(data: &[u8]) -> Vec<u8> {
+        let mut writer = BitWriter::default();
+        // A window of 16 bits.
+        writer.write(0, 1);
+        for chunk in data.chunks(32 * 1024) {
+            // ISLAST, MNIBBLES (4), MLEN - 1 and ISUNCOMPRESSED.
+            writer.write(0, 1);
+            writer.write(0, 2);
+            writer.write(chunk.len() as u32 - 1, 16);
+            writer.write(1, 1);
+            writer.align();
+            writer.data.extend_from_slice(chunk);
+        }
+        // ISLAST and ISLASTEMPTY.
+        writer.write(0b11, 2);
+        writer.data
+    }
+
+    fn write_base_128(value: u32, out: &mut Vec<u8>) {
+        let mut bytes = Vec::new();
+        let mut value = value;
+        loop {
+            bytes.push((value & 0x7F) as u8);
+            value >>= 7;
+            if value == 0 {
+                break;
+            }
+        }
+        for (i, byte) in bytes.iter().enumerate().rev() {
+            out.push(if i == 0 { *byte } else { byte | 0x80 });
+        }
+    }
+
+    /// Writes a `255UInt16` value, always using the word code.
+    fn write_u255_16(value: u16, out: &mut Vec<u8>) {
+        out.push(253);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    /// Transforms the glyf table to transform version 0, with the given
+    /// loca offsets.
+    fn transform_glyf(glyf: &[u8], offsets: &[usize], index_format: u16) -> Vec<u8> {
+        let num_glyphs = offsets.len() - 1;
+        let mut n_contours = Vec::new();
+        let mut n_points = Vec::new();
+        let mut flags = Vec::new();
+        let mut glyphs = Vec::new();
+        let mut composites = Vec::new();
+        let mut bbox_bitmap = alloc::vec![0_u8; num_glyphs.div_ceil(32) * 4];
+        let mut bboxes = Vec::new();
+        let mut instructions = Vec::new();
+        for index in 0..num_glyphs {
+            let data = &glyf[offsets[index]..offsets[index + 1]];
+            if data.is_empty() {
+                n_contours.extend_from_slice(&0_i16.to_be_bytes());
+                continue;
+            }
+            let contour_count = i16::from_be_bytes([data[0], data[1]]);
+            n_contours.extend_from_slice(&contour_count.to_be_bytes());
+            if contour_count < 0 {
+                bbox_bitmap[index >> 3] |= 0x80 >> (index & 7);
+                bboxes.extend_from_slice(&data[2..10]);
+                let mut reader = Reader::new(&data[10..]);
+                let have_instructions = skip_composite(&mut reader).unwrap();
+                composites.extend_from_slice(&data[10..10 + reader.pos]);
+                if have_instructions {
+                    let len = reader.u16().unwrap();
+                    write_u255_16(len, &mut glyphs);
+                    instructions.extend_from_slice(reader.bytes(len as usize).unwrap());
+                }
+                continue;
+            }
+
+            let simple = SimpleGlyph::read(FontData::new(data)).unwrap();
+            // Always store the bounding box, as it may not be tight.
+            bbox_bitmap[index >> 3] |= 0x80 >> (index & 7);
+            bboxes.extend_from_slice(&data[2..10]);
+            let mut start = 0;
+            for end in simple.end_pts_of_contours() {
+                let end = end.get() + 1;
+                write_u255_16(end - start, &mut n_points);
+                start = end;
+            }
+            let (mut last_x, mut last_y) = (0_i32, 0_i32);
+            for point in simple.points() {
+                let (dx, dy) = (i32::from(point.x) - last_x, i32::from(point.y) - last_y);
+                (last_x, last_y) = (i32::from(point.x), i32::from(point.y));
+                let mut flag = 124 | u8::from(dx >= 0) | (u8::from(dy >= 0) << 1);
+                if !point.on_curve {
+                    flag |= 0x80;
+                }
+                flags.push(flag);
+                glyphs.extend_from_slice(&(dx.unsigned_abs() as u16).to_be_bytes());
+                glyphs.extend_from_slice(&(dy.unsigned_abs() as u16).to_be_bytes());
+            }
+            write_u255_16(simple.instruction_length(), &mut glyphs);
+            instructions.extend_from_slice(simple.instructions());
+        }
+
+        let mut bbox_stream = bbox_bitmap;
+        bbox_stream.extend_from_slice(&bboxes);
+        let streams = [
+            n_contours,
+            n_points,
+            flags,
+            glyphs,
+            composites,
+            bbox_stream,
+            instructions,
+        ];
+        let mut out = Vec::new();
+        out.extend_from_slice(&0_u16.to_be_bytes());
+        out.extend_from_slice(&0_u16.to_be_bytes());
+        out.extend_from_slice(&(num_glyphs as u16).to_be_bytes());
+        out.extend_from_slice(&index_format.to_be_bytes());
+        for stream in &streams {
+            out.extend_from_slice(&(stream.len() as u32).to_be_bytes());
+        }
+        for stream in &streams {
+            out.extend_from_slice(stream);
+        }
+        out
+    }
+
+    /// Encodes sfnt data as a WOFF2 file, optionally transforming the glyf
+    /// and loca tables.
+    fn encode_woff2(sfnt: &[u8], transform_glyf_table: bool) -> Vec<u8> {
+        let font = FontRef::new(sfnt).unwrap();
+        let mut directory = Vec::new();
+        let mut stream = Vec::new();
+        let records = font.table_directory().table_records();
+        for record in records {
+            let tag = record.tag().to_be_bytes();
+            let data = font.table_data(record.tag()).unwrap().as_bytes();
+            let index = KNOWN_TAGS.iter().position(|known| **known == tag);
+            let is_glyf_or_loca = tag == GLYF || tag == LOCA;
+            let transform_version = if is_glyf_or_loca && !transform_glyf_table {
+                3
+            } else {
+                0
+            };
+            directory.push(index.unwrap_or(0x3F) as u8 | (transform_version << 6));
+            if index.is_none() {
+                directory.extend_from_slice(&tag);
+            }
+            write_base_128(data.len() as u32, &mut directory);
+            if is_glyf_or_loca && transform_glyf_table {
+                let transformed = if tag == GLYF {
+                    let index_format = font.head().unwrap().index_to_loc_format() as u16;
+                    let loca = font.table_data(Tag::new(&LOCA)).unwrap();
+                    let offsets = loca_offsets(loca.as_bytes(), index_format);
+                    transform_glyf(data, &offsets, index_format)
+                } else {
+                    Vec::new()
+                };
+                write_base_128(transformed.len() as u32, &mut directory);
+                stream.extend_from_slice(&transformed);
+            } else {
+                stream.extend_from_slice(data);
+            }
+        }
+
+        let compressed = brotli_store(&stream);
+        let length = HEADER_LEN + directory.len() + compressed.len();
+        let mut woff2 = Vec::new();
+        woff2.extend_from_slice(b"wOF2");
+        woff2.extend_from_slice(&sfnt[..4]);
+        woff2.extend_from_slice(&(length as u32).to_be_bytes());
+        woff2.extend_from_slice(&(records.len() as u16).to_be_bytes());
+        woff2.extend_from_slice(&[0; 2]);
+        woff2.extend_from_slice(&(sfnt.len() as u32).to_be_bytes());
+        woff2.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        woff2.extend_from_slice(&[0; 24]);
+        woff2.extend_from_slice(&directory);
+        woff2.extend_from_slice(&compressed);
+        woff2
+    }
+
+    fn loca_offsets(loca: &[u8], index_format: u16) -> Vec<usize> {
+        if index_format == 0 {
+            loca.chunks_exact(2)
+                .map(|offset| u16::from_be_bytes([offset[0], offset[1]]) as usize * 2)
+                .collect()
+        } else {
+            loca.chunks_exact(4)
+                .map(|offset| {
+                    u32::from_be_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn woff2_round_trip() {
+        let woff2 = encode_woff2(ROBOTO, false);
+        let sfnt = decompress(&woff2).unwrap();
+
+        let original = FontRef::new(ROBOTO).unwrap();
+        let decoded = FontRef::new(&sfnt).unwrap();
+        for record in original.table_directory().table_records() {
+            let tag = record.tag();
+            let expected = original.table_data(tag).unwrap();
+            let actual = decoded.table_data(tag).unwrap();
+            if tag == Tag::new(b"head") {
+                // The checksum adjustment is recomputed.
+                assert_eq!(actual.as_bytes()[..8], expected.as_bytes()[..8]);
+                assert_eq!(actual.as_bytes()[12..], expected.as_bytes()[12..]);
+            } else {
+                assert_eq!(
+                    actual.as_bytes(),
+                    expected.as_bytes(),
+                    "table {tag} should be decoded unchanged"
+                );
+            }
+        }
+        assert_eq!(checksum(&sfnt), 0xB1B0_AFBA);
+    }
+
+    #[test]
+    fn woff2_transformed_glyf() {
+        let woff2 = encode_woff2(ROBOTO, true);
+        let sfnt = decompress(&woff2).unwrap();
+
+        let original = FontRef::new(ROBOTO).unwrap();
+        let decoded = FontRef::new(&sfnt).unwrap();
+        let (original_glyf, original_loca) =
+            (original.glyf().unwrap(), original.loca(None).unwrap());
+        let (decoded_glyf, decoded_loca) = (decoded.glyf().unwrap(), decoded.loca(None).unwrap());
+        assert_eq!(decoded_loca.len(), original_loca.len());
+        for gid in 0..original_loca.len() as u32 {
+            let gid = GlyphId::new(gid);
+            let expected = original_loca.get_glyf(gid, &original_glyf).unwrap();
+            let actual = decoded_loca.get_glyf(gid, &decoded_glyf).unwrap();
+            match (expected, actual) {
+                (None, None) => {}
+                (Some(Glyph::Simple(expected)), Some(Glyph::Simple(actual))) => {
+                    assert_eq!(actual.end_pts_of_contours(), expected.end_pts_of_contours());
+                    assert_eq!(actual.instructions(), expected.instructions());
+                    assert!(actual.points().eq(expected.points()));
+                    assert_eq!(
+                        [
+                            actual.x_min(),
+                            actual.y_min(),
+                            actual.x_max(),
+                            actual.y_max()
+                        ],
+                        [
+                            expected.x_min(),
+                            expected.y_min(),
+                            expected.x_max(),
+                            expected.y_max()
+                        ]
+                    );
+                }
+                (Some(Glyph::Composite(expected)), Some(Glyph::Composite(actual))) => {
+                    assert!(
+                        actual
+                            .component_glyphs_and_flags()
+                            .eq(expected.component_glyphs_and_flags())
+                    );
+                    assert_eq!(actual.instructions(), expected.instructions());
+                    assert_eq!(actual.x_min(), expected.x_min());
+                }
+                _ => panic!("glyph {gid} should have the same kind after decoding"),
+            }
+        }
+        assert_eq!(checksum(&sfnt), 0xB1B0_AFBA);
+    }
+
+    #[test]
+    fn woff2_path_source_decoded_once() {
+        use crate::woff::DECODES;
+        use crate::{FontInfo, SourceCache, SourceId, SourceInfo, SourceKind};
+
+        let dir = std::env::temp_dir();
+        let woff2_path = dir.join(alloc::format!(
+            "fontique-woff2-{}.woff2",
+            std::process::id()
+        ));
+        let ttf_path = dir.join(alloc::format!("fontique-woff2-{}.ttf", std::process::id()));
+        std::fs::write(&woff2_path, encode_woff2(ROBOTO, true)).unwrap();
+        std::fs::write(&ttf_path, ROBOTO).unwrap();
+        let woff2 = SourceInfo::new(
+            SourceId::new(),
+            SourceKind::Path(woff2_path.as_path().into()),
+        );
+        let ttf = SourceInfo::new(SourceId::new(), SourceKind::Path(ttf_path.as_path().into()));
+
+        DECODES.with(|decodes| decodes.set(0));
+        let mut source_cache = SourceCache::default();
+        for _ in 0..3 {
+            FontInfo::from_cached_source(woff2.clone(), 0, Some(&mut source_cache)).unwrap();
+            source_cache.get(&woff2).unwrap();
+        }
+        FontInfo::from_source(ttf, 0).unwrap();
+        let decodes = DECODES.with(|decodes| decodes.get());
+
+        std::fs::remove_file(&woff2_path).unwrap();
+        std::fs::remove_file(&ttf_path).unwrap();
+        assert_eq!(decodes, 1, "the file should be decoded once for the cache");
+    }
+
+    #[test]
+    fn woff2_rejects_truncated_data() {
+        let woff2 = encode_woff2(ROBOTO, false);
+        assert!(decompress(&woff2[..woff2.len() - 1]).is_none());
+        assert!(decompress(&woff2[..HEADER_LEN]).is_none());
+    }
+
+    #[test]
+    fn base_128_values() {
+        let read = |data: &[u8]| base_128(&mut Reader::new(data));
+        assert_eq!(read(&[0x3F]), Some(63));
+        assert_eq!(read(&[0x81, 0x00]), Some(128));
+        assert_eq!(read(&[0x8F, 0xFF, 0xFF, 0xFF, 0x7F]), Some(u32::MAX));
+        // Leading zeros.
+        assert_eq!(read(&[0x80, 0x01]), None);
+        // Overflow.
+        assert_eq!(read(&[0x9F, 0xFF, 0xFF, 0xFF, 0x7F]), None);
+        // More than five bytes.
+        assert_eq!(read(&[0x81, 0x81, 0x81, 0x81, 0x81, 0x01]), None);
+    }
+
+    #[test]
+    fn u255_16_values() {
+        let read = |data: &[u8]| u255_16(&mut Reader::new(data));
+        assert_eq!(read(&[252]), Some(252));
+        assert_eq!(read(&[255, 0]), Some(253));
+        assert_eq!(read(&[254, 0]), Some(506));
+        assert_eq!(read(&[253, 0x12, 0x34]), Some(0x1234));
+        assert_eq!(read(&[253, 0x12]), None);
+    }
+
+    #[test]
+    fn hmtx_reconstruction() {
+        let x_mins = [10, 20, 30];
+        // Proportional side bearings omitted, monospace side bearings given.
+        let mut data = alloc::vec![0x01];
+        data.extend_from_slice(&500_u16.to_be_bytes());
+        data.extend_from_slice(&600_u16.to_be_bytes());
+        data.extend_from_slice(&(-5_i16).to_be_bytes());
+        let hmtx = reconstruct_hmtx(&data, 2, &x_mins).unwrap();
+        let mut expected = Vec::new();
+        for value in [500_u16, 10, 600, 20, 0xFFFB] {
+            expected.extend_from_slice(&value.to_be_bytes());
+        }
+        assert_eq!(hmtx, expected);
+        assert!(reconstruct_hmtx(&data, 4, &x_mins).is_none());
+    }
+}
@@ -57,6 +57,7 @@ pub enum BidiOverride {
 // TODO: it would be quite nice for this to implement
 // <https://doc.rust-lang.org/stable/core/iter/trait.Step.html>, once stabilized.
 #[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct BidiLevel(u8);
 
@@ -15,6 +15,7 @@ use core::fmt;
 /// In CSS, this corresponds to the `font-weight` property:
 /// <https://www.w3.org/TR/css-fonts-4/#font-weight-prop>.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontWeight(f32);
 
 impl FontWeight {
@@ -136,6 +137,7 @@ impl fmt::Display for FontWeight {
 /// In CSS, this corresponds to the `font-width` property:
 /// <https://www.w3.org/TR/css-fonts-4/#font-width-prop>.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontWidth(f32);
 
 impl FontWidth {
@@ -297,6 +299,7 @@ impl fmt::Display for FontWidth {
 /// In CSS, this corresponds to the `font-style` property:
 /// <https://www.w3.org/TR/css-fonts-4/#font-style-prop>.
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontStyle {
     /// An upright or "roman" style.
     #[default]
@@ -398,6 +401,26 @@ impl fmt::Display for FontStyle {
     }
 }
 
+/// Whether emoji are drawn with text or emoji (usually colored) presentation.
+///
+/// In CSS, this corresponds to the `font-variant-emoji` property:
+/// <https://www.w3.org/TR/css-fonts-4/#font-variant-emoji-prop>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FontVariantEmoji {
+    /// Emoji fonts are only used for emoji the other fonts don't cover.
+    #[default]
+    Normal,
+    /// Emoji are drawn with text presentation, even when followed by the emoji presentation
+    /// selector (U+FE0F).
+    Text,
+    /// Emoji are drawn with emoji presentation, even when followed by the text presentation
+    /// selector (U+FE0E).
+    Emoji,
+    /// Emoji are drawn with their default presentation from Unicode, unless a presentation
+    /// selector requests the other one.
+    Unicode,
+}
+
 #[cfg(test)]
 mod tests {
     extern crate alloc;
@@ -505,3 +528,144 @@ mod tests {
         assert_eq!(FontStyle::parse_css("Oblique"), None);
     }
 }
+
+/// Whether the `opsz` axis of variable fonts is set from the font size.
+///
+/// In CSS, this corresponds to the `font-optical-sizing` property:
+/// <https://www.w3.org/TR/css-fonts-4/#font-optical-sizing-def>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FontOpticalSizing {
+    /// The `opsz` axis is set to the font size, if the font has one.
+    ///
+    /// An `opsz` value in the font variations takes precedence.
+    #[default]
+    Auto,
+    /// The `opsz` axis keeps its default value, unless it's set by the font variations.
+    None,
+}
+
+/// How the font size of each font is adjusted to give it the same x-height or cap height.
+///
+/// This keeps text evenly sized when it falls back to fonts with a different aspect, such as in
+/// mixed-script text.
+///
+/// In CSS, this corresponds to the `font-size-adjust` property:
+/// <https://www.w3.org/TR/css-fonts-5/#font-size-adjust-prop>.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum FontSizeAdjust {
+    /// Font sizes aren't adjusted.
+    #[default]
+    None,
+    /// Each font's size is scaled so that its x-height is this fraction of the font size.
+    ExHeight(f32),
+    /// Each font's size is scaled so that its cap height is this fraction of the font size.
+    CapHeight(f32),
+    /// Each font's size is scaled so that its x-height matches that of the first available font
+    /// of the font family list.
+    ExHeightFromFont,
+    /// Each font's size is scaled so that its cap height matches that of the first available
+    /// font of the font family list.
+    CapHeightFromFont,
+}
+
+/// Which kinds of typefaces may be synthesized when a font family lacks them.
+///
+/// In CSS, this corresponds to the `font-synthesis` property:
+/// <https://www.w3.org/TR/css-fonts-4/#font-synthesis>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FontSynthesis {
+    /// Whether bold faces may be synthesized by emboldening glyph outlines.
+    pub weight: bool,
+    /// Whether italic and oblique faces may be synthesized by skewing glyph outlines.
+    pub style: bool,
+    /// Whether small capitals may be synthesized from scaled capitals.
+    ///
+    /// Small capitals are never synthesized, so this has no effect. It's kept so that every
+    /// value of the CSS property can be represented.
+    pub small_caps: bool,
+    /// Whether subscript and superscript glyphs may be synthesized from scaled glyphs.
+    ///
+    /// Subscript and superscript glyphs are never synthesized, so this has no effect. It's kept
+    /// so that every value of the CSS property can be represented.
+    pub position: bool,
+}
+
+impl FontSynthesis {
+    /// All kinds of synthesis are allowed.
+    pub const ALL: Self = Self {
+        weight: true,
+        style: true,
+        small_caps: true,
+        position: true,
+    };
+
+    /// No synthesis is allowed.
+    pub const NONE: Self = Self {
+        weight: false,
+        style: false,
+        small_caps: false,
+        position: false,
+    };
+}
+
+impl Default for FontSynthesis {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// The palette of a color font that glyphs are painted with, before any overrides.
+///
+/// In CSS, this corresponds to the `base-palette` descriptor of `@font-palette-values` and the
+/// keyword values of the `font-palette` property:
+/// <https://www.w3.org/TR/css-fonts-4/#font-palette-prop>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BasePalette {
+    /// The font's default palette, which is its first palette.
+    ///
+    /// With a dark [`ColorScheme`], the first palette the font marks as usable on dark
+    /// backgrounds is used instead, if it has one.
+    #[default]
+    Normal,
+    /// The first palette the font marks as usable on light backgrounds.
+    Light,
+    /// The first palette the font marks as usable on dark backgrounds.
+    Dark,
+    /// The palette with the given index.
+    ///
+    /// Indices that are out of range for the font select its first palette.
+    Index(u16),
+}
+
+/// A color that replaces one entry of a color font's palette.
+///
+/// In CSS, this corresponds to an entry of the `override-colors` descriptor of
+/// `@font-palette-values`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PaletteOverride {
+    /// The index of the palette entry to replace.
+    pub index: u16,
+    /// The replacement color as non-premultiplied 8-bit RGBA components.
+    pub color: [u8; 4],
+}
+
+impl PaletteOverride {
+    /// Creates a new palette override.
+    pub const fn new(index: u16, color: [u8; 4]) -> Self {
+        Self { index, color }
+    }
+}
+
+/// Whether text is displayed in a light or a dark environment.
+///
+/// This corresponds to the `prefers-color-scheme` media feature of CSS:
+/// <https://www.w3.org/TR/mediaqueries-5/#prefers-color-scheme>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ColorScheme {
+    /// Dark text on a light background.
+    #[default]
+    Light,
+    /// Light text on a dark background.
+    Dark,
+}
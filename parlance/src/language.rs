@@ -264,6 +264,35 @@ impl fmt::Display for Language {
     }
 }
 
+/// Languages are serialized as their canonical tag, such as `zh-Hant-TW`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Language {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Language {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Language;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a BCP 47 language tag")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Language, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
 /// An error returned when parsing a [`Language`] fails.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ParseLanguageError {
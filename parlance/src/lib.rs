@@ -11,6 +11,8 @@
 //!
 //! - `std` (enabled by default): This is currently unused and is provided for forward compatibility.
 //! - `bytemuck`: Implement traits from `bytemuck` on [`GenericFamily`].
+//! - `serde`: Implement `Serialize` and `Deserialize` from `serde` on the property types used by
+//!   laid out text.
 //!
 //! ## Example
 //!
@@ -50,10 +52,16 @@ mod tag;
 mod text;
 
 pub use bidi::{BidiControl, BidiDirection, BidiLevel, BidiOverride};
-pub use font::{FontStyle, FontWeight, FontWidth};
+pub use font::{
+    BasePalette, ColorScheme, FontOpticalSizing, FontSizeAdjust, FontStyle, FontSynthesis,
+    FontVariantEmoji, FontWeight, FontWidth, PaletteOverride,
+};
 pub use font_family::{FontFamily, FontFamilyName, ParseFontFamilyError, ParseFontFamilyErrorKind};
 pub use generic_family::GenericFamily;
 pub use language::{Language, ParseLanguageError};
 pub use script::{ParseScriptError, Script};
 pub use tag::{FontFeature, FontVariation, ParseSettingsError, ParseSettingsErrorKind, Tag};
-pub use text::{BaseDirection, OverflowWrap, TextWrapMode, WordBreak};
+pub use text::{
+    BaseDirection, HangingPunctuation, LineBreak, OverflowWrap, TextAutospace, TextSpacingTrim,
+    TextWrapMode, WordBreak,
+};
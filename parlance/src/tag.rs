@@ -5,6 +5,7 @@ use core::fmt;
 
 /// A 4-byte OpenType tag (for example `wght`, `liga`).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct Tag([u8; 4]);
 
@@ -110,6 +111,7 @@ impl core::error::Error for ParseSettingsError {}
 
 /// OpenType font feature setting (tag + `u16` value).
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontFeature {
     /// The OpenType tag for this setting.
     pub tag: Tag,
@@ -178,6 +180,7 @@ fn parse_u16_feature_value(value_str: &str) -> Result<u16, ParseSettingsErrorKin
 
 /// OpenType font variation setting (tag + `f32` value).
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontVariation {
     /// The OpenType tag for this setting.
     pub tag: Tag,
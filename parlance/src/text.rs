@@ -27,10 +27,32 @@ pub enum WordBreak {
     KeepAll,
 }
 
+/// Strictness of line-breaking rules, named for the CSS property.
+///
+/// This mostly affects breaks around CJK punctuation, iteration marks and small kana, which are
+/// forbidden at the start or end of a line to different degrees (kinsoku shori).
+///
+/// See: <https://www.w3.org/TR/css-text-3/#line-break-property>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LineBreak {
+    /// The default rules, which are currently the same as `Strict`.
+    #[default]
+    Auto,
+    /// The least restrictive rules, for short lines such as in newspapers.
+    Loose,
+    /// The most common rules, e.g. allowing breaks before small kana.
+    Normal,
+    /// The most restrictive rules.
+    Strict,
+    /// A soft wrap opportunity between every typographic character unit.
+    Anywhere,
+}
+
 /// Control over "emergency" line-breaking.
 ///
 /// See: <https://www.w3.org/TR/css-text-3/#overflow-wrap-property>
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OverflowWrap {
     /// Even with extremely long words, lines can only break at places specified in [`WordBreak`].
     #[default]
@@ -45,6 +67,7 @@ pub enum OverflowWrap {
 ///
 /// See: <https://www.w3.org/TR/css-text-4/#text-wrap-mode>
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextWrapMode {
     /// Wrap as needed to prevent overflow.
     #[default]
@@ -52,3 +75,50 @@ pub enum TextWrapMode {
     /// Do not wrap at soft-wrap opportunities.
     NoWrap,
 }
+
+/// Whether punctuation may be placed outside the line box, named for the CSS property.
+///
+/// See: <https://www.w3.org/TR/css-text-3/#hanging-punctuation-property>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HangingPunctuation {
+    /// No punctuation hangs.
+    #[default]
+    None,
+    /// A stop or comma at the end of a line hangs if it doesn't otherwise fit.
+    AllowEnd,
+}
+
+/// Trimming of the blank half of fullwidth CJK punctuation, named for the CSS property.
+///
+/// Fullwidth opening punctuation such as `「` has its blank space before the glyph, and closing
+/// punctuation such as `」` or `。` after it. Trimming removes that half-em of space.
+///
+/// See: <https://www.w3.org/TR/css-text-4/#text-spacing-trim-property>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextSpacingTrim {
+    /// Punctuation is not trimmed.
+    #[default]
+    SpaceAll,
+    /// Opening punctuation is trimmed at the start of wrapped lines, and adjacent pairs of
+    /// punctuation are trimmed so that only one blank half remains between them.
+    Normal,
+    /// Like `Normal`, but opening punctuation is also trimmed at the start of a paragraph.
+    TrimStart,
+    /// All fullwidth opening and closing punctuation is trimmed.
+    TrimAll,
+}
+
+/// Automatic spacing between ideographic and non-ideographic text, named for the CSS property.
+///
+/// See: <https://www.w3.org/TR/css-text-4/#text-autospace-property>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextAutospace {
+    /// No extra space is added.
+    #[default]
+    NoAutospace,
+    /// An eighth of an em is added between ideographs and non-ideographic letters or numerals.
+    Normal,
+}
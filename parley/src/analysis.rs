@@ -7,7 +7,7 @@ use parley_engine::break_overrides::LineBreakOverrideFn;
 
 use parley_engine::AnalysisOptions;
 
-use parlance::{BaseDirection, WordBreak};
+use parlance::{BaseDirection, LineBreak, WordBreak};
 
 pub(crate) fn analyze_text<B: Brush>(
     lcx: &mut LayoutContext<B>,
@@ -26,9 +26,18 @@ pub(crate) fn analyze_text<B: Brush>(
             (word_break != WordBreak::Normal).then(|| (sr.range.clone(), word_break))
         }));
 
+    // Likewise for line break strictness, where gaps are `LineBreak::Auto`.
+    lcx.line_break.clear();
+    lcx.line_break
+        .extend(lcx.style_runs.iter().filter_map(|sr| {
+            let line_break = lcx.style_table[sr.style_index as usize].line_break;
+            (line_break != LineBreak::Auto).then(|| (sr.range.clone(), line_break))
+        }));
+
     let options = AnalysisOptions {
         base_direction,
         word_break: &lcx.word_break,
+        line_break: &lcx.line_break,
         line_break_override,
     };
     lcx.analyzer.analyze(text, &options, &mut lcx.analysis);
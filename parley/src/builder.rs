@@ -5,18 +5,20 @@
 
 use super::FontContext;
 use super::context::LayoutContext;
-use super::style::{Brush, StyleProperty, TextStyle, WhiteSpaceCollapse};
+use super::style::{Brush, ParagraphStyle, StyleProperty, TextStyle, WhiteSpaceCollapse};
 
 use super::layout::Layout;
 
 use alloc::string::String;
+use alloc::{vec, vec::Vec};
 use core::ops::{Bound, Range, RangeBounds};
-use parlance::BaseDirection;
+use parlance::{BaseDirection, ColorScheme};
 use parley_engine::break_overrides::LineBreakOverrideFn;
 
 use crate::InlineBoxKind;
 use crate::inline_box::InlineBox;
 use crate::resolve::{ResolvedStyle, StyleRun, tree::ItemKind};
+use crate::shape::FontSelector;
 
 #[derive(Clone, Copy)]
 pub(crate) struct BuilderOptions<'a> {
@@ -24,6 +26,8 @@ pub(crate) struct BuilderOptions<'a> {
     quantize: bool,
     base_direction: BaseDirection,
     line_break_override: Option<&'a LineBreakOverrideFn>,
+    font_selector: Option<&'a dyn FontSelector>,
+    color_scheme: ColorScheme,
 }
 
 impl BuilderOptions<'_> {
@@ -33,6 +37,8 @@ impl BuilderOptions<'_> {
             quantize,
             base_direction: BaseDirection::Auto,
             line_break_override: None,
+            font_selector: None,
+            color_scheme: ColorScheme::Light,
         }
     }
 }
@@ -70,6 +76,17 @@ impl<'b, B: Brush> RangedBuilder<'b, B> {
         self.lcx.inline_boxes.push(inline_box);
     }
 
+    /// Sets the style of every paragraph overlapping `range`.
+    ///
+    /// Paragraphs are delimited by hard line breaks. Where the ranges of several pushed
+    /// paragraph styles overlap the same paragraph, the one pushed last applies.
+    pub fn push_paragraph_style(&mut self, style: ParagraphStyle, range: impl RangeBounds<usize>) {
+        let range = resolve_range(range, usize::MAX);
+        self.lcx
+            .paragraph_styles
+            .push((range, style.scaled(self.options.scale)));
+    }
+
     /// Sets the paragraph's base direction.
     ///
     /// The default is [`BaseDirection::Auto`], which infers the direction from the text.
@@ -84,6 +101,26 @@ impl<'b, B: Brush> RangedBuilder<'b, B> {
         self.options.line_break_override = overrides;
     }
 
+    /// Set the policy used to choose the font for each cluster of text.
+    ///
+    /// If this is `None`, which is the default, the selector set with
+    /// [`LayoutContext::set_font_selector`] is used, or [`DefaultFontSelector`] if there is none.
+    ///
+    /// [`DefaultFontSelector`]: crate::DefaultFontSelector
+    pub fn set_font_selector(&mut self, selector: Option<&'b dyn FontSelector>) {
+        self.options.font_selector = selector;
+    }
+
+    /// Sets whether the text is displayed in a light or a dark environment.
+    ///
+    /// With [`ColorScheme::Dark`], color fonts styled with [`BasePalette::Normal`] are painted
+    /// with their first palette for dark backgrounds. The default is [`ColorScheme::Light`].
+    ///
+    /// [`BasePalette::Normal`]: crate::BasePalette::Normal
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        self.options.color_scheme = color_scheme;
+    }
+
     pub fn build_into(self, layout: &mut Layout<B>, text: impl AsRef<str>) {
         // Apply RangedStyleBuilder styles directly to style-table/style-run state.
         self.lcx
@@ -168,6 +205,17 @@ impl<'b, B: Brush> StyleRunBuilder<'b, B> {
         self.lcx.inline_boxes.push(inline_box);
     }
 
+    /// Sets the style of every paragraph overlapping `range`.
+    ///
+    /// Paragraphs are delimited by hard line breaks. Where the ranges of several pushed
+    /// paragraph styles overlap the same paragraph, the one pushed last applies.
+    pub fn push_paragraph_style(&mut self, style: ParagraphStyle, range: impl RangeBounds<usize>) {
+        let range = resolve_range(range, self.len);
+        self.lcx
+            .paragraph_styles
+            .push((range, style.scaled(self.options.scale)));
+    }
+
     /// Sets the paragraph's base direction.
     ///
     /// The default is [`BaseDirection::Auto`], which infers the direction from the text.
@@ -182,6 +230,26 @@ impl<'b, B: Brush> StyleRunBuilder<'b, B> {
         self.options.line_break_override = overrides;
     }
 
+    /// Set the policy used to choose the font for each cluster of text.
+    ///
+    /// If this is `None`, which is the default, the selector set with
+    /// [`LayoutContext::set_font_selector`] is used, or [`DefaultFontSelector`] if there is none.
+    ///
+    /// [`DefaultFontSelector`]: crate::DefaultFontSelector
+    pub fn set_font_selector(&mut self, selector: Option<&'b dyn FontSelector>) {
+        self.options.font_selector = selector;
+    }
+
+    /// Sets whether the text is displayed in a light or a dark environment.
+    ///
+    /// With [`ColorScheme::Dark`], color fonts styled with [`BasePalette::Normal`] are painted
+    /// with their first palette for dark backgrounds. The default is [`ColorScheme::Light`].
+    ///
+    /// [`BasePalette::Normal`]: crate::BasePalette::Normal
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        self.options.color_scheme = color_scheme;
+    }
+
     pub fn build_into(self, layout: &mut Layout<B>, text: impl AsRef<str>) {
         assert!(
             self.cursor == self.len,
@@ -268,6 +336,17 @@ impl<'b, B: Brush> TreeBuilder<'b, B> {
         self.lcx.inline_boxes.push(inline_box);
     }
 
+    /// Sets the style of the paragraph containing the text pushed next.
+    ///
+    /// Paragraphs are delimited by hard line breaks. If the style of a paragraph is set more
+    /// than once, the last one applies.
+    pub fn set_paragraph_style(&mut self, style: ParagraphStyle) {
+        let index = self.lcx.tree_style_builder.pending_text_len();
+        self.lcx
+            .paragraph_styles
+            .push((index..index, style.scaled(self.options.scale)));
+    }
+
     pub fn set_white_space_mode(&mut self, white_space_collapse: WhiteSpaceCollapse) {
         self.lcx
             .tree_style_builder
@@ -288,6 +367,26 @@ impl<'b, B: Brush> TreeBuilder<'b, B> {
         self.options.line_break_override = overrides;
     }
 
+    /// Set the policy used to choose the font for each cluster of text.
+    ///
+    /// If this is `None`, which is the default, the selector set with
+    /// [`LayoutContext::set_font_selector`] is used, or [`DefaultFontSelector`] if there is none.
+    ///
+    /// [`DefaultFontSelector`]: crate::DefaultFontSelector
+    pub fn set_font_selector(&mut self, selector: Option<&'b dyn FontSelector>) {
+        self.options.font_selector = selector;
+    }
+
+    /// Sets whether the text is displayed in a light or a dark environment.
+    ///
+    /// With [`ColorScheme::Dark`], color fonts styled with [`BasePalette::Normal`] are painted
+    /// with their first palette for dark backgrounds. The default is [`ColorScheme::Light`].
+    ///
+    /// [`BasePalette::Normal`]: crate::BasePalette::Normal
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        self.options.color_scheme = color_scheme;
+    }
+
     #[inline]
     pub fn build_into(self, layout: &mut Layout<B>) -> String {
         // Apply TreeStyleBuilder styles to LayoutContext.
@@ -341,6 +440,7 @@ fn build_into_layout<B: Brush>(
     layout.data.quantize = options.quantize;
     layout.data.base_level = lcx.analysis.paragraph_level();
     layout.data.text_len = text.len();
+    layout.data.font_generation = lcx.font_generation.unwrap_or_default();
 
     lcx.char_style_indices
         .resize(lcx.analysis.char_info().len(), 0);
@@ -362,6 +462,10 @@ fn build_into_layout<B: Brush>(
     // Note: It's important that this is a stable sort to allow users to control the order of contiguous inline boxes
     lcx.inline_boxes.sort_by_key(|b| b.index);
 
+    // Generic families depend on the locale, which is only known per style
+    // once all properties are applied.
+    lcx.rcx.resolve_font_stacks(fcx, &mut lcx.style_table);
+
     {
         super::shape::shape_text(
             &lcx.rcx,
@@ -374,6 +478,14 @@ fn build_into_layout<B: Brush>(
             text,
             layout,
             &lcx.analysis_data_sources,
+            options.font_selector.or_else(|| {
+                lcx.font_selector
+                    .as_deref()
+                    .map(|selector| selector as &dyn FontSelector)
+            }),
+            options.color_scheme,
+            lcx.font_diagnostics,
+            lcx.parallel_shaping,
         );
     }
 
@@ -381,9 +493,67 @@ fn build_into_layout<B: Brush>(
     layout.data.inline_boxes.clear();
     core::mem::swap(&mut layout.data.inline_boxes, &mut lcx.inline_boxes);
 
+    if !lcx.paragraph_styles.is_empty() {
+        resolve_paragraph_styles(
+            &mut layout.data.paragraph_styles,
+            text,
+            &lcx.paragraph_styles,
+            &layout.data.inline_boxes,
+        );
+    }
+
     layout.data.finish();
 }
 
+/// Resolves the style of each paragraph of `text` from the pushed paragraph styles.
+///
+/// Paragraphs are split at the same hard line breaks as line breaking splits lines at, so that
+/// `styles` has one entry for each paragraph line breaking encounters.
+fn resolve_paragraph_styles(
+    styles: &mut Vec<ParagraphStyle>,
+    text: &str,
+    pushed: &[(Range<usize>, ParagraphStyle)],
+    inline_boxes: &[InlineBox],
+) {
+    let mut paragraph_starts = vec![0];
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if !matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}') {
+            continue;
+        }
+        let end = index + c.len_utf8();
+        // A CRLF sequence is a single hard break, unless an inline box separates the two. The
+        // boxes are sorted by index at this point.
+        if c == '\r'
+            && chars.peek().is_some_and(|&(_, next)| next == '\n')
+            && inline_boxes
+                .binary_search_by_key(&end, |inline_box| inline_box.index)
+                .is_err()
+        {
+            continue;
+        }
+        paragraph_starts.push(end);
+    }
+
+    let paragraph_at = |index: usize| paragraph_starts.partition_point(|&start| start <= index) - 1;
+    styles.clear();
+    styles.resize(paragraph_starts.len(), ParagraphStyle::default());
+    for (range, style) in pushed {
+        let start = range.start.min(text.len());
+        let end = range.end.min(text.len());
+        let first = paragraph_at(start);
+        let last = if end == text.len() {
+            // This includes the empty paragraph after a trailing hard break.
+            paragraph_at(end)
+        } else if end > start {
+            paragraph_at(end - 1)
+        } else {
+            first
+        };
+        styles[first..=last].fill(*style);
+    }
+}
+
 fn resolve_range(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
     let start = match range.start_bound() {
         Bound::Unbounded => 0,
@@ -5,19 +5,23 @@
 
 use core::ops::Range;
 
+use alloc::sync::Arc;
 use alloc::{vec, vec::Vec};
 
-use parlance::WordBreak;
+use parlance::{LineBreak, WordBreak};
+#[cfg(feature = "std")]
+use parley_engine::SharedShapePlanCache;
 use parley_engine::{Analysis, AnalysisDataSources, Analyzer, Shaper};
 
 use super::FontContext;
 use super::builder::{BuilderOptions, RangedBuilder, StyleRunBuilder};
 use super::resolve::tree::TreeStyleBuilder;
 use super::resolve::{RangedStyleBuilder, ResolveContext, ResolvedStyle, StyleRun};
-use super::style::{Brush, TextStyle};
+use super::style::{Brush, ParagraphStyle, TextStyle};
 
 use crate::builder::TreeBuilder;
 use crate::inline_box::InlineBox;
+use crate::shape::FontSelector;
 
 /// Shared scratch space used when constructing text layouts.
 ///
@@ -27,11 +31,14 @@ pub struct LayoutContext<B: Brush = [u8; 4]> {
     pub(crate) style_table: Vec<ResolvedStyle<B>>,
     pub(crate) style_runs: Vec<StyleRun>,
     pub(crate) inline_boxes: Vec<InlineBox>,
+    /// Paragraph styles and the text ranges they were pushed for, in push order.
+    pub(crate) paragraph_styles: Vec<(Range<usize>, ParagraphStyle)>,
 
     // Reusable text analysis
     pub(crate) analyzer: Analyzer,
     pub(crate) analysis: Analysis,
     pub(crate) word_break: Vec<(Range<usize>, WordBreak)>,
+    pub(crate) line_break: Vec<(Range<usize>, LineBreak)>,
 
     // Reusable style builders (to amortise allocations)
     pub(crate) ranged_style_builder: RangedStyleBuilder<B>,
@@ -40,6 +47,14 @@ pub struct LayoutContext<B: Brush = [u8; 4]> {
     /// Style index for each character, parallel to [`Analysis::char_info`].
     pub(crate) char_style_indices: Vec<u16>,
     pub(crate) scx: Shaper,
+    /// The generation of the font collection that [`Self::scx`] cached fonts for.
+    pub(crate) font_generation: Option<u64>,
+    /// Whether the items of long texts are shaped on multiple threads.
+    pub(crate) parallel_shaping: bool,
+    /// Whether font diagnostics are recorded for the layouts built with this context.
+    pub(crate) font_diagnostics: bool,
+    /// The font selection policy of layouts whose builder doesn't set one.
+    pub(crate) font_selector: Option<Arc<dyn FontSelector + Send + Sync>>,
 
     // Unicode analysis data sources (provided by icu)
     pub(crate) analysis_data_sources: AnalysisDataSources,
@@ -52,14 +67,20 @@ impl<B: Brush> LayoutContext<B> {
             style_table: vec![],
             style_runs: vec![],
             inline_boxes: vec![],
+            paragraph_styles: vec![],
             analyzer: Analyzer::new(),
             analysis: Analysis::new(),
             word_break: Vec::new(),
+            line_break: Vec::new(),
             ranged_style_builder: RangedStyleBuilder::default(),
             tree_style_builder: TreeStyleBuilder::default(),
             char_style_indices: vec![],
             analysis_data_sources: AnalysisDataSources::new(),
             scx: Shaper::default(),
+            font_generation: None,
+            parallel_shaping: false,
+            font_diagnostics: false,
+            font_selector: None,
         }
     }
 
@@ -99,14 +120,12 @@ impl<B: Brush> LayoutContext<B> {
         scale: f32,
         quantize: bool,
     ) -> RangedBuilder<'a, B> {
-        self.begin();
+        self.begin(fcx);
 
         let resolved_root_style = self.resolve_style_set(fcx, scale, &TextStyle::default());
         self.ranged_style_builder
             .begin(resolved_root_style, text.len());
 
-        fcx.source_cache.prune(128, false);
-
         RangedBuilder {
             options: BuilderOptions::new(scale, quantize),
             lcx: self,
@@ -129,9 +148,7 @@ impl<B: Brush> LayoutContext<B> {
         scale: f32,
         quantize: bool,
     ) -> StyleRunBuilder<'a, B> {
-        self.begin();
-
-        fcx.source_cache.prune(128, false);
+        self.begin(fcx);
 
         StyleRunBuilder {
             options: BuilderOptions::new(scale, quantize),
@@ -168,13 +185,11 @@ impl<B: Brush> LayoutContext<B> {
         quantize: bool,
         root_style: &TextStyle<'_, '_, B>,
     ) -> TreeBuilder<'a, B> {
-        self.begin();
+        self.begin(fcx);
 
         let resolved_root_style = self.resolve_style_set(fcx, scale, root_style);
         self.tree_style_builder.begin(resolved_root_style);
 
-        fcx.source_cache.prune(128, false);
-
         TreeBuilder {
             options: BuilderOptions::new(scale, quantize),
             lcx: self,
@@ -182,11 +197,68 @@ impl<B: Brush> LayoutContext<B> {
         }
     }
 
-    fn begin(&mut self) {
+    /// Sets the maximum number of shaped words to reuse across layouts, or `0` to not reuse any,
+    /// which is the default.
+    ///
+    /// See [`Shaper::set_word_cache_size`] for how words are reused.
+    pub fn set_word_cache_size(&mut self, max_words: usize) {
+        self.scx.set_word_cache_size(max_words);
+    }
+
+    /// Sets whether the items of long texts are shaped on multiple threads. They are shaped on the
+    /// calling thread by default.
+    ///
+    /// This has no effect unless the `rayon` feature is enabled. Fonts are still selected on the
+    /// calling thread, and the result is the same as shaping on one thread. The other threads
+    /// reuse the shaping plans and words this context cached, and the words they shape are kept
+    /// for later layouts. This is best left disabled when layouts are already built on multiple
+    /// threads.
+    pub fn set_parallel_shaping(&mut self, enabled: bool) {
+        self.parallel_shaping = enabled;
+    }
+
+    /// Sets whether [font diagnostics](crate::Layout::font_diagnostics) are recorded for the
+    /// layouts built with this context. They are not recorded by default.
+    pub fn set_font_diagnostics(&mut self, enabled: bool) {
+        self.font_diagnostics = enabled;
+    }
+
+    /// Sets the policy used to choose the font for each cluster of text in the layouts built with
+    /// this context, or `None` to use [`DefaultFontSelector`], which is the default.
+    ///
+    /// A selector set on a builder with `set_font_selector` takes precedence over this one.
+    ///
+    /// [`DefaultFontSelector`]: crate::DefaultFontSelector
+    pub fn set_font_selector(&mut self, selector: Option<Arc<dyn FontSelector + Send + Sync>>) {
+        self.font_selector = selector;
+    }
+
+    /// Sets a shaping plan cache shared with other layout contexts, or `None` to stop sharing.
+    ///
+    /// Building a shaping plan is expensive, so when layout contexts on several threads shape text
+    /// with the same fonts, they can reuse each other's plans through a clone of the same cache.
+    #[cfg(feature = "std")]
+    pub fn set_shared_shape_plan_cache(&mut self, cache: Option<SharedShapePlanCache>) {
+        self.scx.set_shared_plan_cache(cache);
+    }
+
+    fn begin(&mut self, fcx: &mut FontContext) {
+        // Drop the font data cached for a previous state of the collection.
+        let generation = fcx.generation();
+        if self.font_generation != Some(generation) {
+            self.scx.clear_caches();
+            if self.font_generation.is_some() {
+                fcx.source_cache.prune(0, true);
+            }
+            self.font_generation = Some(generation);
+        }
+        fcx.source_cache.prune(128, false);
+
         self.rcx.clear();
         self.style_table.clear();
         self.style_runs.clear();
         self.inline_boxes.clear();
+        self.paragraph_styles.clear();
     }
 }
 
@@ -198,7 +270,15 @@ impl<B: Brush> Default for LayoutContext<B> {
 
 impl<B: Brush> Clone for LayoutContext<B> {
     fn clone(&self) -> Self {
-        // None of the internal state is visible so just return a new instance.
-        Self::new()
+        // The scratch space isn't visible, so only the configuration and the shaping caches are
+        // kept.
+        Self {
+            scx: self.scx.clone(),
+            font_generation: self.font_generation,
+            parallel_shaping: self.parallel_shaping,
+            font_diagnostics: self.font_diagnostics,
+            font_selector: self.font_selector.clone(),
+            ..Self::new()
+        }
     }
 }
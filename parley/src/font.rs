@@ -19,4 +19,27 @@ impl FontContext {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Returns the generation of the font collection.
+    ///
+    /// This changes whenever the fonts, generic families or fallbacks of the collection change,
+    /// for example when a rescan of a font directory finds new fonts. Layout contexts drop their
+    /// cached font data when it changes, and [`Layout::font_generation`] tells which layouts were
+    /// built before the change.
+    ///
+    /// [`Layout::font_generation`]: crate::Layout::font_generation
+    pub fn generation(&mut self) -> u64 {
+        self.collection.generation()
+    }
+
+    /// Shares the font collection and source cache of this context with all of its clones.
+    ///
+    /// After this, a clone of the context can be sent to each thread that builds layouts. The
+    /// clones resolve fonts against the same collection and only load each font file once, while
+    /// each thread keeps its own [`LayoutContext`](crate::LayoutContext).
+    #[cfg(feature = "std")]
+    pub fn make_shared(&mut self) {
+        self.collection.make_shared();
+        self.source_cache.make_shared();
+    }
 }
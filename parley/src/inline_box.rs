@@ -3,6 +3,7 @@
 
 /// A box to be laid out inline with text
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InlineBox {
     /// User-specified identifier for the box, which can be used by the user to determine which box in
     /// parley's output corresponds to which box in its input.
@@ -20,11 +21,14 @@ pub struct InlineBox {
     ///
     /// If `None`, the baseline is the box's bottom edge.
     pub baseline: Option<f32>,
+    /// How the box is aligned vertically within its line.
+    pub vertical_align: VerticalAlign,
 }
 
 /// Whether a box is in-flow (takes up space in the layout) or out-of-flow (e.g. absolutely positioned)
 /// or custom-out-of-flow (line-breaking should yield control flow)
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InlineBoxKind {
     /// `InFlow` boxes take up space in the layout and flow in line with text
     ///
@@ -42,3 +46,94 @@ pub enum InlineBoxKind {
     /// They can be used to implement advanced layout modes such as CSS's `float`
     CustomOutOfFlow,
 }
+
+/// The vertical alignment of an inline box within its line.
+///
+/// This corresponds to the CSS `vertical-align` property. Alignments relative to the "parent" use
+/// the metrics of the text run surrounding the box.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerticalAlign {
+    /// Align the box's baseline with the parent's baseline.
+    #[default]
+    Baseline,
+    /// Align the top of the box with the top of the line box.
+    Top,
+    /// Align the bottom of the box with the bottom of the line box.
+    Bottom,
+    /// Align the vertical midpoint of the box with the parent's baseline plus half its x-height.
+    Middle,
+    /// Align the top of the box with the top of the parent's font (its ascent).
+    TextTop,
+    /// Align the bottom of the box with the bottom of the parent's font (its descent).
+    TextBottom,
+    /// Lower the box's baseline to the position for subscripts of the parent.
+    Sub,
+    /// Raise the box's baseline to the position for superscripts of the parent.
+    Super,
+    /// Raise the box's baseline by the given amount in pixels above the parent's baseline.
+    ///
+    /// Negative values lower the box.
+    Length(f32),
+}
+
+/// The metrics of the text surrounding an inline box, used to resolve its [`VerticalAlign`].
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ParentMetrics {
+    pub(crate) font_size: f32,
+    pub(crate) ascent: f32,
+    pub(crate) descent: f32,
+    pub(crate) x_height: f32,
+}
+
+/// The resolved vertical placement of an in-flow inline box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ResolvedVerticalAlign {
+    /// The box extends `ascent` above and `descent` below the line's baseline.
+    Baseline { ascent: f32, descent: f32 },
+    /// The box is aligned with the top of the line box.
+    Top,
+    /// The box is aligned with the bottom of the line box.
+    Bottom,
+}
+
+impl InlineBox {
+    /// Resolve the vertical placement of this box against the metrics of the surrounding text.
+    pub(crate) fn resolve_vertical_align(&self, parent: &ParentMetrics) -> ResolvedVerticalAlign {
+        // By default (no explicit baseline) the bottom of the box is aligned with the baseline,
+        // i.e. the box is all ascent and zero descent.
+        let baseline = self.baseline.unwrap_or(self.height);
+        let raise = match self.vertical_align {
+            VerticalAlign::Top => return ResolvedVerticalAlign::Top,
+            VerticalAlign::Bottom => return ResolvedVerticalAlign::Bottom,
+            VerticalAlign::Middle => {
+                let ascent = (self.height + parent.x_height) * 0.5;
+                return ResolvedVerticalAlign::Baseline {
+                    ascent,
+                    descent: self.height - ascent,
+                };
+            }
+            VerticalAlign::TextTop => {
+                return ResolvedVerticalAlign::Baseline {
+                    ascent: parent.ascent,
+                    descent: self.height - parent.ascent,
+                };
+            }
+            VerticalAlign::TextBottom => {
+                return ResolvedVerticalAlign::Baseline {
+                    ascent: self.height - parent.descent,
+                    descent: parent.descent,
+                };
+            }
+            VerticalAlign::Baseline => 0.0,
+            // These match the offsets browsers commonly use in the absence of better font data.
+            VerticalAlign::Sub => -parent.font_size / 5.0,
+            VerticalAlign::Super => parent.font_size / 3.0,
+            VerticalAlign::Length(length) => length,
+        };
+        ResolvedVerticalAlign::Baseline {
+            ascent: baseline + raise,
+            descent: self.height - baseline - raise,
+        }
+    }
+}
@@ -122,7 +122,16 @@ impl LayoutAccessibility {
         if matches!(attrs.style, FontStyle::Italic) {
             node.set_italic();
         }
-        if let Some(align) = run.layout.data.alignment {
+        // Once the layout is aligned, the run's paragraph may override the layout's alignment.
+        let layout_data = &run.layout.data;
+        let alignment = layout_data.alignment.map(|align| {
+            layout_data
+                .lines
+                .get(run.line_index as usize)
+                .and_then(|line| layout_data.paragraph_style(line.paragraph).alignment)
+                .unwrap_or(align)
+        });
+        if let Some(align) = alignment {
             node.set_text_align(match align {
                 Alignment::Start => {
                     if run.is_rtl() {
@@ -9,6 +9,7 @@ use parley_engine::shape::ShapedCluster;
 
 /// Alignment of a layout.
 #[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Alignment {
     /// This is [`Alignment::Left`] for LTR text and [`Alignment::Right`] for RTL text.
@@ -66,7 +67,12 @@ pub(crate) fn align<B: Brush>(
     {
         layout.alignment = Some(alignment);
     }
-    layout.is_aligned_justified = alignment == Alignment::Justify;
+    let justifies = alignment == Alignment::Justify
+        || layout
+            .paragraph_styles
+            .iter()
+            .any(|style| style.alignment == Some(Alignment::Justify));
+    layout.justified_alignment = justifies.then_some(alignment);
 
     align_impl::<_, false>(layout, alignment, options);
 }
@@ -76,14 +82,15 @@ pub(crate) fn align<B: Brush>(
 /// This is part of resetting state in preparation for re-line-breaking or re-aligning the same
 /// layout.
 pub(crate) fn unjustify<B: Brush>(layout: &mut LayoutData<B>) {
-    if layout.is_aligned_justified {
-        align_impl::<_, true>(layout, Alignment::Justify, AlignmentOptions::default());
-        layout.is_aligned_justified = false;
+    if let Some(alignment) = layout.justified_alignment.take() {
+        align_impl::<_, true>(layout, alignment, AlignmentOptions::default());
     }
 }
 
 /// The actual alignment implementation.
 ///
+/// Lines are aligned with the alignment of their paragraph, falling back to `alignment`.
+///
 /// This is const-generic over `UNDO_JUSTIFICATION`: justified alignment adjusts clusters'
 /// [`ShapedCluster::advance`], and this mutation has to be undone for re-line-breaking or
 /// re-aligning. `UNDO_JUSTIFICATION` indicates whether the adjustment has to be applied, or
@@ -101,20 +108,30 @@ fn align_impl<B: Brush, const UNDO_JUSTIFICATION: bool>(
     // Apply alignment to line items
     for line in &mut layout.lines {
         let indent = line.indent;
+        let paragraph = layout
+            .paragraph_styles
+            .get(line.paragraph)
+            .copied()
+            .unwrap_or_default();
+        let end_indent = paragraph.end_indent;
+        let alignment = paragraph.alignment.unwrap_or(alignment);
 
         if is_rtl {
             // In RTL text, trailing whitespace is on the left. As we hang that whitespace, offset
-            // the line to the left. Note: indent is not subtracted here because `free_space` below
-            // already accounts for it.
-            line.metrics.offset = -line.metrics.trailing_whitespace;
+            // the line to the left. Note: the start indent is not subtracted here because
+            // `free_space` below already accounts for it.
+            line.metrics.offset = end_indent - line.metrics.trailing_whitespace;
         } else {
-            line.metrics.offset = indent;
+            // Opening punctuation trimmed at the start of the line has its blank half moved
+            // before the start edge.
+            line.metrics.offset = indent - line.start_trim;
         }
 
-        // Compute free space.
+        // Compute free space. Hanging punctuation is excluded, like trailing whitespace.
         let line_width = line.metrics.inline_max_coord - line.metrics.inline_min_coord;
-        let free_space =
-            line_width - indent - line.metrics.advance + line.metrics.trailing_whitespace;
+        let free_space = line_width - indent - end_indent - line.metrics.advance
+            + line.metrics.trailing_whitespace
+            + line.hanging;
 
         if !options.align_when_overflowing && free_space <= 0.0 {
             if is_rtl {
@@ -1,26 +1,41 @@
 // Copyright 2021 the Parley Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::inline_box::InlineBox;
+use crate::inline_box::{InlineBox, ParentMetrics, ResolvedVerticalAlign};
+use crate::layout::FontDiagnostics;
+use crate::layout::text_spacing;
 use crate::layout::{ContentWidths, LineMetrics, Style};
 use crate::resolve::ResolvedStyle;
-use crate::style::Brush;
+use crate::style::{Brush, PaintProperty};
 use crate::util::nearly_zero;
-use crate::{IndentOptions, InlineBoxKind, LineHeight, OverflowWrap, TextWrapMode};
+use crate::{
+    Alignment, BasePalette, BaselineGrid, ColorScheme, FontData, IndentOptions, InlineBoxKind,
+    LineHeight, OverflowWrap, PaletteOverride, ParagraphStyle, TextBoxEdge, TextBoxOverEdge,
+    TextBoxTrim, TextBoxUnderEdge, TextWrapMode,
+};
 use core::ops::Range;
 
 use alloc::vec::Vec;
+#[cfg(feature = "libm")]
+#[allow(unused_imports)]
+use core_maths::CoreFloat;
+use fontique::ColorPalettes;
 use parlance::BidiLevel;
-use parley_engine::shape::Whitespace;
-use parley_engine::{Boundary, ShapedSlice, ShapedText};
+use parley_engine::shape::{ShapedCluster, Whitespace};
+use parley_engine::{Boundary, FontMetrics, Glyph, ShapedSlice, ShapedText};
 
 /// `HarfRust`-based run data
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct RunData {
     /// Font attributes, needed for accessibility.
     pub(crate) font_attrs: fontique::Attributes,
     /// Synthesis for rendering (contains variation settings)
     pub(crate) synthesis: fontique::Synthesis,
+    /// The index of the palette that color glyphs are painted with.
+    pub(crate) palette_index: u16,
+    /// Range of the palette overrides in [`LayoutData::palette_overrides`].
+    pub(crate) palette_overrides: Range<usize>,
     /// The line height
     pub line_height: f32,
     /// Additional word spacing.
@@ -30,6 +45,7 @@ pub(crate) struct RunData {
 }
 
 #[derive(Copy, Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BreakReason {
     #[default]
     None,
@@ -39,6 +55,7 @@ pub enum BreakReason {
 }
 
 #[derive(Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct LineData {
     /// Range of the source text.
     pub(crate) text_range: Range<usize>,
@@ -52,8 +69,16 @@ pub(crate) struct LineData {
     pub(crate) max_advance: f32,
     /// Number of justified clusters on the line.
     pub(crate) num_spaces: usize,
-    /// Text indent applied to this line.
+    /// Text indent applied to this line, including the indents of its paragraph.
     pub(crate) indent: f32,
+    /// The index of the paragraph the line starts in.
+    pub(crate) paragraph: usize,
+    /// The blank space trimmed before opening punctuation at the start of the line.
+    pub(crate) start_trim: f32,
+    /// The advance of punctuation hanging past the end of the line.
+    pub(crate) hanging: f32,
+    /// The typographic edges of the text on this line, used for trimming.
+    pub(crate) text_edges: TextEdges,
 }
 
 impl LineData {
@@ -62,7 +87,55 @@ impl LineData {
     }
 }
 
+/// The largest typographic edges of the text on a line, measured from the baseline.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct TextEdges {
+    /// Whether any text contributed to these edges.
+    pub(crate) has_text: bool,
+    pub(crate) ascent: f32,
+    pub(crate) descent: f32,
+    pub(crate) cap_height: f32,
+    pub(crate) x_height: f32,
+}
+
+impl TextEdges {
+    /// Grow the edges to fit text with the given metrics.
+    ///
+    /// `ascent` and `descent` are passed separately as they may already have been quantized.
+    pub(crate) fn add(&mut self, metrics: &FontMetrics, ascent: f32, descent: f32, quantize: bool) {
+        let round = |value: f32| if quantize { value.round() } else { value };
+        self.has_text = true;
+        self.ascent = self.ascent.max(ascent);
+        self.descent = self.descent.max(descent);
+        self.cap_height = self
+            .cap_height
+            .max(metrics.cap_height.map(round).unwrap_or(ascent));
+        self.x_height = self
+            .x_height
+            .max(metrics.x_height.map(round).unwrap_or(ascent));
+    }
+
+    /// The distance of the given over edge above the baseline.
+    pub(crate) fn over(&self, edge: TextBoxOverEdge) -> f32 {
+        match edge {
+            TextBoxOverEdge::Text => self.ascent,
+            TextBoxOverEdge::Cap => self.cap_height,
+            TextBoxOverEdge::Ex => self.x_height,
+        }
+    }
+
+    /// The distance of the given under edge below the baseline.
+    pub(crate) fn under(&self, edge: TextBoxUnderEdge) -> f32 {
+        match edge {
+            TextBoxUnderEdge::Text => self.descent,
+            TextBoxUnderEdge::Alphabetic => 0.,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct LineItemData {
     /// Whether the item is a run or an inline box
     pub(crate) kind: LayoutItemKind,
@@ -143,6 +216,38 @@ impl LineItemData {
     }
 }
 
+/// Adds `spacing` to the advance of `cluster`.
+pub(crate) fn add_cluster_spacing(cluster: &mut ShapedCluster, glyphs: &mut [Glyph], spacing: f32) {
+    cluster.advance += spacing;
+    // An inline glyph's advance is the cluster's advance, so it needs no separate adjustment.
+    if !cluster.has_inline_glyph() && cluster.glyph_len() > 0 {
+        let start = cluster.glyph_offset as usize;
+        let end = start + cluster.glyph_len() as usize;
+        if let Some(last) = glyphs[start..end].last_mut() {
+            last.advance += spacing;
+        }
+    }
+}
+
+/// The index of the palette of `font` selected by `base` in the given color scheme.
+///
+/// Falls back to the font's first palette where `base` selects none, as CSS does.
+pub(crate) fn select_palette(font: &FontData, base: BasePalette, color_scheme: ColorScheme) -> u16 {
+    if base == BasePalette::Normal && color_scheme == ColorScheme::Light {
+        return 0;
+    }
+    let Some(palettes) = ColorPalettes::new(font.data.as_ref(), font.index) else {
+        return 0;
+    };
+    match base {
+        BasePalette::Normal => palettes.dark(),
+        BasePalette::Light => palettes.light(),
+        BasePalette::Dark => palettes.dark(),
+        BasePalette::Index(index) => (index < palettes.len()).then_some(index),
+    }
+    .unwrap_or(0)
+}
+
 /// The number of graphemes in `slice`.
 ///
 /// This is `O(n)` in the slice's characters.
@@ -155,12 +260,14 @@ pub(crate) fn count_graphemes(slice: ShapedSlice<'_>) -> usize {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum LayoutItemKind {
     TextRun,
     InlineBox,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct LayoutItem {
     /// Whether the item is a run or an inline box
     pub(crate) kind: LayoutItemKind,
@@ -171,6 +278,7 @@ pub(crate) struct LayoutItem {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct LayoutData<B: Brush> {
     // General settings (directly from the "builder")
     /// The display scale factor
@@ -181,15 +289,31 @@ pub(crate) struct LayoutData<B: Brush> {
     pub(crate) base_level: BidiLevel,
     /// The length of the text in the layout
     pub(crate) text_len: usize,
+    /// The generation of the font collection the layout was shaped with
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) font_generation: u64,
 
     // Output of style resolution (input to line breaking)
     pub(crate) styles: Vec<Style<B>>,
     pub(crate) inline_boxes: Vec<InlineBox>,
+    /// The style of each paragraph, or empty if no paragraph styles were set.
+    pub(crate) paragraph_styles: Vec<ParagraphStyle>,
 
     // Output of shaping (input to line breaking)
     pub(crate) shaped_text: ShapedText,
     pub(crate) runs: Vec<RunData>,
     pub(crate) items: Vec<LayoutItem>,
+    /// Spacing added to cluster advances for the boundary after them, as `(shaped cluster index,
+    /// spacing)` pairs sorted by index.
+    ///
+    /// This is autospacing and trimmed opening punctuation, which don't apply when a line ends at
+    /// the boundary.
+    pub(crate) boundary_spacing: Vec<(u32, f32)>,
+    /// Clusters with missing glyphs, fallback fonts or the last resort font.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) font_diagnostics: FontDiagnostics,
+    /// The palette overrides of all runs, sorted by entry within each run.
+    pub(crate) palette_overrides: Vec<PaletteOverride>,
 
     // Output of line breaking
     /// The lines in the
@@ -210,12 +334,18 @@ pub(crate) struct LayoutData<B: Brush> {
     /// Directly store the alignment if accessibility is enabled so we can
     /// set the corresponding AccessKit property.
     pub(crate) alignment: Option<super::Alignment>,
-    /// Whether the layout is aligned with [`crate::Alignment::Justify`].
-    pub(crate) is_aligned_justified: bool,
+    /// The alignment the layout was last aligned with, if that justified any of its lines.
+    pub(crate) justified_alignment: Option<Alignment>,
     /// The text-indent amount in layout units.
     pub(crate) indent_amount: f32,
     /// Options controlling text-indent behavior (each-line, hanging).
     pub(crate) indent_options: IndentOptions,
+    /// Which sides of the layout have their leading trimmed.
+    pub(crate) text_box_trim: TextBoxTrim,
+    /// The edges trimmed sides are trimmed to.
+    pub(crate) text_box_edge: TextBoxEdge,
+    /// The grid that line baselines are snapped to, if any.
+    pub(crate) baseline_grid: Option<BaselineGrid>,
 }
 
 impl<B: Brush> Default for LayoutData<B> {
@@ -225,22 +355,30 @@ impl<B: Brush> Default for LayoutData<B> {
             quantize: true,
             base_level: BidiLevel::new(0),
             text_len: 0,
+            font_generation: 0,
             width: 0.,
             full_width: 0.,
             height: 0.,
             styles: Vec::new(),
             inline_boxes: Vec::new(),
+            paragraph_styles: Vec::new(),
             shaped_text: ShapedText::new(),
             runs: Vec::new(),
             items: Vec::new(),
+            boundary_spacing: Vec::new(),
+            font_diagnostics: FontDiagnostics::default(),
+            palette_overrides: Vec::new(),
             lines: Vec::new(),
             line_items: Vec::new(),
             #[cfg(feature = "accesskit")]
             alignment: None,
-            is_aligned_justified: false,
+            justified_alignment: None,
             layout_max_advance: 0.0,
             indent_amount: 0.0,
             indent_options: IndentOptions::default(),
+            text_box_trim: TextBoxTrim::None,
+            text_box_edge: TextBoxEdge::default(),
+            baseline_grid: None,
         }
     }
 }
@@ -251,18 +389,55 @@ impl<B: Brush> LayoutData<B> {
         self.quantize = true;
         self.base_level = BidiLevel::new(0);
         self.text_len = 0;
+        self.font_generation = 0;
         self.width = 0.;
         self.full_width = 0.;
         self.height = 0.;
         self.styles.clear();
         self.inline_boxes.clear();
+        self.paragraph_styles.clear();
         self.shaped_text.clear();
         self.runs.clear();
         self.items.clear();
+        self.boundary_spacing.clear();
+        self.font_diagnostics.clear();
+        self.palette_overrides.clear();
         self.lines.clear();
         self.line_items.clear();
     }
 
+    /// Applies `property` to the styles of the characters starting in the byte range `range`.
+    ///
+    /// Characters whose style changes are given an equal style from the table if there is one,
+    /// and a new style otherwise, so other text sharing their old style is left as is.
+    ///
+    /// # Panics
+    ///
+    /// If this would give the layout more than 65536 styles.
+    pub(crate) fn restyle(&mut self, range: Range<usize>, property: &PaintProperty<B>) {
+        let styles = &mut self.styles;
+        // The new index of each old style, once a character with that style has been restyled.
+        let mut remap: Vec<Option<u16>> = alloc::vec![None; styles.len()];
+        self.shaped_text.restyle_characters(|character| {
+            let old = character.style_index;
+            if !range.contains(&(character.text_byte_start as usize)) {
+                return old;
+            }
+            *remap[usize::from(old)].get_or_insert_with(|| {
+                let mut style = styles[usize::from(old)].clone();
+                property.apply(&mut style);
+                let index = styles
+                    .iter()
+                    .position(|other| *other == style)
+                    .unwrap_or_else(|| {
+                        styles.push(style);
+                        styles.len() - 1
+                    });
+                u16::try_from(index).expect("a layout can't have more than 65536 styles")
+            })
+        });
+    }
+
     /// Push an inline box to the list of items
     pub(crate) fn push_inline_box(&mut self, index: usize) {
         // Give the box the same bidi level as the preceding text run
@@ -280,11 +455,56 @@ impl<B: Brush> LayoutData<B> {
             bidi_level,
         });
     }
+    /// The style of the paragraph with the given index.
+    pub(crate) fn paragraph_style(&self, paragraph: usize) -> ParagraphStyle {
+        self.paragraph_styles
+            .get(paragraph)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The space between the last line of the paragraph `prev` and the first line of the
+    /// paragraph `next`.
+    pub(crate) fn paragraph_spacing(&self, prev: usize, next: usize) -> f32 {
+        self.paragraph_style(prev).space_after + self.paragraph_style(next).space_before
+    }
+
+    /// Resolve the vertical placement of `inline_box` against the text run surrounding it.
+    ///
+    /// The surrounding run is the one containing the box's byte index, or otherwise the run just
+    /// before it. Layouts without text resolve against zeroed metrics.
+    pub(crate) fn resolve_inline_box_align(&self, inline_box: &InlineBox) -> ResolvedVerticalAlign {
+        let runs = self.shaped_text.runs();
+        let run_idx = runs
+            .partition_point(|run| run.range.byte_range.start < inline_box.index)
+            .saturating_sub(1);
+        let parent = runs
+            .get(run_idx)
+            .map(|run| {
+                let metrics = &run.font_metrics;
+                let (ascent, descent) = if self.quantize {
+                    (metrics.ascent.round(), metrics.descent.round())
+                } else {
+                    (metrics.ascent, metrics.descent)
+                };
+                ParentMetrics {
+                    font_size: run.font_size,
+                    ascent,
+                    descent,
+                    x_height: metrics.x_height.unwrap_or(run.font_size * 0.5),
+                }
+            })
+            .unwrap_or_default();
+        inline_box.resolve_vertical_align(&parent)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn process_shaped_run(
         &mut self,
         shaped_run_idx: usize,
         run_style: &ResolvedStyle<B>,
+        palette_overrides: &[PaletteOverride],
+        color_scheme: ColorScheme,
         word_spacing: f32,
         letter_spacing: f32,
     ) {
@@ -312,6 +532,20 @@ impl<B: Brush> LayoutData<B> {
         };
 
         let font = &self.shaped_text.fonts()[shaped_run.font_index];
+        let palette_index = select_palette(&font.font, run_style.font_palette.base, color_scheme);
+        // Consecutive runs usually share their overrides, so reuse those of the previous run.
+        let palette_overrides = match self.runs.last() {
+            Some(prev)
+                if self.palette_overrides[prev.palette_overrides.clone()] == *palette_overrides =>
+            {
+                prev.palette_overrides.clone()
+            }
+            _ => {
+                let start = self.palette_overrides.len();
+                self.palette_overrides.extend_from_slice(palette_overrides);
+                start..self.palette_overrides.len()
+            }
+        };
         let run = RunData {
             font_attrs: fontique::Attributes {
                 width: run_style.font_width,
@@ -319,6 +553,8 @@ impl<B: Brush> LayoutData<B> {
                 style: run_style.font_style,
             },
             synthesis: font.synthesis,
+            palette_index,
+            palette_overrides,
             line_height,
             word_spacing,
             letter_spacing,
@@ -351,19 +587,40 @@ impl<B: Brush> LayoutData<B> {
                     spacing += word;
                 }
                 if !nearly_zero(spacing) {
-                    cluster.advance += spacing;
-                    // An inline glyph's advance is the cluster's advance, so it needs no separate
-                    // adjustment.
-                    if !cluster.has_inline_glyph() && cluster.glyph_len() > 0 {
-                        let start = cluster.glyph_offset as usize;
-                        let end = start + cluster.glyph_len() as usize;
-                        if let Some(last) = glyphs[start..end].last_mut() {
-                            last.advance += spacing;
-                        }
-                    }
+                    add_cluster_spacing(cluster, glyphs, spacing);
                 }
             }
         }
+        text_spacing::apply_spacing(self);
+    }
+
+    /// The spacing [`text_spacing`] added after the cluster at `cluster_idx` for the boundary
+    /// following it, which doesn't apply if a line ends at that boundary.
+    pub(crate) fn boundary_spacing(&self, cluster_idx: u32) -> f32 {
+        self.boundary_spacing
+            .binary_search_by_key(&cluster_idx, |(idx, _)| *idx)
+            .map_or(0., |idx| self.boundary_spacing[idx].1)
+    }
+
+    /// Whether a fragment starting at the line `first_line` may end before the line `line_idx`,
+    /// according to the styles of the paragraphs around the break.
+    pub(crate) fn allows_fragment_break(&self, first_line: usize, line_idx: usize) -> bool {
+        let prev = &self.lines[line_idx - 1];
+        let next = &self.lines[line_idx];
+        if prev.paragraph != next.paragraph {
+            return !self.paragraph_style(prev.paragraph).keep_with_next;
+        }
+        let style = self.paragraph_style(next.paragraph);
+        let orphans = self.lines[first_line..line_idx]
+            .iter()
+            .rev()
+            .take_while(|line| line.paragraph == next.paragraph)
+            .count();
+        let widows = self.lines[line_idx..]
+            .iter()
+            .take_while(|line| line.paragraph == next.paragraph)
+            .count();
+        orphans >= style.orphans as usize && widows >= style.widows as usize
     }
 
     // TODO: this method does not handle mixed direction text at all.
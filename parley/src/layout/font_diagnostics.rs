@@ -0,0 +1,133 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Diagnostics about the fonts chosen during shaping.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use fontique::FamilyId;
+use parley_engine::FontInstance;
+use parley_engine::shape::Coverage;
+
+/// Clusters of a [`Layout`](crate::Layout) whose font selection may need attention.
+///
+/// This records text that isn't fully covered by its font, which is usually rendered as boxes
+/// ("tofu"), text set in a font from outside its requested family stack, and text set in the last
+/// resort font. It can be used to flag unsupported or untranslated strings.
+///
+/// Adjacent clusters with the same requested families, font and flags are merged into one
+/// [`FontDiagnostic`].
+///
+/// Diagnostics are only recorded when enabled with
+/// [`LayoutContext::set_font_diagnostics`](crate::LayoutContext::set_font_diagnostics).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontDiagnostics {
+    entries: Vec<FontDiagnostic>,
+}
+
+impl FontDiagnostics {
+    /// Returns `true` if all text was set in its requested families, with complete coverage.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns all recorded diagnostics, in text order.
+    pub fn entries(&self) -> &[FontDiagnostic] {
+        &self.entries
+    }
+
+    /// Returns the diagnostics of text with characters missing from its font.
+    pub fn missing_glyphs(&self) -> impl Iterator<Item = &FontDiagnostic> + Clone {
+        self.entries
+            .iter()
+            .filter(|entry| entry.has_missing_glyphs())
+    }
+
+    /// Returns the diagnostics of text set in a font outside its requested family stack.
+    pub fn fallbacks(&self) -> impl Iterator<Item = &FontDiagnostic> + Clone {
+        self.entries.iter().filter(|entry| entry.is_fallback)
+    }
+
+    /// Returns the diagnostics of text set in the last resort font.
+    pub fn last_resort(&self) -> impl Iterator<Item = &FontDiagnostic> + Clone {
+        self.entries.iter().filter(|entry| entry.is_last_resort)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Records a cluster, merging it into the previous entry if that describes the same font
+    /// selection for the text directly before it.
+    ///
+    /// The selection is only copied when it starts a new entry.
+    pub(crate) fn push(&mut self, cluster: ClusterSelection<'_>) {
+        if let Some(last) = self.entries.last_mut()
+            && last.text_range.end == cluster.text_range.start
+            && last.family == cluster.family
+            && last.font.as_ref() == cluster.font
+            && last.is_fallback == cluster.is_fallback
+            && last.is_last_resort == cluster.is_last_resort
+            && last.requested_families == cluster.requested_families
+        {
+            last.text_range.end = cluster.text_range.end;
+            last.coverage = last.coverage.min(cluster.coverage);
+            return;
+        }
+        self.entries.push(FontDiagnostic {
+            text_range: cluster.text_range,
+            requested_families: cluster.requested_families.to_vec(),
+            family: cluster.family,
+            font: cluster.font.cloned(),
+            coverage: cluster.coverage,
+            is_fallback: cluster.is_fallback,
+            is_last_resort: cluster.is_last_resort,
+        });
+    }
+}
+
+/// The font selection of a single cluster, borrowed from the shaping state.
+///
+/// See [`FontDiagnostic`] for the meaning of the fields.
+pub(crate) struct ClusterSelection<'a> {
+    pub(crate) text_range: Range<usize>,
+    pub(crate) requested_families: &'a [FamilyId],
+    pub(crate) family: Option<FamilyId>,
+    pub(crate) font: Option<&'a FontInstance>,
+    pub(crate) coverage: Coverage,
+    pub(crate) is_fallback: bool,
+    pub(crate) is_last_resort: bool,
+}
+
+/// The font selection of a range of text, as recorded in [`FontDiagnostics`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontDiagnostic {
+    /// The range of the text in the layout, in bytes.
+    pub text_range: Range<usize>,
+    /// The resolved font family stack of the text's style.
+    pub requested_families: Vec<FamilyId>,
+    /// The family of the chosen font, or `None` if no font was found at all.
+    pub family: Option<FamilyId>,
+    /// The chosen font, or `None` if no font was found at all.
+    pub font: Option<FontInstance>,
+    /// The chosen font's coverage of the text.
+    ///
+    /// For merged clusters, this is the coverage of the least covered cluster.
+    pub coverage: Coverage,
+    /// Whether the chosen font is from outside [`Self::requested_families`].
+    ///
+    /// This includes fonts from script and locale fallback families. Fonts from the
+    /// [emoji generic family](crate::GenericFamily::Emoji), which is added to the families of
+    /// emoji, are not fallbacks for emoji.
+    pub is_fallback: bool,
+    /// Whether the chosen font is the last resort font, used when the font query found no font.
+    pub is_last_resort: bool,
+}
+
+impl FontDiagnostic {
+    /// Returns `true` if some of the characters of the text are missing from the chosen font.
+    pub fn has_missing_glyphs(&self) -> bool {
+        !self.coverage.is_complete()
+    }
+}
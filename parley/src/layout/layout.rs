@@ -8,12 +8,13 @@ use crate::layout::data::LayoutData;
 use crate::style::Brush;
 use core::cmp::Ordering;
 use core::fmt;
+use core::ops::Range;
 
-use crate::IndentOptions;
 use crate::layout::{
-    ContentWidths, Style, alignment::Alignment, alignment::AlignmentOptions, line::Line,
-    line_break::BreakLines,
+    ContentWidths, FontDiagnostics, Style, alignment::Alignment, alignment::AlignmentOptions,
+    line::Line, line_break::BreakLines,
 };
+use crate::{BaselineGrid, IndentOptions, PaintProperty, TextBoxEdge, TextBoxTrim};
 
 /// Text layout.
 ///
@@ -59,6 +60,33 @@ impl<B: Brush> Layout<B> {
         &self.data.styles
     }
 
+    /// Returns the style collection for the layout, for changing how text with each style is
+    /// painted.
+    ///
+    /// The fields of [`Style`] that can be changed don't affect the layout's metrics, so the layout
+    /// doesn't need to be built again. A style may be shared by separate ranges of text; use
+    /// [`Self::restyle`] to change the style of a range.
+    ///
+    /// Changing a style's `brush` here doesn't change the brush of its decorations, even those
+    /// drawn with the text's brush. [`Self::restyle`] with [`PaintProperty::Brush`] updates both.
+    pub fn styles_mut(&mut self) -> &mut [Style<B>] {
+        &mut self.data.styles
+    }
+
+    /// Applies `property` to the text in the byte range `range`, without building the layout
+    /// again.
+    ///
+    /// Text with a style that only partly overlaps `range` is given a new style, which is added to
+    /// the [style collection](Self::styles). Clusters, such as ligatures, take the style of their
+    /// first character.
+    ///
+    /// # Panics
+    ///
+    /// If this would give the layout more than 65536 styles.
+    pub fn restyle(&mut self, range: Range<usize>, property: PaintProperty<B>) {
+        self.data.restyle(range, &property);
+    }
+
     /// The `max_advance` that was used to line break the `Layout`
     pub fn layout_max_advance(&self) -> f32 {
         self.data.layout_max_advance
@@ -127,6 +155,48 @@ impl<B: Brush> Layout<B> {
         self.data.base_level.is_rtl()
     }
 
+    /// Returns the generation of the font collection the layout was built with.
+    ///
+    /// A layout whose generation differs from [`FontContext::generation`] may use fonts
+    /// that were since removed or replaced, and should be built again.
+    ///
+    /// [`FontContext::generation`]: crate::FontContext::generation
+    pub fn font_generation(&self) -> u64 {
+        self.data.font_generation
+    }
+
+    /// Returns the clusters with missing glyphs or fallback fonts, as recorded during shaping.
+    ///
+    /// This is empty unless diagnostics were enabled with
+    /// [`LayoutContext::set_font_diagnostics`](crate::LayoutContext::set_font_diagnostics).
+    pub fn font_diagnostics(&self) -> &FontDiagnostics {
+        &self.data.font_diagnostics
+    }
+
+    /// Returns a snapshot of the layout that can be serialized, and restored later without
+    /// laying out its text again.
+    ///
+    /// This hashes the data of each font of the layout to identify it. Use
+    /// [`Self::snapshot_with`] to reuse identifiers computed before.
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&self) -> crate::LayoutSnapshot<B> {
+        self.snapshot_with(super::snapshot::hash_font_ids())
+    }
+
+    /// Returns a snapshot of the layout like [`Self::snapshot`], identifying its fonts with
+    /// `font_id`.
+    ///
+    /// `font_id` is called once for each font of the layout. It can return identifiers that were
+    /// computed with [`FontDataId::new`](crate::FontDataId::new) when the fonts were loaded, so
+    /// their data isn't hashed for every snapshot.
+    #[cfg(feature = "serde")]
+    pub fn snapshot_with(
+        &self,
+        font_id: impl FnMut(&crate::FontData) -> crate::FontDataId,
+    ) -> crate::LayoutSnapshot<B> {
+        crate::LayoutSnapshot::new(&self.data, font_id)
+    }
+
     pub fn inline_boxes(&self) -> &[InlineBox] {
         &self.data.inline_boxes
     }
@@ -163,6 +233,34 @@ impl<B: Brush> Layout<B> {
         self.data.indent_options = options;
     }
 
+    /// Sets the leading trim for the layout, corresponding to CSS `text-box-trim` and
+    /// `text-box-edge`.
+    ///
+    /// Trimming removes the space between the edge of the first and/or last line box and the
+    /// given font metric of the text on that line, such as its cap height. This is useful for
+    /// aligning the visible text with surrounding content. [`Layout::height`] and
+    /// [`Layout::first_baseline`] reflect the trim.
+    ///
+    /// This must be called before [`Layout::break_all_lines`] or [`Layout::break_lines`].
+    pub fn set_text_box_trim(&mut self, trim: TextBoxTrim, edge: TextBoxEdge) {
+        self.data.text_box_trim = trim;
+        self.data.text_box_edge = edge;
+    }
+
+    /// Sets the baseline grid for the layout.
+    ///
+    /// When set, each line is pushed down such that its baseline falls on the next line of the
+    /// grid, leaving a gap above the line where needed. The reported [`LineMetrics`], the
+    /// [`BreakLines::committed_y`] and [`Layout::height`] include these gaps. When quantizing, the
+    /// snapped positions are rounded to whole pixels.
+    ///
+    /// This must be called before [`Layout::break_all_lines`] or [`Layout::break_lines`].
+    ///
+    /// [`LineMetrics`]: crate::LineMetrics
+    pub fn set_baseline_grid(&mut self, grid: Option<BaselineGrid>) {
+        self.data.baseline_grid = grid;
+    }
+
     /// Returns line breaker to compute lines for the layout.
     pub fn break_lines(&mut self) -> BreakLines<'_, B> {
         unjustify(&mut self.data);
@@ -191,6 +289,40 @@ impl<B: Brush> Layout<B> {
         align(&mut self.data, alignment, options);
     }
 
+    /// Returns the index of the first line that doesn't fit in a fragment, such as a page or a
+    /// column, which starts at the line `first_line` and has the given `block_size`.
+    ///
+    /// Where the break would fall between lines of the same paragraph, it is moved up to leave
+    /// the paragraph's [`orphans`] in this fragment and its [`widows`] in the next. A break after
+    /// a paragraph with [`keep_with_next`] is moved up too. If that isn't possible, the fragment
+    /// is filled as if none of these were set. At least one line is always placed in the
+    /// fragment, so that callers paginating a layout always make progress.
+    ///
+    /// Returns [`Layout::len`] if all remaining lines fit.
+    ///
+    /// [`orphans`]: crate::ParagraphStyle::orphans
+    /// [`widows`]: crate::ParagraphStyle::widows
+    /// [`keep_with_next`]: crate::ParagraphStyle::keep_with_next
+    pub fn fragment_end(&self, first_line: usize, block_size: f32) -> usize {
+        let lines = &self.data.lines;
+        let Some(first) = lines.get(first_line) else {
+            return lines.len();
+        };
+        let top = first.metrics.block_min_coord;
+        let fitting = lines[first_line..]
+            .iter()
+            .take_while(|line| line.metrics.block_max_coord - top <= block_size)
+            .count();
+        let end = first_line + fitting.max(1);
+        if end >= lines.len() {
+            return lines.len();
+        }
+        (first_line + 1..=end)
+            .rev()
+            .find(|&line_idx| self.data.allows_fragment_break(first_line, line_idx))
+            .unwrap_or(end)
+    }
+
     /// Returns the index and `Line` object for the line containing the
     /// given byte `index` in the source text.
     pub(crate) fn line_for_byte_index(&self, index: usize) -> Option<(usize, Line<'_, B>)> {
@@ -1,15 +1,17 @@
 // Copyright 2021 the Parley Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::inline_box::ResolvedVerticalAlign;
 use crate::layout::Style;
 use crate::layout::data::BreakReason;
 use crate::layout::data::{LayoutItemKind, LineData};
 use crate::layout::layout::Layout;
 use crate::layout::run::Run;
 use crate::style::Brush;
-use crate::{InlineBox, InlineBoxKind};
+use crate::{InlineBox, InlineBoxKind, VerticalAlign};
 
 use core::ops::Range;
+use fontique::{RenderHints, Synthesis};
 use parley_engine::Glyph;
 
 /// Line in a text layout.
@@ -118,6 +120,7 @@ impl<'a, B: Brush> Line<'a, B> {
 /// A line has an inline axis and a block axis. The inline axis runs along a line of a text; the
 /// block axis is perpendicular to it. Lines are stacked along the block axis.
 #[derive(Copy, Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineMetrics {
     /// The absolute line height (in layout units).
     pub line_height: f32,
@@ -215,6 +218,7 @@ pub enum PositionedLayoutItem<'a, B: Brush> {
 #[derive(Debug, Clone)]
 pub struct PositionedInlineBox {
     pub x: f32,
+    /// The top of the box, resolved according to its [`Self::vertical_align`].
     pub y: f32,
     pub width: f32,
     pub height: f32,
@@ -223,6 +227,8 @@ pub struct PositionedInlineBox {
     /// This is `None` if the box does not specify a baseline, in which case its bottom edge is
     /// aligned with the text baseline.
     pub baseline: Option<f32>,
+    /// The vertical alignment the box was placed with.
+    pub vertical_align: VerticalAlign,
     pub id: u64,
     pub kind: InlineBoxKind,
 }
@@ -245,6 +251,27 @@ impl<'a, B: Brush> GlyphRun<'a, B> {
         &self.run
     }
 
+    /// Returns the synthesis suggestions for the run's font.
+    ///
+    /// See [`Run::synthesis`].
+    pub fn synthesis(&self) -> Synthesis {
+        self.run.synthesis()
+    }
+
+    /// Returns the amount faux bold widens the glyphs.
+    ///
+    /// See [`Run::embolden_strength`].
+    pub fn embolden_strength(&self) -> f32 {
+        self.run.embolden_strength()
+    }
+
+    /// Returns the rendering settings for the run's font.
+    ///
+    /// See [`Run::render_hints`].
+    pub fn render_hints(&self) -> RenderHints {
+        self.run.render_hints()
+    }
+
     /// Returns the associated style.
     ///
     /// See also [`Self::style_index`].
@@ -321,13 +348,30 @@ impl<'a, B: Brush> Iterator for GlyphRunIter<'a, B> {
                     if inline_box.kind == InlineBoxKind::InFlow {
                         self.offset += inline_box.width;
                     }
+                    let metrics = &self.line.data.metrics;
+                    let y = match inline_box.kind {
+                        InlineBoxKind::InFlow => {
+                            match self.line.layout.data.resolve_inline_box_align(inline_box) {
+                                ResolvedVerticalAlign::Baseline { ascent, .. } => {
+                                    metrics.baseline - ascent
+                                }
+                                ResolvedVerticalAlign::Top => metrics.block_min_coord,
+                                ResolvedVerticalAlign::Bottom => {
+                                    metrics.block_max_coord - inline_box.height
+                                }
+                            }
+                        }
+                        InlineBoxKind::OutOfFlow | InlineBoxKind::CustomOutOfFlow => {
+                            metrics.baseline - inline_box.baseline.unwrap_or(inline_box.height)
+                        }
+                    };
                     return Some(PositionedLayoutItem::InlineBox(PositionedInlineBox {
                         x,
-                        y: self.line.data.metrics.baseline
-                            - inline_box.baseline.unwrap_or(inline_box.height),
+                        y,
                         width: inline_box.width,
                         height: inline_box.height,
                         baseline: inline_box.baseline,
+                        vertical_align: inline_box.vertical_align,
                         id: inline_box.id,
                         kind: inline_box.kind,
                     }));
@@ -10,13 +10,15 @@ use alloc::vec::Vec;
 use core_maths::CoreFloat;
 use parlance::BidiLevel;
 
-use crate::layout::data::count_graphemes;
+use crate::inline_box::ResolvedVerticalAlign;
+use crate::layout::data::{TextEdges, count_graphemes};
+use crate::layout::text_spacing;
 use crate::layout::{
     BreakReason, Layout, LayoutData, LayoutItem, LayoutItemKind, LineData, LineItemData,
     LineMetrics, Run,
 };
 use crate::style::Brush;
-use crate::{InlineBoxKind, OverflowWrap, TextWrapMode};
+use crate::{InlineBoxKind, OverflowWrap, TextBoxOverEdge, TextWrapMode};
 
 use core::ops::Range;
 use parley_engine::shape::{Character, ShapedCluster, Whitespace};
@@ -54,6 +56,9 @@ struct LineState {
     /// We lag the text-wrap-mode by one cluster due to line-breaking boundaries only
     /// being triggered on the cluster after the linebreak.
     text_wrap_mode: TextWrapMode,
+
+    /// The blank space trimmed before opening punctuation at the start of the line.
+    start_trim: f32,
 }
 
 impl LineState {
@@ -61,6 +66,7 @@ impl LineState {
     fn reset(&mut self) {
         self.x = 0.0;
         self.box_metrics = LineBoxMetrics::default();
+        self.start_trim = 0.0;
     }
 }
 
@@ -71,9 +77,10 @@ impl LineState {
 ///
 /// Following CSS 2.2 § 10.8 (line height calculations in "Visual formatting model details"), line
 /// boxes are sized to fit the line's inline content. Inline content is first aligned to each other
-/// (we currently only align content by their baselines). We model this as the inline content being
-/// aligned to the line box's own "baseline," and carry the line box's height over and under that
-/// baseline. See <https://www.w3.org/TR/CSS22/visudet.html#line-height>.
+/// relative to their baselines. We model this as the inline content being aligned to the line
+/// box's own "baseline," and carry the line box's height over and under that baseline. Inline
+/// boxes aligned to the top or bottom of the line box are only placed once the rest of the line is
+/// known. See <https://www.w3.org/TR/CSS22/visudet.html#line-height>.
 #[derive(Clone, Copy, Debug, Default)]
 struct LineBoxMetrics {
     /// The extents from the line box's baseline.
@@ -89,6 +96,12 @@ struct LineBoxMetrics {
     ///
     /// Like [`Self::line_box`], these are in block flow direction.
     content_box: Extents,
+    /// The size of the largest inline box aligned to the top of the line box.
+    top_aligned: f32,
+    /// The size of the largest inline box aligned to the bottom of the line box.
+    bottom_aligned: f32,
+    /// The typographic edges of the text on the line, used for leading trim.
+    text_edges: TextEdges,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -117,7 +130,9 @@ impl LineBoxMetrics {
     /// The line height seen so far.
     #[inline(always)]
     fn line_height(self) -> f32 {
-        self.line_box.over + self.line_box.under
+        (self.line_box.over + self.line_box.under)
+            .max(self.top_aligned)
+            .max(self.bottom_aligned)
     }
 
     fn add_text(&mut self, metrics: &FontMetrics, line_height: f32, quantize: bool) {
@@ -142,6 +157,7 @@ impl LineBoxMetrics {
         self.line_box.under = self.line_box.under.max(under);
         self.content_box.over = self.content_box.over.max(ascent);
         self.content_box.under = self.content_box.under.max(descent);
+        self.text_edges.add(metrics, ascent, descent, quantize);
     }
 
     fn add_inline_box(&mut self, ascent: f32, descent: f32, quantize: bool) {
@@ -157,6 +173,57 @@ impl LineBoxMetrics {
             self.content_box.under = self.content_box.under.max(descent);
         }
     }
+
+    fn add_line_relative_box(
+        &mut self,
+        alignment: ResolvedVerticalAlign,
+        height: f32,
+        quantize: bool,
+    ) {
+        let height = if quantize { height.round() } else { height };
+        match alignment {
+            ResolvedVerticalAlign::Top => self.top_aligned = self.top_aligned.max(height),
+            ResolvedVerticalAlign::Bottom => self.bottom_aligned = self.bottom_aligned.max(height),
+            ResolvedVerticalAlign::Baseline { .. } => {
+                unreachable!("baseline-relative boxes are added with `add_inline_box`")
+            }
+        }
+    }
+
+    /// Grow the line box to fit the boxes aligned to its top or bottom edge.
+    ///
+    /// A top-aligned box hangs down from the top of the line box, so the line box grows below
+    /// the baseline to fit it. Conversely, a bottom-aligned box grows the line box above the
+    /// baseline.
+    fn resolve_line_relative_boxes(&mut self) {
+        if self.top_aligned > 0. {
+            if self.top_aligned > self.line_box.over + self.line_box.under {
+                self.line_box.under = self.top_aligned - self.line_box.over;
+            }
+            self.content_box.over = self.content_box.over.max(self.line_box.over);
+            self.content_box.under = self
+                .content_box
+                .under
+                .max(self.top_aligned - self.line_box.over);
+        }
+        if self.bottom_aligned > 0. {
+            if self.bottom_aligned > self.line_box.over + self.line_box.under {
+                self.line_box.over = self.bottom_aligned - self.line_box.under;
+            }
+            self.content_box.under = self.content_box.under.max(self.line_box.under);
+            self.content_box.over = self
+                .content_box
+                .over
+                .max(self.bottom_aligned - self.line_box.under);
+        }
+    }
+
+    /// Trim the line box over its baseline to the given edge of its text, if it has any.
+    fn trim_over(&mut self, edge: TextBoxOverEdge) {
+        if self.text_edges.has_text {
+            self.line_box.over = self.text_edges.over(edge);
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -254,6 +321,8 @@ pub struct BreakerState {
     //
     // TODO: rename this `shaped_cluster_idx`
     cluster_idx: u32,
+    /// Iteration state: the paragraph the current line starts in
+    paragraph_idx: usize,
 
     /// The x coordinate of the left/start of the current line
     line_x: f32,
@@ -286,6 +355,7 @@ impl Default for BreakerState {
             item_idx: 0,
             run_idx: 0,
             cluster_idx: 0,
+            paragraph_idx: 0,
             line_x: 0.0,
             line_y: 0.0,
             layout_max_advance: 0.0,
@@ -345,6 +415,30 @@ impl BreakerState {
         self.update_max_height_exceeded();
     }
 
+    /// Add an in-flow inline box to the line, placed according to its resolved vertical alignment.
+    fn append_aligned_inline_box_to_line(
+        &mut self,
+        next_x: f32,
+        alignment: ResolvedVerticalAlign,
+        height: f32,
+        quantize: bool,
+    ) {
+        match alignment {
+            ResolvedVerticalAlign::Baseline { ascent, descent } => {
+                self.append_inline_box_to_line(next_x, ascent, descent, quantize);
+            }
+            ResolvedVerticalAlign::Top | ResolvedVerticalAlign::Bottom => {
+                self.item_idx += 1;
+                self.line.items.end += 1;
+                self.line.x = next_x;
+                self.line
+                    .box_metrics
+                    .add_line_relative_box(alignment, height, quantize);
+                self.update_max_height_exceeded();
+            }
+        }
+    }
+
     /// Store the current iteration state so that we can revert to it if we later want to take
     /// the line breaking opportunity at this point.
     fn mark_line_break_opportunity(&mut self) {
@@ -478,8 +572,14 @@ impl<'a, B: Brush> BreakLines<'a, B> {
             line_indent,
         );
 
+        self.state.line.box_metrics.resolve_line_relative_boxes();
+        if self.lines.lines.len() == 1 && self.layout.data.text_box_trim.trims_start() {
+            self.state
+                .line
+                .box_metrics
+                .trim_over(self.layout.data.text_box_edge.over);
+        }
         let line_height = self.state.line.box_metrics.line_height();
-        let line_y_start = self.state.line_y;
 
         self.state.items = self.lines.line_items.len();
         self.state.lines = self.lines.lines.len();
@@ -488,9 +588,14 @@ impl<'a, B: Brush> BreakLines<'a, B> {
 
         // `finish_line` reads the line's accumulated vertical metrics from `self.state.line`, so
         // it must run before we reset the per-line running state.
+        // This may move the line down to snap it to the baseline grid.
         self.finish_line(self.lines.lines.len() - 1, line_height);
         self.state.line.reset();
+        if reason == BreakReason::Explicit {
+            self.state.paragraph_idx += 1;
+        }
 
+        let line_y_start = self.state.line_y;
         self.state.line_y += line_height as f64;
 
         Some(YieldData::LineBreak(
@@ -609,8 +714,13 @@ impl<'a, B: Brush> BreakLines<'a, B> {
             };
 
         let line_indent = self.resolve_indent();
+        let end_indent = self
+            .layout
+            .data
+            .paragraph_style(self.state.paragraph_idx)
+            .end_indent;
 
-        let max_advance = max_advance - line_indent;
+        let max_advance = max_advance - line_indent - end_indent;
 
         // dbg!(&self.layout.items);
 
@@ -633,29 +743,33 @@ impl<'a, B: Brush> BreakLines<'a, B> {
                     let inline_box = &self.layout.data.inline_boxes[item.index];
 
                     // The portion of the box above the baseline contributes to the line's ascent
-                    // and the portion below to its descent. By default (no explicit baseline) the
-                    // bottom of the box is aligned with the text baseline, i.e. the box is all
-                    // ascent and zero descent. Out-of-flow boxes contribute nothing.
-                    let (width_contribution, height_contribution, resolved_baseline) =
-                        match inline_box.kind {
-                            InlineBoxKind::InFlow => {
-                                let baseline = inline_box.baseline.unwrap_or(inline_box.height);
-                                (inline_box.width, inline_box.height, baseline)
-                            }
-                            InlineBoxKind::OutOfFlow => (0.0, 0.0, 0.0),
-                            // If the box is a `CustomOutOfFlow` box then we yield control flow back to the caller.
-                            // It is then the caller's responsibility to handle placement of the box.
-                            InlineBoxKind::CustomOutOfFlow => {
-                                return Some(YieldData::InlineBoxBreak(BoxBreakData {
-                                    inline_box_id: inline_box.id,
-                                    inline_box_index: item.index,
-                                    advance: self.state.line.x,
-                                }));
-                            }
-                        };
-
-                    let ascent_contribution = resolved_baseline;
-                    let descent_contribution = height_contribution - resolved_baseline;
+                    // and the portion below to its descent, as determined by the box's vertical
+                    // alignment. Out-of-flow boxes contribute nothing.
+                    let (width_contribution, height_contribution, alignment) = match inline_box.kind
+                    {
+                        InlineBoxKind::InFlow => (
+                            inline_box.width,
+                            inline_box.height,
+                            self.layout.data.resolve_inline_box_align(inline_box),
+                        ),
+                        InlineBoxKind::OutOfFlow => (
+                            0.0,
+                            0.0,
+                            ResolvedVerticalAlign::Baseline {
+                                ascent: 0.0,
+                                descent: 0.0,
+                            },
+                        ),
+                        // If the box is a `CustomOutOfFlow` box then we yield control flow back to the caller.
+                        // It is then the caller's responsibility to handle placement of the box.
+                        InlineBoxKind::CustomOutOfFlow => {
+                            return Some(YieldData::InlineBoxBreak(BoxBreakData {
+                                inline_box_id: inline_box.id,
+                                inline_box_index: item.index,
+                                advance: self.state.line.x,
+                            }));
+                        }
+                    };
 
                     // Compute the x position of the content being currently processed
                     let next_x = self.state.line.x + width_contribution;
@@ -673,10 +787,10 @@ impl<'a, B: Brush> BreakLines<'a, B> {
                     {
                         // println!("BOX FITS");
 
-                        self.state.append_inline_box_to_line(
+                        self.state.append_aligned_inline_box_to_line(
                             next_x,
-                            ascent_contribution,
-                            descent_contribution,
+                            alignment,
+                            height_contribution,
                             self.layout.data.quantize,
                         );
 
@@ -686,10 +800,10 @@ impl<'a, B: Brush> BreakLines<'a, B> {
                         // If we're at the start of the line, this box will never fit, so consume it and accept the overflow.
                         let reason = if self.state.line.x == 0.0 {
                             // println!("BOX EMERGENCY BREAK");
-                            self.state.append_inline_box_to_line(
+                            self.state.append_aligned_inline_box_to_line(
                                 next_x,
-                                ascent_contribution,
-                                descent_contribution,
+                                alignment,
+                                height_contribution,
                                 self.layout.data.quantize,
                             );
                             BreakReason::Emergency
@@ -798,6 +912,19 @@ impl<'a, B: Brush> BreakLines<'a, B> {
                             self.state.mark_emergency_break_opportunity();
                         }
 
+                        if self.state.line.x == 0.0 {
+                            let paragraph_start = self
+                                .lines
+                                .lines
+                                .last()
+                                .is_none_or(|line| line.break_reason == BreakReason::Explicit);
+                            self.state.line.start_trim = text_spacing::line_start_trim(
+                                &self.layout.data,
+                                atom.shaped_clusters_range().start,
+                                paragraph_start,
+                            );
+                        }
+
                         // Breaking an atom requires reshaping, which we don't do here, so it is
                         // consumed as a whole (this includes all clusters of a ligature).
                         let advance = atom.advance();
@@ -805,12 +932,20 @@ impl<'a, B: Brush> BreakLines<'a, B> {
                         // Compute the x position of the content being currently processed
                         let next_x = self.state.line.x + advance;
 
+                        // The width of the line if it were to end after this atom. This excludes
+                        // punctuation trimmed at the line start and spacing that only applies
+                        // between this atom and the next.
+                        let last_cluster = atom.shaped_clusters_range().end - 1;
+                        let line_end_x = next_x
+                            - self.state.line.start_trim
+                            - self.layout.data.boundary_spacing(last_cluster);
+
                         // println!("Cluster {} next_x: {}", self.state.cluster_idx, next_x);
 
                         // If the content fits (the x position does NOT exceed max_advance)
                         //
                         // We simply append the atom to the current line
-                        if next_x <= max_advance {
+                        if line_end_x <= max_advance {
                             if max_height_exceeded {
                                 return self.max_height_break_data(line_height);
                             }
@@ -831,10 +966,30 @@ impl<'a, B: Brush> BreakLines<'a, B> {
                         // in the line. If there is no such line-breaking opportunity (such as if wrapping is disabled), then
                         // we fall back to appending the content to the line anyway.
                         else {
+                            // Case: the atom ends in a stop or comma that may hang, and fits
+                            // without it
+                            //
+                            // We append the atom, letting the punctuation hang past the line end.
+                            if text_wrap_mode == TextWrapMode::Wrap
+                                && line_end_x
+                                    - text_spacing::hanging_advance(&self.layout.data, last_cluster)
+                                    <= max_advance
+                            {
+                                if max_height_exceeded {
+                                    return self.max_height_break_data(line_height);
+                                }
+                                self.state.append_atom_to_line(
+                                    &atom,
+                                    next_x,
+                                    metrics,
+                                    line_height,
+                                    self.layout.data.quantize,
+                                );
+                            }
                             // Case: the atom is a space character (and wrapping is enabled)
                             //
                             // We hang any overflowing whitespace and then line-break.
-                            if is_space && text_wrap_mode == TextWrapMode::Wrap {
+                            else if is_space && text_wrap_mode == TextWrapMode::Wrap {
                                 if max_height_exceeded {
                                     return self.max_height_break_data(line_height);
                                 }
@@ -959,13 +1114,11 @@ impl<'a, B: Brush> BreakLines<'a, B> {
 
                     // Compute the x position for the line width tracking
                     let next_x = self.state.line.x + inline_box.width;
-                    // The portion above the baseline is ascent, the rest descent. A box without an
-                    // explicit baseline is bottom-aligned, i.e. all ascent and zero descent.
-                    let baseline = inline_box.baseline.unwrap_or(inline_box.height);
-                    self.state.append_inline_box_to_line(
+                    let alignment = self.layout.data.resolve_inline_box_align(inline_box);
+                    self.state.append_aligned_inline_box_to_line(
                         next_x,
-                        baseline,
-                        inline_box.height - baseline,
+                        alignment,
+                        inline_box.height,
                         self.layout.data.quantize,
                     );
                     char_count += 1;
@@ -1099,36 +1252,48 @@ impl<'a, B: Brush> BreakLines<'a, B> {
         }
     }
 
+    /// Resolve the indent on the start edge of the current line, from the layout's text indent
+    /// and the indents of the line's paragraph.
     #[inline]
     fn resolve_indent(&self) -> f32 {
+        let is_paragraph_start = self.lines.lines.is_empty()
+            || self.lines.lines.last().map(|l| l.break_reason) == Some(BreakReason::Explicit);
         let should_indent = {
             let is_scope_line = if self.layout.data.indent_options.each_line {
-                self.lines.lines.is_empty()
-                    || self.lines.lines.last().map(|l| l.break_reason)
-                        == Some(BreakReason::Explicit)
+                is_paragraph_start
             } else {
                 self.lines.lines.is_empty()
             };
             is_scope_line ^ self.layout.data.indent_options.hanging
         };
 
-        if should_indent {
+        let text_indent = if should_indent {
             self.layout.data.indent_amount
         } else {
             0.0
+        };
+        let paragraph = self.layout.data.paragraph_style(self.state.paragraph_idx);
+        if is_paragraph_start {
+            text_indent + paragraph.start_indent + paragraph.first_line_indent
+        } else {
+            text_indent + paragraph.start_indent
         }
     }
 
     fn finish_line(&mut self, line_idx: usize, line_height: f32) {
-        let prev_line_metrics = match line_idx {
-            0 => None,
-            idx => Some(self.lines.lines[idx - 1].metrics),
+        let (prev_line_metrics, prev_paragraph) = match line_idx {
+            0 => (None, None),
+            idx => {
+                let prev_line = &self.lines.lines[idx - 1];
+                (Some(prev_line.metrics), Some(prev_line.paragraph))
+            }
         };
         let line = &mut self.lines.lines[line_idx];
 
         // Reset metrics for line
         line.metrics.offset = 0.;
         line.text_range.start = usize::MAX;
+        line.paragraph = self.state.paragraph_idx;
 
         line.metrics.line_height = line_height;
 
@@ -1238,6 +1403,26 @@ impl<'a, B: Brush> BreakLines<'a, B> {
             })
             .unwrap_or(0.0);
 
+        // Adjust the line's advance for CJK punctuation trimmed at its start, spacing after its
+        // last cluster that only applies mid-line, and punctuation hanging past its end. These
+        // are only supported in left-to-right lines.
+        line.start_trim = 0.;
+        line.hanging = 0.;
+        if !self.layout.is_rtl() && !needs_reorder {
+            let last_cluster = self.lines.line_items[line.item_range.clone()]
+                .last()
+                .filter(|item| item.is_text_run() && !item.shaped_cluster_range.is_empty())
+                .map(|item| item.shaped_cluster_range.end - 1);
+            line.start_trim = self.state.line.start_trim;
+            line.metrics.advance -= line.start_trim
+                + last_cluster.map_or(0., |cluster| self.layout.data.boundary_spacing(cluster));
+            if let Some(cluster) = last_cluster
+                && line.metrics.advance - line.metrics.trailing_whitespace > line.max_advance
+            {
+                line.hanging = text_spacing::hanging_advance(&self.layout.data, cluster);
+            }
+        }
+
         // Whether metrics should be quantized to pixel boundaries
         let quantize = self.layout.data.quantize;
 
@@ -1292,11 +1477,29 @@ impl<'a, B: Brush> BreakLines<'a, B> {
             }
         }
 
-        let top = if quantize {
-            self.state.line_y.round() as f32
+        if let Some(prev_paragraph) = prev_paragraph
+            && prev_paragraph != line.paragraph
+        {
+            self.state.line_y += f64::from(
+                self.layout
+                    .data
+                    .paragraph_spacing(prev_paragraph, line.paragraph),
+            );
+        }
+
+        let mut top = if quantize {
+            self.state.line_y.round()
         } else {
-            self.state.line_y as f32
+            self.state.line_y
         };
+        // Snap after quantizing, so that rounding doesn't move the baseline off the grid.
+        if let Some(grid) = self.layout.data.baseline_grid {
+            let baseline = top + f64::from(line_box_extents.over);
+            let shift = grid.snap(baseline) - baseline;
+            top += shift;
+            self.state.line_y += shift;
+        }
+        let top = top as f32;
         line.metrics.baseline = top + line_box_extents.over;
         line.metrics.block_min_coord = top;
         line.metrics.block_max_coord = if quantize {
@@ -1315,18 +1518,69 @@ impl<'a, B: Brush> BreakLines<'a, B> {
 
         line.metrics.inline_min_coord = self.state.line_x;
         line.metrics.inline_max_coord = self.state.line_x + self.state.line_max_advance;
+        line.text_edges = self.state.line.box_metrics.text_edges;
+    }
+
+    /// Trim the line box under the baseline of the last line with content to the configured edge
+    /// of its text.
+    ///
+    /// Only the trailing empty line following a final newline can come after it; that line is
+    /// moved up accordingly.
+    fn trim_end(&mut self) {
+        let lines = &mut self.lines.lines;
+        let Some(mut last_idx) = lines.len().checked_sub(1) else {
+            return;
+        };
+        if last_idx > 0 && lines[last_idx].item_range.is_empty() {
+            last_idx -= 1;
+        }
+        let line = &mut lines[last_idx];
+        if !line.text_edges.has_text {
+            return;
+        }
+
+        let under = line.text_edges.under(self.layout.data.text_box_edge.under);
+        let block_max_coord = if self.layout.data.quantize {
+            (line.metrics.baseline + under).round()
+        } else {
+            line.metrics.baseline + under
+        };
+        let trimmed = line.metrics.block_max_coord - block_max_coord;
+        line.metrics.block_max_coord = block_max_coord;
+        line.metrics.line_height -= trimmed;
+
+        for line in &mut lines[last_idx + 1..] {
+            let metrics = &mut line.metrics;
+            metrics.baseline -= trimmed;
+            metrics.block_min_coord -= trimmed;
+            metrics.block_max_coord -= trimmed;
+            metrics.content_block_min_coord -= trimmed;
+            metrics.content_block_max_coord -= trimmed;
+        }
+        self.state.line_y -= trimmed as f64;
     }
 }
 
 impl<B: Brush> Drop for BreakLines<'_, B> {
     fn drop(&mut self) {
+        // The last line is only known to be the end of the text once breaking is done.
+        if self.done && self.layout.data.text_box_trim.trims_end() {
+            self.trim_end();
+        }
+
         // Compute the overall width and height of the entire layout
         // The "width" excludes trailing whitespace. The "full_width" includes it.
         let mut layout_width = 0_f32;
         let mut layout_full_width = 0_f32;
         let mut height = 0_f64; // f32 causes test failures due to accumulated error
         for line in &mut self.lines.lines {
-            let indent_extra = line.indent.max(0.0);
+            let indent_extra = line.indent.max(0.0)
+                + self
+                    .layout
+                    .data
+                    .paragraph_style(line.paragraph)
+                    .end_indent
+                    .max(0.0);
             let line_max = line.metrics.inline_min_coord + line.metrics.advance + indent_extra;
             layout_full_width = layout_full_width.max(line_max);
             layout_width = layout_width.max(line_max - line.metrics.trailing_whitespace);
@@ -1344,10 +1598,33 @@ impl<B: Brush> Drop for BreakLines<'_, B> {
         }
 
         // Don't include the last line's line_height in the layout's height if the last line is empty
-        if let Some(last_line) = self.lines.lines.last()
+        let mut counted_lines = &self.lines.lines[..];
+        if let Some((last_line, rest)) = self.lines.lines.split_last()
             && last_line.item_range.is_empty()
         {
             height -= last_line.metrics.line_height as f64;
+            counted_lines = rest;
+        }
+
+        // The space between paragraphs is not part of any line's height.
+        height += counted_lines
+            .windows(2)
+            .filter(|pair| pair[0].paragraph != pair[1].paragraph)
+            .map(|pair| {
+                f64::from(
+                    self.layout
+                        .data
+                        .paragraph_spacing(pair[0].paragraph, pair[1].paragraph),
+                )
+            })
+            .sum::<f64>();
+
+        // Snapping to a baseline grid leaves gaps between lines, which are not part of any line's
+        // height. Measure up to the bottom of the last line instead.
+        if self.layout.data.baseline_grid.is_some() {
+            height = counted_lines
+                .last()
+                .map_or(0., |line| f64::from(line.metrics.block_max_coord));
         }
 
         // Save the computed widths/height to the layout
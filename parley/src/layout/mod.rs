@@ -7,9 +7,13 @@
 mod accessibility;
 mod alignment;
 mod cluster;
+mod font_diagnostics;
 mod line;
 mod line_break;
 mod run;
+#[cfg(feature = "serde")]
+mod snapshot;
+mod text_spacing;
 
 // TODO - Add to allowed lint set?
 #[expect(
@@ -27,12 +31,16 @@ pub use accessibility::LayoutAccessibility;
 pub use alignment::{Alignment, AlignmentOptions};
 pub use cluster::{Affinity, Cluster, ClusterPath, ClusterSide};
 pub use data::BreakReason;
+pub(crate) use font_diagnostics::ClusterSelection;
+pub use font_diagnostics::{FontDiagnostic, FontDiagnostics};
 pub use layout::Layout;
 pub use line::{GlyphRun, Line, LineMetrics, PositionedInlineBox, PositionedLayoutItem};
 pub use line_break::{
     BoxBreakData, BreakLines, BreakerState, LineBreakData, MaxHeightBreakData, YieldData,
 };
 pub use run::Run;
+#[cfg(feature = "serde")]
+pub use snapshot::{FontDataId, LayoutSnapshot, SnapshotError};
 
 pub(crate) use data::{LayoutData, LayoutItem, LayoutItemKind, LineData, LineItemData};
 pub(crate) use line::LineItem;
@@ -43,12 +51,19 @@ pub use crate::editing::{Cursor, Selection};
 
 // TODO - Move the following to `style` module and submodules.
 
+#[cfg(feature = "libm")]
+#[allow(unused_imports)]
+use core_maths::CoreFloat;
+
 use crate::style::Brush;
-use crate::{LineHeight, OverflowWrap, TextWrapMode};
+use crate::{
+    HangingPunctuation, LineHeight, OverflowWrap, TextAutospace, TextSpacingTrim, TextWrapMode,
+};
 
 #[allow(clippy::partial_pub_fields)]
 /// Style properties.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Style<B: Brush> {
     /// Brush for drawing glyphs.
     pub brush: B,
@@ -56,12 +71,30 @@ pub struct Style<B: Brush> {
     pub underline: Option<Decoration<B>>,
     /// Strikethrough decoration.
     pub strikethrough: Option<Decoration<B>>,
+    /// Brush set for the underline, or `None` if it's drawn with `brush`.
+    pub(crate) underline_brush: Option<B>,
+    /// Brush set for the strikethrough, or `None` if it's drawn with `brush`.
+    pub(crate) strikethrough_brush: Option<B>,
+    /// Offset of the underline from the baseline, used when it's turned on by a restyle.
+    pub(crate) underline_offset: Option<f32>,
+    /// Thickness of the underline, used when it's turned on by a restyle.
+    pub(crate) underline_size: Option<f32>,
+    /// Offset of the strikethrough from the baseline, used when it's turned on by a restyle.
+    pub(crate) strikethrough_offset: Option<f32>,
+    /// Thickness of the strikethrough, used when it's turned on by a restyle.
+    pub(crate) strikethrough_size: Option<f32>,
     /// Partially resolved line height, either in in layout units or dependent on metrics
     pub(crate) line_height: LineHeight,
     /// Per-cluster overflow-wrap setting
     pub(crate) overflow_wrap: OverflowWrap,
     /// Per-cluster text-wrap-mode setting
     pub(crate) text_wrap_mode: TextWrapMode,
+    /// Per-cluster hanging-punctuation setting
+    pub(crate) hanging_punctuation: HangingPunctuation,
+    /// Per-cluster text-spacing-trim setting
+    pub(crate) text_spacing_trim: TextSpacingTrim,
+    /// Per-cluster text-autospace setting
+    pub(crate) text_autospace: TextAutospace,
     #[cfg(feature = "accesskit")]
     /// Locale if any, so we can set the corresponding AccessKit property
     pub(crate) locale: Option<fontique::Language>,
@@ -69,6 +102,7 @@ pub struct Style<B: Brush> {
 
 /// Underline or strikethrough decoration.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Decoration<B: Brush> {
     /// Brush used to draw the decoration.
     pub brush: B,
@@ -93,6 +127,7 @@ pub struct ContentWidths {
 
 /// Options controlling text-indent behavior, corresponding to CSS `text-indent` keywords.
 #[derive(Copy, Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndentOptions {
     /// If `true`, indent also applies after every hard line break, not just to the first line.
     /// Corresponds to the CSS `each-line` keyword. Defaults to `false`.
@@ -101,3 +136,98 @@ pub struct IndentOptions {
     /// instead of the first line(s). Corresponds to the CSS `hanging` keyword. Defaults to `false`.
     pub hanging: bool,
 }
+
+/// Which sides of a layout have their leading trimmed, corresponding to CSS `text-box-trim`.
+///
+/// See [`Layout::set_text_box_trim`].
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextBoxTrim {
+    /// Don't trim.
+    #[default]
+    None,
+    /// Trim the block-start side of the first line.
+    TrimStart,
+    /// Trim the block-end side of the last line.
+    TrimEnd,
+    /// Trim both the block-start side of the first line and the block-end side of the last line.
+    TrimBoth,
+}
+
+impl TextBoxTrim {
+    /// Whether the block-start side of the first line is trimmed.
+    pub fn trims_start(self) -> bool {
+        matches!(self, Self::TrimStart | Self::TrimBoth)
+    }
+
+    /// Whether the block-end side of the last line is trimmed.
+    pub fn trims_end(self) -> bool {
+        matches!(self, Self::TrimEnd | Self::TrimBoth)
+    }
+}
+
+/// The font metrics that trimmed sides are trimmed to, corresponding to CSS `text-box-edge`.
+///
+/// See [`Layout::set_text_box_trim`].
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextBoxEdge {
+    /// The edge the block-start side of the first line is trimmed to.
+    pub over: TextBoxOverEdge,
+    /// The edge the block-end side of the last line is trimmed to.
+    pub under: TextBoxUnderEdge,
+}
+
+/// The over edge of [`TextBoxEdge`].
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextBoxOverEdge {
+    /// The font's ascent.
+    #[default]
+    Text,
+    /// The font's cap height, or its ascent if the font doesn't specify a cap height.
+    Cap,
+    /// The font's x-height, or its ascent if the font doesn't specify an x-height.
+    Ex,
+}
+
+/// The under edge of [`TextBoxEdge`].
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextBoxUnderEdge {
+    /// The font's descent.
+    #[default]
+    Text,
+    /// The alphabetic baseline.
+    Alphabetic,
+}
+
+/// A grid that the baselines of lines are snapped to.
+///
+/// This is useful for aligning lines across columns or pages, such as with InDesign's "align to
+/// baseline grid". See [`Layout::set_baseline_grid`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaselineGrid {
+    /// The block-axis coordinate of one of the grid's lines, relative to the top of the layout.
+    pub origin: f32,
+    /// The distance between consecutive grid lines.
+    ///
+    /// Grids with a non-positive increment are ignored.
+    pub increment: f32,
+}
+
+impl BaselineGrid {
+    /// Returns the first grid line at or after `position`.
+    pub(crate) fn snap(self, position: f64) -> f64 {
+        if self.increment <= 0. {
+            return position;
+        }
+        let origin = f64::from(self.origin);
+        let increment = f64::from(self.increment);
+        // Allow for some floating point error, so positions on the grid are not pushed to the
+        // next grid line.
+        let steps = ((position - origin) / increment - 1e-6).ceil();
+        origin + steps * increment
+    }
+}
@@ -4,10 +4,10 @@
 use crate::layout::cluster::{Cluster, ClusterPath};
 use crate::layout::data::{LineItemData, RunData, count_graphemes};
 use crate::layout::layout::Layout;
-use crate::style::Brush;
+use crate::style::{Brush, PaletteOverride};
 
 use core::ops::Range;
-use fontique::Synthesis;
+use fontique::{RenderHints, Synthesis};
 use parley_engine::{
     Atom, Atoms, FontInstance, FontMetrics, Glyph, Graphemes, NormalizedCoord, ShapedRun,
     ShapedSlice,
@@ -101,10 +101,43 @@ impl<'a, B: Brush> Run<'a, B> {
     }
 
     /// Returns the synthesis suggestions for the font associated with the run.
+    ///
+    /// Kinds of synthesis disallowed by the run's
+    /// [`FontSynthesis`](crate::FontSynthesis) style are not suggested.
     pub fn synthesis(&self) -> Synthesis {
         self.data.synthesis
     }
 
+    /// Returns the amount faux bold widens the run's glyphs, or zero if the
+    /// run isn't emboldened.
+    ///
+    /// Glyph advances already include this width, so renderers should expand
+    /// glyph outlines by half of it on each side.
+    pub fn embolden_strength(&self) -> f32 {
+        self.data.synthesis.embolden_strength(self.font_size())
+    }
+
+    /// Returns the rendering settings the system configures for the run's font,
+    /// such as hinting and antialiasing.
+    pub fn render_hints(&self) -> RenderHints {
+        self.font().render_hints
+    }
+
+    /// Returns the index of the palette that color glyphs of the run are
+    /// painted with.
+    ///
+    /// This is selected from the font's palettes by the run's
+    /// [`FontPalette`](crate::FontPalette) style and the layout's color scheme.
+    pub fn palette_index(&self) -> u16 {
+        self.data.palette_index
+    }
+
+    /// Returns the colors replacing entries of the run's palette, sorted by
+    /// entry index.
+    pub fn palette_overrides(&self) -> &'a [PaletteOverride] {
+        &self.layout.data.palette_overrides[self.data.palette_overrides.clone()]
+    }
+
     /// Returns the normalized variation coordinates for the font associated
     /// with the run.
     pub fn normalized_coords(&self) -> &[NormalizedCoord] {
@@ -0,0 +1,369 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Serializable snapshots of laid out text.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use fontique::{RenderHints, Synthesis};
+use parlance::FontFeature;
+use parley_engine::FontInstance;
+use serde::de::{self, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::layout::data::{LayoutData, LayoutItemKind};
+use crate::style::Brush;
+use crate::{FontData, Layout};
+
+/// A stable identifier of the data of a font, used to refer to fonts from a [`LayoutSnapshot`].
+///
+/// Unlike the IDs of font blobs, this is the same across processes, so it can be used to find
+/// the font again when a snapshot is restored.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct FontDataId {
+    /// The 64-bit FNV-1a hash of the font's data.
+    ///
+    /// For font collections, this is the hash of the whole collection file.
+    pub hash: u64,
+    /// The index of the font in a font collection, or `0` for other fonts.
+    pub index: u32,
+}
+
+impl FontDataId {
+    /// Computes the identifier of `font`.
+    ///
+    /// This hashes all of the font's data, so it is best computed once per font.
+    pub fn new(font: &FontData) -> Self {
+        Self {
+            hash: fnv1a(font.data.as_ref()),
+            index: font.index,
+        }
+    }
+}
+
+/// Returns a function identifying fonts like [`FontDataId::new`], which only hashes the data of
+/// each blob once.
+pub(crate) fn hash_font_ids() -> impl FnMut(&FontData) -> FontDataId {
+    let mut hashes: Vec<(u64, u64)> = Vec::new();
+    move |font| {
+        let blob_id = font.data.id();
+        let hash = match hashes.iter().find(|(id, _)| *id == blob_id) {
+            Some((_, hash)) => *hash,
+            None => {
+                let hash = fnv1a(font.data.as_ref());
+                hashes.push((blob_id, hash));
+                hash
+            }
+        };
+        FontDataId {
+            hash,
+            index: font.index,
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash of `data`.
+fn fnv1a(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    data.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// A font of a snapshot, with its data replaced by a [`FontDataId`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SnapshotFont {
+    id: FontDataId,
+    synthesis: Synthesis,
+    features: Option<Vec<FontFeature>>,
+    size_adjust: f32,
+    optical_size: Option<f32>,
+    render_hints: RenderHints,
+}
+
+/// A [`Layout`] that has been shaped, broken into lines and aligned, in a form that can be
+/// serialized with `serde`.
+///
+/// This is created with [`Layout::snapshot`] or [`Layout::snapshot_with`] and turned back into a layout with
+/// [`LayoutSnapshot::restore`], without shaping or breaking its text again. Fonts are not part of
+/// the snapshot; they are referred to by their [`FontDataId`], and are provided again by the
+/// caller on restore.
+///
+/// Snapshots store the [`version`](Self::VERSION) of their format, which is the first field when
+/// serialized. It is read before the rest of the snapshot, so a snapshot of another version still
+/// deserializes, with only its version, and [`restore`](Self::restore) reports it as
+/// [`SnapshotError::UnsupportedVersion`]. Such snapshots should be discarded and laid out again.
+///
+/// A restored layout has no [font diagnostics](Layout::font_diagnostics), and a
+/// [font generation](Layout::font_generation) of `0`, as both refer to the font collection the
+/// layout was originally built with.
+#[derive(Clone, Debug, Serialize)]
+pub struct LayoutSnapshot<B: Brush> {
+    version: u32,
+    fonts: Vec<SnapshotFont>,
+    data: LayoutData<B>,
+}
+
+impl<B: Brush> LayoutSnapshot<B> {
+    /// The version of the snapshot format.
+    ///
+    /// This changes whenever the layout data of a snapshot changes in an incompatible way.
+    pub const VERSION: u32 = 1;
+
+    /// Creates a snapshot of `data`, identifying its fonts with `font_id`.
+    pub(crate) fn new(
+        data: &LayoutData<B>,
+        mut font_id: impl FnMut(&FontData) -> FontDataId,
+    ) -> Self {
+        // Many font instances share the same font, so each is only identified once.
+        let mut ids: Vec<(u64, u32, FontDataId)> = Vec::new();
+        let fonts = data
+            .shaped_text
+            .fonts()
+            .iter()
+            .map(|font| {
+                let (blob_id, index) = (font.font.data.id(), font.font.index);
+                let id = match ids
+                    .iter()
+                    .find(|(id, idx, _)| (*id, *idx) == (blob_id, index))
+                {
+                    Some((_, _, id)) => *id,
+                    None => {
+                        let id = font_id(&font.font);
+                        ids.push((blob_id, index, id));
+                        id
+                    }
+                };
+                SnapshotFont {
+                    id,
+                    synthesis: font.synthesis,
+                    features: font.features.as_deref().map(<[FontFeature]>::to_vec),
+                    size_adjust: font.size_adjust,
+                    optical_size: font.optical_size,
+                    render_hints: font.render_hints,
+                }
+            })
+            .collect();
+        Self {
+            version: Self::VERSION,
+            fonts,
+            data: data.clone(),
+        }
+    }
+
+    /// Returns the version of the format the snapshot was made with.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the fonts the snapshot refers to, which must be provided to restore it.
+    ///
+    /// This can be used to load the fonts before restoring a snapshot. The same font may be
+    /// returned more than once, and none are returned for snapshots of another version.
+    pub fn fonts(&self) -> impl Iterator<Item = FontDataId> + '_ {
+        self.fonts.iter().map(|font| font.id)
+    }
+
+    /// Turns the snapshot back into a [`Layout`].
+    ///
+    /// `resolve_font` is called with the identifier of each font of the snapshot, and returns the
+    /// data of that font. It is trusted to return the font that was identified, which can be
+    /// checked with [`FontDataId::new`].
+    ///
+    /// The layout is the same as the one the snapshot was made from, as broken into lines and
+    /// aligned at the time. It can be broken and aligned again as usual.
+    pub fn restore(
+        self,
+        mut resolve_font: impl FnMut(FontDataId) -> Option<FontData>,
+    ) -> Result<Layout<B>, SnapshotError> {
+        if self.version != Self::VERSION {
+            return Err(SnapshotError::UnsupportedVersion(self.version));
+        }
+        let fonts = self
+            .fonts
+            .into_iter()
+            .map(|font| {
+                Ok(FontInstance {
+                    font: resolve_font(font.id).ok_or(SnapshotError::MissingFont(font.id))?,
+                    synthesis: font.synthesis,
+                    features: font.features.map(Into::into),
+                    size_adjust: font.size_adjust,
+                    optical_size: font.optical_size,
+                    render_hints: font.render_hints,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut data = self.data;
+        if !data.shaped_text.set_fonts(fonts) || !is_consistent(&data) {
+            return Err(SnapshotError::InvalidData);
+        }
+        Ok(Layout { data })
+    }
+}
+
+impl<'de, B: Brush + Deserialize<'de>> Deserialize<'de> for LayoutSnapshot<B> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct(
+            "LayoutSnapshot",
+            &["version", "fonts", "data"],
+            SnapshotVisitor(core::marker::PhantomData),
+        )
+    }
+}
+
+/// The fields of a serialized [`LayoutSnapshot`], including those of other versions.
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum SnapshotField {
+    Version,
+    Fonts,
+    Data,
+    #[serde(other)]
+    Other,
+}
+
+/// Deserializes the version of a snapshot first, and the rest of it only if the version is
+/// supported, as the layout data of other versions may not deserialize at all.
+struct SnapshotVisitor<B>(core::marker::PhantomData<B>);
+
+impl<B: Brush> SnapshotVisitor<B> {
+    fn unsupported(version: u32) -> LayoutSnapshot<B> {
+        LayoutSnapshot {
+            version,
+            fonts: Vec::new(),
+            data: LayoutData::default(),
+        }
+    }
+}
+
+impl<'de, B: Brush + Deserialize<'de>> Visitor<'de> for SnapshotVisitor<B> {
+    type Value = LayoutSnapshot<B>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a layout snapshot")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let version: u32 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        if version != LayoutSnapshot::<B>::VERSION {
+            // The remaining elements can't be skipped in formats that aren't self-describing.
+            return Ok(Self::unsupported(version));
+        }
+        let fonts = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let data = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        Ok(LayoutSnapshot {
+            version,
+            fonts,
+            data,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut version: Option<u32> = None;
+        let mut fonts = None;
+        let mut data = None;
+        while let Some(field) = map.next_key()? {
+            let supported = version.is_none_or(|version| version == LayoutSnapshot::<B>::VERSION);
+            match field {
+                SnapshotField::Version if version.is_none() => version = Some(map.next_value()?),
+                SnapshotField::Version => return Err(de::Error::duplicate_field("version")),
+                SnapshotField::Fonts if supported => fonts = Some(map.next_value()?),
+                SnapshotField::Data if supported => data = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+        if version != LayoutSnapshot::<B>::VERSION {
+            return Ok(Self::unsupported(version));
+        }
+        Ok(LayoutSnapshot {
+            version,
+            fonts: fonts.ok_or_else(|| de::Error::missing_field("fonts"))?,
+            data: data.ok_or_else(|| de::Error::missing_field("data"))?,
+        })
+    }
+}
+
+/// Whether the indices between the parts of deserialized layout data are in bounds.
+///
+/// The shaped text is checked separately by [`parley_engine::ShapedText::set_fonts`].
+fn is_consistent<B: Brush>(data: &LayoutData<B>) -> bool {
+    fn within(range: &Range<usize>, len: usize) -> bool {
+        range.start <= range.end && range.end <= len
+    }
+    let shaped_text = &data.shaped_text;
+    let item_is_valid = |kind: LayoutItemKind, index: usize| match kind {
+        LayoutItemKind::TextRun => index < data.runs.len(),
+        LayoutItemKind::InlineBox => index < data.inline_boxes.len(),
+    };
+    let style_is_valid = |style_index: u16| usize::from(style_index) < data.styles.len();
+
+    data.runs.len() == shaped_text.runs().len()
+        && data
+            .runs
+            .iter()
+            .all(|run| within(&run.palette_overrides, data.palette_overrides.len()))
+        && data
+            .items
+            .iter()
+            .all(|item| item_is_valid(item.kind, item.index))
+        && shaped_text
+            .characters()
+            .iter()
+            .all(|character| style_is_valid(character.style_index))
+        && shaped_text
+            .shaped_clusters()
+            .iter()
+            .all(|cluster| style_is_valid(cluster.style_index))
+        && data.lines.iter().all(|line| {
+            within(&line.item_range, data.line_items.len())
+                && within(&line.text_range, data.text_len)
+        })
+        && data.line_items.iter().all(|item| {
+            let clusters =
+                item.shaped_cluster_range.start as usize..item.shaped_cluster_range.end as usize;
+            item_is_valid(item.kind, item.index)
+                && within(&item.text_range, data.text_len)
+                && within(&clusters, shaped_text.shaped_clusters().len())
+        })
+}
+
+/// An error restoring a [`LayoutSnapshot`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SnapshotError {
+    /// The snapshot was made with a different version of the snapshot format.
+    UnsupportedVersion(u32),
+    /// The font with this identifier wasn't provided.
+    MissingFont(FontDataId),
+    /// The snapshot's data is inconsistent, for example because it was modified.
+    InvalidData,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported layout snapshot version {version}")
+            }
+            Self::MissingFont(id) => write!(
+                f,
+                "missing font with hash {:016x} and index {}",
+                id.hash, id.index
+            ),
+            Self::InvalidData => f.write_str("inconsistent layout snapshot data"),
+        }
+    }
+}
+
+impl core::error::Error for SnapshotError {}
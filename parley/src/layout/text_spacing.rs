@@ -0,0 +1,279 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! CJK punctuation trimming, autospacing and hanging punctuation.
+//!
+//! Adjustments between two clusters are applied to the advance of the first one, in the same way
+//! as letter spacing. Those caused by the boundary itself, rather than by the first cluster, are
+//! recorded in [`LayoutData::boundary_spacing`], as they don't apply when a line ends at the
+//! boundary. Trimming at the start of a line is resolved during line breaking instead.
+//!
+//! These adjustments are only made in left-to-right text.
+
+use super::data::{LayoutData, add_cluster_spacing};
+use crate::style::Brush;
+use crate::util::nearly_zero;
+use crate::{HangingPunctuation, TextAutospace, TextSpacingTrim};
+
+use parley_engine::shape::ShapedCluster;
+
+/// The classes of fullwidth punctuation relevant to [`TextSpacingTrim`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Punctuation {
+    /// Opening brackets and quotes, which have their blank half before the glyph.
+    Opening,
+    /// Closing brackets and quotes, commas and full stops, which have their blank half after the
+    /// glyph.
+    Closing,
+    /// Middle dots, colons and semicolons, which are centered in their em box.
+    Middle,
+    /// The ideographic space.
+    Space,
+}
+
+impl Punctuation {
+    fn of(c: char) -> Option<Self> {
+        Some(match c {
+            '\u{2018}' | '\u{201C}' | '\u{3008}' | '\u{300A}' | '\u{300C}' | '\u{300E}'
+            | '\u{3010}' | '\u{3014}' | '\u{3016}' | '\u{3018}' | '\u{301A}' | '\u{301D}'
+            | '\u{FF08}' | '\u{FF3B}' | '\u{FF5B}' | '\u{FF5F}' => Self::Opening,
+            '\u{2019}' | '\u{201D}' | '\u{3001}' | '\u{3002}' | '\u{3009}' | '\u{300B}'
+            | '\u{300D}' | '\u{300F}' | '\u{3011}' | '\u{3015}' | '\u{3017}' | '\u{3019}'
+            | '\u{301B}' | '\u{301E}' | '\u{301F}' | '\u{FF09}' | '\u{FF0C}' | '\u{FF0E}'
+            | '\u{FF3D}' | '\u{FF5D}' | '\u{FF60}' => Self::Closing,
+            '\u{00B7}' | '\u{2027}' | '\u{30FB}' | '\u{FF1A}' | '\u{FF1B}' => Self::Middle,
+            '\u{3000}' => Self::Space,
+            _ => return None,
+        })
+    }
+}
+
+/// Whether `c` has an East Asian Width of wide or fullwidth, approximated by block.
+fn is_wide(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{115F}'
+            | '\u{2E80}'..='\u{303E}'
+            | '\u{3041}'..='\u{33FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{A000}'..='\u{A4CF}'
+            | '\u{AC00}'..='\u{D7A3}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FE30}'..='\u{FE4F}'
+            | '\u{FF00}'..='\u{FF60}'
+            | '\u{FFE0}'..='\u{FFE6}'
+            | '\u{20000}'..='\u{3FFFD}'
+    )
+}
+
+/// Whether `c` is an ideograph in the sense of `text-autospace`: a wide letter that isn't Hangul.
+fn is_ideograph(c: char) -> bool {
+    is_wide(c)
+        && c.is_alphabetic()
+        && !matches!(c, '\u{1100}'..='\u{115F}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7A3}')
+}
+
+/// Whether `c` is a non-ideographic letter or numeral in the sense of `text-autospace`.
+fn is_non_ideographic_alphanumeric(c: char) -> bool {
+    c.is_alphanumeric() && !is_wide(c)
+}
+
+/// Whether `c` is a stop or comma that may hang with [`HangingPunctuation::AllowEnd`].
+fn is_stop_or_comma(c: char) -> bool {
+    matches!(
+        c,
+        ',' | '.'
+            | '\u{060C}'
+            | '\u{06D4}'
+            | '\u{3001}'
+            | '\u{3002}'
+            | '\u{FE50}'
+            | '\u{FE51}'
+            | '\u{FE52}'
+            | '\u{FF0C}'
+            | '\u{FF0E}'
+            | '\u{FF61}'
+            | '\u{FF64}'
+    )
+}
+
+/// The blank half of a cluster of fullwidth punctuation, or zero if the font sets the
+/// punctuation proportionally.
+fn blank_half(advance: f32, font_size: f32) -> f32 {
+    if advance >= font_size * 0.9 {
+        font_size * 0.5
+    } else {
+        0.
+    }
+}
+
+/// The cluster preceding the one currently visited by [`apply_spacing`].
+struct Previous {
+    cluster_idx: usize,
+    last_char: char,
+    font_size: f32,
+    trim: TextSpacingTrim,
+    autospace: TextAutospace,
+}
+
+/// Applies [`TextSpacingTrim`] between punctuation and [`TextAutospace`] to cluster advances.
+///
+/// This must be called once, after shaping.
+pub(crate) fn apply_spacing<B: Brush>(layout: &mut LayoutData<B>) {
+    layout.boundary_spacing.clear();
+    if !layout.styles.iter().any(|style| {
+        style.text_spacing_trim != TextSpacingTrim::SpaceAll
+            || style.text_autospace != TextAutospace::NoAutospace
+    }) {
+        return;
+    }
+
+    let mut previous: Option<Previous> = None;
+    for run_idx in 0..layout.shaped_text.runs().len() {
+        let run = &layout.shaped_text.runs()[run_idx];
+        if run.bidi_level.is_rtl() {
+            previous = None;
+            continue;
+        }
+        let font_size = run.font_size;
+        let cluster_range =
+            run.shaped_clusters_range.start as usize..run.shaped_clusters_range.end as usize;
+
+        let (characters, clusters, glyphs) = layout
+            .shaped_text
+            .characters_shaped_clusters_and_glyphs_mut();
+        for cluster_idx in cluster_range {
+            let cluster = &clusters[cluster_idx];
+            let chars = cluster.chars_range();
+            let first_char = characters[chars.start as usize].info.source_char();
+            let last_char = characters[chars.end as usize - 1].info.source_char();
+            let style = &layout.styles[cluster.style_index as usize];
+            let punctuation = Punctuation::of(first_char);
+
+            if let Some(prev) = &previous {
+                let prev_punctuation = Punctuation::of(prev.last_char);
+                // Spacing belonging to the boundary between the clusters.
+                let mut boundary = 0.;
+                if prev.autospace == TextAutospace::Normal
+                    && (is_ideograph(prev.last_char) && is_non_ideographic_alphanumeric(first_char)
+                        || is_non_ideographic_alphanumeric(prev.last_char)
+                            && is_ideograph(first_char))
+                {
+                    boundary += prev.font_size * 0.125;
+                }
+                if punctuation == Some(Punctuation::Opening)
+                    && match style.text_spacing_trim {
+                        TextSpacingTrim::SpaceAll => false,
+                        TextSpacingTrim::Normal | TextSpacingTrim::TrimStart => {
+                            prev_punctuation.is_some()
+                        }
+                        TextSpacingTrim::TrimAll => true,
+                    }
+                {
+                    boundary -= blank_half(cluster.advance, font_size);
+                }
+                // Trimming the blank half after closing punctuation.
+                let mut end = 0.;
+                if prev_punctuation == Some(Punctuation::Closing)
+                    && matches!(
+                        prev.trim,
+                        TextSpacingTrim::Normal | TextSpacingTrim::TrimStart
+                    )
+                    && matches!(
+                        punctuation,
+                        Some(Punctuation::Closing | Punctuation::Middle | Punctuation::Space)
+                    )
+                {
+                    end -= blank_half(clusters[prev.cluster_idx].advance, prev.font_size);
+                }
+
+                let spacing = boundary + end;
+                if !nearly_zero(spacing) {
+                    add_cluster_spacing(&mut clusters[prev.cluster_idx], glyphs, spacing);
+                }
+                if !nearly_zero(boundary) {
+                    layout
+                        .boundary_spacing
+                        .push((prev.cluster_idx as u32, boundary));
+                }
+            }
+
+            if style.text_spacing_trim == TextSpacingTrim::TrimAll
+                && Punctuation::of(last_char) == Some(Punctuation::Closing)
+            {
+                let cluster = &mut clusters[cluster_idx];
+                let trim = blank_half(cluster.advance, font_size);
+                add_cluster_spacing(cluster, glyphs, -trim);
+            }
+            previous = Some(Previous {
+                cluster_idx,
+                last_char,
+                font_size,
+                trim: style.text_spacing_trim,
+                autospace: style.text_autospace,
+            });
+        }
+    }
+}
+
+/// The blank space to trim before the cluster starting a line, if it is fullwidth opening
+/// punctuation.
+pub(crate) fn line_start_trim<B: Brush>(
+    layout: &LayoutData<B>,
+    cluster_idx: u32,
+    paragraph_start: bool,
+) -> f32 {
+    let Some((cluster, font_size)) = ltr_cluster(layout, cluster_idx) else {
+        return 0.;
+    };
+    let trims = match layout.styles[cluster.style_index as usize].text_spacing_trim {
+        TextSpacingTrim::SpaceAll => false,
+        TextSpacingTrim::Normal => !paragraph_start,
+        TextSpacingTrim::TrimStart | TextSpacingTrim::TrimAll => true,
+    };
+    let first_char = layout.shaped_text.characters()[cluster.chars_range().start as usize]
+        .info
+        .source_char();
+    if trims && Punctuation::of(first_char) == Some(Punctuation::Opening) {
+        blank_half(cluster.advance, font_size)
+    } else {
+        0.
+    }
+}
+
+/// The advance of the cluster ending a line that may hang outside of it, if the cluster is a stop
+/// or comma with [`HangingPunctuation::AllowEnd`].
+pub(crate) fn hanging_advance<B: Brush>(layout: &LayoutData<B>, cluster_idx: u32) -> f32 {
+    let Some((cluster, _)) = ltr_cluster(layout, cluster_idx) else {
+        return 0.;
+    };
+    let chars = cluster.chars_range();
+    let characters = &layout.shaped_text.characters()[chars.start as usize..chars.end as usize];
+    if layout.styles[cluster.style_index as usize].hanging_punctuation
+        == HangingPunctuation::AllowEnd
+        && characters.len() == 1
+        && is_stop_or_comma(characters[0].info.source_char())
+    {
+        cluster.advance
+    } else {
+        0.
+    }
+}
+
+/// The cluster and the font size of its run, unless it is in right-to-left text.
+fn ltr_cluster<B: Brush>(layout: &LayoutData<B>, cluster_idx: u32) -> Option<(ShapedCluster, f32)> {
+    if layout.base_level.is_rtl() {
+        return None;
+    }
+    let runs = layout.shaped_text.runs();
+    let run = runs.get(runs.partition_point(|run| run.shaped_clusters_range.end <= cluster_idx))?;
+    if run.bidi_level.is_rtl() {
+        return None;
+    }
+    let cluster = *layout
+        .shaped_text
+        .shaped_clusters()
+        .get(cluster_idx as usize)?;
+    Some((cluster, run.font_size))
+}
@@ -27,7 +27,7 @@
 //! ```rust
 //! use parley::{
 //!    Alignment, AlignmentOptions, FontContext, FontWeight, InlineBox, InlineBoxKind, Layout,
-//!    LayoutContext, LineHeight, PositionedLayoutItem, StyleProperty,
+//!    LayoutContext, LineHeight, PositionedLayoutItem, StyleProperty, VerticalAlign,
 //! };
 //!
 //! // Create a FontContext (font database) and LayoutContext (scratch space).
@@ -47,7 +47,7 @@
 //! builder.push(StyleProperty::FontWeight(FontWeight::new(600.0)), 0..4);
 //!
 //! // Add a box to be laid out inline with the text
-//! builder.push_inline_box(InlineBox { id: 0, kind: InlineBoxKind::InFlow, index: 5, width: 50.0, height: 50.0, baseline: None });
+//! builder.push_inline_box(InlineBox { id: 0, kind: InlineBoxKind::InFlow, index: 5, width: 50.0, height: 50.0, baseline: None, vertical_align: VerticalAlign::Baseline });
 //!
 //! // Build the builder into a Layout
 //! let mut layout: Layout<()> = builder.build(&TEXT);
@@ -127,17 +127,22 @@ mod tests;
 
 pub use linebender_resource_handle::FontData;
 pub use parlance::BaseDirection;
+pub use parley_engine::FontInstance;
+#[cfg(feature = "std")]
+pub use parley_engine::SharedShapePlanCache;
 pub use parley_engine::break_overrides::{
     AsciiLineBreakTable, AsciiLineBreakTableBuilder, CHROMIUM_LINE_BREAK_OVERRIDE,
     LineBreakContext, LineBreakOverrideFn,
 };
+pub use parley_engine::shape::{CharCluster, Coverage};
 
 pub use builder::{RangedBuilder, StyleRunBuilder, TreeBuilder};
 pub use context::LayoutContext;
 pub use font::FontContext;
-pub use inline_box::{InlineBox, InlineBoxKind};
+pub use inline_box::{InlineBox, InlineBoxKind, VerticalAlign};
 #[doc(inline)]
 pub use layout::Layout;
+pub use shape::{DefaultFontSelector, FontSelectionStyle, FontSelector, select_font_by_coverage};
 pub use util::BoundingBox;
 
 pub use editing::*;
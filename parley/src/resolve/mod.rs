@@ -11,18 +11,21 @@ pub(crate) use range::RangedStyleBuilder;
 use alloc::{vec, vec::Vec};
 
 use super::style::{
-    Brush, FontFamily, FontFamilyName, FontFeature, FontFeatures, FontStyle, FontVariation,
-    FontVariations, FontWeight, FontWidth, StyleProperty,
+    BasePalette, Brush, FontFamily, FontFamilyName, FontFeature, FontFeatures, FontOpticalSizing,
+    FontPalette, FontSizeAdjust, FontStyle, FontSynthesis, FontVariantEmoji, FontVariation,
+    FontVariations, FontWeight, FontWidth, PaletteOverride, StyleProperty,
 };
 use crate::font::FontContext;
 use crate::style::TextStyle;
 use crate::util::nearly_eq;
+use crate::{
+    HangingPunctuation, LineBreak, TextAutospace, TextSpacingTrim, TextWrapMode, WordBreak,
+};
 use crate::{LineHeight, OverflowWrap, layout};
-use crate::{TextWrapMode, WordBreak};
 use core::borrow::Borrow;
 use core::ops::Range;
-use fontique::FamilyId;
 use fontique::Language;
+use fontique::{FamilyId, GenericFamily, GenericFamilyKey};
 
 /// Style with an associated range.
 #[derive(Debug, Clone)]
@@ -121,15 +124,29 @@ impl<T: Clone + PartialEq> Cache<T> {
     }
 }
 
+/// Entry of a resolved `font-family` list.
+///
+/// Generic families are kept unexpanded until the locale of the style is
+/// known, as they may resolve to different families per locale.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum StackFamily {
+    Id(FamilyId),
+    Generic(GenericFamily),
+}
+
 /// Context for managing dynamic properties during layout.
 #[derive(Clone, Default)]
 pub(crate) struct ResolveContext {
+    family_specs: Cache<StackFamily>,
     families: Cache<FamilyId>,
     variations: Cache<FontVariation>,
     features: Cache<FontFeature>,
+    palette_overrides: Cache<PaletteOverride>,
+    tmp_family_specs: Vec<StackFamily>,
     tmp_families: Vec<FamilyId>,
     tmp_variations: Vec<FontVariation>,
     tmp_features: Vec<FontFeature>,
+    tmp_palette_overrides: Vec<PaletteOverride>,
 }
 
 impl ResolveContext {
@@ -148,6 +165,11 @@ impl ResolveContext {
             StyleProperty::FontWeight(value) => FontWeight(*value),
             StyleProperty::FontVariations(value) => FontVariations(self.resolve_variations(value)),
             StyleProperty::FontFeatures(value) => FontFeatures(self.resolve_features(value)),
+            StyleProperty::FontVariantEmoji(value) => FontVariantEmoji(*value),
+            StyleProperty::FontOpticalSizing(value) => FontOpticalSizing(*value),
+            StyleProperty::FontSizeAdjust(value) => FontSizeAdjust(*value),
+            StyleProperty::FontSynthesis(value) => FontSynthesis(*value),
+            StyleProperty::FontPalette(value) => FontPalette(self.resolve_palette(value)),
             StyleProperty::Locale(value) => Locale(*value),
             StyleProperty::Brush(value) => Brush(value.clone()),
             StyleProperty::Underline(value) => Underline(*value),
@@ -166,6 +188,10 @@ impl ResolveContext {
             StyleProperty::WordBreak(value) => WordBreak(*value),
             StyleProperty::OverflowWrap(value) => OverflowWrap(*value),
             StyleProperty::TextWrapMode(value) => TextWrapMode(*value),
+            StyleProperty::LineBreak(value) => LineBreak(*value),
+            StyleProperty::HangingPunctuation(value) => HangingPunctuation(*value),
+            StyleProperty::TextSpacingTrim(value) => TextSpacingTrim(*value),
+            StyleProperty::TextAutospace(value) => TextAutospace(*value),
         }
     }
 
@@ -177,12 +203,18 @@ impl ResolveContext {
     ) -> ResolvedStyle<B> {
         ResolvedStyle {
             font_family: self.resolve_font_family(fcx, &raw_style.font_family),
+            font_stack: Resolved::default(),
             font_size: raw_style.font_size * scale,
             font_width: raw_style.font_width,
             font_style: raw_style.font_style,
             font_weight: raw_style.font_weight,
             font_variations: self.resolve_variations(&raw_style.font_variations),
             font_features: self.resolve_features(&raw_style.font_features),
+            font_variant_emoji: raw_style.font_variant_emoji,
+            font_optical_sizing: raw_style.font_optical_sizing,
+            font_size_adjust: raw_style.font_size_adjust,
+            font_synthesis: raw_style.font_synthesis,
+            font_palette: self.resolve_palette(&raw_style.font_palette),
             locale: raw_style.locale,
             brush: raw_style.brush.clone(),
             underline: ResolvedDecoration {
@@ -203,6 +235,10 @@ impl ResolveContext {
             word_break: raw_style.word_break,
             overflow_wrap: raw_style.overflow_wrap,
             text_wrap_mode: raw_style.text_wrap_mode,
+            line_break: raw_style.line_break,
+            hanging_punctuation: raw_style.hanging_punctuation,
+            text_spacing_trim: raw_style.text_spacing_trim,
+            text_autospace: raw_style.text_autospace,
         }
     }
 
@@ -211,20 +247,19 @@ impl ResolveContext {
         &mut self,
         fcx: &mut FontContext,
         value: &FontFamily<'_>,
-    ) -> Resolved<FamilyId> {
-        self.tmp_families.clear();
+    ) -> Resolved<StackFamily> {
+        self.tmp_family_specs.clear();
         match value {
             FontFamily::Source(source) => {
                 for family in FontFamilyName::parse_css_list(source).map_while(Result::ok) {
                     match family {
                         FontFamilyName::Named(name) => {
                             if let Some(family) = fcx.collection.family_by_name(&name) {
-                                self.tmp_families.push(family.id());
+                                self.tmp_family_specs.push(StackFamily::Id(family.id()));
                             }
                         }
                         FontFamilyName::Generic(family) => {
-                            self.tmp_families
-                                .extend(fcx.collection.generic_families(family));
+                            self.tmp_family_specs.push(StackFamily::Generic(family));
                         }
                     }
                 }
@@ -232,12 +267,11 @@ impl ResolveContext {
             FontFamily::Single(family) => match family {
                 FontFamilyName::Named(name) => {
                     if let Some(family) = fcx.collection.family_by_name(name) {
-                        self.tmp_families.push(family.id());
+                        self.tmp_family_specs.push(StackFamily::Id(family.id()));
                     }
                 }
                 FontFamilyName::Generic(family) => {
-                    self.tmp_families
-                        .extend(fcx.collection.generic_families(*family));
+                    self.tmp_family_specs.push(StackFamily::Generic(*family));
                 }
             },
             FontFamily::List(families) => {
@@ -246,22 +280,45 @@ impl ResolveContext {
                     match family {
                         FontFamilyName::Named(name) => {
                             if let Some(family) = fcx.collection.family_by_name(name) {
-                                self.tmp_families.push(family.id());
+                                self.tmp_family_specs.push(StackFamily::Id(family.id()));
                             }
                         }
                         FontFamilyName::Generic(family) => {
-                            self.tmp_families
-                                .extend(fcx.collection.generic_families(*family));
+                            self.tmp_family_specs.push(StackFamily::Generic(*family));
                         }
                     }
                 }
             }
         }
-        let resolved = self.families.insert(&self.tmp_families);
-        self.tmp_families.clear();
+        let resolved = self.family_specs.insert(&self.tmp_family_specs);
+        self.tmp_family_specs.clear();
         resolved
     }
 
+    /// Resolves the font stack of each style from its `font-family` list,
+    /// expanding generic families for the locale of the style.
+    pub(crate) fn resolve_font_stacks<B: Brush>(
+        &mut self,
+        fcx: &mut FontContext,
+        styles: &mut [ResolvedStyle<B>],
+    ) {
+        for style in styles {
+            self.tmp_families.clear();
+            for family in self.family_specs.get(style.font_family).unwrap_or(&[]) {
+                match *family {
+                    StackFamily::Id(id) => self.tmp_families.push(id),
+                    StackFamily::Generic(generic) => {
+                        let key = GenericFamilyKey::new(generic, style.locale.as_ref());
+                        self.tmp_families
+                            .extend(fcx.collection.generic_families(key));
+                    }
+                }
+            }
+            style.font_stack = self.families.insert(&self.tmp_families);
+        }
+        self.tmp_families.clear();
+    }
+
     /// Resolves font variation settings.
     pub(crate) fn resolve_variations(
         &mut self,
@@ -312,6 +369,35 @@ impl ResolveContext {
         resolved
     }
 
+    /// Resolves a font palette.
+    pub(crate) fn resolve_palette(&mut self, palette: &FontPalette<'_>) -> ResolvedPalette {
+        if palette.overrides.is_empty() {
+            return ResolvedPalette {
+                base: palette.base,
+                overrides: Resolved::default(),
+            };
+        }
+        self.tmp_palette_overrides.clear();
+        self.tmp_palette_overrides
+            .extend_from_slice(&palette.overrides);
+        // The sort is stable, so the last of several overrides of an entry is kept.
+        self.tmp_palette_overrides.sort_by_key(|a| a.index);
+        self.tmp_palette_overrides.dedup_by(|later, earlier| {
+            if later.index == earlier.index {
+                *earlier = *later;
+                true
+            } else {
+                false
+            }
+        });
+        let overrides = self.palette_overrides.insert(&self.tmp_palette_overrides);
+        self.tmp_palette_overrides.clear();
+        ResolvedPalette {
+            base: palette.base,
+            overrides,
+        }
+    }
+
     /// Returns the list of font families for the specified handle.
     pub(crate) fn stack(&self, stack: Resolved<FamilyId>) -> Option<&[FamilyId]> {
         self.families.get(stack)
@@ -330,11 +416,21 @@ impl ResolveContext {
         self.features.get(features)
     }
 
+    /// Returns the list of palette overrides for the specified handle.
+    pub(crate) fn palette_overrides(
+        &self,
+        overrides: Resolved<PaletteOverride>,
+    ) -> Option<&[PaletteOverride]> {
+        self.palette_overrides.get(overrides)
+    }
+
     /// Clears the resources in the context.
     pub(crate) fn clear(&mut self) {
+        self.family_specs.clear();
         self.families.clear();
         self.variations.clear();
         self.features.clear();
+        self.palette_overrides.clear();
     }
 }
 
@@ -342,7 +438,7 @@ impl ResolveContext {
 #[derive(Clone, PartialEq)]
 pub(crate) enum ResolvedProperty<B: Brush> {
     /// `font-family`.
-    FontFamily(Resolved<FamilyId>),
+    FontFamily(Resolved<StackFamily>),
     /// Font size.
     FontSize(f32),
     /// Font width.
@@ -355,6 +451,16 @@ pub(crate) enum ResolvedProperty<B: Brush> {
     FontVariations(Resolved<FontVariation>),
     /// Font feature settings.
     FontFeatures(Resolved<FontFeature>),
+    /// Text or emoji presentation of emoji.
+    FontVariantEmoji(FontVariantEmoji),
+    /// Automatic setting of the `opsz` axis.
+    FontOpticalSizing(FontOpticalSizing),
+    /// Adjustment of each font's size.
+    FontSizeAdjust(FontSizeAdjust),
+    /// Allowed font synthesis.
+    FontSynthesis(FontSynthesis),
+    /// Palette of color fonts.
+    FontPalette(ResolvedPalette),
     /// Locale.
     Locale(Option<Language>),
     /// Brush for rendering text.
@@ -387,13 +493,33 @@ pub(crate) enum ResolvedProperty<B: Brush> {
     OverflowWrap(OverflowWrap),
     /// Control over non-"emergency" line-breaking.
     TextWrapMode(TextWrapMode),
+    /// Strictness of line-breaking rules.
+    LineBreak(LineBreak),
+    /// Control over hanging punctuation.
+    HangingPunctuation(HangingPunctuation),
+    /// Trimming of fullwidth punctuation.
+    TextSpacingTrim(TextSpacingTrim),
+    /// Spacing between ideographic and non-ideographic text.
+    TextAutospace(TextAutospace),
+}
+
+/// Font palette with resolved overrides.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub(crate) struct ResolvedPalette {
+    /// The palette of the font to start from.
+    pub(crate) base: BasePalette,
+    /// Colors that replace entries of the base palette, sorted by entry.
+    pub(crate) overrides: Resolved<PaletteOverride>,
 }
 
 /// Flattened group of style properties.
 #[derive(Clone, PartialEq, Debug, Default)]
 pub(crate) struct ResolvedStyle<B: Brush> {
     /// `font-family`.
-    pub(crate) font_family: Resolved<FamilyId>,
+    pub(crate) font_family: Resolved<StackFamily>,
+    /// The families of `font-family` with generic families expanded for the
+    /// locale, resolved when building the layout.
+    pub(crate) font_stack: Resolved<FamilyId>,
     /// Font size.
     pub(crate) font_size: f32,
     /// Font width.
@@ -406,6 +532,16 @@ pub(crate) struct ResolvedStyle<B: Brush> {
     pub(crate) font_variations: Resolved<FontVariation>,
     /// Font feature settings.
     pub(crate) font_features: Resolved<FontFeature>,
+    /// Text or emoji presentation of emoji.
+    pub(crate) font_variant_emoji: FontVariantEmoji,
+    /// Automatic setting of the `opsz` axis.
+    pub(crate) font_optical_sizing: FontOpticalSizing,
+    /// Adjustment of each font's size.
+    pub(crate) font_size_adjust: FontSizeAdjust,
+    /// Allowed font synthesis.
+    pub(crate) font_synthesis: FontSynthesis,
+    /// Palette of color fonts.
+    pub(crate) font_palette: ResolvedPalette,
     /// Locale.
     pub(crate) locale: Option<Language>,
     /// Brush for rendering text.
@@ -426,6 +562,14 @@ pub(crate) struct ResolvedStyle<B: Brush> {
     pub(crate) overflow_wrap: OverflowWrap,
     /// Control over non-"emergency" line-breaking.
     pub(crate) text_wrap_mode: TextWrapMode,
+    /// Strictness of line-breaking rules.
+    pub(crate) line_break: LineBreak,
+    /// Control over hanging punctuation.
+    pub(crate) hanging_punctuation: HangingPunctuation,
+    /// Trimming of fullwidth punctuation.
+    pub(crate) text_spacing_trim: TextSpacingTrim,
+    /// Spacing between ideographic and non-ideographic text.
+    pub(crate) text_autospace: TextAutospace,
 }
 
 impl<B: Brush> ResolvedStyle<B> {
@@ -440,6 +584,11 @@ impl<B: Brush> ResolvedStyle<B> {
             FontWeight(value) => self.font_weight = value,
             FontVariations(value) => self.font_variations = value,
             FontFeatures(value) => self.font_features = value,
+            FontVariantEmoji(value) => self.font_variant_emoji = value,
+            FontOpticalSizing(value) => self.font_optical_sizing = value,
+            FontSizeAdjust(value) => self.font_size_adjust = value,
+            FontSynthesis(value) => self.font_synthesis = value,
+            FontPalette(value) => self.font_palette = value,
             Locale(value) => self.locale = value,
             Brush(value) => self.brush = value,
             Underline(value) => self.underline.enabled = value,
@@ -456,6 +605,10 @@ impl<B: Brush> ResolvedStyle<B> {
             WordBreak(value) => self.word_break = value,
             OverflowWrap(value) => self.overflow_wrap = value,
             TextWrapMode(value) => self.text_wrap_mode = value,
+            LineBreak(value) => self.line_break = value,
+            HangingPunctuation(value) => self.hanging_punctuation = value,
+            TextSpacingTrim(value) => self.text_spacing_trim = value,
+            TextAutospace(value) => self.text_autospace = value,
         }
     }
 
@@ -469,6 +622,11 @@ impl<B: Brush> ResolvedStyle<B> {
             FontWeight(value) => self.font_weight == *value,
             FontVariations(value) => self.font_variations == *value,
             FontFeatures(value) => self.font_features == *value,
+            FontVariantEmoji(value) => self.font_variant_emoji == *value,
+            FontOpticalSizing(value) => self.font_optical_sizing == *value,
+            FontSizeAdjust(value) => self.font_size_adjust == *value,
+            FontSynthesis(value) => self.font_synthesis == *value,
+            FontPalette(value) => self.font_palette == *value,
             Locale(value) => self.locale == *value,
             Brush(value) => self.brush == *value,
             Underline(value) => self.underline.enabled == *value,
@@ -485,6 +643,10 @@ impl<B: Brush> ResolvedStyle<B> {
             WordBreak(value) => self.word_break == *value,
             OverflowWrap(value) => self.overflow_wrap == *value,
             TextWrapMode(value) => self.text_wrap_mode == *value,
+            LineBreak(value) => self.line_break == *value,
+            HangingPunctuation(value) => self.hanging_punctuation == *value,
+            TextSpacingTrim(value) => self.text_spacing_trim == *value,
+            TextAutospace(value) => self.text_autospace == *value,
         }
     }
 
@@ -493,9 +655,18 @@ impl<B: Brush> ResolvedStyle<B> {
             brush: self.brush.clone(),
             underline: self.underline.as_layout_decoration(&self.brush),
             strikethrough: self.strikethrough.as_layout_decoration(&self.brush),
+            underline_brush: self.underline.brush.clone(),
+            strikethrough_brush: self.strikethrough.brush.clone(),
+            underline_offset: self.underline.offset,
+            underline_size: self.underline.size,
+            strikethrough_offset: self.strikethrough.offset,
+            strikethrough_size: self.strikethrough.size,
             line_height: self.line_height,
             overflow_wrap: self.overflow_wrap,
             text_wrap_mode: self.text_wrap_mode,
+            hanging_punctuation: self.hanging_punctuation,
+            text_spacing_trim: self.text_spacing_trim,
+            text_autospace: self.text_autospace,
             #[cfg(feature = "accesskit")]
             locale: self.locale,
         }
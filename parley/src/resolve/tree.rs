@@ -158,6 +158,11 @@ impl<B: Brush> TreeStyleBuilder<B> {
         self.text.len()
     }
 
+    /// The length of the text, including text that has been pushed but not yet committed.
+    pub(crate) fn pending_text_len(&self) -> usize {
+        self.text.len() + self.uncommitted_text.len()
+    }
+
     pub(crate) fn push_style_span(&mut self, style: ResolvedStyle<B>) {
         self.push_uncommitted_text(false);
 
@@ -4,20 +4,44 @@
 //! Text shaping implementation using `harfrust`for shaping
 //! and `icu` for text analysis.
 
-use parley_engine::shape::{CharCluster, Coverage};
-use parley_engine::{Analysis, AnalysisDataSources, FontInstance, ShapeOptions, Shaper};
+use core::iter::{Enumerate, Peekable};
+use core::ops::Range;
+use core::slice;
+
+use parley_engine::shape::{CharCluster, Coverage, EmojiPresentation};
+use parley_engine::{
+    Analysis, AnalysisDataSources, FontInstance, FontMetrics, ShapeOptions, Shaper,
+};
 use smallvec::SmallVec;
+#[cfg(feature = "rayon")]
+use {
+    alloc::vec::Vec,
+    parley_engine::itemize::Item,
+    parley_engine::{FontSegment, ShapedText},
+    rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator},
+    rayon::slice::ParallelSlice,
+};
 
-use super::layout::Layout;
+mod selector;
+
+pub use selector::{
+    DefaultFontSelector, FontSelectionStyle, FontSelector, select_font_by_coverage,
+};
+
+use selector::{best_coverage_font, font_coverage};
+
+use super::layout::{ClusterSelection, FontDiagnostics, Layout};
 use super::resolve::{ResolveContext, ResolvedStyle};
-use super::style::{Brush, FontFeature, FontVariation};
+use super::style::{
+    Brush, FontFeature, FontOpticalSizing, FontSizeAdjust, FontVariantEmoji, FontVariation,
+};
 use crate::inline_box::InlineBox;
 use crate::util::{nearly_eq, nearly_zero};
 use crate::{FontContext, FontData};
 use fontique::Language;
 
 use fontique::{self, Query, QueryFamily, QueryFont};
-use parlance::{GenericFamily, Script, Tag};
+use parlance::{ColorScheme, GenericFamily, Script, Tag};
 
 /// If these font features are passed to the shaper, optional ligatures are not applied.
 ///
@@ -41,6 +65,10 @@ const OPTIONAL_LIGATURES_OFF: [FontFeature; 4] = [
     FontFeature::new(Tag::new(b"hlig"), 0),
 ];
 
+/// The length of text, in bytes, from which its items are shaped in parallel.
+#[cfg(feature = "rayon")]
+const PARALLEL_SHAPING_MIN_LEN: usize = 4096;
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn shape_text<'a, B: Brush>(
     rcx: &'a ResolveContext,
@@ -53,6 +81,17 @@ pub(crate) fn shape_text<'a, B: Brush>(
     mut text: &str,
     layout: &mut Layout<B>,
     analysis_data_sources: &AnalysisDataSources,
+    selector: Option<&'a dyn FontSelector>,
+    color_scheme: ColorScheme,
+    collect_diagnostics: bool,
+    #[cfg_attr(
+        not(feature = "rayon"),
+        expect(
+            unused_variables,
+            reason = "Items are only shaped in parallel with `rayon`"
+        )
+    )]
+    parallel_shaping: bool,
 ) {
     // If we have both empty text and no inline boxes, shape with a fake space
     // to generate metrics that can be used to size a cursor.
@@ -108,43 +147,140 @@ pub(crate) fn shape_text<'a, B: Brush>(
                 || style.font_features != item_style.font_features
                 || !nearly_eq(style.letter_spacing, item_style.letter_spacing)
                 || !nearly_eq(style.word_spacing, item_style.word_spacing)
+                // The palette doesn't affect shaping, but runs only have a single one.
+                || style.font_palette != item_style.font_palette
         } else {
             false
         }
     };
 
-    let mut features_scratch = SmallVec::<[FontFeature; 8]>::new();
     let mut inline_box_iter = inline_boxes.iter().enumerate().peekable();
-    for item in analysis.itemize(text, split_after) {
-        // Push inline boxes positioned before the start of this item.
-        while let Some((box_idx, inline_box)) = inline_box_iter.peek() {
-            if inline_box.index <= item.range.byte_range.start {
-                layout.data.push_inline_box(*box_idx);
-                inline_box_iter.next();
-            } else {
-                break;
-            }
+    let items = analysis.itemize(text, split_after);
+
+    #[cfg(feature = "rayon")]
+    if parallel_shaping && text.len() >= PARALLEL_SHAPING_MIN_LEN {
+        // Select the fonts of all items first, as that needs the font collection, and then shape
+        // the items on multiple threads.
+        let mut items_to_shape = Vec::new();
+        for item in items {
+            let style_index = char_style_indices[item.range.char_range.start];
+            let style = &styles[usize::from(style_index)];
+            let mut font_selector = ClusterFontSelector::new(
+                &mut fq,
+                rcx,
+                styles,
+                style_index,
+                item.script,
+                style.locale,
+                selector,
+                layout.data.scale,
+                collect_diagnostics.then_some(&mut layout.data.font_diagnostics),
+            );
+            let mut selected = SelectedItem {
+                item,
+                style_index,
+                language: style.locale,
+                font_size: style.font_size,
+                features: SmallVec::new(),
+                variations: rcx.variations(style.font_variations).unwrap_or(&[]),
+                segments: Vec::new(),
+            };
+            push_item_features(rcx, style, &mut selected.features);
+            scx.select_fonts(
+                text,
+                analysis,
+                &selected.item,
+                &selected.shape_options(char_style_indices),
+                |char_cluster| font_selector.select_font(char_cluster, analysis_data_sources),
+                &mut selected.segments,
+            );
+            items_to_shape.push(selected);
+        }
+
+        // The items are shaped in chunks, each with a fork of the context's shaper, so they reuse
+        // its shaping plans and words, and the words they shape are kept for later layouts.
+        let chunk_len = items_to_shape
+            .len()
+            .div_ceil(4 * rayon::current_num_threads())
+            .max(1);
+        let forks: Vec<Shaper> = items_to_shape
+            .chunks(chunk_len)
+            .map(|_| scx.fork())
+            .collect();
+        let shaped_chunks: Vec<(Shaper, Vec<ShapedText>)> = items_to_shape
+            .par_chunks(chunk_len)
+            .zip(forks.into_par_iter())
+            .map(|(chunk, mut shaper)| {
+                let shaped_items = chunk
+                    .iter()
+                    .map(|selected| {
+                        let mut shaped_text = ShapedText::new();
+                        shaper.shape_segments(
+                            text,
+                            analysis,
+                            &selected.item,
+                            &selected.shape_options(char_style_indices),
+                            &selected.segments,
+                            &mut shaped_text,
+                        );
+                        shaped_text
+                    })
+                    .collect();
+                (shaper, shaped_items)
+            })
+            .collect();
+        let mut shaped_items = Vec::with_capacity(items_to_shape.len());
+        for (shaper, shaped_chunk) in shaped_chunks {
+            scx.merge(shaper);
+            shaped_items.extend(shaped_chunk);
         }
 
+        for (selected, shaped_text) in items_to_shape.iter().zip(&shaped_items) {
+            push_inline_boxes_before(
+                &mut inline_box_iter,
+                selected.item.range.byte_range.start,
+                layout,
+            );
+            let shaped_runs_range = layout.data.shaped_text.append(shaped_text);
+            process_shaped_runs(
+                rcx,
+                styles,
+                char_style_indices,
+                &styles[usize::from(selected.style_index)],
+                color_scheme,
+                shaped_runs_range,
+                layout,
+            );
+        }
+
+        // Process any remaining inline boxes whose index is greater than the length of the text
+        for (box_idx, _inline_box) in inline_box_iter {
+            layout.data.push_inline_box(box_idx);
+        }
+        return;
+    }
+
+    let mut features = SmallVec::<[FontFeature; 8]>::new();
+    for item in items {
+        // Push inline boxes positioned before the start of this item.
+        push_inline_boxes_before(&mut inline_box_iter, item.range.byte_range.start, layout);
+
         let style_index = char_style_indices[item.range.char_range.start];
         let style = &styles[usize::from(style_index)];
-        let mut font_selector =
-            FontSelector::new(&mut fq, rcx, styles, style_index, item.script, style.locale);
-
-        let style_features = rcx.features(style.font_features).unwrap_or(&[]);
-        let features = if !nearly_zero(style.letter_spacing) {
-            if style_features.is_empty() {
-                OPTIONAL_LIGATURES_OFF.as_slice()
-            } else {
-                features_scratch.clear();
-                // Later values override earlier values.
-                features_scratch
-                    .extend(OPTIONAL_LIGATURES_OFF.iter().chain(style_features).copied());
-                features_scratch.as_slice()
-            }
-        } else {
-            style_features
-        };
+        let mut font_selector = ClusterFontSelector::new(
+            &mut fq,
+            rcx,
+            styles,
+            style_index,
+            item.script,
+            style.locale,
+            selector,
+            layout.data.scale,
+            collect_diagnostics.then_some(&mut layout.data.font_diagnostics),
+        );
+
+        features.clear();
+        push_item_features(rcx, style, &mut features);
 
         let shaped_runs_range = scx.shape_item(
             text,
@@ -153,7 +289,7 @@ pub(crate) fn shape_text<'a, B: Brush>(
             &ShapeOptions {
                 language: style.locale,
                 font_size: style.font_size,
-                features,
+                features: &features,
                 variations: rcx.variations(style.font_variations).unwrap_or(&[]),
                 char_style_indices,
             },
@@ -161,17 +297,15 @@ pub(crate) fn shape_text<'a, B: Brush>(
             |char_cluster| font_selector.select_font(char_cluster, analysis_data_sources),
             &mut layout.data.shaped_text,
         );
-        for shaped_run_idx in shaped_runs_range {
-            let shaped_run = &layout.data.shaped_text.runs()[shaped_run_idx];
-            let run_style_index = char_style_indices[shaped_run.range.char_range.start];
-            let run_style = &styles[usize::from(run_style_index)];
-            layout.data.process_shaped_run(
-                shaped_run_idx,
-                run_style,
-                style.word_spacing,
-                style.letter_spacing,
-            );
-        }
+        process_shaped_runs(
+            rcx,
+            styles,
+            char_style_indices,
+            style,
+            color_scheme,
+            shaped_runs_range,
+            layout,
+        );
     }
 
     // Process any remaining inline boxes whose index is greater than the length of the text
@@ -180,30 +314,128 @@ pub(crate) fn shape_text<'a, B: Brush>(
     }
 }
 
+/// An item whose fonts were selected, to be shaped on any thread.
+#[cfg(feature = "rayon")]
+struct SelectedItem<'a> {
+    item: Item,
+    style_index: u16,
+    language: Option<Language>,
+    font_size: f32,
+    features: SmallVec<[FontFeature; 8]>,
+    variations: &'a [FontVariation],
+    segments: Vec<FontSegment>,
+}
+
+#[cfg(feature = "rayon")]
+impl SelectedItem<'_> {
+    fn shape_options<'b>(&'b self, char_style_indices: &'b [u16]) -> ShapeOptions<'b> {
+        ShapeOptions {
+            language: self.language,
+            font_size: self.font_size,
+            features: &self.features,
+            variations: self.variations,
+            char_style_indices,
+        }
+    }
+}
+
+/// Pushes the inline boxes positioned at or before `index` that weren't pushed yet.
+fn push_inline_boxes_before<B: Brush>(
+    inline_box_iter: &mut Peekable<Enumerate<slice::Iter<'_, InlineBox>>>,
+    index: usize,
+    layout: &mut Layout<B>,
+) {
+    while let Some((box_idx, inline_box)) = inline_box_iter.peek() {
+        if inline_box.index <= index {
+            layout.data.push_inline_box(*box_idx);
+            inline_box_iter.next();
+        } else {
+            break;
+        }
+    }
+}
+
+/// Pushes the font features to shape an item with `style` with onto `features`.
+fn push_item_features<B: Brush>(
+    rcx: &ResolveContext,
+    style: &ResolvedStyle<B>,
+    features: &mut SmallVec<[FontFeature; 8]>,
+) {
+    if !nearly_zero(style.letter_spacing) {
+        features.extend_from_slice(&OPTIONAL_LIGATURES_OFF);
+    }
+    // Later values override earlier values.
+    features.extend_from_slice(rcx.features(style.font_features).unwrap_or(&[]));
+}
+
+/// Processes the runs shaped for an item whose style is `style`.
+fn process_shaped_runs<B: Brush>(
+    rcx: &ResolveContext,
+    styles: &[ResolvedStyle<B>],
+    char_style_indices: &[u16],
+    style: &ResolvedStyle<B>,
+    color_scheme: ColorScheme,
+    shaped_runs_range: Range<usize>,
+    layout: &mut Layout<B>,
+) {
+    for shaped_run_idx in shaped_runs_range {
+        let shaped_run = &layout.data.shaped_text.runs()[shaped_run_idx];
+        let run_style_index = char_style_indices[shaped_run.range.char_range.start];
+        let run_style = &styles[usize::from(run_style_index)];
+        let palette_overrides = rcx
+            .palette_overrides(run_style.font_palette.overrides)
+            .unwrap_or(&[]);
+        layout.data.process_shaped_run(
+            shaped_run_idx,
+            run_style,
+            palette_overrides,
+            color_scheme,
+            style.word_spacing,
+            style.letter_spacing,
+        );
+    }
+}
+
 /// The font to use if a font query doesn't return any font candidates at all.
 #[derive(Debug)]
 enum LastResortFont {
     Unresolved,
-    Resolved(FontInstance),
+    Resolved(QueryFont),
     Unavailable,
 }
 
-struct FontSelector<'a, 'b, B: Brush> {
+struct ClusterFontSelector<'a, 'b, B: Brush> {
     query: &'b mut Query<'a>,
     fonts_id: Option<usize>,
     rcx: &'a ResolveContext,
     styles: &'a [ResolvedStyle<B>],
     style_index: u16,
+    script: Script,
+    /// The locale of the item being shaped.
+    locale: Option<Language>,
     attrs: fontique::Attributes,
     variations: &'a [FontVariation],
     features: &'a [FontFeature],
+    /// The user's font selection policy, if not the default.
+    selector: Option<&'a dyn FontSelector>,
+    /// The display scale factor the font sizes of the styles were multiplied by.
+    scale: f32,
+    /// Where to record the diagnostics of clusters, if they are collected.
+    diagnostics: Option<&'b mut FontDiagnostics>,
 
     /// The font to use if [`Self::query`] doesn't return any font.
     last_resort_font: LastResortFont,
+    /// The style index [`Self::size_adjust`] was resolved for.
+    size_adjust_style: Option<u16>,
+    /// The metric to adjust font sizes by, and the ratio of the font size to adjust it to.
+    size_adjust: Option<(SizeAdjustMetric, f32)>,
+    /// The blob ID and index of the last font whose size adjustment was computed, and the
+    /// adjustment.
+    last_size_adjust: Option<(u64, u32, f32)>,
 }
 
-impl<'a, 'b, B: Brush> FontSelector<'a, 'b, B> {
-    /// Construct a new `FontSelector`.
+impl<'a, 'b, B: Brush> ClusterFontSelector<'a, 'b, B> {
+    /// Construct a new `ClusterFontSelector`.
     fn new(
         query: &'b mut Query<'a>,
         rcx: &'a ResolveContext,
@@ -211,10 +443,13 @@ impl<'a, 'b, B: Brush> FontSelector<'a, 'b, B> {
         style_index: u16,
         script: Script,
         locale: Option<Language>,
+        selector: Option<&'a dyn FontSelector>,
+        scale: f32,
+        diagnostics: Option<&'b mut FontDiagnostics>,
     ) -> Self {
         let style = &styles[style_index as usize];
-        let fonts_id = style.font_family.id();
-        let fonts = rcx.stack(style.font_family).unwrap_or(&[]);
+        let fonts_id = style.font_stack.id();
+        let fonts = rcx.stack(style.font_stack).unwrap_or(&[]);
         let attrs = fontique::Attributes {
             width: style.font_width,
             weight: style.font_weight,
@@ -222,6 +457,9 @@ impl<'a, 'b, B: Brush> FontSelector<'a, 'b, B> {
         };
         let variations = rcx.variations(style.font_variations).unwrap_or(&[]);
         let features = rcx.features(style.font_features).unwrap_or(&[]);
+        // Items are split at locale changes, so this holds for the emoji
+        // family of every cluster.
+        query.set_locale(locale.as_ref());
         query.set_families(fonts.iter().copied());
 
         query.set_fallbacks(fontique::FallbackKey::new(script, locale.as_ref()));
@@ -233,10 +471,18 @@ impl<'a, 'b, B: Brush> FontSelector<'a, 'b, B> {
             rcx,
             styles,
             style_index,
+            script,
+            locale,
             attrs,
             variations,
             features,
+            selector,
+            scale,
+            diagnostics,
             last_resort_font: LastResortFont::Unresolved,
+            size_adjust_style: None,
+            size_adjust: None,
+            last_size_adjust: None,
         }
     }
 
@@ -251,13 +497,17 @@ impl<'a, 'b, B: Brush> FontSelector<'a, 'b, B> {
             self.style_index = style_index;
             let style = &self.styles[style_index as usize];
 
-            let fonts_id = style.font_family.id();
-            let fonts = self.rcx.stack(style.font_family).unwrap_or(&[]);
+            let fonts_id = style.font_stack.id();
+            let fonts = self.rcx.stack(style.font_stack).unwrap_or(&[]);
             let fonts = fonts.iter().copied().map(QueryFamily::Id);
             if is_emoji {
                 use core::iter::once;
                 let emoji_family = QueryFamily::Generic(GenericFamily::Emoji);
-                self.query.set_families(fonts.chain(once(emoji_family)));
+                if prefers_emoji_font(style.font_variant_emoji, cluster.emoji_presentation()) {
+                    self.query.set_families(once(emoji_family).chain(fonts));
+                } else {
+                    self.query.set_families(fonts.chain(once(emoji_family)));
+                }
                 self.fonts_id = None;
             } else if self.fonts_id != Some(fonts_id) {
                 self.query.set_families(fonts);
@@ -276,74 +526,202 @@ impl<'a, 'b, B: Brush> FontSelector<'a, 'b, B> {
             self.variations = self.rcx.variations(style.font_variations).unwrap_or(&[]);
             self.features = self.rcx.features(style.font_features).unwrap_or(&[]);
         }
-        let mut selected_font = None;
-        let mut best_coverage = Coverage::NONE;
-        self.query.matches_with(|font| {
-            let Some(charmap) = font.charmap() else {
-                return fontique::QueryStatus::Continue;
-            };
 
-            let coverage = cluster.calculate_coverage(
-                |ch| {
-                    charmap
-                        .map(ch)
-                        .map(|g| {
-                            // Any non-zero value indicates the existence of a glyph.
-                            g != 0
-                        })
-                        .unwrap_or_default()
-                },
-                analysis_data_sources,
-            );
-            if coverage > best_coverage {
-                selected_font = Some(SelectedFont { font: font.clone() });
-                best_coverage = coverage;
+        let style = &self.styles[style_index as usize];
+        let requested_families = self.rcx.stack(style.font_stack).unwrap_or(&[]);
+        let prefers_emoji =
+            prefers_emoji_font(style.font_variant_emoji, cluster.emoji_presentation());
+        if self.size_adjust_style != Some(style_index) {
+            self.size_adjust_style = Some(style_index);
+            self.size_adjust =
+                resolve_size_adjust(style.font_size_adjust, self.query, requested_families);
+            self.last_size_adjust = None;
+        }
+        let mut selected_font = if let Some(selector) = self.selector {
+            let selection_style = FontSelectionStyle {
+                style_index,
+                families: requested_families,
+                attributes: self.attrs,
+                font_size: style.font_size,
+                script: self.script,
+                locale: style.locale,
+                font_variant_emoji: style.font_variant_emoji,
+                prefers_emoji,
+                variations: self.variations,
+                features: self.features,
+            };
+            let font = selector.select_font(cluster, &selection_style, self.query);
+            // The selector may have changed the query, so set it up again for the next cluster.
+            self.query.set_locale(self.locale.as_ref());
+            self.query.set_attributes(self.attrs);
+            self.query.set_fallbacks(fontique::FallbackKey::new(
+                self.script,
+                self.locale.as_ref(),
+            ));
+            self.query.set_codepoint_fallback(None);
+            self.fonts_id = None;
+            font.map(|font| {
+                let coverage = font_coverage(cluster, &font, prefers_emoji, analysis_data_sources);
+                (font, coverage)
+            })
+        } else {
+            best_coverage_font(cluster, self.query, prefers_emoji, analysis_data_sources)
+        };
 
-                if coverage.is_complete() {
-                    fontique::QueryStatus::Stop
+        let is_last_resort = selected_font.is_none();
+        if is_last_resort {
+            if matches!(self.last_resort_font, LastResortFont::Unresolved) {
+                if let Some(font) = any_font(self.query) {
+                    self.last_resort_font = LastResortFont::Resolved(font);
                 } else {
-                    fontique::QueryStatus::Continue
+                    self.last_resort_font = LastResortFont::Unavailable;
                 }
-            } else {
-                if selected_font.is_none() {
-                    selected_font = Some(SelectedFont { font: font.clone() });
-                }
-                fontique::QueryStatus::Continue
+
+                self.fonts_id = None;
+            }
+
+            if let LastResortFont::Resolved(ref font) = self.last_resort_font {
+                let coverage = font_coverage(cluster, font, prefers_emoji, analysis_data_sources);
+                selected_font = Some((font.clone(), coverage));
+            }
+        }
+
+        let coverage = selected_font
+            .as_ref()
+            .map_or(Coverage::NONE, |(_, coverage)| *coverage);
+        let family = selected_font.as_ref().map(|(font, _)| font.family.0);
+        let font = selected_font.map(|(font, _)| {
+            let font_data = FontData {
+                data: font.blob,
+                index: font.index,
+            };
+            let size_adjust = self.size_adjust_for(&font_data);
+            // The optical size is in CSS pixels, so it doesn't change with the display scale.
+            let optical_size = (style.font_optical_sizing == FontOpticalSizing::Auto
+                && font.has_optical_size_axis)
+                .then(|| style.font_size * size_adjust / self.scale);
+            FontInstance {
+                font: font_data,
+                synthesis: font.synthesis.restrict(style.font_synthesis),
+                features: font.features,
+                size_adjust,
+                optical_size,
+                render_hints: font.render_hints,
             }
         });
+        if let Some(diagnostics) = self.diagnostics.as_deref_mut() {
+            // The emoji family is added to the families of emoji implicitly, so its fonts are
+            // not reported as fallbacks.
+            let is_fallback = family.is_some_and(|family| {
+                !requested_families.contains(&family)
+                    && !(is_emoji
+                        && self
+                            .query
+                            .generic_families(GenericFamily::Emoji)
+                            .any(|id| id == family))
+            });
+            if !coverage.is_complete() || is_fallback || is_last_resort {
+                let range = cluster.range();
+                diagnostics.push(ClusterSelection {
+                    text_range: range.start as usize..range.end as usize,
+                    requested_families,
+                    family,
+                    font: font.as_ref(),
+                    coverage,
+                    is_fallback,
+                    is_last_resort,
+                });
+            }
+        }
+        font
+    }
+}
 
-        selected_font
-            .map(|selected_font| selected_font.font)
-            .map(|font| FontInstance {
-                font: FontData {
-                    data: font.blob,
-                    index: font.index,
-                },
-                synthesis: font.synthesis,
-            })
-            .or_else(|| {
-                if matches!(self.last_resort_font, LastResortFont::Unresolved) {
-                    if let Some(font) = any_font(self.query) {
-                        self.last_resort_font = LastResortFont::Resolved(FontInstance {
-                            font: FontData {
-                                data: font.blob,
-                                index: font.index,
-                            },
-                            synthesis: font.synthesis,
-                        });
-                    } else {
-                        self.last_resort_font = LastResortFont::Unavailable;
-                    }
-
-                    self.fonts_id = None;
-                }
+impl<B: Brush> ClusterFontSelector<'_, '_, B> {
+    /// Returns the factor to scale the font size by for `font`, following `font-size-adjust`.
+    fn size_adjust_for(&mut self, font: &FontData) -> f32 {
+        let Some((metric, target)) = self.size_adjust else {
+            return 1.0;
+        };
+        let id = font.data.id();
+        if let Some((last_id, last_index, adjust)) = self.last_size_adjust
+            && last_id == id
+            && last_index == font.index
+        {
+            return adjust;
+        }
+        let adjust = metric.ratio(font).map_or(1.0, |ratio| target / ratio);
+        self.last_size_adjust = Some((id, font.index, adjust));
+        adjust
+    }
+}
+
+/// A font metric that font sizes can be adjusted by.
+#[derive(Clone, Copy, Debug)]
+enum SizeAdjustMetric {
+    ExHeight,
+    CapHeight,
+}
+
+impl SizeAdjustMetric {
+    /// Returns the metric of `font` as a fraction of its em size.
+    fn ratio(self, font: &FontData) -> Option<f32> {
+        let metrics = FontMetrics::from_font(font, 1.0)?;
+        let ratio = match self {
+            Self::ExHeight => metrics.x_height,
+            Self::CapHeight => metrics.cap_height,
+        };
+        ratio.filter(|ratio| *ratio > 0.0)
+    }
+}
 
-                if let LastResortFont::Resolved(ref font) = self.last_resort_font {
-                    Some(font.clone())
+/// Resolves the metric and target ratio for `font-size-adjust`.
+///
+/// For the `from-font` values, the ratio is taken from the first available font of
+/// `requested_families`, as queried by `query`.
+fn resolve_size_adjust(
+    adjust: FontSizeAdjust,
+    query: &mut Query<'_>,
+    requested_families: &[fontique::FamilyId],
+) -> Option<(SizeAdjustMetric, f32)> {
+    let (metric, ratio) = match adjust {
+        FontSizeAdjust::None => return None,
+        FontSizeAdjust::ExHeight(ratio) => (SizeAdjustMetric::ExHeight, Some(ratio)),
+        FontSizeAdjust::CapHeight(ratio) => (SizeAdjustMetric::CapHeight, Some(ratio)),
+        FontSizeAdjust::ExHeightFromFont => (SizeAdjustMetric::ExHeight, None),
+        FontSizeAdjust::CapHeightFromFont => (SizeAdjustMetric::CapHeight, None),
+    };
+    let ratio = match ratio {
+        Some(ratio) => ratio,
+        None => {
+            // The query may list the emoji family first, so look for the first font of a
+            // requested family rather than the first font.
+            let mut primary = None;
+            query.matches_with(|font: &QueryFont| {
+                if requested_families.contains(&font.family.0) {
+                    primary = Some(FontData {
+                        data: font.blob.clone(),
+                        index: font.index,
+                    });
+                    fontique::QueryStatus::Stop
                 } else {
-                    None
+                    fontique::QueryStatus::Continue
                 }
-            })
+            });
+            metric.ratio(&primary?)?
+        }
+    };
+    (ratio > 0.0).then_some((metric, ratio))
+}
+
+/// Whether the emoji family should be queried before the style's families for an emoji.
+///
+/// Otherwise, it comes after them, so that it is only used for emoji those don't cover.
+fn prefers_emoji_font(variant: FontVariantEmoji, presentation: EmojiPresentation) -> bool {
+    match variant {
+        FontVariantEmoji::Normal | FontVariantEmoji::Text => false,
+        FontVariantEmoji::Emoji => true,
+        FontVariantEmoji::Unicode => presentation.prefers_emoji(),
     }
 }
 
@@ -378,13 +756,3 @@ fn any_font(query: &mut Query<'_>) -> Option<QueryFont> {
 
     found
 }
-
-struct SelectedFont {
-    font: QueryFont,
-}
-
-impl PartialEq for SelectedFont {
-    fn eq(&self, other: &Self) -> bool {
-        self.font.family == other.font.family && self.font.synthesis == other.font.synthesis
-    }
-}
@@ -0,0 +1,213 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Customisable font selection.
+
+use fontique::{Attributes, FamilyId, Language, Query, QueryFont, QueryStatus};
+use parlance::Script;
+use parley_engine::AnalysisDataSources;
+use parley_engine::shape::{CharCluster, Coverage};
+
+use crate::style::{FontFeature, FontVariantEmoji, FontVariation};
+
+/// Chooses the font used to shape each cluster of text.
+///
+/// A selector can be set for all layouts built with a context with
+/// [`LayoutContext::set_font_selector`], or for a single layout with the builders'
+/// `set_font_selector` methods, such as [`RangedBuilder::set_font_selector`]. Without one,
+/// [`DefaultFontSelector`] is used.
+///
+/// [`LayoutContext::set_font_selector`]: crate::LayoutContext::set_font_selector
+/// [`RangedBuilder::set_font_selector`]: crate::RangedBuilder::set_font_selector
+pub trait FontSelector {
+    /// Selects the font for `cluster`, which has the resolved font properties of `style`.
+    ///
+    /// `query` has been set up with the family stack and attributes of `style`, and with the
+    /// fallback families for its script and locale. For emoji clusters, the
+    /// [emoji generic family](crate::GenericFamily::Emoji) is added to the family stack, before
+    /// or after the style's families depending on [`FontSelectionStyle::font_variant_emoji`].
+    /// Changes made to `query` don't carry over to later clusters.
+    ///
+    /// The chosen font is usually one of the candidates produced by `query`. Its family is
+    /// compared against the requested family stack to report fallbacks in the layout's
+    /// [`FontDiagnostics`](crate::FontDiagnostics).
+    ///
+    /// If this returns `None`, the first font found in the sans-serif, serif or monospace generic
+    /// families is used as a last resort.
+    ///
+    /// The default implementation is [`select_font_by_coverage`].
+    fn select_font(
+        &self,
+        cluster: &mut CharCluster,
+        style: &FontSelectionStyle<'_>,
+        query: &mut Query<'_>,
+    ) -> Option<QueryFont> {
+        select_font_by_coverage(cluster, style, query)
+    }
+}
+
+/// The font selection policy used when no [`FontSelector`] is set.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultFontSelector;
+
+impl FontSelector for DefaultFontSelector {}
+
+/// The resolved style of a cluster, as seen by a [`FontSelector`].
+#[derive(Clone, Copy, Debug)]
+pub struct FontSelectionStyle<'a> {
+    /// The index of the style in the layout's style table.
+    pub style_index: u16,
+    /// The font family stack of the style, without the emoji family added for emoji clusters.
+    pub families: &'a [FamilyId],
+    /// The font width, weight and style.
+    pub attributes: Attributes,
+    /// The font size, multiplied by the layout's scale.
+    pub font_size: f32,
+    /// The script of the text item containing the cluster.
+    pub script: Script,
+    /// The locale of the style.
+    pub locale: Option<Language>,
+    /// The presentation of emoji, which decides where the emoji family is added to `query`.
+    pub font_variant_emoji: FontVariantEmoji,
+    /// Whether the cluster is drawn with emoji presentation, resolved from
+    /// [`Self::font_variant_emoji`] and the cluster's presentation.
+    ///
+    /// See [`CharCluster::calculate_coverage`].
+    pub prefers_emoji: bool,
+    /// The font variation settings of the style.
+    pub variations: &'a [FontVariation],
+    /// The font feature settings of the style.
+    pub features: &'a [FontFeature],
+}
+
+/// Selects the first font in `query` with the best [`Coverage`] of `cluster`, drawn with the
+/// presentation of `style`.
+///
+/// Fonts with complete coverage end the search early. If no font covers all of the cluster, the
+/// registered families covering its first unmapped character are searched as well, see
+/// [`Query::set_codepoint_fallback`]. If no font covers any of the cluster, the first font with a
+/// character map is chosen.
+pub fn select_font_by_coverage(
+    cluster: &mut CharCluster,
+    style: &FontSelectionStyle<'_>,
+    query: &mut Query<'_>,
+) -> Option<QueryFont> {
+    best_coverage_font(
+        cluster,
+        query,
+        style.prefers_emoji,
+        &AnalysisDataSources::new(),
+    )
+    .map(|(font, _)| font)
+}
+
+/// Like [`select_font_by_coverage`], but also returns the coverage of the selected font.
+pub(crate) fn best_coverage_font(
+    cluster: &mut CharCluster,
+    query: &mut Query<'_>,
+    prefers_emoji: bool,
+    analysis_data_sources: &AnalysisDataSources,
+) -> Option<(QueryFont, Coverage)> {
+    let selected = match_best_coverage_font(cluster, query, prefers_emoji, analysis_data_sources);
+    if selected
+        .as_ref()
+        .is_some_and(|(_, coverage)| coverage.is_complete())
+    {
+        return selected;
+    }
+
+    // Retry with the registered families covering the first character the selected font can't
+    // map, which may lie outside of the fallback families for the script.
+    let uncovered = {
+        let charmap = selected.as_ref().and_then(|(font, _)| font.charmap());
+        cluster
+            .chars()
+            .iter()
+            .filter(|ch| ch.contributes_to_shaping)
+            .map(|ch| ch.ch)
+            .find(|ch| {
+                !charmap
+                    .as_ref()
+                    .is_some_and(|charmap| charmap.map(*ch).is_some_and(|g| g != 0))
+            })
+    };
+    let Some(uncovered) = uncovered else {
+        return selected;
+    };
+    // Without families covering the character beyond those already queried, the result would be
+    // the same.
+    if !query.set_codepoint_fallback(Some(uncovered)) {
+        query.set_codepoint_fallback(None);
+        return selected;
+    }
+    let fallback = match_best_coverage_font(cluster, query, prefers_emoji, analysis_data_sources);
+    query.set_codepoint_fallback(None);
+    match (selected, fallback) {
+        (Some(selected), Some(fallback)) if fallback.1 <= selected.1 => Some(selected),
+        (selected, None) => selected,
+        (_, fallback) => fallback,
+    }
+}
+
+fn match_best_coverage_font(
+    cluster: &mut CharCluster,
+    query: &mut Query<'_>,
+    prefers_emoji: bool,
+    analysis_data_sources: &AnalysisDataSources,
+) -> Option<(QueryFont, Coverage)> {
+    let mut selected_font = None;
+    let mut best_coverage = Coverage::NONE;
+    query.matches_with(|font| {
+        let Some(charmap) = font.charmap() else {
+            return QueryStatus::Continue;
+        };
+
+        let coverage = cluster.calculate_coverage(
+            |ch| {
+                charmap
+                    .map(ch)
+                    .map(|g| {
+                        // Any non-zero value indicates the existence of a glyph.
+                        g != 0
+                    })
+                    .unwrap_or_default()
+            },
+            prefers_emoji,
+            analysis_data_sources,
+        );
+        if coverage > best_coverage {
+            selected_font = Some(font.clone());
+            best_coverage = coverage;
+
+            if coverage.is_complete() {
+                QueryStatus::Stop
+            } else {
+                QueryStatus::Continue
+            }
+        } else {
+            if selected_font.is_none() {
+                selected_font = Some(font.clone());
+            }
+            QueryStatus::Continue
+        }
+    });
+
+    selected_font.map(|font| (font, best_coverage))
+}
+
+/// Calculates the coverage of `cluster` by `font`.
+pub(crate) fn font_coverage(
+    cluster: &mut CharCluster,
+    font: &QueryFont,
+    prefers_emoji: bool,
+    analysis_data_sources: &AnalysisDataSources,
+) -> Coverage {
+    let Some(charmap) = font.charmap() else {
+        return Coverage::NONE;
+    };
+    cluster.calculate_coverage(
+        |ch| charmap.map(ch).is_some_and(|g| g != 0),
+        prefers_emoji,
+        analysis_data_sources,
+    )
+}
@@ -5,7 +5,10 @@ use alloc::borrow::Cow;
 
 pub use crate::setting::{FontFeature, FontVariation};
 pub use fontique::{FontStyle, FontWeight, FontWidth, GenericFamily};
-pub use parlance::{FontFamily, FontFamilyName};
+pub use parlance::{
+    BasePalette, ColorScheme, FontFamily, FontFamilyName, FontOpticalSizing, FontSizeAdjust,
+    FontSynthesis, FontVariantEmoji, PaletteOverride,
+};
 
 /// Font variation settings that can be supplied as a raw source string or a parsed slice.
 #[derive(Clone, PartialEq, Debug)]
@@ -74,3 +77,37 @@ impl<'a, const N: usize> From<&'a [FontFeature; N]> for FontFeatures<'a> {
         Self::List(Cow::Borrowed(&value[..]))
     }
 }
+
+/// The palette that the glyphs of color fonts are painted with.
+///
+/// In CSS, this corresponds to the `font-palette` property, where a palette other than the
+/// font's own ones is defined with `@font-palette-values`:
+/// <https://www.w3.org/TR/css-fonts-4/#font-palette-prop>.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct FontPalette<'a> {
+    /// The palette of the font to start from.
+    pub base: BasePalette,
+    /// Colors that replace entries of the base palette.
+    ///
+    /// Where several overrides replace the same entry, the last one applies.
+    pub overrides: Cow<'a, [PaletteOverride]>,
+}
+
+impl<'a> FontPalette<'a> {
+    /// Creates a palette from `base` with the given overrides.
+    pub fn new(base: BasePalette, overrides: impl Into<Cow<'a, [PaletteOverride]>>) -> Self {
+        Self {
+            base,
+            overrides: overrides.into(),
+        }
+    }
+}
+
+impl From<BasePalette> for FontPalette<'_> {
+    fn from(base: BasePalette) -> Self {
+        Self {
+            base,
+            overrides: Cow::Borrowed(&[]),
+        }
+    }
+}
@@ -5,20 +5,28 @@
 
 mod brush;
 mod font;
+mod paragraph;
 mod styleset;
 
 use alloc::borrow::Cow;
 
 pub use brush::*;
 pub use font::{
-    FontFamily, FontFamilyName, FontFeature, FontFeatures, FontStyle, FontVariation,
-    FontVariations, FontWeight, FontWidth, GenericFamily,
+    BasePalette, ColorScheme, FontFamily, FontFamilyName, FontFeature, FontFeatures,
+    FontOpticalSizing, FontPalette, FontSizeAdjust, FontStyle, FontSynthesis, FontVariantEmoji,
+    FontVariation, FontVariations, FontWeight, FontWidth, GenericFamily, PaletteOverride,
 };
 pub use fontique::Language;
-pub use parlance::{OverflowWrap, TextWrapMode, WordBreak};
+pub use paragraph::ParagraphStyle;
+pub use parlance::{
+    HangingPunctuation, LineBreak, OverflowWrap, TextAutospace, TextSpacingTrim, TextWrapMode,
+    WordBreak,
+};
 pub use styleset::StyleSet;
 
+use crate::layout::{Decoration, Style};
 use crate::util::nearly_eq;
+use fontique::NamedInstance;
 
 #[derive(Debug, Clone, Copy)]
 pub enum WhiteSpaceCollapse {
@@ -29,6 +37,7 @@ pub enum WhiteSpaceCollapse {
 /// The height that this text takes up. The default is `MetricsRelative(1.0)`, which is the given
 /// font's preferred line height.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LineHeight {
     /// The line's height is a multiple of the "line height" defined by the font's metrics--the sum
     /// of the ascender height, descender height, and line gap / leading.
@@ -85,6 +94,16 @@ pub enum StyleProperty<'a, B: Brush> {
     FontVariations(FontVariations<'a>),
     /// Font feature settings.
     FontFeatures(FontFeatures<'a>),
+    /// Text or emoji presentation of emoji.
+    FontVariantEmoji(FontVariantEmoji),
+    /// Automatic setting of the `opsz` axis from the font size.
+    FontOpticalSizing(FontOpticalSizing),
+    /// Adjustment of each font's size to a common x-height or cap height.
+    FontSizeAdjust(FontSizeAdjust),
+    /// Kinds of typefaces that may be synthesized when a font family lacks them.
+    FontSynthesis(FontSynthesis),
+    /// Palette that the glyphs of color fonts are painted with.
+    FontPalette(FontPalette<'a>),
     /// Locale.
     Locale(Option<Language>),
     /// Brush for rendering text.
@@ -117,6 +136,121 @@ pub enum StyleProperty<'a, B: Brush> {
     OverflowWrap(OverflowWrap),
     /// Control over non-"emergency" line-breaking.
     TextWrapMode(TextWrapMode),
+    /// Strictness of line-breaking rules around CJK punctuation and small kana.
+    LineBreak(LineBreak),
+    /// Control over punctuation hanging outside the line box.
+    HangingPunctuation(HangingPunctuation),
+    /// Trimming of the blank half of fullwidth CJK punctuation.
+    TextSpacingTrim(TextSpacingTrim),
+    /// Automatic spacing between ideographic and non-ideographic text.
+    TextAutospace(TextAutospace),
+}
+
+/// Style properties that only change how text is painted, so can be changed on a built
+/// [`Layout`](crate::Layout) with [`Layout::restyle`](crate::Layout::restyle).
+///
+/// These correspond to the [`StyleProperty`] variants of the same names.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PaintProperty<B: Brush> {
+    /// Brush for rendering text.
+    ///
+    /// Decorations without a brush of their own are drawn with this brush too.
+    Brush(B),
+    /// Underline decoration.
+    ///
+    /// An underline that is turned on is drawn with its brush, or the text's brush if it has
+    /// none, and the offset and size from the metrics of its run.
+    Underline(bool),
+    /// Brush for rendering the underline decoration, or `None` for the text's brush.
+    UnderlineBrush(Option<B>),
+    /// Strikethrough decoration.
+    ///
+    /// A strikethrough that is turned on is drawn with its brush, or the text's brush if it has
+    /// none, and the offset and size from the metrics of its run.
+    Strikethrough(bool),
+    /// Brush for rendering the strikethrough decoration, or `None` for the text's brush.
+    StrikethroughBrush(Option<B>),
+}
+
+impl<B: Brush> PaintProperty<B> {
+    /// Applies the property to a resolved style.
+    pub(crate) fn apply(&self, style: &mut Style<B>) {
+        // Sets the brush of a decoration that is turned on to its own brush, if it has one, or
+        // the text's brush.
+        let paint = |decoration: &mut Option<Decoration<B>>, own: Option<&B>, text: &B| {
+            if let Some(decoration) = decoration {
+                decoration.brush = own.cloned().unwrap_or_else(|| text.clone());
+            }
+        };
+        // Turns a decoration on with the offset and size resolved for the style, or off.
+        let enable = |decoration: &mut Option<Decoration<B>>,
+                      on: bool,
+                      offset: Option<f32>,
+                      size: Option<f32>| {
+            if !on {
+                *decoration = None;
+            } else if decoration.is_none() {
+                *decoration = Some(Decoration {
+                    brush: B::default(),
+                    offset,
+                    size,
+                });
+            }
+        };
+        match self {
+            Self::Brush(brush) => {
+                style.brush = brush.clone();
+                if style.underline_brush.is_none() {
+                    paint(&mut style.underline, None, &style.brush);
+                }
+                if style.strikethrough_brush.is_none() {
+                    paint(&mut style.strikethrough, None, &style.brush);
+                }
+            }
+            Self::Underline(on) => {
+                enable(
+                    &mut style.underline,
+                    *on,
+                    style.underline_offset,
+                    style.underline_size,
+                );
+                paint(
+                    &mut style.underline,
+                    style.underline_brush.as_ref(),
+                    &style.brush,
+                );
+            }
+            Self::UnderlineBrush(brush) => {
+                style.underline_brush = brush.clone();
+                paint(
+                    &mut style.underline,
+                    style.underline_brush.as_ref(),
+                    &style.brush,
+                );
+            }
+            Self::Strikethrough(on) => {
+                enable(
+                    &mut style.strikethrough,
+                    *on,
+                    style.strikethrough_offset,
+                    style.strikethrough_size,
+                );
+                paint(
+                    &mut style.strikethrough,
+                    style.strikethrough_brush.as_ref(),
+                    &style.brush,
+                );
+            }
+            Self::StrikethroughBrush(brush) => {
+                style.strikethrough_brush = brush.clone();
+                paint(
+                    &mut style.strikethrough,
+                    style.strikethrough_brush.as_ref(),
+                    &style.brush,
+                );
+            }
+        }
+    }
 }
 
 /// Unresolved styles.
@@ -136,6 +270,16 @@ pub struct TextStyle<'family, 'settings, B: Brush> {
     pub font_variations: FontVariations<'settings>,
     /// Font feature settings.
     pub font_features: FontFeatures<'settings>,
+    /// Text or emoji presentation of emoji.
+    pub font_variant_emoji: FontVariantEmoji,
+    /// Automatic setting of the `opsz` axis from the font size.
+    pub font_optical_sizing: FontOpticalSizing,
+    /// Adjustment of each font's size to a common x-height or cap height.
+    pub font_size_adjust: FontSizeAdjust,
+    /// Kinds of typefaces that may be synthesized when a font family lacks them.
+    pub font_synthesis: FontSynthesis,
+    /// Palette that the glyphs of color fonts are painted with.
+    pub font_palette: FontPalette<'settings>,
     /// Locale.
     pub locale: Option<Language>,
     /// Brush for rendering text.
@@ -168,6 +312,14 @@ pub struct TextStyle<'family, 'settings, B: Brush> {
     pub overflow_wrap: OverflowWrap,
     /// Control over non-"emergency" line-breaking.
     pub text_wrap_mode: TextWrapMode,
+    /// Strictness of line-breaking rules around CJK punctuation and small kana.
+    pub line_break: LineBreak,
+    /// Control over punctuation hanging outside the line box.
+    pub hanging_punctuation: HangingPunctuation,
+    /// Trimming of the blank half of fullwidth CJK punctuation.
+    pub text_spacing_trim: TextSpacingTrim,
+    /// Automatic spacing between ideographic and non-ideographic text.
+    pub text_autospace: TextAutospace,
 }
 
 impl<B: Brush> Default for TextStyle<'static, 'static, B> {
@@ -180,6 +332,11 @@ impl<B: Brush> Default for TextStyle<'static, 'static, B> {
             font_weight: FontWeight::default(),
             font_variations: FontVariations::empty(),
             font_features: FontFeatures::empty(),
+            font_variant_emoji: FontVariantEmoji::default(),
+            font_optical_sizing: FontOpticalSizing::default(),
+            font_size_adjust: FontSizeAdjust::default(),
+            font_synthesis: FontSynthesis::default(),
+            font_palette: FontPalette::default(),
             locale: None,
             brush: B::default(),
             has_underline: false,
@@ -196,7 +353,32 @@ impl<B: Brush> Default for TextStyle<'static, 'static, B> {
             word_break: WordBreak::default(),
             overflow_wrap: OverflowWrap::default(),
             text_wrap_mode: TextWrapMode::default(),
+            line_break: LineBreak::default(),
+            hanging_punctuation: HangingPunctuation::default(),
+            text_spacing_trim: TextSpacingTrim::default(),
+            text_autospace: TextAutospace::default(),
+        }
+    }
+}
+
+impl<B: Brush> TextStyle<'_, '_, B> {
+    /// Sets the font attributes and variations that select a named instance
+    /// of a variable font.
+    ///
+    /// The weight, width and style are set where the font has the axes for
+    /// them, and the instance's coordinates on all axes are set as the font
+    /// variations.
+    pub fn set_named_instance(&mut self, instance: &NamedInstance) {
+        if let Some(weight) = instance.weight() {
+            self.font_weight = weight;
+        }
+        if let Some(width) = instance.width() {
+            self.font_width = width;
         }
+        if let Some(style) = instance.style() {
+            self.font_style = style;
+        }
+        self.font_variations = FontVariations::List(Cow::Owned(instance.coords.clone()));
     }
 }
 
@@ -260,6 +442,36 @@ impl<B: Brush> From<FontWeight> for StyleProperty<'_, B> {
     }
 }
 
+impl<B: Brush> From<FontVariantEmoji> for StyleProperty<'_, B> {
+    fn from(value: FontVariantEmoji) -> Self {
+        StyleProperty::FontVariantEmoji(value)
+    }
+}
+
+impl<B: Brush> From<FontOpticalSizing> for StyleProperty<'_, B> {
+    fn from(value: FontOpticalSizing) -> Self {
+        StyleProperty::FontOpticalSizing(value)
+    }
+}
+
+impl<B: Brush> From<FontSizeAdjust> for StyleProperty<'_, B> {
+    fn from(value: FontSizeAdjust) -> Self {
+        StyleProperty::FontSizeAdjust(value)
+    }
+}
+
+impl<B: Brush> From<FontSynthesis> for StyleProperty<'_, B> {
+    fn from(value: FontSynthesis) -> Self {
+        StyleProperty::FontSynthesis(value)
+    }
+}
+
+impl<'a, B: Brush> From<FontPalette<'a>> for StyleProperty<'a, B> {
+    fn from(value: FontPalette<'a>) -> Self {
+        StyleProperty::FontPalette(value)
+    }
+}
+
 impl<B: Brush> From<WordBreak> for StyleProperty<'_, B> {
     fn from(value: WordBreak) -> Self {
         StyleProperty::WordBreak(value)
@@ -277,3 +489,27 @@ impl<B: Brush> From<TextWrapMode> for StyleProperty<'_, B> {
         StyleProperty::TextWrapMode(value)
     }
 }
+
+impl<B: Brush> From<LineBreak> for StyleProperty<'_, B> {
+    fn from(value: LineBreak) -> Self {
+        StyleProperty::LineBreak(value)
+    }
+}
+
+impl<B: Brush> From<HangingPunctuation> for StyleProperty<'_, B> {
+    fn from(value: HangingPunctuation) -> Self {
+        StyleProperty::HangingPunctuation(value)
+    }
+}
+
+impl<B: Brush> From<TextSpacingTrim> for StyleProperty<'_, B> {
+    fn from(value: TextSpacingTrim) -> Self {
+        StyleProperty::TextSpacingTrim(value)
+    }
+}
+
+impl<B: Brush> From<TextAutospace> for StyleProperty<'_, B> {
+    fn from(value: TextAutospace) -> Self {
+        StyleProperty::TextAutospace(value)
+    }
+}
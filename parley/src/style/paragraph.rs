@@ -0,0 +1,89 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Paragraph-level styling.
+
+use crate::Alignment;
+
+/// Style properties that apply to whole paragraphs.
+///
+/// A paragraph is a run of text ended by a hard line break (or the end of the text). Paragraph
+/// styles are set through the builders, e.g. with [`RangedBuilder::push_paragraph_style`], and
+/// are applied by line breaking and [`Layout::align`].
+///
+/// Lengths are in the same units as other style properties, so are multiplied by the layout's
+/// scale.
+///
+/// [`RangedBuilder::push_paragraph_style`]: crate::RangedBuilder::push_paragraph_style
+/// [`Layout::align`]: crate::Layout::align
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParagraphStyle {
+    /// The alignment of the paragraph's lines.
+    ///
+    /// If `None`, the alignment passed to [`Layout::align`](crate::Layout::align) is used.
+    pub alignment: Option<Alignment>,
+    /// The margin on the start edge of every line of the paragraph.
+    pub start_indent: f32,
+    /// The margin on the end edge of every line of the paragraph.
+    pub end_indent: f32,
+    /// The additional margin on the start edge of the first line of the paragraph.
+    ///
+    /// Negative values create a hanging first line. This is applied on top of the layout's
+    /// [text indent](crate::Layout::set_text_indent).
+    pub first_line_indent: f32,
+    /// The space above the paragraph.
+    ///
+    /// This is not applied to the first paragraph of the layout.
+    pub space_before: f32,
+    /// The space below the paragraph.
+    ///
+    /// This is not applied to the last paragraph of the layout. Spacing between two paragraphs
+    /// is the sum of the first's space after and the second's space before.
+    pub space_after: f32,
+    /// The minimum number of lines of the paragraph to keep at the end of a fragment, such as a
+    /// page or column, when the paragraph is split across fragments.
+    ///
+    /// See [`Layout::fragment_end`](crate::Layout::fragment_end).
+    pub orphans: u32,
+    /// The minimum number of lines of the paragraph to move to the start of the next fragment
+    /// when the paragraph is split across fragments.
+    ///
+    /// See [`Layout::fragment_end`](crate::Layout::fragment_end).
+    pub widows: u32,
+    /// Whether to avoid a fragment break between the last line of this paragraph and the first
+    /// line of the next.
+    ///
+    /// See [`Layout::fragment_end`](crate::Layout::fragment_end).
+    pub keep_with_next: bool,
+}
+
+impl Default for ParagraphStyle {
+    fn default() -> Self {
+        Self {
+            alignment: None,
+            start_indent: 0.,
+            end_indent: 0.,
+            first_line_indent: 0.,
+            space_before: 0.,
+            space_after: 0.,
+            orphans: 1,
+            widows: 1,
+            keep_with_next: false,
+        }
+    }
+}
+
+impl ParagraphStyle {
+    /// Returns the style with its lengths multiplied by `scale`.
+    pub(crate) fn scaled(self, scale: f32) -> Self {
+        Self {
+            start_indent: self.start_indent * scale,
+            end_indent: self.end_indent * scale,
+            first_line_indent: self.first_line_indent * scale,
+            space_before: self.space_before * scale,
+            space_after: self.space_after * scale,
+            ..self
+        }
+    }
+}
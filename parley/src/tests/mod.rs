@@ -3,4 +3,8 @@
 
 mod test_analysis;
 mod test_builders;
+mod test_restyle;
+#[cfg(feature = "serde")]
+mod test_snapshot;
+mod test_threads;
 mod utils;
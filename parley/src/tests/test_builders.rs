@@ -11,16 +11,18 @@ use peniko::{Blob, color::palette};
 
 use super::utils::{ColorBrush, asserts::assert_eq_layout_data};
 use crate::{
-    BaseDirection, FontContext, FontFamily, FontFeatures, FontVariations, Layout, LayoutContext,
-    LineHeight, OverflowWrap, RangedBuilder, StyleProperty, StyleRunBuilder, TextStyle,
-    TextWrapMode, TreeBuilder, WordBreak,
+    BaseDirection, BasePalette, FontContext, FontFamily, FontFeatures, FontOpticalSizing,
+    FontPalette, FontSizeAdjust, FontSynthesis, FontVariantEmoji, FontVariations,
+    HangingPunctuation, Layout, LayoutContext, LineBreak, LineHeight, OverflowWrap, RangedBuilder,
+    StyleProperty, StyleRunBuilder, TextAutospace, TextSpacingTrim, TextStyle, TextWrapMode,
+    TreeBuilder, WordBreak,
 };
 
 // TODO: `FONT_FAMILY_LIST`, `load_fonts`, and `create_font_context` are
 // duplicated between this crate and `parley_test`. We can't move the builder
 // tests into `parley_test` because they use private APIs, but should eventually
 // figure out some way to reduce the duplication.
-const FONT_FAMILY_LIST: &[FontFamilyName<'_>] = &[
+pub(crate) const FONT_FAMILY_LIST: &[FontFamilyName<'_>] = &[
     FontFamilyName::Named(Cow::Borrowed("Roboto")),
     FontFamilyName::Named(Cow::Borrowed("Noto Kufi Arabic")),
 ];
@@ -51,10 +53,11 @@ pub(crate) fn load_fonts(
     Ok(())
 }
 
-fn create_font_context() -> FontContext {
+pub(crate) fn create_font_context() -> FontContext {
     let mut collection = Collection::new(CollectionOptions {
         shared: false,
         system_fonts: false,
+        ..CollectionOptions::default()
     });
     load_fonts(&mut collection, parley_dev::font_dirs()).unwrap();
     for font in FONT_FAMILY_LIST {
@@ -265,6 +268,11 @@ fn create_root_style() -> TextStyle<'static, 'static, ColorBrush> {
         font_weight: FontWeight::BOLD,
         font_variations: FontVariations::empty(), // TODO: Set a non-default value
         font_features: FontFeatures::empty(),     // TODO: Set a non-default value
+        font_variant_emoji: FontVariantEmoji::Unicode,
+        font_optical_sizing: FontOpticalSizing::None,
+        font_size_adjust: FontSizeAdjust::ExHeight(0.5),
+        font_synthesis: FontSynthesis::NONE,
+        font_palette: FontPalette::from(BasePalette::Dark),
         locale: Some("en-US".parse().unwrap()),
         brush: ColorBrush::new(palette::css::GREEN),
         has_underline: true,
@@ -281,6 +289,10 @@ fn create_root_style() -> TextStyle<'static, 'static, ColorBrush> {
         word_break: WordBreak::BreakAll,
         overflow_wrap: OverflowWrap::Anywhere,
         text_wrap_mode: TextWrapMode::Wrap,
+        line_break: LineBreak::Strict,
+        hanging_punctuation: HangingPunctuation::AllowEnd,
+        text_spacing_trim: TextSpacingTrim::TrimStart,
+        text_autospace: TextAutospace::Normal,
     }
 }
 
@@ -295,6 +307,11 @@ fn set_root_style(rb: &mut RangedBuilder<'_, ColorBrush>) {
     rb.push_default(StyleProperty::FontWeight(FontWeight::BOLD));
     rb.push_default(FontVariations::empty());
     rb.push_default(FontFeatures::empty());
+    rb.push_default(StyleProperty::FontVariantEmoji(FontVariantEmoji::Unicode));
+    rb.push_default(StyleProperty::FontOpticalSizing(FontOpticalSizing::None));
+    rb.push_default(StyleProperty::FontSizeAdjust(FontSizeAdjust::ExHeight(0.5)));
+    rb.push_default(StyleProperty::FontSynthesis(FontSynthesis::NONE));
+    rb.push_default(FontPalette::from(BasePalette::Dark));
     rb.push_default(StyleProperty::Locale(Some("en-US".parse().unwrap())));
     rb.push_default(StyleProperty::Brush(ColorBrush::new(palette::css::GREEN)));
     rb.push_default(StyleProperty::Underline(true));
@@ -314,6 +331,12 @@ fn set_root_style(rb: &mut RangedBuilder<'_, ColorBrush>) {
     rb.push_default(StyleProperty::LetterSpacing(1.5));
     rb.push_default(StyleProperty::WordBreak(WordBreak::BreakAll));
     rb.push_default(StyleProperty::OverflowWrap(OverflowWrap::Anywhere));
+    rb.push_default(StyleProperty::LineBreak(LineBreak::Strict));
+    rb.push_default(StyleProperty::HangingPunctuation(
+        HangingPunctuation::AllowEnd,
+    ));
+    rb.push_default(StyleProperty::TextSpacingTrim(TextSpacingTrim::TrimStart));
+    rb.push_default(StyleProperty::TextAutospace(TextAutospace::Normal));
 }
 
 /// Test that all the builders have the same default behavior.
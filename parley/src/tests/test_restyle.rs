@@ -0,0 +1,126 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Test that restyling a built layout matches building it with the new styles.
+
+use std::format;
+
+use super::test_builders::{FONT_FAMILY_LIST, create_font_context};
+use super::utils::asserts::assert_eq_layout_data;
+use crate::{FontFamily, Layout, LayoutContext, PaintProperty, StyleProperty};
+
+type Brush = [u8; 4];
+
+const TEXT: &str = "Some office text that wraps onto more than one line.";
+const RED: Brush = [255, 0, 0, 255];
+const BLUE: Brush = [0, 0, 255, 255];
+
+fn build_layout(
+    defaults: &[StyleProperty<'_, Brush>],
+    styles: &[StyleProperty<'_, Brush>],
+) -> Layout<Brush> {
+    let mut fcx = create_font_context();
+    let mut lcx = LayoutContext::<Brush>::new();
+    let mut builder = lcx.ranged_builder(&mut fcx, TEXT, 1.0, true);
+    builder.push_default(FontFamily::from(FONT_FAMILY_LIST));
+    builder.push_default(StyleProperty::FontSize(16.0));
+    for style in defaults {
+        builder.push_default(style.clone());
+    }
+    for style in styles {
+        // This starts inside "office", where a ligature of "ffi" keeps the style of its first
+        // character.
+        builder.push(style.clone(), 7..20);
+    }
+    let mut layout = builder.build(TEXT);
+    layout.break_all_lines(Some(150.0));
+    layout
+}
+
+#[test]
+fn restyle_matches_build() {
+    // The styles of the whole text, the styles of the restyled range, and the properties that
+    // restyle it.
+    type Case<'a> = (
+        &'a [StyleProperty<'a, Brush>],
+        &'a [StyleProperty<'a, Brush>],
+        &'a [PaintProperty<Brush>],
+    );
+    let cases: &[Case<'_>] = &[
+        (
+            &[],
+            &[StyleProperty::Brush(RED)],
+            &[PaintProperty::Brush(RED)],
+        ),
+        (
+            &[],
+            &[StyleProperty::Underline(true)],
+            &[PaintProperty::Underline(true)],
+        ),
+        (
+            &[],
+            &[StyleProperty::Strikethrough(true)],
+            &[PaintProperty::Strikethrough(true)],
+        ),
+        (
+            &[],
+            &[StyleProperty::Underline(true), StyleProperty::Brush(RED)],
+            &[PaintProperty::Underline(true), PaintProperty::Brush(RED)],
+        ),
+        (
+            &[],
+            &[
+                StyleProperty::Strikethrough(true),
+                StyleProperty::StrikethroughBrush(Some(BLUE)),
+                StyleProperty::Brush(RED),
+            ],
+            &[
+                PaintProperty::Strikethrough(true),
+                PaintProperty::StrikethroughBrush(Some(BLUE)),
+                PaintProperty::Brush(RED),
+            ],
+        ),
+        (
+            &[
+                StyleProperty::UnderlineOffset(Some(2.0)),
+                StyleProperty::UnderlineSize(Some(3.0)),
+            ],
+            &[StyleProperty::Underline(true)],
+            &[PaintProperty::Underline(true)],
+        ),
+        (
+            &[StyleProperty::StrikethroughOffset(Some(4.0))],
+            &[StyleProperty::Strikethrough(true)],
+            &[PaintProperty::Strikethrough(true)],
+        ),
+    ];
+    for (defaults, styles, properties) in cases {
+        let case = format!("{defaults:?} {properties:?}");
+        let expected = build_layout(defaults, styles);
+        let mut restyled = build_layout(defaults, &[]);
+        for property in *properties {
+            restyled.restyle(7..20, property.clone());
+        }
+        assert_eq_layout_data(&expected.data, &restyled.data, &case);
+    }
+}
+
+#[test]
+fn restyle_reuses_styles() {
+    let mut layout = build_layout(&[], &[]);
+    layout.restyle(7..20, PaintProperty::Underline(true));
+    layout.restyle(7..20, PaintProperty::Underline(false));
+    layout.restyle(7..20, PaintProperty::Underline(true));
+    assert_eq!(layout.styles().len(), 2, "equal styles should be reused");
+
+    layout.restyle(0..TEXT.len(), PaintProperty::Underline(false));
+    assert!(
+        layout
+            .data
+            .shaped_text
+            .characters()
+            .iter()
+            .all(|character| character.style_index == 0),
+        "all text should have its original style again"
+    );
+}
@@ -0,0 +1,105 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Test that layout snapshots restore the layouts they were made from.
+
+use std::format;
+use std::vec::Vec;
+
+use super::test_builders::{FONT_FAMILY_LIST, create_font_context};
+use super::utils::asserts::assert_eq_layout_data;
+use crate::{
+    Alignment, AlignmentOptions, FontData, FontDataId, FontFamily, FontWeight, Layout,
+    LayoutContext, LayoutSnapshot, SnapshotError, StyleProperty,
+};
+
+type Brush = [u8; 4];
+
+fn build_layout() -> Layout<Brush> {
+    let text =
+        "Some bold text with a line that wraps, and \u{0645}\u{0631}\u{062D}\u{0628}\u{0627}.";
+    let mut fcx = create_font_context();
+    let mut lcx = LayoutContext::<Brush>::new();
+    let mut builder = lcx.ranged_builder(&mut fcx, text, 1.0, true);
+    builder.push_default(FontFamily::from(FONT_FAMILY_LIST));
+    builder.push_default(StyleProperty::FontSize(16.0));
+    builder.push(StyleProperty::FontWeight(FontWeight::BOLD), 5..9);
+    builder.push(StyleProperty::Brush([255, 0, 0, 255]), 10..14);
+    builder.push(StyleProperty::Underline(true), 10..14);
+    let mut layout = builder.build(text);
+    layout.break_all_lines(Some(150.0));
+    layout.align(Alignment::Center, AlignmentOptions::default());
+    layout
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let layout = build_layout();
+    let fonts: Vec<(FontDataId, FontData)> = layout
+        .data
+        .shaped_text
+        .fonts()
+        .iter()
+        .map(|font| (FontDataId::new(&font.font), font.font.clone()))
+        .collect();
+
+    let json = serde_json::to_string(&layout.snapshot()).unwrap();
+    let snapshot: LayoutSnapshot<Brush> = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot.version(), LayoutSnapshot::<Brush>::VERSION);
+    let restored = snapshot
+        .restore(|id| {
+            fonts
+                .iter()
+                .find(|(other, _)| *other == id)
+                .map(|(_, font)| font.clone())
+        })
+        .unwrap();
+
+    // Diagnostics and the font generation are not part of snapshots.
+    let mut expected = layout.data.clone();
+    expected.font_diagnostics.clear();
+    expected.font_generation = 0;
+    assert_eq_layout_data(&expected, &restored.data, "restored snapshot");
+}
+
+#[test]
+fn snapshot_missing_font() {
+    let snapshot = build_layout().snapshot();
+    let missing = snapshot.fonts().next().unwrap();
+    assert_eq!(
+        snapshot.restore(|_| None).unwrap_err(),
+        SnapshotError::MissingFont(missing)
+    );
+}
+
+#[test]
+fn snapshot_with_font_ids() {
+    let layout = build_layout();
+    let expected: Vec<FontDataId> = layout.snapshot().fonts().collect();
+
+    // Identifiers computed beforehand are used as is, and asked for once per font.
+    let mut calls = 0;
+    let snapshot = layout.snapshot_with(|font| {
+        calls += 1;
+        FontDataId::new(font)
+    });
+    assert_eq!(snapshot.fonts().collect::<Vec<_>>(), expected);
+    let mut distinct = expected.clone();
+    distinct.sort_by_key(|id| (id.hash, id.index));
+    distinct.dedup();
+    assert_eq!(calls, distinct.len());
+}
+
+#[test]
+fn snapshot_unsupported_version() {
+    // Snapshots of other versions may have a different layout, which is skipped.
+    let version = LayoutSnapshot::<Brush>::VERSION + 1;
+    let json = format!(r#"{{"version":{version},"fonts":{{"count":2}},"runs":[1,"two"]}}"#);
+    let snapshot: LayoutSnapshot<Brush> = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot.version(), version);
+    assert_eq!(snapshot.fonts().count(), 0);
+    assert_eq!(
+        snapshot.restore(|_| None).unwrap_err(),
+        SnapshotError::UnsupportedVersion(version)
+    );
+}
@@ -0,0 +1,178 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Test that layouts built on multiple threads are identical to layouts built on one.
+
+use std::{format, vec::Vec};
+
+use super::test_builders::{FONT_FAMILY_LIST, create_font_context};
+use super::utils::{ColorBrush, asserts::assert_eq_layout_data};
+use crate::{FontContext, FontFamily, Layout, LayoutContext, SharedShapePlanCache, StyleProperty};
+#[cfg(feature = "rayon")]
+use {
+    crate::{InlineBox, InlineBoxKind, VerticalAlign},
+    std::string::String,
+};
+
+const TEXTS: &[&str] = &[
+    "The quick brown fox jumps over the lazy dog.",
+    "Some text with a longer line that wraps onto several lines of the layout.",
+    "Mixed bidi text: Hello العربية world 123.",
+    "Ligatures like ffi and fl, and numbers like 0123456789.",
+];
+
+fn build_layouts(
+    fcx: &mut FontContext,
+    lcx: &mut LayoutContext<ColorBrush>,
+) -> Vec<Layout<ColorBrush>> {
+    TEXTS
+        .iter()
+        .map(|text| {
+            let mut builder = lcx.ranged_builder(fcx, text, 1.0, true);
+            builder.push_default(FontFamily::from(FONT_FAMILY_LIST));
+            builder.push_default(StyleProperty::FontSize(16.0));
+            let mut layout = builder.build(text);
+            layout.break_all_lines(Some(120.0));
+            layout
+        })
+        .collect()
+}
+
+#[test]
+fn layouts_match_across_threads() {
+    let mut fcx = create_font_context();
+    fcx.make_shared();
+    let plans = SharedShapePlanCache::default();
+
+    let expected = build_layouts(&mut fcx.clone(), &mut LayoutContext::new());
+
+    std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let mut fcx = fcx.clone();
+                let plans = plans.clone();
+                scope.spawn(move || {
+                    let mut lcx = LayoutContext::new();
+                    lcx.set_shared_shape_plan_cache(Some(plans));
+                    // Build twice, so that the second round uses cached plans.
+                    build_layouts(&mut fcx, &mut lcx);
+                    build_layouts(&mut fcx, &mut lcx)
+                })
+            })
+            .collect();
+        for (thread_idx, thread) in threads.into_iter().enumerate() {
+            let layouts = thread.join().unwrap();
+            for (text_idx, (expected, actual)) in expected.iter().zip(&layouts).enumerate() {
+                assert_eq_layout_data(
+                    &expected.data,
+                    &actual.data,
+                    &format!("thread {thread_idx}, text {text_idx}"),
+                );
+            }
+        }
+    });
+}
+
+#[test]
+fn cloned_contexts_keep_configuration() {
+    let mut fcx = create_font_context();
+    let plans = SharedShapePlanCache::default();
+    let mut lcx = LayoutContext::<ColorBrush>::new();
+    lcx.set_shared_shape_plan_cache(Some(plans.clone()));
+    lcx.set_word_cache_size(1024);
+    lcx.set_font_diagnostics(true);
+    lcx.set_parallel_shaping(true);
+
+    // A clone shares the shaping plan cache of the context...
+    let mut clone = lcx.clone();
+    assert!(clone.font_diagnostics);
+    assert!(clone.parallel_shaping);
+    let expected = build_layouts(&mut fcx, &mut clone);
+    assert!(
+        !plans.is_empty(),
+        "the clone should use the shared plan cache"
+    );
+
+    // ...and reuses the words the context cached.
+    build_layouts(&mut fcx, &mut lcx);
+    let cached = lcx.scx.cached_words();
+    assert!(cached > 0, "the shaped words should be cached");
+    let mut clone = lcx.clone();
+    let layouts = build_layouts(&mut fcx, &mut clone);
+    assert_eq!(clone.scx.cached_words(), cached);
+    for (text_idx, (expected, actual)) in expected.iter().zip(&layouts).enumerate() {
+        assert_eq_layout_data(&expected.data, &actual.data, &format!("text {text_idx}"));
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_shaping_matches_sequential() {
+    let mut fcx = create_font_context();
+    let paragraph = "Some text, \u{0645}\u{0631}\u{062D}\u{0628}\u{0627} \u{0628}\u{0643}, and more office text.\n";
+    let text: String = paragraph.repeat(100);
+    assert!(
+        text.len() >= 4096,
+        "the text should be long enough to be shaped in parallel"
+    );
+
+    let mut build = |parallel_shaping: bool| {
+        let mut lcx = LayoutContext::<ColorBrush>::new();
+        lcx.set_parallel_shaping(parallel_shaping);
+        let mut builder = lcx.ranged_builder(&mut fcx, &text, 1.0, true);
+        builder.push_default(FontFamily::from(FONT_FAMILY_LIST));
+        builder.push_default(StyleProperty::FontSize(16.0));
+        for (start, _) in text.match_indices("more") {
+            builder.push(StyleProperty::LetterSpacing(1.5), start..start + 4);
+            builder.push_inline_box(InlineBox {
+                id: 0,
+                kind: InlineBoxKind::InFlow,
+                index: start,
+                width: 10.0,
+                height: 10.0,
+                baseline: None,
+                vertical_align: VerticalAlign::Baseline,
+            });
+        }
+        let mut layout = builder.build(&text);
+        layout.break_all_lines(Some(200.0));
+        layout
+    };
+
+    let sequential = build(false);
+    let parallel = build(true);
+    assert_eq_layout_data(&sequential.data, &parallel.data, "parallel shaping");
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_shaping_uses_word_cache() {
+    let mut fcx = create_font_context();
+    let text: String = "Some words, and more office text.\n".repeat(150);
+    assert!(
+        text.len() >= 4096,
+        "the text should be long enough to be shaped in parallel"
+    );
+
+    let mut lcx = LayoutContext::<ColorBrush>::new();
+    lcx.set_parallel_shaping(true);
+    lcx.set_word_cache_size(1024);
+    let mut build = |lcx: &mut LayoutContext<ColorBrush>| {
+        let mut builder = lcx.ranged_builder(&mut fcx, &text, 1.0, true);
+        builder.push_default(FontFamily::from(FONT_FAMILY_LIST));
+        builder.push_default(StyleProperty::FontSize(16.0));
+        let mut layout = builder.build(&text);
+        layout.break_all_lines(Some(200.0));
+        layout
+    };
+
+    // The words shaped on other threads are kept by the context...
+    let first = build(&mut lcx);
+    let cached = lcx.scx.cached_words();
+    assert!(cached > 0, "the shaped words should be cached");
+
+    // ...and reused when the text is shaped again.
+    let second = build(&mut lcx);
+    assert_eq!(lcx.scx.cached_words(), cached);
+    assert_eq_layout_data(&first.data, &second.data, "cached words");
+}
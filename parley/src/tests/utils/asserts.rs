@@ -59,6 +59,10 @@ pub(crate) fn assert_eq_layout_data<B: Brush>(a: &LayoutData<B>, b: &LayoutData<
         a.inline_boxes, b.inline_boxes,
         "{case} inline_boxes mismatch"
     );
+    assert_eq!(
+        a.paragraph_styles, b.paragraph_styles,
+        "{case} paragraph_styles mismatch"
+    );
 
     // Output of shaping
     assert_eq!(a.runs, b.runs, "{case} runs mismatch");
@@ -78,6 +82,14 @@ pub(crate) fn assert_eq_layout_data<B: Brush>(a: &LayoutData<B>, b: &LayoutData<
         b.shaped_text.glyphs(),
         "{case} glyphs mismatch"
     );
+    assert_eq!(
+        a.boundary_spacing, b.boundary_spacing,
+        "{case} boundary_spacing mismatch"
+    );
+    assert_eq!(
+        a.font_diagnostics, b.font_diagnostics,
+        "{case} font_diagnostics mismatch"
+    );
 
     // Output of line breaking
     assert_eq!(a.lines, b.lines, "{case} lines mismatch");
@@ -85,8 +97,8 @@ pub(crate) fn assert_eq_layout_data<B: Brush>(a: &LayoutData<B>, b: &LayoutData<
 
     // Output of alignment
     assert_eq!(
-        a.is_aligned_justified, b.is_aligned_justified,
-        "{case} is_aligned_justified mismatch"
+        a.justified_alignment, b.justified_alignment,
+        "{case} justified_alignment mismatch"
     );
     assert_eq!(
         a.layout_max_advance, b.layout_max_advance,
@@ -31,6 +31,7 @@ pub fn system_fonts_init() -> Vec<Benchmark> {
                 let mut collection = Collection::new(CollectionOptions {
                     shared: false,
                     system_fonts: true,
+                    ..CollectionOptions::default()
                 });
 
                 // Force a read to prevent the optimizer from treating initialization as unused.
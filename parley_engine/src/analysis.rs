@@ -17,12 +17,14 @@ use icu_properties::props::{BidiMirroringGlyph, GeneralCategory, GraphemeCluster
 use icu_properties::{
     CodePointMapData, CodePointMapDataBorrowed, PropertyNamesShort, PropertyNamesShortBorrowed,
 };
-use icu_segmenter::options::{LineBreakOptions, LineBreakWordOption, WordBreakInvariantOptions};
+use icu_segmenter::options::{
+    LineBreakOptions, LineBreakStrictness, LineBreakWordOption, WordBreakInvariantOptions,
+};
 use icu_segmenter::{
     GraphemeClusterSegmenter, GraphemeClusterSegmenterBorrowed, LineSegmenter,
     LineSegmenterBorrowed, WordSegmenter, WordSegmenterBorrowed,
 };
-use parlance::{BaseDirection, BidiLevel, WordBreak};
+use parlance::{BaseDirection, BidiLevel, LineBreak, WordBreak};
 use parley_data::Properties;
 
 use crate::bidi;
@@ -115,24 +117,26 @@ impl AnalysisDataSources {
     }
 
     #[inline(always)]
-    fn line_segmenter(&self, word_break_strength: WordBreak) -> LineSegmenterBorrowed<'static> {
-        match word_break_strength {
-            WordBreak::Normal => {
-                let mut opt = LineBreakOptions::default();
-                opt.word_option = Some(LineBreakWordOption::Normal);
-                line_segmenter_impl(opt)
-            }
-            WordBreak::BreakAll => {
-                let mut opt = LineBreakOptions::default();
-                opt.word_option = Some(LineBreakWordOption::BreakAll);
-                line_segmenter_impl(opt)
-            }
-            WordBreak::KeepAll => {
-                let mut opt = LineBreakOptions::default();
-                opt.word_option = Some(LineBreakWordOption::KeepAll);
-                line_segmenter_impl(opt)
-            }
-        }
+    fn line_segmenter(
+        &self,
+        word_break_strength: WordBreak,
+        line_break_strictness: LineBreak,
+    ) -> LineSegmenterBorrowed<'static> {
+        let mut opt = LineBreakOptions::default();
+        opt.word_option = Some(match word_break_strength {
+            WordBreak::Normal => LineBreakWordOption::Normal,
+            WordBreak::BreakAll => LineBreakWordOption::BreakAll,
+            WordBreak::KeepAll => LineBreakWordOption::KeepAll,
+        });
+        // ICU4X uses strict rules when no strictness is given, which is what `auto` resolves to.
+        opt.strictness = match line_break_strictness {
+            LineBreak::Auto => None,
+            LineBreak::Loose => Some(LineBreakStrictness::Loose),
+            LineBreak::Normal => Some(LineBreakStrictness::Normal),
+            LineBreak::Strict => Some(LineBreakStrictness::Strict),
+            LineBreak::Anywhere => Some(LineBreakStrictness::Anywhere),
+        };
+        line_segmenter_impl(opt)
     }
 
     #[inline(always)]
@@ -288,6 +292,7 @@ impl CharInfo {
 
 /// Boundary type of a character or cluster.
 #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Boundary {
     /// Not a boundary.
@@ -306,23 +311,24 @@ pub(crate) fn analyze_text(
     options: &AnalysisOptions<'_>,
     analysis: &mut Analysis,
 ) {
-    /// Turns the sparse, sorted, non-overlapping `options.word_break` into a contiguous sequence of
-    /// `(range, word-break)` segments covering all of `text`.
+    /// Turns sparse, sorted, non-overlapping `(range, value)` overrides, such as
+    /// `options.word_break`, into a contiguous sequence of segments covering all of `text`.
     ///
-    /// Any region not covered by an override takes the default `WordBreak::Normal`.
-    struct DenseWordBreaks<'a> {
-        word_break: &'a [(Range<usize>, WordBreak)],
-        /// Index of the next word break to emit.
+    /// Any region not covered by an override takes the default value (`WordBreak::Normal` or
+    /// `LineBreak::Auto`).
+    struct DenseRanges<'a, T> {
+        ranges: &'a [(Range<usize>, T)],
+        /// Index of the next override to emit.
         next: usize,
         /// Start of the next segment to emit.
         cursor: usize,
         text_len: usize,
     }
 
-    impl<'a> DenseWordBreaks<'a> {
-        fn new(word_break: &'a [(Range<usize>, WordBreak)], text_len: usize) -> Self {
+    impl<'a, T> DenseRanges<'a, T> {
+        fn new(ranges: &'a [(Range<usize>, T)], text_len: usize) -> Self {
             Self {
-                word_break,
+                ranges,
                 next: 0,
                 cursor: 0,
                 text_len,
@@ -330,8 +336,8 @@ pub(crate) fn analyze_text(
         }
     }
 
-    impl Iterator for DenseWordBreaks<'_> {
-        type Item = (Range<usize>, WordBreak);
+    impl<T: Copy + Default> Iterator for DenseRanges<'_, T> {
+        type Item = (Range<usize>, T);
 
         fn next(&mut self) -> Option<Self::Item> {
             if self.cursor >= self.text_len {
@@ -340,51 +346,99 @@ pub(crate) fn analyze_text(
 
             // Ignore empty ranges.
             while self
-                .word_break
+                .ranges
                 .get(self.next)
                 .is_some_and(|(range, _)| range.is_empty())
             {
                 self.next += 1;
             }
 
-            match self.word_break.get(self.next) {
+            match self.ranges.get(self.next) {
                 // A gap before the next override: fill it with the default up to its start.
                 Some((range, _)) if self.cursor < range.start => {
                     let segment = self.cursor..range.start;
                     self.cursor = range.start;
-                    Some((segment, WordBreak::Normal))
+                    Some((segment, T::default()))
                 }
                 // At the next override: emit it.
-                Some((range, word_break)) => {
+                Some((range, value)) => {
                     self.cursor = range.end;
                     self.next += 1;
-                    Some((range.start..range.end, *word_break))
+                    Some((range.start..range.end, *value))
                 }
                 // No overrides remain: fill the default to the end.
                 None => {
                     let segment = self.cursor..self.text_len;
                     self.cursor = self.text_len;
-                    Some((segment, WordBreak::Normal))
+                    Some((segment, T::default()))
                 }
             }
         }
     }
 
+    /// Intersects the dense word break and line break segments into segments carrying both.
+    struct MergedSegments<'a> {
+        word_break: DenseRanges<'a, WordBreak>,
+        line_break: DenseRanges<'a, LineBreak>,
+        current_word_break: Option<(Range<usize>, WordBreak)>,
+        current_line_break: Option<(Range<usize>, LineBreak)>,
+    }
+
+    impl<'a> MergedSegments<'a> {
+        fn new(options: &AnalysisOptions<'a>, text_len: usize) -> Self {
+            let mut word_break = DenseRanges::new(options.word_break, text_len);
+            let mut line_break = DenseRanges::new(options.line_break, text_len);
+            Self {
+                current_word_break: word_break.next(),
+                current_line_break: line_break.next(),
+                word_break,
+                line_break,
+            }
+        }
+    }
+
+    impl Iterator for MergedSegments<'_> {
+        type Item = (Range<usize>, BreakConfig);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let (word_range, word_break) = self.current_word_break.clone()?;
+            let (line_range, line_break) = self.current_line_break.clone()?;
+            // Both sequences cover the text contiguously, so their current segments start at the
+            // same position.
+            let start = word_range.start;
+            let end = word_range.end.min(line_range.end);
+            self.current_word_break = if word_range.end == end {
+                self.word_break.next()
+            } else {
+                Some((end..word_range.end, word_break))
+            };
+            self.current_line_break = if line_range.end == end {
+                self.line_break.next()
+            } else {
+                Some((end..line_range.end, line_break))
+            };
+            Some((start..end, (word_break, line_break)))
+        }
+    }
+
+    /// The options that select a line segmenter.
+    type BreakConfig = (WordBreak, LineBreak);
+
     struct WordBreakSegmentIter<'a, I: Iterator> {
         text: &'a str,
         segments: I,
         char_indices: core::str::CharIndices<'a>,
         current_char: (usize, char),
         building_range_start: usize,
-        previous_word_break_style: WordBreak,
+        previous_word_break_style: BreakConfig,
         done: bool,
     }
 
     impl<'a, I> WordBreakSegmentIter<'a, I>
     where
-        I: Iterator<Item = (Range<usize>, WordBreak)>,
+        I: Iterator<Item = (Range<usize>, BreakConfig)>,
     {
-        fn new(text: &'a str, segments: I, first_segment: (Range<usize>, WordBreak)) -> Self {
+        fn new(text: &'a str, segments: I, first_segment: (Range<usize>, BreakConfig)) -> Self {
             let mut char_indices = text.char_indices();
             let current_char_len = char_indices.next().unwrap();
 
@@ -402,9 +456,9 @@ pub(crate) fn analyze_text(
 
     impl<'a, I> Iterator for WordBreakSegmentIter<'a, I>
     where
-        I: Iterator<Item = (Range<usize>, WordBreak)>,
+        I: Iterator<Item = (Range<usize>, BreakConfig)>,
     {
-        type Item = (&'a str, WordBreak, bool);
+        type Item = (&'a str, BreakConfig, bool);
 
         fn next(&mut self) -> Option<Self::Item> {
             if self.done {
@@ -460,7 +514,7 @@ pub(crate) fn analyze_text(
     //
     // This breaks text into sequences with similar line boundary config (part of style
     // information). If this config is consistent for all text, we use a fast path through this.
-    let mut segments = DenseWordBreaks::new(options.word_break, text.len());
+    let mut segments = MergedSegments::new(options, text.len());
     // `text` is non-empty (checked above), so there is always at least one segment.
     let first_segment = segments.next().unwrap();
     let contiguous_word_break_substrings = WordBreakSegmentIter::new(text, segments, first_segment);
@@ -470,13 +524,13 @@ pub(crate) fn analyze_text(
 
     let data_sources = AnalysisDataSources::new();
 
-    for (substring_index, (substring, word_break_strength, last)) in
+    for (substring_index, (substring, (word_break_strength, line_break_strictness), last)) in
         contiguous_word_break_substrings.enumerate()
     {
-        // Fast path for text with a single word-break option.
+        // Fast path for text with a single line-break configuration.
         if substring_index == 0 && last {
             let mut lb_iter = data_sources
-                .line_segmenter(word_break_strength)
+                .line_segmenter(word_break_strength, line_break_strictness)
                 .segment_str(substring);
 
             let _first = lb_iter.next();
@@ -498,7 +552,7 @@ pub(crate) fn analyze_text(
         }
 
         let line_boundaries_iter = data_sources
-            .line_segmenter(word_break_strength)
+            .line_segmenter(word_break_strength, line_break_strictness)
             .segment_str(substring);
 
         let mut substring_chars = substring.chars();
@@ -5,7 +5,7 @@
 
 use core::ops::Range;
 
-use parlance::{BaseDirection, WordBreak};
+use parlance::{BaseDirection, LineBreak, WordBreak};
 
 use crate::{bidi::BidiResolver, break_overrides::LineBreakOverrideFn};
 
@@ -52,6 +52,12 @@ pub struct AnalysisOptions<'a> {
     /// the text. Empty ranges are ignored. Gaps use [`WordBreak::Normal`].
     pub word_break: &'a [(Range<usize>, WordBreak)],
 
+    /// Line break strictness for ranges of the source text.
+    ///
+    /// This selects how strictly line breaks around CJK punctuation and small kana are forbidden
+    /// (kinsoku shori). Ranges follow the same rules as `word_break`. Gaps use [`LineBreak::Auto`].
+    pub line_break: &'a [(Range<usize>, LineBreak)],
+
     /// The callback which will be called as a first provider of line breaking decisions.
     ///
     /// See [`LineBreakOverrideFn`] for more details.
@@ -63,16 +69,19 @@ impl core::fmt::Debug for AnalysisOptions<'_> {
         f.debug_struct("AnalysisOptions")
             .field("base_direction", &self.base_direction)
             .field("word_break", &self.word_break)
+            .field("line_break", &self.line_break)
             .finish_non_exhaustive()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use parlance::BidiLevel;
+    use alloc::vec::Vec;
+
+    use parlance::{BidiLevel, LineBreak};
 
     use super::{AnalysisOptions, Analyzer};
-    use crate::{Analysis, BaseDirection};
+    use crate::{Analysis, BaseDirection, Boundary};
 
     fn analyze(text: &str, base_direction: BaseDirection) -> Analysis {
         let mut analyzer = Analyzer::new();
@@ -128,6 +137,57 @@ mod tests {
         assert!(analysis.bidi_levels().iter().all(|level| level.is_ltr()));
     }
 
+    fn line_boundaries(
+        text: &str,
+        line_break: &[(core::ops::Range<usize>, LineBreak)],
+    ) -> Vec<bool> {
+        let mut analyzer = Analyzer::new();
+        let mut analysis = Analysis::new();
+        analyzer.analyze(
+            text,
+            &AnalysisOptions {
+                line_break,
+                ..AnalysisOptions::default()
+            },
+            &mut analysis,
+        );
+        analysis
+            .char_info()
+            .iter()
+            .map(|info| info.boundary == Boundary::Line)
+            .collect()
+    }
+
+    #[test]
+    fn line_break_strictness_controls_breaks_before_small_kana() {
+        // Small kana may only start a line with the `normal` and `loose` rules.
+        let text = "あぁあぁ";
+        let strict = line_boundaries(text, &[]);
+        assert_eq!(strict, [false, false, true, false]);
+        assert_eq!(
+            line_boundaries(text, &[(0..text.len(), LineBreak::Strict)]),
+            strict
+        );
+        assert_eq!(
+            line_boundaries(text, &[(0..text.len(), LineBreak::Normal)]),
+            [false, true, true, true]
+        );
+
+        // Only the range the option is set for is affected.
+        assert_eq!(
+            line_boundaries(text, &[(0..6, LineBreak::Loose)]),
+            [false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn line_break_anywhere_breaks_within_words() {
+        assert_eq!(
+            line_boundaries("abc", &[(0..3, LineBreak::Anywhere)]),
+            [false, true, true]
+        );
+    }
+
     #[test]
     fn explicit_direction_applies_to_empty_text() {
         let analysis = analyze("", BaseDirection::Rtl);
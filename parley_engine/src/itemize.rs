@@ -12,6 +12,7 @@ use crate::{Analysis, CharInfo};
 
 /// A range of text.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextRange {
     /// The range of byte offsets.
     pub byte_range: Range<usize>,
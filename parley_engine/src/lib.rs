@@ -5,7 +5,11 @@
 //!
 //! ## Features
 //!
-//! - `std` (enabled by default): This is currently unused and is provided for forward compatibility.
+//! - `std` (enabled by default): Enables [`SharedShapePlanCache`] to share shaping plans between
+//!   threads.
+//! - `serde`: Implements `Serialize` and `Deserialize` on [`ShapedText`] and the types it is made
+//!   of. The fonts of shaped text aren't serialized, and are restored with
+//!   [`ShapedText::set_fonts`].
 
 // LINEBENDER LINT SET - lib.rs - v3
 // See https://linebender.org/wiki/canonical-lints/
@@ -40,4 +44,7 @@ pub use analyzer::{AnalysisOptions, Analyzer};
 pub use glyph::Glyph;
 pub use shape::atom::{Atom, Atoms, Grapheme, Graphemes, ShapedSlice};
 pub use shape::shaped_text::{FontMetrics, NormalizedCoord, ShapedRun, ShapedText};
-pub use shape::shaper::{FontInstance, ShapeOptions, Shaper};
+pub use shape::shaper::{FontInstance, FontSegment, ShapeOptions, Shaper};
+
+#[cfg(feature = "std")]
+pub use shape::SharedShapePlanCache;
@@ -5,6 +5,7 @@ use alloc::vec::Vec;
 use hashbrown::Equivalent;
 
 /// An entry in the cache.
+#[derive(Clone)]
 pub(crate) struct Entry<ID, T> {
     pub epoch: u64,
     pub id: ID,
@@ -14,6 +15,7 @@ pub(crate) struct Entry<ID, T> {
 /// A least-recently-used cache. This cache uses a linear scan of its entries
 /// to find a given entry - it is optimised for a low number of entries. Preferably
 /// keep `max_entries` low - in the order of tens.
+#[derive(Clone)]
 pub(crate) struct LruCache<ID, T> {
     entries: Vec<Entry<ID, T>>,
     epoch: u64,
@@ -45,6 +47,16 @@ impl<ID, T> LruCache<ID, T> {
         &entry.data
     }
 
+    /// Returns the number of entries.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Removes all entries.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
     fn find_entry<K>(&mut self, id: K, make_data: impl FnOnce() -> T) -> usize
     where
         K: Equivalent<ID> + Into<ID>,
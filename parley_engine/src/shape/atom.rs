@@ -796,7 +796,7 @@ impl Grapheme {
 mod tests {
     use alloc::{sync::Arc, vec};
 
-    use fontique::Synthesis;
+    use fontique::{RenderHints, Synthesis};
     use linebender_resource_handle::{Blob, FontData};
 
     use crate::{
@@ -825,6 +825,10 @@ mod tests {
         FontInstance {
             font: FontData::new(Blob::new(Arc::new(font_data)), 0),
             synthesis: Synthesis::default(),
+            features: None,
+            size_adjust: 1.0,
+            optical_size: None,
+            render_hints: RenderHints::default(),
         }
     }
 
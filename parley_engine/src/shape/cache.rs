@@ -4,6 +4,12 @@
 use alloc::boxed::Box;
 use hashbrown::Equivalent;
 use parlance::FontVariation;
+#[cfg(feature = "std")]
+use {
+    crate::lru_cache::LruCache,
+    alloc::sync::Arc,
+    std::sync::{Mutex, PoisonError},
+};
 
 #[derive(PartialEq, Copy, Clone)]
 pub(crate) struct ShapeDataKey {
@@ -92,6 +98,7 @@ impl<'a> From<ShapeInstanceKey<'a>> for ShapeInstanceId {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct ShapePlanId {
     /// The font collection's blob ID.
     font_blob_id: u64,
@@ -105,6 +112,7 @@ pub(crate) struct ShapePlanId {
     variations: Option<Box<[FontVariation]>>,
 }
 
+#[derive(Clone)]
 pub(crate) struct ShapePlanKey<'a> {
     /// The font collection's blob ID.
     font_blob_id: u64,
@@ -176,3 +184,78 @@ impl<'a> From<ShapePlanKey<'a>> for ShapePlanId {
         }
     }
 }
+
+/// A cache of shaping plans that can be shared by [`Shaper`]s on different threads.
+///
+/// Shaping plans depend on the font, script, language, direction and features of the shaped
+/// text, but not on the text itself, so threads laying out similar text can share them. Each
+/// `Shaper` still keeps its own cache of recently used plans, and only consults the shared
+/// cache for plans it doesn't have.
+///
+/// Cloning this is cheap, and clones share the same cache.
+///
+/// [`Shaper`]: crate::Shaper
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct SharedShapePlanCache {
+    plans: Arc<Mutex<LruCache<ShapePlanId, Arc<harfrust::ShapePlan>>>>,
+}
+
+#[cfg(feature = "std")]
+impl SharedShapePlanCache {
+    /// Creates a cache holding up to `max_entries` shaping plans.
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            plans: Arc::new(Mutex::new(LruCache::new(max_entries))),
+        }
+    }
+
+    /// Returns the number of cached plans.
+    pub fn len(&self) -> usize {
+        self.plans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns true if no plans are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops all cached plans.
+    pub fn clear(&self) {
+        self.plans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Returns the plan for `key`, creating it with `make_plan` if it isn't cached.
+    pub(crate) fn get_or_insert(
+        &self,
+        key: ShapePlanKey<'_>,
+        make_plan: impl FnOnce() -> harfrust::ShapePlan,
+    ) -> Arc<harfrust::ShapePlan> {
+        self.plans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(key, || Arc::new(make_plan()))
+            .clone()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for SharedShapePlanCache {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Debug for SharedShapePlanCache {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedShapePlanCache")
+            .finish_non_exhaustive()
+    }
+}
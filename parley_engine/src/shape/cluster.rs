@@ -9,12 +9,13 @@ use core::cmp::Ordering;
 use alloc::vec::Vec;
 use icu_normalizer::properties::Decomposed;
 
+use super::emoji::{EMOJI_PRESENTATION_SELECTOR, EmojiPresentation, TEXT_PRESENTATION_SELECTOR};
 use crate::{CharInfo, analysis::AnalysisDataSources};
 
 #[derive(Debug, Default)]
 pub struct CharCluster {
     chars: Vec<Char>,
-    is_emoji: bool,
+    emoji: EmojiPresentation,
     map_len: u8,
     start: u32,
     end: u32,
@@ -37,9 +38,16 @@ impl CharCluster {
         &self.chars
     }
 
+    /// Whether the cluster is an emoji, see [`Self::emoji_presentation`].
     #[inline(always)]
     pub fn is_emoji(&self) -> bool {
-        self.is_emoji
+        self.emoji.is_emoji()
+    }
+
+    /// The presentation of the cluster, if it is an emoji character or sequence.
+    #[inline(always)]
+    pub fn emoji_presentation(&self) -> EmojiPresentation {
+        self.emoji
     }
 }
 
@@ -168,7 +176,7 @@ impl CharCluster {
     #[inline]
     pub(crate) fn clear(&mut self) {
         self.chars.clear();
-        self.is_emoji = false;
+        self.emoji = EmojiPresentation::None;
         self.map_len = 0;
         self.start = 0;
         self.end = 0;
@@ -275,9 +283,35 @@ impl CharCluster {
     /// [`Coverage::total`] may be different from the count of [`Self::chars`], and may also differ
     /// between subsequent calls due to returning the best coverage of either the original
     /// characters or one of the normalized forms.
+    ///
+    /// `prefers_emoji` is whether the cluster is drawn with emoji presentation, as resolved from
+    /// its [presentation](Self::emoji_presentation) and the style of the text. Presentation
+    /// selectors are ignored, unless the cluster's selector requests the resolved presentation, in
+    /// which case the font needs to cover the selector as well. Fonts covering the whole emoji
+    /// presentation sequence are then preferred over fonts only covering its emoji.
     pub fn calculate_coverage(
         &mut self,
         covers: impl Fn(char) -> bool,
+        prefers_emoji: bool,
+        analysis_data_sources: &AnalysisDataSources,
+    ) -> Coverage {
+        let coverage = self.calculate_chars_coverage(&covers, analysis_data_sources);
+        let selector = match (self.emoji, prefers_emoji) {
+            (EmojiPresentation::EmojiSequence, true) => EMOJI_PRESENTATION_SELECTOR,
+            (EmojiPresentation::TextSequence, false) => TEXT_PRESENTATION_SELECTOR,
+            _ => return coverage,
+        };
+        Coverage {
+            covered: coverage.covered.saturating_add(u8::from(covers(selector))),
+            total: coverage.total.saturating_add(1),
+        }
+    }
+
+    /// Calculates the coverage of the characters of the cluster, without its presentation
+    /// selector.
+    fn calculate_chars_coverage(
+        &mut self,
+        covers: &impl Fn(char) -> bool,
         analysis_data_sources: &AnalysisDataSources,
     ) -> Coverage {
         let len = self.len();
@@ -285,12 +319,12 @@ impl CharCluster {
             chars: &self.chars[..len],
             map_len: self.map_len,
         }
-        .coverage(&covers);
+        .coverage(covers);
         if best_coverage.is_complete() {
             return best_coverage;
         }
         if self.force_normalize && self.composed(analysis_data_sources).is_some() {
-            let coverage = self.comp.coverage(&covers);
+            let coverage = self.comp.coverage(covers);
             if coverage > best_coverage {
                 best_coverage = coverage;
                 if coverage.is_complete() {
@@ -299,7 +333,7 @@ impl CharCluster {
             }
         }
         if self.decomposed(analysis_data_sources).is_some() {
-            let coverage = self.decomp.coverage(&covers);
+            let coverage = self.decomp.coverage(covers);
             if coverage > best_coverage {
                 best_coverage = coverage;
                 if coverage.is_complete() {
@@ -307,7 +341,7 @@ impl CharCluster {
                 }
             }
             if !self.force_normalize && self.composed(analysis_data_sources).is_some() {
-                let coverage = self.comp.coverage(&covers);
+                let coverage = self.comp.coverage(covers);
                 if coverage > best_coverage {
                     best_coverage = coverage;
                     if coverage.is_complete() {
@@ -348,9 +382,6 @@ impl CharCluster {
             segment_text.char_indices().zip(item_infos_iter.by_ref())
         {
             force_normalize |= info.force_normalize();
-            // TODO - make emoji detection more complete, as per (except using composite Trie tables as
-            //  much as possible:
-            //  https://github.com/conor-93/parley/blob/4637d826732a1a82bbb3c904c7f47a16a21cceec/parley/src/shape/mod.rs#L221-L269
             is_emoji_or_pictograph |= info.is_emoji_or_pictograph();
             *code_unit_offset_in_string += ch.len_utf8();
 
@@ -388,7 +419,11 @@ impl CharCluster {
 
         // Finalize cluster metadata
         let end = *code_unit_offset_in_string as u32;
-        self.is_emoji = is_emoji_or_pictograph;
+        // Only clusters containing an emoji character can be emoji sequences, which avoids the
+        // property lookups for most text.
+        if is_emoji_or_pictograph {
+            self.emoji = EmojiPresentation::of(self.chars.iter().map(|c| c.ch));
+        }
         self.map_len = map_len;
         self.start = start;
         self.end = end;
@@ -490,7 +525,53 @@ impl<'a> Mapper<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::Coverage;
+    use super::{CharCluster, Coverage};
+    use crate::{Analysis, AnalysisDataSources, AnalysisOptions, Analyzer};
+
+    fn cluster(text: &str) -> CharCluster {
+        let mut analysis = Analysis::new();
+        Analyzer::new().analyze(text, &AnalysisOptions::default(), &mut analysis);
+        let mut cluster = CharCluster::default();
+        let mut offset = 0;
+        cluster.fill(
+            text,
+            &mut analysis.char_info().iter().map(|info| (*info, 0)),
+            &mut offset,
+        );
+        cluster
+    }
+
+    #[test]
+    fn presentation_selector_coverage() {
+        let analysis_data_sources = AnalysisDataSources::new();
+        // The text font only covers the emoji, the emoji font covers the whole sequence.
+        let text_font = |ch| ch == '✌';
+        let emoji_font = |ch| matches!(ch, '✌' | '\u{FE0F}');
+
+        let mut emoji_sequence = cluster("✌\u{FE0F}");
+        let text_coverage =
+            emoji_sequence.calculate_coverage(text_font, true, &analysis_data_sources);
+        let emoji_coverage =
+            emoji_sequence.calculate_coverage(emoji_font, true, &analysis_data_sources);
+        assert!(!text_coverage.is_complete());
+        assert!(emoji_coverage.is_complete());
+        assert!(emoji_coverage > text_coverage);
+
+        // With text presentation, as for `font-variant-emoji: text`, the selector is ignored.
+        assert!(
+            emoji_sequence
+                .calculate_coverage(text_font, false, &analysis_data_sources)
+                .is_complete()
+        );
+
+        // Clusters without a selector don't depend on the presentation.
+        let mut emoji = cluster("✌");
+        assert!(
+            emoji
+                .calculate_coverage(text_font, true, &analysis_data_sources)
+                .is_complete()
+        );
+    }
 
     #[test]
     fn ordering() {
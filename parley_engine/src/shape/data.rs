@@ -12,6 +12,7 @@ use crate::{Boundary, shape::Whitespace};
 /// This is a character in the Unicode scalar value sense, i.e., it corresponds to a single `char`
 /// of the source text.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Character {
     pub text_byte_start: u32,
     pub info: ClusterInfo,
@@ -30,6 +31,7 @@ impl Character {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct ShapedClusterFlags(u16);
 
 impl ShapedClusterFlags {
@@ -97,6 +99,7 @@ impl ShapedClusterFlags {
 ///
 /// [harfbuzz]: https://harfbuzz.github.io/working-with-harfbuzz-clusters.html
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShapedCluster {
     /// The first character of this cluster, as an index into [`ShapedText::characters`](crate::ShapedText::characters).
     ///
@@ -182,6 +185,7 @@ impl ShapedCluster {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClusterInfo {
     boundary: Boundary,
     source_char: char,
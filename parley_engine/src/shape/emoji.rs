@@ -0,0 +1,222 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Detection of emoji sequences, following the grammar of [UTS #51 § 1.4.9][grammar].
+//!
+//! [grammar]: https://www.unicode.org/reports/tr51/#EBNF_and_Regex
+
+use core::iter::Peekable;
+
+use icu_properties::CodePointSetData;
+use icu_properties::props::{
+    Emoji, EmojiModifier, EmojiModifierBase, EmojiPresentation as EmojiPresentationProperty,
+    ExtendedPictographic, RegionalIndicator,
+};
+
+const ZWJ: char = '\u{200D}';
+pub(super) const TEXT_PRESENTATION_SELECTOR: char = '\u{FE0E}';
+pub(super) const EMOJI_PRESENTATION_SELECTOR: char = '\u{FE0F}';
+const COMBINING_ENCLOSING_KEYCAP: char = '\u{20E3}';
+const CANCEL_TAG: char = '\u{E007F}';
+
+/// How a cluster is presented, if it is an emoji.
+///
+/// See [UTS #51 § 4][presentation] for the presentation of emoji.
+///
+/// [presentation]: https://www.unicode.org/reports/tr51/#Presentation_Style
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EmojiPresentation {
+    /// The cluster is not an emoji.
+    #[default]
+    None,
+    /// An emoji character with text presentation by default, such as `©`.
+    Text,
+    /// An emoji with emoji presentation by default.
+    ///
+    /// This is an emoji character such as `😀`, or a keycap, modifier, flag, tag or ZWJ sequence.
+    Emoji,
+    /// An emoji character followed by the text presentation selector (U+FE0E).
+    TextSequence,
+    /// An emoji character followed by the emoji presentation selector (U+FE0F).
+    EmojiSequence,
+}
+
+impl EmojiPresentation {
+    /// Whether the cluster is an emoji.
+    #[inline(always)]
+    pub fn is_emoji(self) -> bool {
+        self != Self::None
+    }
+
+    /// Whether the cluster should be drawn with emoji presentation, following its presentation
+    /// selector if it has one and its default presentation otherwise.
+    #[inline(always)]
+    pub fn prefers_emoji(self) -> bool {
+        matches!(self, Self::Emoji | Self::EmojiSequence)
+    }
+
+    /// Whether the presentation was requested with a presentation selector.
+    #[inline(always)]
+    pub fn has_selector(self) -> bool {
+        matches!(self, Self::TextSequence | Self::EmojiSequence)
+    }
+
+    /// Classifies the grapheme cluster `chars`.
+    ///
+    /// Characters following a complete emoji sequence, such as combining marks, don't change the
+    /// presentation of the cluster.
+    pub fn of(chars: impl IntoIterator<Item = char>) -> Self {
+        let mut chars = chars.into_iter().peekable();
+        let Some(mut presentation) = emoji_element(&mut chars) else {
+            return Self::None;
+        };
+        while chars.next_if_eq(&ZWJ).is_some() {
+            if emoji_element(&mut chars).is_none() {
+                break;
+            }
+            presentation = Self::Emoji;
+        }
+        presentation
+    }
+}
+
+/// Parses one element of an emoji ZWJ sequence, or a complete keycap or flag sequence.
+fn emoji_element(chars: &mut Peekable<impl Iterator<Item = char>>) -> Option<EmojiPresentation> {
+    let first = chars.next()?;
+
+    // emoji_flag_sequence := regional_indicator regional_indicator
+    if is_regional_indicator(first) {
+        return if chars.next_if(|&c| is_regional_indicator(c)).is_some() {
+            Some(EmojiPresentation::Emoji)
+        } else {
+            Some(EmojiPresentation::Text)
+        };
+    }
+
+    // emoji_keycap_sequence := [0-9#*] \x{FE0F 20E3}
+    //
+    // The digits, `#` and `*` are emoji characters, but are only treated as such in sequences.
+    if matches!(first, '0'..='9' | '#' | '*') {
+        let has_selector = chars.next_if_eq(&EMOJI_PRESENTATION_SELECTOR).is_some();
+        // Keycap sequences without the presentation selector aren't fully qualified, but are
+        // still displayed as emoji.
+        return if chars.next_if_eq(&COMBINING_ENCLOSING_KEYCAP).is_some() {
+            Some(EmojiPresentation::Emoji)
+        } else if has_selector {
+            Some(EmojiPresentation::EmojiSequence)
+        } else {
+            None
+        };
+    }
+
+    if !is_emoji_character(first) {
+        return None;
+    }
+    let mut presentation = if is_emoji_presentation(first) {
+        EmojiPresentation::Emoji
+    } else {
+        EmojiPresentation::Text
+    };
+    match chars.peek().copied() {
+        // emoji_modifier_sequence := emoji_modifier_base emoji_modifier
+        Some(c) if is_emoji_modifier(c) && is_emoji_modifier_base(first) => {
+            chars.next();
+            presentation = EmojiPresentation::Emoji;
+        }
+        // emoji_presentation_sequence := emoji_character \x{FE0F}
+        Some(EMOJI_PRESENTATION_SELECTOR) => {
+            chars.next();
+            presentation = EmojiPresentation::EmojiSequence;
+        }
+        Some(TEXT_PRESENTATION_SELECTOR) => {
+            chars.next();
+            presentation = EmojiPresentation::TextSequence;
+        }
+        _ => {}
+    }
+
+    // emoji_tag_sequence := tag_base tag_spec tag_end
+    let mut has_tag_spec = false;
+    while chars.next_if(|&c| is_tag_spec(c)).is_some() {
+        has_tag_spec = true;
+    }
+    if has_tag_spec && chars.next_if_eq(&CANCEL_TAG).is_some() {
+        presentation = EmojiPresentation::Emoji;
+    }
+
+    Some(presentation)
+}
+
+fn is_emoji_character(c: char) -> bool {
+    const { CodePointSetData::new::<Emoji>() }.contains(c)
+        || const { CodePointSetData::new::<ExtendedPictographic>() }.contains(c)
+}
+
+fn is_emoji_presentation(c: char) -> bool {
+    const { CodePointSetData::new::<EmojiPresentationProperty>() }.contains(c)
+}
+
+fn is_emoji_modifier(c: char) -> bool {
+    const { CodePointSetData::new::<EmojiModifier>() }.contains(c)
+}
+
+fn is_emoji_modifier_base(c: char) -> bool {
+    const { CodePointSetData::new::<EmojiModifierBase>() }.contains(c)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    const { CodePointSetData::new::<RegionalIndicator>() }.contains(c)
+}
+
+fn is_tag_spec(c: char) -> bool {
+    matches!(c, '\u{E0020}'..='\u{E007E}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EmojiPresentation;
+
+    fn presentation(s: &str) -> EmojiPresentation {
+        EmojiPresentation::of(s.chars())
+    }
+
+    #[test]
+    fn emoji_characters() {
+        assert_eq!(presentation("a"), EmojiPresentation::None);
+        assert_eq!(presentation("1"), EmojiPresentation::None);
+        assert_eq!(presentation("©"), EmojiPresentation::Text);
+        assert_eq!(presentation("😀"), EmojiPresentation::Emoji);
+    }
+
+    #[test]
+    fn emoji_presentation_sequences() {
+        assert_eq!(presentation("✌\u{FE0E}"), EmojiPresentation::TextSequence);
+        assert_eq!(presentation("✌\u{FE0F}"), EmojiPresentation::EmojiSequence);
+        assert_eq!(presentation("😀\u{FE0E}"), EmojiPresentation::TextSequence);
+        assert!(EmojiPresentation::EmojiSequence.has_selector());
+        assert!(!EmojiPresentation::TextSequence.prefers_emoji());
+    }
+
+    #[test]
+    fn emoji_sequences() {
+        // Keycaps, with and without the presentation selector.
+        assert_eq!(presentation("1\u{FE0F}\u{20E3}"), EmojiPresentation::Emoji);
+        assert_eq!(presentation("#\u{20E3}"), EmojiPresentation::Emoji);
+        // Skin tone modifier.
+        assert_eq!(presentation("👋\u{1F3FD}"), EmojiPresentation::Emoji);
+        // Flag.
+        assert_eq!(presentation("🇳🇱"), EmojiPresentation::Emoji);
+        // Subdivision flag (England).
+        assert_eq!(
+            presentation("🏴\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}"),
+            EmojiPresentation::Emoji
+        );
+        // ZWJ sequence of a text-default character (rainbow flag).
+        assert_eq!(
+            presentation("🏳\u{FE0F}\u{200D}🌈"),
+            EmojiPresentation::Emoji
+        );
+        // A ZWJ that doesn't join two emoji doesn't make a sequence.
+        assert_eq!(presentation("©\u{200D}a"), EmojiPresentation::Text);
+    }
+}
@@ -7,10 +7,16 @@ pub(crate) mod atom;
 mod cache;
 mod cluster;
 mod data;
+mod emoji;
 pub(crate) mod shaped_text;
 pub(crate) mod shaper;
+mod word_cache;
 
 pub use cluster::{Char, CharCluster, Coverage, SourceRange, Whitespace};
 pub use data::{Character, ClusterInfo, ShapedCluster, to_whitespace};
+pub use emoji::EmojiPresentation;
+
+#[cfg(feature = "std")]
+pub use cache::SharedShapePlanCache;
 
 pub(crate) use data::ShapedClusterFlags;
@@ -5,7 +5,8 @@
 
 use core::ops::Range;
 
-use alloc::vec::Vec;
+use alloc::{sync::Arc, vec::Vec};
+use linebender_resource_handle::FontData;
 use parlance::BidiLevel;
 
 use crate::{
@@ -18,6 +19,7 @@ use super::{
     ClusterInfo, Whitespace,
     atom::ShapedSlice,
     data::{Character, ShapedCluster},
+    word_cache::ShapedWord,
 };
 
 /// A normalized font coordinate.
@@ -26,6 +28,7 @@ use super::{
 /// useful values are in the range -1.0..=1.0.
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NormalizedCoord(i16);
 
 impl NormalizedCoord {
@@ -65,6 +68,7 @@ impl NormalizedCoord {
 // just follows what `parley` used to do in its `RunMetrics`. If we go unscaled, we should then
 // either also store units per em, or em-normalize the values like CSS does.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontMetrics {
     /// Distance from the baseline to the top of the alignment box.
     pub ascent: f32,
@@ -87,6 +91,65 @@ pub struct FontMetrics {
     pub x_height: Option<f32>,
 }
 
+impl FontMetrics {
+    /// Reads the metrics of a font's default instance at the given font size.
+    ///
+    /// Returns `None` if the font data can't be parsed.
+    pub fn from_font(font: &FontData, font_size: f32) -> Option<Self> {
+        let font_ref = skrifa::FontRef::from_index(font.data.as_ref(), font.index).ok()?;
+        let (metrics, _) = Self::read(
+            &font_ref,
+            font_size,
+            skrifa::instance::LocationRef::default(),
+        );
+        Some(metrics)
+    }
+
+    /// Reads the metrics of a font instance, along with the font's units per em.
+    fn read(
+        font_ref: &skrifa::FontRef<'_>,
+        font_size: f32,
+        location: skrifa::instance::LocationRef<'_>,
+    ) -> (Self, f32) {
+        let metrics = skrifa::metrics::Metrics::new(
+            font_ref,
+            skrifa::prelude::Size::new(font_size),
+            location,
+        );
+        let units_per_em = metrics.units_per_em as f32;
+
+        // TODO: The following seems to be in the wrong scale, as its staying in design units rather
+        // than scaled to the font size like the other fields for `FontMetrics`.
+        let (underline_offset, underline_size) = if let Some(underline) = metrics.underline {
+            (underline.offset, underline.thickness)
+        } else {
+            // Default values from Harfbuzz: https://github.com/harfbuzz/harfbuzz/blob/00492ec7df0038f41f78d43d477c183e4e4c506e/src/hb-ot-metrics.cc#L334
+            let default = units_per_em / 18.0;
+            (default, default)
+        };
+        let (strikethrough_offset, strikethrough_size) = if let Some(strikeout) = metrics.strikeout
+        {
+            (strikeout.offset, strikeout.thickness)
+        } else {
+            // Default values from HarfBuzz: https://github.com/harfbuzz/harfbuzz/blob/00492ec7df0038f41f78d43d477c183e4e4c506e/src/hb-ot-metrics.cc#L334-L347
+            (metrics.ascent / 2.0, units_per_em / 18.0)
+        };
+
+        let font_metrics = Self {
+            ascent: metrics.ascent,
+            descent: -metrics.descent,
+            leading: metrics.leading,
+            underline_offset,
+            underline_size,
+            strikethrough_offset,
+            strikethrough_size,
+            x_height: metrics.x_height,
+            cap_height: metrics.cap_height,
+        };
+        (font_metrics, units_per_em)
+    }
+}
+
 /// The result of shaping.
 ///
 /// After [itemizing][crate::itemize::Item] your text,
@@ -101,11 +164,15 @@ pub struct FontMetrics {
 /// of shaped text, such as walking [atoms](crate::Atom) (e.g., [`ShapedSlice::atoms_start`]) and
 /// [graphemes](crate::Grapheme) (e.g., [`ShapedSlice::graphemes_start`]).
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShapedText {
     runs: Vec<ShapedRun>,
     characters: Vec<Character>,
     shaped_clusters: Vec<ShapedCluster>,
     glyphs: Vec<Glyph>,
+    /// Fonts are not serialized, as their data is usually stored elsewhere. They are restored with
+    /// [`Self::set_fonts`].
+    #[cfg_attr(feature = "serde", serde(skip))]
     fonts: Vec<FontInstance>,
     normalized_coords: Vec<NormalizedCoord>,
 }
@@ -188,6 +255,22 @@ impl ShapedText {
         }
     }
 
+    /// Escape hatch for `parley` to change the styles of characters without shaping them again,
+    /// for styles that don't affect shaping. This is not for public consumption!
+    ///
+    /// `restyle` returns the new style index of each character. Clusters take the style of their
+    /// first character.
+    #[doc(hidden)]
+    pub fn restyle_characters(&mut self, mut restyle: impl FnMut(&Character) -> u16) {
+        for character in self.characters.iter_mut() {
+            character.style_index = restyle(character);
+        }
+        for shaped_cluster in self.shaped_clusters.iter_mut() {
+            shaped_cluster.style_index =
+                self.characters[shaped_cluster.chars_range.0 as usize].style_index;
+        }
+    }
+
     /// The characters of the shaped runs, in logical order.
     ///
     /// [`ShapedRun::characters_range`] indexes into this.
@@ -223,6 +306,39 @@ impl ShapedText {
         &self.fonts
     }
 
+    /// Replaces the fonts that [`ShapedRun::font_index`] indexes into.
+    ///
+    /// This is for restoring the fonts of deserialized shaped text, which doesn't include them. It
+    /// also checks that the runs and clusters index into the shaped text correctly, as
+    /// deserialized data may not. If they don't, this returns `false` and leaves the fonts as is.
+    pub fn set_fonts(&mut self, fonts: Vec<FontInstance>) -> bool {
+        let runs_valid = self.runs.iter().all(|run| {
+            run.font_index < fonts.len()
+                && range_within(u32_range(&run.characters_range), self.characters.len())
+                && range_within(
+                    u32_range(&run.shaped_clusters_range),
+                    self.shaped_clusters.len(),
+                )
+                && range_within(run.glyphs_range.clone(), self.glyphs.len())
+                && range_within(
+                    run.normalized_coords_range.clone(),
+                    self.normalized_coords.len(),
+                )
+        });
+        let clusters_valid = self.shaped_clusters.iter().all(|cluster| {
+            let (start, end) = cluster.chars_range;
+            range_within(start as usize..end as usize, self.characters.len())
+                && (cluster.has_inline_glyph()
+                    || cluster.glyph_offset as usize + cluster.glyph_len() as usize
+                        <= self.glyphs.len())
+        });
+        if !runs_valid || !clusters_valid {
+            return false;
+        }
+        self.fonts = fonts;
+        true
+    }
+
     /// The normalized font coordinates used by runs in this shaped text.
     ///
     /// [`ShapedRun::normalized_coords_range`] splits this into per-run slices.
@@ -231,6 +347,68 @@ impl ShapedText {
         &self.normalized_coords
     }
 
+    /// Append the runs of `other`, as if they had been shaped into this [`ShapedText`].
+    ///
+    /// This allows shaping the items of a text into separate [`ShapedText`]s, for example on
+    /// different threads, and concatenating them in order afterwards. The result is the same as
+    /// shaping all items into one [`ShapedText`].
+    ///
+    /// Returns the index range of the appended runs.
+    #[expect(clippy::cast_possible_truncation, reason = "Deferred")]
+    pub fn append(&mut self, other: &Self) -> Range<usize> {
+        let runs_start = self.runs.len();
+        let characters_offset = self.characters.len() as u32;
+        let shaped_clusters_offset = self.shaped_clusters.len() as u32;
+        let glyphs_offset = self.glyphs.len();
+        let normalized_coords_offset = self.normalized_coords.len();
+
+        self.characters.extend_from_slice(&other.characters);
+        self.shaped_clusters
+            .extend(other.shaped_clusters.iter().map(|cluster| {
+                let mut cluster = *cluster;
+                cluster.chars_range = (
+                    cluster.chars_range.0 + characters_offset,
+                    cluster.chars_range.1 + characters_offset,
+                );
+                if !cluster.has_inline_glyph() {
+                    cluster.glyph_offset += glyphs_offset as u32;
+                }
+                cluster
+            }));
+        self.glyphs.extend_from_slice(&other.glyphs);
+        self.normalized_coords
+            .extend_from_slice(&other.normalized_coords);
+
+        for run in &other.runs {
+            let font = &other.fonts[run.font_index];
+            let font_index = self
+                .fonts
+                .iter()
+                .position(|f| f == font)
+                .unwrap_or_else(|| {
+                    self.fonts.push(font.clone());
+                    self.fonts.len() - 1
+                });
+            let offset_u32 =
+                |range: &Range<u32>, offset: u32| range.start + offset..range.end + offset;
+            self.runs.push(ShapedRun {
+                font_index,
+                characters_range: offset_u32(&run.characters_range, characters_offset),
+                shaped_clusters_range: offset_u32(
+                    &run.shaped_clusters_range,
+                    shaped_clusters_offset,
+                ),
+                glyphs_range: run.glyphs_range.start + glyphs_offset
+                    ..run.glyphs_range.end + glyphs_offset,
+                normalized_coords_range: run.normalized_coords_range.start
+                    + normalized_coords_offset
+                    ..run.normalized_coords_range.end + normalized_coords_offset,
+                ..run.clone()
+            });
+        }
+        runs_start..self.runs.len()
+    }
+
     #[expect(clippy::cast_possible_truncation, reason = "Deferred")]
     pub(crate) fn push_run(
         &mut self,
@@ -240,11 +418,14 @@ impl ShapedText {
         options: &ShapeOptions<'_>,
         char_info: &[CharInfo],
         font: &FontInstance,
-        glyph_buffer: &harfrust::GlyphBuffer,
+        glyphs: RunGlyphs<'_>,
         normalized_coords: &[harfrust::NormalizedCoord],
     ) {
-        let glyph_infos = glyph_buffer.glyph_infos();
-        if glyph_infos.is_empty() {
+        let is_empty = match glyphs {
+            RunGlyphs::Shaped(glyph_buffer) => glyph_buffer.glyph_infos().is_empty(),
+            RunGlyphs::Cached(words) => words.is_empty(),
+        };
+        if is_empty {
             return;
         }
 
@@ -268,52 +449,14 @@ impl ShapedText {
                 index
             });
 
-        let metrics = {
+        let font_size = options.font_size * font.size_adjust;
+        let (font_metrics, units_per_em) = {
             let font = &self.fonts[font_index];
             let font_ref =
                 skrifa::FontRef::from_index(font.font.data.as_ref(), font.font.index).unwrap();
-            skrifa::metrics::Metrics::new(
-                &font_ref,
-                skrifa::prelude::Size::new(options.font_size),
-                normalized_coords,
-            )
+            FontMetrics::read(&font_ref, font_size, normalized_coords.into())
         };
-        let units_per_em = metrics.units_per_em as f32;
 
-        // TODO: The following seems to be in the wrong scale, as its staying in design units rather
-        // than scaled to the font size like the other fields for `FontMetrics`.
-        let (underline_offset, underline_size) = if let Some(underline) = metrics.underline {
-            (underline.offset, underline.thickness)
-        } else {
-            // Default values from Harfbuzz: https://github.com/harfbuzz/harfbuzz/blob/00492ec7df0038f41f78d43d477c183e4e4c506e/src/hb-ot-metrics.cc#L334
-            let default = units_per_em / 18.0;
-            (default, default)
-        };
-        let (strikethrough_offset, strikethrough_size) = if let Some(strikeout) = metrics.strikeout
-        {
-            (strikeout.offset, strikeout.thickness)
-        } else {
-            // Default values from HarfBuzz: https://github.com/harfbuzz/harfbuzz/blob/00492ec7df0038f41f78d43d477c183e4e4c506e/src/hb-ot-metrics.cc#L334-L347
-            (metrics.ascent / 2.0, units_per_em / 18.0)
-        };
-
-        let font_metrics = FontMetrics {
-            ascent: metrics.ascent,
-            descent: -metrics.descent,
-            leading: metrics.leading,
-            underline_offset,
-            underline_size,
-            strikethrough_offset,
-            strikethrough_size,
-            x_height: metrics.x_height,
-            cap_height: metrics.cap_height,
-        };
-
-        // `HarfRust` returns glyphs in visual order, so we need to process them as such while
-        // maintaining logical ordering of clusters.
-
-        let glyph_positions = glyph_buffer.glyph_positions();
-        let scale_factor = options.font_size / units_per_em;
         let shaped_clusters_start = self.shaped_clusters.len();
 
         // Push all characters.
@@ -336,11 +479,37 @@ impl ShapedText {
         self.characters[characters_start].grapheme_start = true;
 
         let glyphs_start = self.glyphs.len();
+        let glyph_buffer = match glyphs {
+            RunGlyphs::Shaped(glyph_buffer) => glyph_buffer,
+            RunGlyphs::Cached(words) => {
+                self.push_cached_words(words, characters_start);
+                return self.finish_run(
+                    range,
+                    item,
+                    font_size,
+                    font_index,
+                    characters_start,
+                    shaped_clusters_start,
+                    glyphs_start,
+                    normalized_coords_range,
+                    font_metrics,
+                );
+            }
+        };
+
+        // `HarfRust` returns glyphs in visual order, so we need to process them as such while
+        // maintaining logical ordering of clusters.
+        let glyph_infos = glyph_buffer.glyph_infos();
+        let glyph_positions = glyph_buffer.glyph_positions();
+        let scale_factor = font_size / units_per_em;
+        // Faux bold widens glyphs, so their advances are widened to match.
+        let embolden_strength = font.synthesis.embolden_strength(font_size);
         if item.bidi_level.is_ltr() {
             process_shaped_clusters(
                 &mut self.shaped_clusters,
                 &mut self.glyphs,
                 scale_factor,
+                embolden_strength,
                 glyph_infos.iter(),
                 glyph_positions.iter(),
                 &options.char_style_indices[range.char_range.clone()],
@@ -352,6 +521,7 @@ impl ShapedText {
                 &mut self.shaped_clusters,
                 &mut self.glyphs,
                 scale_factor,
+                embolden_strength,
                 glyph_infos.iter().rev(),
                 glyph_positions.iter().rev(),
                 &options.char_style_indices[range.char_range.clone()],
@@ -369,6 +539,62 @@ impl ShapedText {
             }
         }
 
+        self.finish_run(
+            range,
+            item,
+            font_size,
+            font_index,
+            characters_start,
+            shaped_clusters_start,
+            glyphs_start,
+            normalized_coords_range,
+            font_metrics,
+        );
+    }
+
+    /// Appends the clusters and glyphs of cached words, whose characters start at
+    /// `characters_start`.
+    #[expect(clippy::cast_possible_truncation, reason = "Deferred")]
+    fn push_cached_words(&mut self, words: &[Arc<ShapedWord>], characters_start: usize) {
+        let mut word_start = characters_start as u32;
+        for word in words {
+            let glyphs_start = self.glyphs.len() as u32;
+            for cluster in &word.clusters {
+                let mut cluster = *cluster;
+                cluster.chars_range = (
+                    word_start + cluster.chars_range.0,
+                    word_start + cluster.chars_range.1,
+                );
+                // The style and grapheme boundaries are those of this text, not the cached text.
+                let first_character = &self.characters[cluster.chars_range.0 as usize];
+                cluster.style_index = first_character.style_index;
+                cluster.flags = cluster
+                    .flags
+                    .with_grapheme_start(first_character.grapheme_start);
+                if !cluster.has_inline_glyph() {
+                    cluster.glyph_offset += glyphs_start;
+                }
+                self.shaped_clusters.push(cluster);
+            }
+            self.glyphs.extend_from_slice(&word.glyphs);
+            word_start += word.char_len;
+        }
+    }
+
+    /// Pushes the run whose characters, clusters and glyphs were appended last.
+    #[expect(clippy::cast_possible_truncation, reason = "Deferred")]
+    fn finish_run(
+        &mut self,
+        range: TextRange,
+        item: &Item,
+        font_size: f32,
+        font_index: usize,
+        characters_start: usize,
+        shaped_clusters_start: usize,
+        glyphs_start: usize,
+        normalized_coords_range: Range<usize>,
+        font_metrics: FontMetrics,
+    ) {
         let shaped_clusters_range = shaped_clusters_start as u32..self.shaped_clusters.len() as u32;
         let run_advance = self.shaped_clusters[shaped_clusters_start..]
             .iter()
@@ -377,7 +603,7 @@ impl ShapedText {
 
         self.runs.push(ShapedRun {
             range,
-            font_size: options.font_size,
+            font_size,
             font_index,
             characters_range: characters_start as u32..self.characters.len() as u32,
             shaped_clusters_range,
@@ -390,8 +616,27 @@ impl ShapedText {
     }
 }
 
+fn u32_range(range: &Range<u32>) -> Range<usize> {
+    range.start as usize..range.end as usize
+}
+
+/// Whether `range` is a valid range into a slice of length `len`.
+fn range_within(range: Range<usize>, len: usize) -> bool {
+    range.start <= range.end && range.end <= len
+}
+
+/// The glyphs to push a run from.
+#[derive(Clone, Copy)]
+pub(crate) enum RunGlyphs<'a> {
+    /// The output of shaping the run with `HarfRust`.
+    Shaped(&'a harfrust::GlyphBuffer),
+    /// The cached words making up the run, in logical order.
+    Cached(&'a [Arc<ShapedWord>]),
+}
+
 /// One shaped run, belonging to a [`ShapedText`].
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShapedRun {
     /// The range of text this run corresponds to.
     pub range: TextRange,
@@ -429,6 +674,7 @@ pub struct ShapedRun {
 ///
 /// ## Input Parameters:
 /// * `scale_factor` - Scaling factor used to convert font units to the target size.
+/// * `embolden_strength` - Width added to the advance of each advancing glyph for faux bold.
 /// * `glyph_infos` - `HarfRust` glyph information in logical order (i.e., reversed for RTL runs).
 /// * `glyph_positions` - `HarfRust` glyph positioning data in logical order (i.e., reversed for RTL
 ///   runs).
@@ -440,6 +686,7 @@ fn process_shaped_clusters<'a>(
     shaped_clusters: &mut Vec<ShapedCluster>,
     glyphs: &mut Vec<Glyph>,
     scale_factor: f32,
+    embolden_strength: f32,
     glyph_infos: impl Iterator<Item = &'a harfrust::GlyphInfo>,
     glyph_positions: impl Iterator<Item = &'a harfrust::GlyphPosition>,
     char_style_indices: &[u16],
@@ -453,6 +700,7 @@ fn process_shaped_clusters<'a>(
         glyphs: u32,
         inline_glyph: Option<Glyph>,
         advance: f32,
+        unsafe_to_break: bool,
     }
 
     /// Flush `cluster`, whose characters end at `char_end`, onto `shaped_clusters`.
@@ -485,8 +733,7 @@ fn process_shaped_clusters<'a>(
             style_index,
             flags: ShapedClusterFlags::new(glyph_len)
                 .with_grapheme_start(first_character.grapheme_start)
-                // TODO: fill with actual shaping data (`parley` currently just ignores this)
-                .with_safe_to_break_before(false)
+                .with_safe_to_break_before(!cluster.unsafe_to_break)
                 .with_inline_glyph(inline_glyph),
             glyph_offset,
             advance,
@@ -500,6 +747,7 @@ fn process_shaped_clusters<'a>(
         glyphs: 0,
         inline_glyph: None,
         advance: 0.,
+        unsafe_to_break: false,
     };
 
     for (glyph_info, glyph_pos) in glyph_infos.zip(glyph_positions) {
@@ -521,15 +769,20 @@ fn process_shaped_clusters<'a>(
                 glyphs: 0,
                 inline_glyph: None,
                 advance: 0.,
+                unsafe_to_break: false,
             };
         }
 
+        let mut advance = (glyph_pos.x_advance as f32) * scale_factor;
+        if glyph_pos.x_advance != 0 {
+            advance += embolden_strength;
+        }
         let glyph = Glyph {
             id: glyph_info.glyph_id,
             x: (glyph_pos.x_offset as f32) * scale_factor,
             // Convert from font space (Y-up) to layout space (Y-down)
             y: -(glyph_pos.y_offset as f32) * scale_factor,
-            advance: (glyph_pos.x_advance as f32) * scale_factor,
+            advance,
         };
         if cluster.glyphs == 0 && glyph.x == 0. && glyph.y == 0. {
             // Defer this potential zero-offset, single glyph cluster
@@ -542,6 +795,7 @@ fn process_shaped_clusters<'a>(
         }
         cluster.advance += glyph.advance;
         cluster.glyphs += 1;
+        cluster.unsafe_to_break |= glyph_info.unsafe_to_break();
     }
 
     // Flush the final cluster.
@@ -559,7 +813,7 @@ fn process_shaped_clusters<'a>(
 mod tests {
     use alloc::{sync::Arc, vec, vec::Vec};
 
-    use fontique::Synthesis;
+    use fontique::{RenderHints, Synthesis};
     use linebender_resource_handle::{Blob, FontData};
 
     use crate::{Analysis, AnalysisOptions, Analyzer, FontInstance, ShapeOptions, Shaper};
@@ -589,6 +843,10 @@ mod tests {
         FontInstance {
             font: FontData::new(Blob::new(Arc::new(font_data)), 0),
             synthesis: Synthesis::default(),
+            features: None,
+            size_adjust: 1.0,
+            optical_size: None,
+            render_hints: RenderHints::default(),
         }
     }
 
@@ -703,6 +961,131 @@ mod tests {
         }
     }
 
+    fn shape(text: &str, font: &FontInstance, shaper: &mut Shaper) -> ShapedText {
+        let analysis = analyze(text);
+        let mut shaped = ShapedText::new();
+        for item in analysis.itemize(text, |_| false) {
+            shape_item_with_font(text, &analysis, &item, font, shaper, &mut shaped);
+        }
+        shaped
+    }
+
+    /// Text assembled from cached words shapes the same as text shaped from scratch.
+    #[test]
+    fn cached_words_match_shaping() {
+        let roboto = font_instance(ROBOTO);
+        let kufi = font_instance(NOTO_KUFI_ARABIC);
+        // Later texts are assembled from the words of earlier ones.
+        let texts = [
+            ("Cancel OK", &roboto),
+            ("OK Cancel office", &roboto),
+            ("Cancel office", &roboto),
+            ("Cancel OK", &roboto),
+            (
+                "\u{0645}\u{0631}\u{062D}\u{0628}\u{0627} \u{0628}\u{0643}",
+                &kufi,
+            ),
+            (
+                "\u{0645}\u{0631}\u{062D}\u{0628}\u{0627} \u{0645}\u{0631}\u{062D}\u{0628}\u{0627} \u{0628}\u{0643}",
+                &kufi,
+            ),
+        ];
+        let mut shaper = Shaper::default();
+        shaper.set_word_cache_size(64);
+        for (text, font) in texts {
+            assert_eq!(
+                shape(text, font, &mut shaper),
+                shape(text, font, &mut Shaper::default()),
+                "{text}"
+            );
+        }
+    }
+
+    /// Long segments are cached as words, but not whole.
+    #[test]
+    fn long_segments_cached_as_words() {
+        let mut shaper = Shaper::default();
+        shaper.set_word_cache_size(64);
+        shape(
+            &"lorem ipsum dolor sit amet ".repeat(4),
+            &font_instance(ROBOTO),
+            &mut shaper,
+        );
+        // At most the five distinct words are cached.
+        let cached = shaper.cached_words();
+        assert!((1..=5).contains(&cached), "{cached} cached words");
+    }
+
+    /// A forked shaper reuses the words of the shaper it was forked from, and the words it shapes
+    /// are kept once it is merged back.
+    #[test]
+    fn forked_shapers_share_words() {
+        let roboto = font_instance(ROBOTO);
+        let mut shaper = Shaper::default();
+        shaper.set_word_cache_size(64);
+        shape("Cancel OK", &roboto, &mut shaper);
+        let cached = shaper.cached_words();
+        assert!(cached > 0);
+
+        let mut fork = shaper.fork();
+        assert_eq!(
+            shape("Cancel OK", &roboto, &mut fork),
+            shape("Cancel OK", &roboto, &mut Shaper::default())
+        );
+        assert_eq!(fork.cached_words(), 0, "the words should be reused");
+
+        shape("office", &roboto, &mut fork);
+        assert!(fork.cached_words() > 0);
+        shaper.merge(fork);
+        assert!(shaper.cached_words() > cached);
+    }
+
+    /// Items shaped separately and appended in order match items shaped into one `ShapedText`.
+    #[test]
+    fn appended_items_match_shaping() {
+        let text = "Hello \u{0645}\u{0631}\u{062D}\u{0628}\u{0627} world\nffi";
+        let analysis = analyze(text);
+        let roboto = font_instance(ROBOTO);
+        let kufi = font_instance(NOTO_KUFI_ARABIC);
+        let items: Vec<_> = analysis.itemize(text, |_| false).collect();
+        assert!(items.len() > 1);
+        let font_for = |item: &crate::itemize::Item| {
+            if item.bidi_level.is_rtl() {
+                &kufi
+            } else {
+                &roboto
+            }
+        };
+
+        let mut shaper = Shaper::default();
+        let mut expected = ShapedText::new();
+        for item in &items {
+            shape_item_with_font(
+                text,
+                &analysis,
+                item,
+                font_for(item),
+                &mut shaper,
+                &mut expected,
+            );
+        }
+
+        let mut appended = ShapedText::new();
+        for item in &items {
+            let mut shaped = ShapedText::new();
+            shape_item_with_font(
+                text,
+                &analysis,
+                item,
+                font_for(item),
+                &mut shaper,
+                &mut shaped,
+            );
+            appended.append(&shaped);
+        }
+        assert_eq!(appended, expected);
+    }
+
     /// An itemization boundary forces a cluster break in shaping, and must force a grapheme start.
     #[test]
     fn item_boundaries_force_grapheme_start() {
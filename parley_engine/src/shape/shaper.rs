@@ -3,19 +3,27 @@
 
 //! Shaping of text.
 
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::{mem, ops::Range};
 use harfrust::ShapeOptions as HarfShapeOptions;
 use linebender_resource_handle::FontData;
-use parlance::{FontFeature, FontVariation, Language};
+use parlance::{FontFeature, FontVariation, Language, Tag};
 
 use crate::{
     Analysis, CharInfo, ShapedText,
     itemize::{Item, TextRange},
     lru_cache::LruCache,
-    shape::{CharCluster, cache},
+    shape::{
+        CharCluster, cache,
+        shaped_text::RunGlyphs,
+        word_cache::{ShapedWord, WordCache, WordStyle},
+    },
 };
 
+/// The tag of the optical size axis.
+const OPTICAL_SIZE: Tag = Tag::new(b"opsz");
+
 /// Shaping options for one item.
 ///
 /// These are styling options relevant for shaping. They're styling, in that they're not derived
@@ -48,15 +56,46 @@ pub struct FontInstance {
     // TODO: Synthesis carries more than we need, and ties us to `fontique`. We can likely change
     // this to opaque user data.
     pub synthesis: fontique::Synthesis,
+    /// Font features applied before the item's [features][`ShapeOptions::features`].
+    ///
+    /// These are the defaults of a font registered with a
+    /// [`fontique::FontFaceDescriptor`].
+    pub features: Option<Arc<[FontFeature]>>,
+    /// Factor to scale the item's [font size][`ShapeOptions::font_size`] by for this font.
+    ///
+    /// This is `1.0` unless the font's size is adjusted to match the x-height or cap height of
+    /// other fonts.
+    pub size_adjust: f32,
+    /// The value of the `opsz` axis, applied before the item's
+    /// [variations][`ShapeOptions::variations`].
+    ///
+    /// This is usually the font size before the display scale is applied, for
+    /// fonts with automatic optical sizing.
+    pub optical_size: Option<f32>,
+    /// Rendering settings the system configures for the font.
+    ///
+    /// This doesn't affect shaping, but is carried through to the shaped runs for renderers.
+    pub render_hints: fontique::RenderHints,
 }
 
 /// Reusable scratch to shape [items][`Item`] into shaped text using [`Self::shape_item`].
 pub struct Shaper {
     shape_data_cache: LruCache<cache::ShapeDataKey, harfrust::ShaperData>,
     shape_instance_cache: LruCache<cache::ShapeInstanceId, harfrust::ShaperInstance>,
-    shape_plan_cache: LruCache<cache::ShapePlanId, harfrust::ShapePlan>,
+    shape_plan_cache: LruCache<cache::ShapePlanId, Arc<harfrust::ShapePlan>>,
+    #[cfg(feature = "std")]
+    shared_plan_cache: Option<cache::SharedShapePlanCache>,
+    /// The words this shaper cached, shared with the shapers [forked](Self::fork) from it.
+    pub(crate) word_cache: Arc<WordCache>,
+    /// The words of the shaper this one was forked from.
+    base_words: Option<Arc<WordCache>>,
+    /// Scratch space for the cached words of a segment.
+    words: Vec<Arc<ShapedWord>>,
+    /// Scratch space for the font segments of an item.
+    segments: Vec<FontSegment>,
     unicode_buffer: Option<harfrust::UnicodeBuffer>,
     features: Vec<harfrust::Feature>,
+    variations: Vec<FontVariation>,
     char_cluster: CharCluster,
 }
 
@@ -67,13 +106,35 @@ impl Default for Shaper {
             shape_data_cache: LruCache::new(MAX_ENTRIES),
             shape_instance_cache: LruCache::new(MAX_ENTRIES),
             shape_plan_cache: LruCache::new(MAX_ENTRIES),
+            #[cfg(feature = "std")]
+            shared_plan_cache: None,
+            word_cache: Arc::new(WordCache::new(0)),
+            base_words: None,
+            words: Vec::new(),
+            segments: Vec::new(),
             unicode_buffer: Some(harfrust::UnicodeBuffer::new()),
             features: Vec::new(),
+            variations: Vec::new(),
             char_cluster: CharCluster::default(),
         }
     }
 }
 
+/// Clones the shaper's settings, its shared plan cache, the plans it cached and the words it
+/// cached. The shaping data of fonts is created again as it's needed.
+impl Clone for Shaper {
+    fn clone(&self) -> Self {
+        Self {
+            shape_plan_cache: self.shape_plan_cache.clone(),
+            #[cfg(feature = "std")]
+            shared_plan_cache: self.shared_plan_cache.clone(),
+            word_cache: self.word_cache.clone(),
+            base_words: self.base_words.clone(),
+            ..Self::default()
+        }
+    }
+}
+
 impl core::fmt::Debug for Shaper {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Shaper").finish_non_exhaustive()
@@ -81,6 +142,81 @@ impl core::fmt::Debug for Shaper {
 }
 
 impl Shaper {
+    /// Drops the cached shaping data of fonts.
+    ///
+    /// Fonts are cached by the identifier of their data, so this is only
+    /// needed to release memory, for example when the fonts of a collection
+    /// were replaced. A shared plan cache is left as is, since other shapers
+    /// may still be using it.
+    pub fn clear_caches(&mut self) {
+        self.shape_data_cache.clear();
+        self.shape_instance_cache.clear();
+        self.shape_plan_cache.clear();
+        Arc::make_mut(&mut self.word_cache).clear();
+        self.base_words = None;
+    }
+
+    /// Creates a shaper to shape segments on another thread, sharing the caches of this one.
+    ///
+    /// The new shaper uses the same shared plan cache and word cache size, starts with the plans
+    /// this shaper has cached, and reuses the words this shaper has cached. The words it shapes
+    /// itself can be kept for later with [`Self::merge`].
+    pub fn fork(&self) -> Self {
+        Self {
+            shape_plan_cache: self.shape_plan_cache.clone(),
+            #[cfg(feature = "std")]
+            shared_plan_cache: self.shared_plan_cache.clone(),
+            word_cache: Arc::new(WordCache::new(self.word_cache.max_words())),
+            base_words: self
+                .word_cache
+                .is_enabled()
+                .then(|| self.word_cache.clone()),
+            ..Self::default()
+        }
+    }
+
+    /// Keeps the words that `fork`, created with [`Self::fork`], shaped, for reuse by this shaper.
+    pub fn merge(&mut self, fork: Self) {
+        let Self {
+            word_cache,
+            base_words,
+            ..
+        } = fork;
+        // Release the fork's reference to the words of this shaper, so they aren't copied.
+        drop(base_words);
+        let words = Arc::try_unwrap(word_cache).unwrap_or_else(|words| (*words).clone());
+        if self.word_cache.is_enabled() {
+            Arc::make_mut(&mut self.word_cache).absorb(words);
+        }
+    }
+
+    /// Sets the maximum number of shaped words to keep for reuse, or `0` to not keep any, which
+    /// is the default.
+    ///
+    /// With this, text that is shaped again with the same font and shaping options, such as the
+    /// labels of a user interface laid out every frame, reuses the glyphs it was shaped into
+    /// before. Text is split into words at its line break opportunities, and a word is only
+    /// reused if it was safe to break the text it was shaped in before and after it, so reused
+    /// words shape the same as they would in the new text. Short texts are also kept whole, and
+    /// words or texts longer than 64 characters aren't kept.
+    pub fn set_word_cache_size(&mut self, max_words: usize) {
+        Arc::make_mut(&mut self.word_cache).set_max_words(max_words);
+    }
+
+    /// Returns the number of shaped words kept for reuse.
+    pub fn cached_words(&self) -> usize {
+        self.word_cache.len()
+    }
+
+    /// Sets a cache of shaping plans shared with shapers on other threads.
+    ///
+    /// Plans that aren't in this shaper's own cache are looked up in the shared cache before
+    /// they are created.
+    #[cfg(feature = "std")]
+    pub fn set_shared_plan_cache(&mut self, cache: Option<cache::SharedShapePlanCache>) {
+        self.shared_plan_cache = cache;
+    }
+
     /// Shape an [`Item`] produced by [`Analysis::itemize`] into glyphs.
     ///
     /// The item is broken into runs of maximal sequences of character clusters for which
@@ -109,33 +245,100 @@ impl Shaper {
         select_font: impl FnMut(&mut CharCluster) -> Option<FontInstance>,
         shaped_text: &mut ShapedText,
     ) -> Range<usize> {
-        shaped_text.reserve(item.range.char_range.len());
+        let mut segments = mem::take(&mut self.segments);
+        segments.clear();
+        self.select_fonts(text, analysis, item, options, select_font, &mut segments);
+        let runs = self.shape_segments(text, analysis, item, options, &segments, shaped_text);
+        self.segments = segments;
+        runs
+    }
 
-        let start = shaped_text.runs().len();
-        let _ = shape_item(
-            self,
+    /// Select the fonts of an [`Item`] produced by [`Analysis::itemize`], without shaping it.
+    ///
+    /// This splits the item into [`FontSegment`]s the same way as [`Self::shape_item`] and appends
+    /// them to `segments`, so that they can be shaped later with [`Self::shape_segments`], possibly
+    /// by another `Shaper`. This allows selecting fonts, which may need mutable access to a font
+    /// collection, separately from shaping.
+    ///
+    /// Returns `false` if selection was aborted because `select_font` returned `None`. `segments`
+    /// then contains the segments selected before that.
+    pub fn select_fonts(
+        &mut self,
+        text: &str,
+        analysis: &Analysis,
+        item: &Item,
+        options: &ShapeOptions<'_>,
+        select_font: impl FnMut(&mut CharCluster) -> Option<FontInstance>,
+        segments: &mut Vec<FontSegment>,
+    ) -> bool {
+        select_fonts(
+            &mut self.char_cluster,
             text,
             item,
-            options,
+            options.char_style_indices,
             select_font,
             analysis.char_info(),
-            shaped_text,
-        );
+            segments,
+        )
+        .is_ok()
+    }
+
+    /// Shape the [`FontSegment`]s of an item, as selected by [`Self::select_fonts`].
+    ///
+    /// The arguments are those the segments were selected with. The shaped runs are appended to
+    /// `shaped_text`, and their index range is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the font of a segment isn't a parseable font.
+    pub fn shape_segments(
+        &mut self,
+        text: &str,
+        analysis: &Analysis,
+        item: &Item,
+        options: &ShapeOptions<'_>,
+        segments: &[FontSegment],
+        shaped_text: &mut ShapedText,
+    ) -> Range<usize> {
+        shaped_text.reserve(item.range.char_range.len());
+
+        let start = shaped_text.runs().len();
+        for segment in segments {
+            shape_segment(
+                self,
+                text,
+                item,
+                options,
+                analysis.char_info(),
+                segment,
+                shaped_text,
+            );
+        }
         start..shaped_text.runs().len()
     }
 }
 
-/// Shape one item.
+/// A span of an item's text to shape with one font, selected by [`Shaper::select_fonts`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontSegment {
+    /// The text range of the segment.
+    pub range: TextRange,
+    /// The font to shape the segment with.
+    pub font: FontInstance,
+}
+
+/// Select the fonts of one item, appending its font segments to `segments`.
 ///
-/// Returns `Err(())` if shaping should be aborted, which happens iff `select_font` returned `None`.
-fn shape_item(
-    scx: &mut Shaper,
+/// Returns `Err(())` if font selection should be aborted, which happens iff `select_font` returned
+/// `None`. The segment whose clusters were being selected is then not appended.
+fn select_fonts(
+    char_cluster: &mut CharCluster,
     text: &str,
     item: &Item,
-    options: &ShapeOptions<'_>,
+    char_style_indices: &[u16],
     mut select_font: impl FnMut(&mut CharCluster) -> Option<FontInstance>,
     char_info: &[CharInfo],
-    shaped_text: &mut ShapedText,
+    segments: &mut Vec<FontSegment>,
 ) -> Result<(), ()> {
     let text_range = &item.range.byte_range;
     let char_range = &item.range.char_range;
@@ -144,7 +347,7 @@ fn shape_item(
 
     // Only process current item
     let item_char_info = &char_info[char_range.start..char_range.end];
-    let item_char_style_indices = &options.char_style_indices[char_range.start..char_range.end];
+    let item_char_style_indices = &char_style_indices[char_range.start..char_range.end];
 
     if item_text.is_empty() {
         return Ok(()); // No clusters
@@ -155,7 +358,6 @@ fn shape_item(
         .copied()
         .zip(item_char_style_indices.iter().copied());
     let mut code_unit_offset_in_string = text_range.start;
-    let char_cluster = &mut scx.char_cluster;
 
     // Build an iterator of boundaries and consume the first segment to seed the loop
     let mut boundaries_iter = item_text
@@ -207,137 +409,226 @@ fn shape_item(
             }
         }
 
-        // Shape this font segment with harfrust
-        let segment_text = &item_text[segment_start_offset..segment_end_offset];
-        // Shape the entire segment text including newlines
-        // The line breaking algorithm will handle newlines automatically
-
-        // TODO: How do we want to handle errors like this?
-        let font_ref =
-            harfrust::FontRef::from_index(font.font.data.as_ref(), font.font.index).unwrap();
-
-        // Create harfrust shaper
-        let shaper_data = scx.shape_data_cache.entry(
-            cache::ShapeDataKey::new(font.font.data.id(), font.font.index),
-            || harfrust::ShaperData::new(&font_ref),
-        );
-        let instance = scx.shape_instance_cache.entry(
-            cache::ShapeInstanceKey::new(
-                font.font.data.id(),
-                font.font.index,
-                &font.synthesis,
-                Some(options.variations),
-            ),
-            || {
-                harfrust::ShaperInstance::from_variations(
-                    &font_ref,
-                    variations_iter(&font.synthesis, options.variations),
-                )
-            },
-        );
-
-        let direction = if item.bidi_level.is_rtl() {
-            harfrust::Direction::RightToLeft
-        } else {
-            harfrust::Direction::LeftToRight
-        };
-        let hb_script = script_to_harfrust(item.script);
-        let language = options
-            .language
-            .as_ref()
-            .and_then(|lang| lang.language().parse::<harfrust::Language>().ok());
-        scx.features.clear();
-        for feature in options.features {
-            scx.features.push(harfrust::Feature::new(
-                harfrust::Tag::new(&feature.tag.to_bytes()),
-                feature.value as u32,
-                ..,
-            ));
-        }
-        let harf_shaper = shaper_data
-            .shaper(&font_ref)
-            .instance(Some(instance))
-            .build();
-        let shaper_plan = scx.shape_plan_cache.entry(
-            cache::ShapePlanKey::new(
-                font.font.data.id(),
-                font.font.index,
-                &font.synthesis,
-                direction,
-                hb_script,
-                language.clone(),
-                &scx.features,
-                Some(options.variations),
-            ),
-            || {
-                harfrust::ShapePlan::new(
-                    &harf_shaper,
-                    direction,
-                    Some(hb_script),
-                    language.as_ref(),
-                    &scx.features,
-                )
+        let char_start = char_range.start + item_text[..segment_start_offset].chars().count();
+        let segment_char_count = item_text[segment_start_offset..segment_end_offset]
+            .chars()
+            .count();
+        segments.push(FontSegment {
+            range: TextRange {
+                byte_range: (text_range.start + segment_start_offset)
+                    ..(text_range.start + segment_end_offset),
+                char_range: char_start..char_start + segment_char_count,
             },
-        );
-
-        // Prepare harfrust buffer
-        let mut buffer = mem::take(&mut scx.unicode_buffer).unwrap();
-        buffer.clear();
-        buffer.set_cluster_level(harfrust::BufferClusterLevel::MonotoneCharacters);
-
-        // Use the entire segment text including newlines
-        buffer.reserve(segment_text.len());
-        #[expect(clippy::cast_possible_truncation, reason = "Deferred")]
-        for (i, ch) in segment_text.chars().enumerate() {
-            // Ensure that each cluster's index matches the index into `infos`. This is required
-            // for efficient cluster lookup within `data.rs`.
-            //
-            // In other words, instead of using `buffer.push_str`, which iterates `segment_text`
-            // with `char_indices`, push each char individually via `.chars` with a cluster index
-            // that matches its `infos` counterpart. This allows us to lookup `infos` via cluster
-            // index in `data.rs`.
-            buffer.add(ch, i as u32);
-        }
-
-        buffer.set_direction(direction);
+            font,
+        });
+    }
 
-        buffer.set_script(hb_script);
+    Ok(())
+}
 
-        if let Some(lang) = language {
-            buffer.set_language(lang);
-        }
+/// Shape one font segment of an item.
+fn shape_segment(
+    scx: &mut Shaper,
+    text: &str,
+    item: &Item,
+    options: &ShapeOptions<'_>,
+    char_info: &[CharInfo],
+    segment: &FontSegment,
+    shaped_text: &mut ShapedText,
+) {
+    let font = &segment.font;
+    let range = segment.range.clone();
+    // Shape this font segment with harfrust
+    let segment_text = &text[range.byte_range.clone()];
+    // Shape the entire segment text including newlines
+    // The line breaking algorithm will handle newlines automatically
+
+    // TODO: How do we want to handle errors like this?
+    let font_ref = harfrust::FontRef::from_index(font.font.data.as_ref(), font.font.index).unwrap();
+
+    let variations = if let Some(optical_size) = font.optical_size {
+        // Later values override earlier values, so explicit `opsz` variations take precedence.
+        scx.variations.clear();
+        scx.variations
+            .push(FontVariation::new(OPTICAL_SIZE, optical_size));
+        scx.variations.extend_from_slice(options.variations);
+        scx.variations.as_slice()
+    } else {
+        options.variations
+    };
 
-        let glyph_buffer = harf_shaper.shape(
-            buffer,
-            HarfShapeOptions::new()
-                .plan(Some(shaper_plan))
-                .features(&scx.features)
-                .point_size(Some(options.font_size)),
-        );
+    // Create harfrust shaper
+    let shaper_data = scx.shape_data_cache.entry(
+        cache::ShapeDataKey::new(font.font.data.id(), font.font.index),
+        || harfrust::ShaperData::new(&font_ref),
+    );
+    let instance = scx.shape_instance_cache.entry(
+        cache::ShapeInstanceKey::new(
+            font.font.data.id(),
+            font.font.index,
+            &font.synthesis,
+            Some(variations),
+        ),
+        || {
+            harfrust::ShaperInstance::from_variations(
+                &font_ref,
+                variations_iter(&font.synthesis, variations),
+            )
+        },
+    );
 
-        let char_start = char_range.start + item_text[..segment_start_offset].chars().count();
-        let segment_char_count = segment_text.chars().count();
-        let range = TextRange {
-            byte_range: (item.range.byte_range.start + segment_start_offset)
-                ..(item.range.byte_range.start + segment_end_offset),
-            char_range: char_start..char_start + segment_char_count,
-        };
+    let direction = if item.bidi_level.is_rtl() {
+        harfrust::Direction::RightToLeft
+    } else {
+        harfrust::Direction::LeftToRight
+    };
+    let hb_script = script_to_harfrust(item.script);
+    let language = options
+        .language
+        .as_ref()
+        .and_then(|lang| lang.language().parse::<harfrust::Language>().ok());
+    scx.features.clear();
+    let font_features = font.features.as_deref().unwrap_or_default();
+    for feature in font_features.iter().chain(options.features) {
+        scx.features.push(harfrust::Feature::new(
+            harfrust::Tag::new(&feature.tag.to_bytes()),
+            feature.value as u32,
+            ..,
+        ));
+    }
+    let harf_shaper = shaper_data
+        .shaper(&font_ref)
+        .instance(Some(instance))
+        .build();
+
+    let segment_char_info = &char_info[range.char_range.clone()];
+
+    let word_style = scx.word_cache.is_enabled().then(|| WordStyle {
+        font_blob_id: font.font.data.id(),
+        font_index: font.font.index,
+        synthesis: &font.synthesis,
+        font_size: options.font_size * font.size_adjust,
+        direction,
+        script: hb_script,
+        language: language.as_ref(),
+        features: &scx.features,
+        variations,
+    });
+    if let Some(word_style) = &word_style
+        && Arc::make_mut(&mut scx.word_cache).get(
+            scx.base_words.as_deref(),
+            word_style,
+            segment_text,
+            segment_char_info,
+            &mut scx.words,
+        )
+    {
         shaped_text.push_run(
             text,
             range,
             item,
             options,
             char_info,
-            &font,
-            &glyph_buffer,
+            font,
+            RunGlyphs::Cached(&scx.words),
             harf_shaper.coords(),
         );
+        scx.words.clear();
+        return;
+    }
 
-        // Replace buffer to reuse allocation in next iteration.
-        scx.unicode_buffer = Some(glyph_buffer.clear());
+    let plan_key = cache::ShapePlanKey::new(
+        font.font.data.id(),
+        font.font.index,
+        &font.synthesis,
+        direction,
+        hb_script,
+        language.clone(),
+        &scx.features,
+        Some(variations),
+    );
+    let make_plan = || {
+        harfrust::ShapePlan::new(
+            &harf_shaper,
+            direction,
+            Some(hb_script),
+            language.as_ref(),
+            &scx.features,
+        )
+    };
+    #[cfg(feature = "std")]
+    let shared_plan_cache = scx.shared_plan_cache.as_ref();
+    let shaper_plan = scx.shape_plan_cache.entry(plan_key.clone(), || {
+        #[cfg(feature = "std")]
+        if let Some(shared_plan_cache) = shared_plan_cache {
+            return shared_plan_cache.get_or_insert(plan_key, make_plan);
+        }
+        Arc::new(make_plan())
+    });
+
+    // Prepare harfrust buffer
+    let mut buffer = mem::take(&mut scx.unicode_buffer).unwrap();
+    buffer.clear();
+    buffer.set_cluster_level(harfrust::BufferClusterLevel::MonotoneCharacters);
+
+    // Use the entire segment text including newlines
+    buffer.reserve(segment_text.len());
+    #[expect(clippy::cast_possible_truncation, reason = "Deferred")]
+    for (i, ch) in segment_text.chars().enumerate() {
+        // Ensure that each cluster's index matches the index into `infos`. This is required
+        // for efficient cluster lookup within `data.rs`.
+        //
+        // In other words, instead of using `buffer.push_str`, which iterates `segment_text`
+        // with `char_indices`, push each char individually via `.chars` with a cluster index
+        // that matches its `infos` counterpart. This allows us to lookup `infos` via cluster
+        // index in `data.rs`.
+        buffer.add(ch, i as u32);
     }
 
-    Ok(())
+    buffer.set_direction(direction);
+
+    buffer.set_script(hb_script);
+
+    if let Some(lang) = &language {
+        buffer.set_language(lang.clone());
+    }
+
+    let glyph_buffer = harf_shaper.shape(
+        buffer,
+        HarfShapeOptions::new()
+            .plan(Some(&**shaper_plan))
+            .features(&scx.features)
+            .point_size(Some(options.font_size * font.size_adjust)),
+    );
+
+    let runs_len = shaped_text.runs().len();
+    shaped_text.push_run(
+        text,
+        range,
+        item,
+        options,
+        char_info,
+        font,
+        RunGlyphs::Shaped(&glyph_buffer),
+        harf_shaper.coords(),
+    );
+    if let Some(word_style) = &word_style
+        && let Some(run) = shaped_text.runs().get(runs_len)
+    {
+        let clusters = &shaped_text.shaped_clusters()
+            [run.shaped_clusters_range.start as usize..run.shaped_clusters_range.end as usize];
+        Arc::make_mut(&mut scx.word_cache).insert(
+            word_style,
+            segment_text,
+            segment_char_info,
+            clusters,
+            shaped_text.glyphs(),
+            run.characters_range.start,
+        );
+    }
+
+    // Replace buffer to reuse allocation in next iteration.
+    scx.unicode_buffer = Some(glyph_buffer.clear());
 }
 
 #[inline]
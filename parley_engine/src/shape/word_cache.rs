@@ -0,0 +1,416 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A cache of shaped words, reused across the layouts a [`Shaper`] shapes.
+//!
+//! Segments of text shaped with one font are split into words at their line break opportunities.
+//! A word is only stored when the shaped clusters at both of its edges are safe to break before,
+//! so that shaping it on its own gives the same glyphs as shaping it as part of the segment. A
+//! segment is then assembled from cached words only if all of its words are cached; otherwise it
+//! is shaped as a whole. Short segments are also stored whole, but long ones, such as the
+//! paragraphs of a document, are only stored as words.
+//!
+//! [`Shaper`]: crate::Shaper
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::hash::{Hash, Hasher};
+
+use hashbrown::{Equivalent, HashMap};
+use parlance::FontVariation;
+
+use super::data::ShapedCluster;
+use crate::{Boundary, CharInfo, Glyph};
+
+/// The maximum number of characters of a cached word or whole segment.
+///
+/// Segments are rarely shaped again unless they are short, so longer ones would only take up
+/// memory, and a single entry could hold the glyphs of a whole paragraph.
+const MAX_WORD_CHARS: usize = 64;
+
+/// The shaped clusters and glyphs of one word.
+pub(crate) struct ShapedWord {
+    /// The number of characters of the word.
+    pub(crate) char_len: u32,
+    /// The clusters of the word, in logical order.
+    ///
+    /// Their character ranges are relative to the start of the word, and the glyph offsets of
+    /// clusters without an inline glyph are relative to the start of [`Self::glyphs`]. The style
+    /// index and grapheme start of each cluster are those of the text it is reused for.
+    pub(crate) clusters: Box<[ShapedCluster]>,
+    /// The glyphs of the clusters without an inline glyph.
+    pub(crate) glyphs: Box<[Glyph]>,
+}
+
+/// The shaping parameters shared by all words of a segment.
+pub(crate) struct WordStyle<'a> {
+    /// The font collection's blob ID.
+    pub(crate) font_blob_id: u64,
+    /// The font's index in the font collection.
+    pub(crate) font_index: u32,
+    pub(crate) synthesis: &'a fontique::Synthesis,
+    /// The font size, after size adjustment.
+    pub(crate) font_size: f32,
+    pub(crate) direction: harfrust::Direction,
+    pub(crate) script: harfrust::Script,
+    pub(crate) language: Option<&'a harfrust::Language>,
+    pub(crate) features: &'a [harfrust::Feature],
+    pub(crate) variations: &'a [FontVariation],
+}
+
+struct WordKey<'a> {
+    style: &'a WordStyle<'a>,
+    text: &'a str,
+}
+
+#[derive(Clone)]
+struct WordId {
+    text: Box<str>,
+    font_blob_id: u64,
+    font_index: u32,
+    synthesis: fontique::Synthesis,
+    font_size: f32,
+    direction: harfrust::Direction,
+    script: harfrust::Script,
+    language: Option<harfrust::Language>,
+    features: Box<[harfrust::Feature]>,
+    variations: Box<[FontVariation]>,
+}
+
+/// Hashes the parts of a word's key that tell words apart the most.
+///
+/// This must hash the same for a [`WordKey`] and the [`WordId`] made from it.
+fn hash_word<H: Hasher>(state: &mut H, text: &str, font_blob_id: u64, font_size: f32) {
+    text.hash(state);
+    font_blob_id.hash(state);
+    font_size.to_bits().hash(state);
+}
+
+impl Hash for WordKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_word(
+            state,
+            self.text,
+            self.style.font_blob_id,
+            self.style.font_size,
+        );
+    }
+}
+
+impl Hash for WordId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_word(state, &self.text, self.font_blob_id, self.font_size);
+    }
+}
+
+impl Equivalent<WordId> for WordKey<'_> {
+    fn equivalent(&self, key: &WordId) -> bool {
+        let style = self.style;
+        self.text == &*key.text
+            && style.font_blob_id == key.font_blob_id
+            && style.font_index == key.font_index
+            && *style.synthesis == key.synthesis
+            && style.font_size.to_bits() == key.font_size.to_bits()
+            && style.direction == key.direction
+            && style.script == key.script
+            && style.language == key.language.as_ref()
+            && style.features.len() == key.features.len()
+            && style
+                .features
+                .iter()
+                .zip(key.features.iter())
+                .all(|(a, b)| a == b)
+            && style.variations == &*key.variations
+    }
+}
+
+impl PartialEq for WordId {
+    fn eq(&self, other: &Self) -> bool {
+        let style = WordStyle {
+            font_blob_id: other.font_blob_id,
+            font_index: other.font_index,
+            synthesis: &other.synthesis,
+            font_size: other.font_size,
+            direction: other.direction,
+            script: other.script,
+            language: other.language.as_ref(),
+            features: &other.features,
+            variations: &other.variations,
+        };
+        WordKey {
+            style: &style,
+            text: &other.text,
+        }
+        .equivalent(self)
+    }
+}
+
+// Font sizes and variations are compared by their bits, so this is reflexive.
+impl Eq for WordId {}
+
+impl<'a> From<WordKey<'a>> for WordId {
+    fn from(key: WordKey<'a>) -> Self {
+        let style = key.style;
+        Self {
+            text: key.text.into(),
+            font_blob_id: style.font_blob_id,
+            font_index: style.font_index,
+            synthesis: *style.synthesis,
+            font_size: style.font_size,
+            direction: style.direction,
+            script: style.script,
+            language: style.language.cloned(),
+            features: style.features.into(),
+            variations: style.variations.into(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Entry {
+    epoch: u64,
+    word: Arc<ShapedWord>,
+}
+
+/// A least-recently-used cache of shaped words.
+///
+/// Unlike [`LruCache`](crate::lru_cache::LruCache), this is meant to hold many entries, so they
+/// are hashed, and the least recently used half is evicted at once when it's full.
+#[derive(Clone)]
+pub(crate) struct WordCache {
+    entries: HashMap<WordId, Entry>,
+    epoch: u64,
+    max_words: usize,
+}
+
+impl WordCache {
+    pub(crate) fn new(max_words: usize) -> Self {
+        Self {
+            entries: HashMap::default(),
+            epoch: 0,
+            max_words,
+        }
+    }
+
+    /// Whether words are cached at all.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.max_words != 0
+    }
+
+    /// The maximum number of cached words.
+    pub(crate) fn max_words(&self) -> usize {
+        self.max_words
+    }
+
+    /// Sets the maximum number of cached words, evicting words if there are more.
+    pub(crate) fn set_max_words(&mut self, max_words: usize) {
+        self.max_words = max_words;
+        self.evict();
+    }
+
+    /// Removes all words.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns the number of cached words.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Moves the words of `other` into this cache, as if they were inserted last.
+    pub(crate) fn absorb(&mut self, other: Self) {
+        for (id, mut entry) in other.entries {
+            if !self.entries.contains_key(&id) {
+                self.epoch += 1;
+                entry.epoch = self.epoch;
+                self.entries.insert(id, entry);
+            }
+        }
+        self.evict();
+    }
+
+    /// Looks up the shaped words of the segment `text`, whose characters have `char_info`, in this
+    /// cache and then in `base`, which is only read.
+    ///
+    /// If all of them are cached, they are appended to `words` in logical order and this returns
+    /// `true`. Otherwise, `words` is left as is and this returns `false`.
+    pub(crate) fn get(
+        &mut self,
+        base: Option<&Self>,
+        style: &WordStyle<'_>,
+        text: &str,
+        char_info: &[CharInfo],
+        words: &mut Vec<Arc<ShapedWord>>,
+    ) -> bool {
+        let mut lookup = |text: &str| self.touch(style, text).or_else(|| base?.peek(style, text));
+        // Most segments that are shaped again are short strings, which are cached whole.
+        if char_info.len() <= MAX_WORD_CHARS
+            && let Some(word) = lookup(text)
+        {
+            words.push(word);
+            return true;
+        }
+        let len = words.len();
+        let mut start = 0;
+        for (_, end) in word_boundaries(text, char_info) {
+            let Some(word) = lookup(&text[start..end]) else {
+                words.truncate(len);
+                return false;
+            };
+            words.push(word);
+            start = end;
+        }
+        true
+    }
+
+    /// Stores the words of the segment `text`, whose characters have `char_info`, and which was
+    /// shaped into `clusters` and `glyphs`.
+    ///
+    /// `clusters` are the clusters of the segment's run, and the character ranges of the clusters
+    /// start at `characters_start`.
+    pub(crate) fn insert(
+        &mut self,
+        style: &WordStyle<'_>,
+        text: &str,
+        char_info: &[CharInfo],
+        clusters: &[ShapedCluster],
+        glyphs: &[Glyph],
+        characters_start: u32,
+    ) {
+        let char_len = char_info.len();
+        let cluster_start = |cluster: &ShapedCluster| cluster.chars_range.0 - characters_start;
+
+        // The segment was shaped without surrounding text, so it is always safe to cache whole,
+        // if it is short enough.
+        self.insert_word(style, text, clusters, glyphs, char_len);
+
+        // The start of the current word, as a character, byte and cluster index, if it is safe
+        // to break before.
+        let mut word_start = Some((0, 0, 0));
+        let mut cluster_idx = 0;
+        for (char_idx, byte_idx) in word_boundaries(text, char_info) {
+            while cluster_idx < clusters.len()
+                && (cluster_start(&clusters[cluster_idx]) as usize) < char_idx
+            {
+                cluster_idx += 1;
+            }
+            let is_safe = char_idx == char_len
+                || clusters.get(cluster_idx).is_some_and(|cluster| {
+                    cluster_start(cluster) as usize == char_idx && cluster.is_safe_to_break_before()
+                });
+            if let Some((start_char, start_byte, start_cluster)) = word_start
+                && is_safe
+                && (start_char, char_idx) != (0, char_len)
+            {
+                self.insert_word(
+                    style,
+                    &text[start_byte..byte_idx],
+                    &clusters[start_cluster..cluster_idx],
+                    glyphs,
+                    char_idx - start_char,
+                );
+            }
+            word_start = is_safe.then_some((char_idx, byte_idx, cluster_idx));
+        }
+        self.evict();
+    }
+
+    /// Returns the cached word `text`, marking it as recently used.
+    fn touch(&mut self, style: &WordStyle<'_>, text: &str) -> Option<Arc<ShapedWord>> {
+        let entry = self.entries.get_mut(&WordKey { style, text })?;
+        self.epoch += 1;
+        entry.epoch = self.epoch;
+        Some(entry.word.clone())
+    }
+
+    /// Returns the cached word `text`, without marking it as used.
+    fn peek(&self, style: &WordStyle<'_>, text: &str) -> Option<Arc<ShapedWord>> {
+        let entry = self.entries.get(&WordKey { style, text })?;
+        Some(entry.word.clone())
+    }
+
+    #[expect(clippy::cast_possible_truncation, reason = "Deferred")]
+    fn insert_word(
+        &mut self,
+        style: &WordStyle<'_>,
+        text: &str,
+        clusters: &[ShapedCluster],
+        glyphs: &[Glyph],
+        char_len: usize,
+    ) {
+        if char_len > MAX_WORD_CHARS {
+            return;
+        }
+        let key = WordKey { style, text };
+        if self.entries.contains_key(&key) {
+            return;
+        }
+        let Some(first_char) = clusters.first().map(|cluster| cluster.chars_range.0) else {
+            return;
+        };
+        let mut word_glyphs = Vec::new();
+        let word_clusters = clusters
+            .iter()
+            .map(|cluster| {
+                let mut cluster = *cluster;
+                cluster.chars_range = (
+                    cluster.chars_range.0 - first_char,
+                    cluster.chars_range.1 - first_char,
+                );
+                cluster.style_index = 0;
+                if !cluster.has_inline_glyph() {
+                    let start = cluster.glyph_offset as usize;
+                    cluster.glyph_offset = word_glyphs.len() as u32;
+                    word_glyphs
+                        .extend_from_slice(&glyphs[start..start + cluster.glyph_len() as usize]);
+                }
+                cluster
+            })
+            .collect();
+        self.epoch += 1;
+        self.entries.insert(
+            key.into(),
+            Entry {
+                epoch: self.epoch,
+                word: Arc::new(ShapedWord {
+                    char_len: char_len as u32,
+                    clusters: word_clusters,
+                    glyphs: word_glyphs.into(),
+                }),
+            },
+        );
+    }
+
+    /// Evicts the least recently used half of the words if there are more than allowed.
+    fn evict(&mut self) {
+        if self.entries.len() <= self.max_words {
+            return;
+        }
+        let keep = self.max_words / 2;
+        if keep == 0 {
+            self.entries.clear();
+            return;
+        }
+        let mut epochs: Vec<u64> = self.entries.values().map(|entry| entry.epoch).collect();
+        let cutoff_idx = epochs.len() - keep;
+        let (_, cutoff, _) = epochs.select_nth_unstable(cutoff_idx);
+        let cutoff = *cutoff;
+        self.entries.retain(|_, entry| entry.epoch >= cutoff);
+    }
+}
+
+/// Returns the character and byte indices of the ends of the words of a segment.
+///
+/// Words end at the line break opportunities of the segment, and at its end.
+fn word_boundaries<'a>(
+    text: &'a str,
+    char_info: &'a [CharInfo],
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    text.char_indices()
+        .zip(char_info)
+        .enumerate()
+        .skip(1)
+        .filter(|(_, (_, info))| matches!(info.boundary, Boundary::Line | Boundary::Mandatory))
+        .map(|(char_idx, ((byte_idx, _), _))| (char_idx, byte_idx))
+        .chain(core::iter::once((char_info.len(), text.len())))
+}
@@ -0,0 +1,115 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Baseline grid tests.
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::{BaselineGrid, Layout, StyleProperty, YieldData};
+
+const TEXT: &str = "The quick brown fox jumps over the lazy dog and keeps on running far away.";
+
+const GRID: BaselineGrid = BaselineGrid {
+    origin: 3.0,
+    increment: 14.0,
+};
+
+fn build_layout(env: &mut TestEnv, grid: Option<BaselineGrid>) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(TEXT);
+    builder.push_default(StyleProperty::FontSize(16.0));
+    // Make one of the words taller than the rest, so the lines don't all have the same height.
+    let start = TEXT.find("lazy").unwrap();
+    builder.push(StyleProperty::FontSize(30.0), start..start + "lazy".len());
+    let mut layout = builder.build(TEXT);
+    layout.set_baseline_grid(grid);
+    layout.break_all_lines(Some(150.0));
+    layout
+}
+
+fn assert_on_grid(baseline: f32) {
+    let steps = (baseline - GRID.origin) / GRID.increment;
+    assert_eq!(
+        steps,
+        steps.round(),
+        "baseline {baseline} is not on the baseline grid"
+    );
+}
+
+#[test]
+fn baseline_grid_snaps_all_lines() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let reference = build_layout(&mut env, None);
+    let layout = build_layout(&mut env, Some(GRID));
+
+    assert!(layout.len() > 2, "expected multiple lines");
+    assert_eq!(reference.len(), layout.len());
+    for (reference_line, line) in reference.lines().zip(layout.lines()) {
+        let metrics = line.metrics();
+        assert_on_grid(metrics.baseline);
+        assert!(
+            metrics.baseline >= reference_line.metrics().baseline,
+            "lines are only ever pushed down"
+        );
+    }
+
+    let last_line = layout.lines().next_back().unwrap();
+    assert_eq!(layout.height(), last_line.metrics().block_max_coord);
+}
+
+#[test]
+fn baseline_grid_lines_do_not_overlap() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let layout = build_layout(&mut env, Some(GRID));
+
+    let mut lines = layout.lines();
+    let mut previous = *lines.next().unwrap().metrics();
+    for line in lines {
+        let metrics = *line.metrics();
+        assert!(
+            metrics.block_min_coord >= previous.block_max_coord,
+            "lines should not overlap"
+        );
+        previous = metrics;
+    }
+}
+
+#[test]
+fn baseline_grid_committed_y() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let mut layout = build_layout(&mut env, Some(GRID));
+
+    let mut breaker = layout.break_lines();
+    breaker.state_mut().set_layout_max_advance(150.0);
+    breaker.state_mut().set_line_max_advance(150.0);
+    let mut line_starts = Vec::new();
+    while let Some(yield_data) = breaker.break_next() {
+        if let YieldData::LineBreak(line) = yield_data {
+            assert_eq!(breaker.committed_y(), line.line_y_end);
+            line_starts.push(line.line_y_start as f32);
+        }
+    }
+    breaker.finish();
+
+    assert_eq!(line_starts.len(), layout.len());
+    for (line, line_start) in layout.lines().zip(line_starts) {
+        assert_on_grid(line.metrics().baseline);
+        assert_eq!(line.metrics().block_min_coord, line_start);
+    }
+}
+
+#[test]
+fn baseline_grid_quantized() {
+    let mut env = TestEnv::new(test_name!(), None);
+    // The test environment's builder quantizes, and a fractional font size gives fractional line
+    // metrics, which rounding would otherwise move off the grid.
+    let mut builder = env.ranged_builder(TEXT);
+    builder.push_default(StyleProperty::FontSize(15.3));
+    let mut layout = builder.build(TEXT);
+    layout.set_baseline_grid(Some(GRID));
+    layout.break_all_lines(Some(150.0));
+
+    assert!(layout.len() > 1, "expected multiple lines");
+    for line in layout.lines() {
+        assert_on_grid(line.metrics().baseline);
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for CJK line breaking, punctuation trimming, autospacing and hanging punctuation.
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::{
+    Alignment, AlignmentOptions, HangingPunctuation, Layout, LineBreak, StyleProperty,
+    TextAutospace, TextSpacingTrim,
+};
+
+const FONT_SIZE: f32 = 20.0;
+
+fn build_layout(
+    env: &mut TestEnv,
+    text: &str,
+    styles: &[StyleProperty<'static, ColorBrush>],
+    max_advance: Option<f32>,
+    alignment: Alignment,
+) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(text);
+    builder.push_default(StyleProperty::FontSize(FONT_SIZE));
+    // The test fonts have no CJK glyphs. Widen the glyphs they fall back to, so that the
+    // punctuation is at least an em wide and treated as fullwidth.
+    builder.push_default(StyleProperty::LetterSpacing(FONT_SIZE));
+    for style in styles {
+        builder.push_default(style.clone());
+    }
+    let mut layout = builder.build(text);
+    layout.break_all_lines(max_advance);
+    layout.align(alignment, AlignmentOptions::default());
+    layout
+}
+
+fn line_advances(layout: &Layout<ColorBrush>) -> Vec<f32> {
+    layout.lines().map(|line| line.metrics().advance).collect()
+}
+
+fn assert_approx_eq(actual: f32, expected: f32, message: &str) {
+    assert!(
+        (actual - expected).abs() < 0.01,
+        "{message}: expected {expected}, got {actual}"
+    );
+}
+
+fn line_offsets(layout: &Layout<ColorBrush>) -> Vec<f32> {
+    layout.lines().map(|line| line.metrics().offset).collect()
+}
+
+#[test]
+fn cjk_line_break_strictness() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "あぁあぁ";
+
+    // Small kana can't start a line with the default, strict rules.
+    let strict = build_layout(&mut env, text, &[], Some(0.), Alignment::Start);
+    assert_eq!(strict.len(), 2);
+
+    let normal = build_layout(
+        &mut env,
+        text,
+        &[StyleProperty::LineBreak(LineBreak::Normal)],
+        Some(0.),
+        Alignment::Start,
+    );
+    assert_eq!(normal.len(), 4);
+}
+
+#[test]
+fn cjk_text_autospace() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let autospace = [StyleProperty::TextAutospace(TextAutospace::Normal)];
+    let text = "漢A漢";
+
+    let reference = build_layout(&mut env, text, &[], None, Alignment::Start);
+    let layout = build_layout(&mut env, text, &autospace, None, Alignment::Start);
+    assert_approx_eq(
+        layout.width() - reference.width(),
+        2. * FONT_SIZE / 8.,
+        "an eighth of an em should be added on both sides of the Latin letter",
+    );
+
+    // No space is added at the end of a line.
+    let reference = build_layout(&mut env, text, &[], Some(0.), Alignment::Start);
+    let layout = build_layout(&mut env, text, &autospace, Some(0.), Alignment::Start);
+    assert_eq!(layout.len(), 3);
+    for (advance, reference_advance) in line_advances(&layout)
+        .into_iter()
+        .zip(line_advances(&reference))
+    {
+        assert_approx_eq(advance, reference_advance, "line advance");
+    }
+}
+
+#[test]
+fn cjk_text_spacing_trim_adjacent_punctuation() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "（「漢」）「漢」";
+    let reference = build_layout(&mut env, text, &[], None, Alignment::Start);
+
+    // The pairs `（「`, `」）` and `）「` each lose one blank half.
+    let normal = build_layout(
+        &mut env,
+        text,
+        &[StyleProperty::TextSpacingTrim(TextSpacingTrim::Normal)],
+        None,
+        Alignment::Start,
+    );
+    assert_approx_eq(
+        reference.width() - normal.width(),
+        3. * FONT_SIZE / 2.,
+        "each adjacent pair of punctuation should be trimmed once",
+    );
+    assert_eq!(
+        line_offsets(&normal),
+        [0.],
+        "the start of a paragraph isn't trimmed"
+    );
+
+    // All six marks lose their blank half, including the one at the start of the line.
+    let trim_all = build_layout(
+        &mut env,
+        text,
+        &[StyleProperty::TextSpacingTrim(TextSpacingTrim::TrimAll)],
+        None,
+        Alignment::Start,
+    );
+    assert_approx_eq(
+        reference.width() - trim_all.width(),
+        6. * FONT_SIZE / 2.,
+        "all punctuation should be trimmed",
+    );
+    assert_eq!(line_offsets(&trim_all), [-FONT_SIZE / 2.]);
+}
+
+#[test]
+fn cjk_text_spacing_trim_line_start() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "漢「漢」";
+    let reference = build_layout(&mut env, text, &[], Some(0.), Alignment::Start);
+    assert_eq!(reference.len(), 2);
+    assert_eq!(line_offsets(&reference), [0., 0.]);
+
+    // Opening punctuation at the start of a wrapped line is trimmed.
+    let normal = build_layout(
+        &mut env,
+        text,
+        &[StyleProperty::TextSpacingTrim(TextSpacingTrim::Normal)],
+        Some(0.),
+        Alignment::Start,
+    );
+    assert_eq!(line_offsets(&normal), [0., -FONT_SIZE / 2.]);
+    let reference_advances = line_advances(&reference);
+    assert_eq!(
+        line_advances(&normal),
+        [
+            reference_advances[0],
+            reference_advances[1] - FONT_SIZE / 2.
+        ]
+    );
+
+    // Only `trim-start` also trims at the start of the paragraph.
+    let text = "「漢」";
+    let normal = build_layout(
+        &mut env,
+        text,
+        &[StyleProperty::TextSpacingTrim(TextSpacingTrim::Normal)],
+        None,
+        Alignment::Start,
+    );
+    assert_eq!(line_offsets(&normal), [0.]);
+    let trim_start = build_layout(
+        &mut env,
+        text,
+        &[StyleProperty::TextSpacingTrim(TextSpacingTrim::TrimStart)],
+        None,
+        Alignment::Start,
+    );
+    assert_eq!(line_offsets(&trim_start), [-FONT_SIZE / 2.]);
+}
+
+#[test]
+fn cjk_hanging_punctuation_allow_end() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let content_width = build_layout(&mut env, "漢漢漢", &[], None, Alignment::Start).width();
+    let max_advance = content_width + 0.5;
+    let text = "漢漢漢。";
+
+    // The full stop can't start a line, so the last ideograph moves to the next line with it.
+    let layout = build_layout(&mut env, text, &[], Some(max_advance), Alignment::Start);
+    assert_eq!(layout.len(), 2);
+
+    let layout = build_layout(
+        &mut env,
+        text,
+        &[StyleProperty::HangingPunctuation(
+            HangingPunctuation::AllowEnd,
+        )],
+        Some(max_advance),
+        Alignment::End,
+    );
+    assert_eq!(layout.len(), 1, "the full stop should hang instead");
+    let metrics = *layout.get(0).unwrap().metrics();
+    assert!(
+        metrics.advance > max_advance,
+        "the hanging full stop overflows the line"
+    );
+    assert_approx_eq(
+        metrics.offset,
+        0.5,
+        "the hanging full stop should be ignored when aligning",
+    );
+}
@@ -0,0 +1,84 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for emoji presentation with `font-variant-emoji`.
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::{FontFamily, FontInstance, FontVariantEmoji, GenericFamily, Layout, StyleProperty};
+
+/// Both test emoji fonts cover the test emoji. The CBTF font stands in for a text font, and the
+/// COLR font is the emoji font.
+const TEXT_FONT: &str = "Noto Color Emoji CBTF";
+const EMOJI_FONT: &str = "Noto Color Emoji";
+
+fn build_layout(
+    env: &mut TestEnv,
+    text: &str,
+    family: &str,
+    variant: FontVariantEmoji,
+) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(text);
+    builder.push_default(StyleProperty::FontFamily(FontFamily::named(family)));
+    builder.push_default(StyleProperty::FontVariantEmoji(variant));
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+    layout
+}
+
+fn first_font(layout: &Layout<ColorBrush>) -> FontInstance {
+    let line = layout.lines().next().unwrap();
+    line.runs().next().unwrap().font().clone()
+}
+
+/// Returns the fonts of the text font and the emoji font.
+fn set_up_emoji_family(env: &mut TestEnv) -> (FontInstance, FontInstance) {
+    let emoji_family = env.collection().family_id(EMOJI_FONT).unwrap();
+    env.collection()
+        .set_generic_families(GenericFamily::Emoji, [emoji_family].into_iter());
+    let text_font = first_font(&build_layout(env, "✌", TEXT_FONT, FontVariantEmoji::Normal));
+    let emoji_font = first_font(&build_layout(
+        env,
+        "✌",
+        EMOJI_FONT,
+        FontVariantEmoji::Normal,
+    ));
+    assert_ne!(text_font, emoji_font);
+    (text_font, emoji_font)
+}
+
+#[test]
+fn emoji_presentation_variants() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let (text_font, emoji_font) = set_up_emoji_family(&mut env);
+
+    // U+270C has text presentation by default.
+    for (text, variant, expected) in [
+        ("✌\u{FE0F}", FontVariantEmoji::Normal, &text_font),
+        ("✌\u{FE0F}", FontVariantEmoji::Text, &text_font),
+        ("✌\u{FE0E}", FontVariantEmoji::Emoji, &emoji_font),
+        ("✌", FontVariantEmoji::Emoji, &emoji_font),
+    ] {
+        let layout = build_layout(&mut env, text, TEXT_FONT, variant);
+        assert_eq!(&first_font(&layout), expected, "{text:?} with {variant:?}");
+    }
+}
+
+#[test]
+fn emoji_presentation_unicode() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let (text_font, emoji_font) = set_up_emoji_family(&mut env);
+
+    for (text, expected) in [
+        // Default text presentation.
+        ("✌", &text_font),
+        ("✌\u{FE0E}", &text_font),
+        ("✌\u{FE0F}", &emoji_font),
+        // Default emoji presentation.
+        ("🎉", &emoji_font),
+        ("🎉\u{FE0E}", &text_font),
+    ] {
+        let layout = build_layout(&mut env, text, TEXT_FONT, FontVariantEmoji::Unicode);
+        assert_eq!(&first_font(&layout), expected, "{text:?}");
+    }
+}
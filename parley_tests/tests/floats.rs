@@ -6,7 +6,9 @@ use crate::{
     test_name,
     util::{ColorBrush, draw_layout, render_to_pixmap, samples::LOREM_IPSUM},
 };
-use parley::{Alignment, AlignmentOptions, InlineBox, InlineBoxKind, Layout, YieldData};
+use parley::{
+    Alignment, AlignmentOptions, InlineBox, InlineBoxKind, Layout, VerticalAlign, YieldData,
+};
 use peniko::{Color, kurbo::Rect};
 use taffy::{Clear, FloatContext, FloatDirection};
 
@@ -59,6 +61,7 @@ fn float_simple() {
             width: 0.0,
             height: 0.0,
             baseline: None,
+            vertical_align: VerticalAlign::Baseline,
         });
     }
 
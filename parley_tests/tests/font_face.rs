@@ -0,0 +1,117 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for fonts registered with `@font-face`-like descriptors.
+
+use std::sync::Arc;
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::fontique::{Blob, FontFaceDescriptor};
+use parley::setting::Tag;
+use parley::{FontFamily, FontFeature, FontWeight, Layout, StyleProperty};
+
+const ROBOTO: &[u8] =
+    include_bytes!("../../parley_dev/assets/fonts/roboto_fonts/Roboto-Regular.ttf");
+const ARIMO: &[u8] =
+    include_bytes!("../../parley_dev/assets/fonts/arimo_fonts/Arimo-VariableFont_wght.ttf");
+
+fn build_layout(env: &mut TestEnv, text: &str, family: &str) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(text);
+    builder.push_default(StyleProperty::FontFamily(FontFamily::named(family)));
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+    layout
+}
+
+#[test]
+fn font_face_unicode_range_subsets() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let digits = Blob::new(Arc::new(ROBOTO));
+    let letters = Blob::new(Arc::new(ARIMO));
+    env.collection().register_font_face(
+        digits.clone(),
+        &FontFaceDescriptor {
+            family_name: Some("Split"),
+            unicode_range: &[0x30..=0x39],
+            ..Default::default()
+        },
+    );
+    env.collection().register_font_face(
+        letters.clone(),
+        &FontFaceDescriptor {
+            family_name: Some("Split"),
+            unicode_range: &[0x41..=0x5A, 0x61..=0x7A],
+            ..Default::default()
+        },
+    );
+
+    env.layout_context().set_font_diagnostics(true);
+    let text = "abc123def";
+    let layout = build_layout(&mut env, text, "Split");
+    let runs: Vec<_> = layout
+        .lines()
+        .flat_map(|line| line.runs())
+        .map(|run| (run.text_range(), run.font().font.data.id()))
+        .collect();
+    assert_eq!(
+        runs,
+        [
+            (0..3, letters.id()),
+            (3..6, digits.id()),
+            (6..9, letters.id()),
+        ],
+        "each subset should only be used for its own unicode range"
+    );
+    assert!(layout.font_diagnostics().is_empty());
+}
+
+#[test]
+fn font_face_weight_range() {
+    let mut env = TestEnv::new(test_name!(), None);
+    env.collection().register_font_face(
+        Blob::new(Arc::new(ARIMO)),
+        &FontFaceDescriptor {
+            family_name: Some("Ranged"),
+            weight: Some(FontWeight::NORMAL..=FontWeight::BOLD),
+            ..Default::default()
+        },
+    );
+
+    let wght = Tag::new(b"wght");
+    for (weight, expected) in [(600., 600.), (900., 700.)] {
+        let text = "Hello";
+        let mut builder = env.ranged_builder(text);
+        builder.push_default(StyleProperty::FontFamily(FontFamily::named("Ranged")));
+        builder.push_default(StyleProperty::FontWeight(FontWeight::new(weight)));
+        let mut layout = builder.build(text);
+        layout.break_all_lines(None);
+
+        let run = layout.lines().next().unwrap().runs().next().unwrap();
+        let synthesis = run.synthesis();
+        assert_eq!(
+            synthesis.variation_settings(),
+            &[(wght, expected)],
+            "weight {weight} should be clamped to the registered range"
+        );
+        assert!(!synthesis.embolden());
+    }
+}
+
+#[test]
+fn font_face_default_features() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let features = [FontFeature::new(Tag::new(b"tnum"), 1)];
+    env.collection().register_font_face(
+        Blob::new(Arc::new(ROBOTO)),
+        &FontFaceDescriptor {
+            family_name: Some("Tabular"),
+            features: &features,
+            ..Default::default()
+        },
+    );
+
+    let layout = build_layout(&mut env, "123", "Tabular");
+    let run = layout.lines().next().unwrap().runs().next().unwrap();
+    assert_eq!(run.font().features.as_deref(), Some(&features[..]));
+}
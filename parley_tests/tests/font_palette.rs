@@ -0,0 +1,109 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for `font-palette` selection and palette overrides.
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::{
+    BasePalette, ColorScheme, FontFamily, FontPalette, Layout, PaletteOverride, StyleProperty,
+};
+
+const TEXT: &str = "\u{2705}\u{1f440}";
+
+const RED: [u8; 4] = [0xFF, 0, 0, 0xFF];
+const GREEN: [u8; 4] = [0, 0xFF, 0, 0xFF];
+const BLUE: [u8; 4] = [0, 0, 0xFF, 0xFF];
+
+fn build_layout(
+    env: &mut TestEnv,
+    color_scheme: ColorScheme,
+    palette: FontPalette<'_>,
+) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(TEXT);
+    builder.set_color_scheme(color_scheme);
+    builder.push_default(StyleProperty::FontFamily(FontFamily::named(
+        "Noto Color Emoji",
+    )));
+    builder.push_default(StyleProperty::FontSize(24.0));
+    builder.push_default(palette);
+    let mut layout = builder.build(TEXT);
+    layout.break_all_lines(None);
+    layout
+}
+
+/// The palette index and overrides of each run of the layout.
+fn run_palettes(layout: &Layout<ColorBrush>) -> Vec<(u16, Vec<PaletteOverride>)> {
+    layout
+        .lines()
+        .flat_map(|line| line.runs().collect::<Vec<_>>())
+        .map(|run| (run.palette_index(), run.palette_overrides().to_vec()))
+        .collect()
+}
+
+#[test]
+fn palette_falls_back_to_first() {
+    let mut env = TestEnv::new(test_name!(), None);
+    // The emoji font has a single palette without light or dark types.
+    for (color_scheme, base) in [
+        (ColorScheme::Dark, BasePalette::Normal),
+        (ColorScheme::Light, BasePalette::Light),
+        (ColorScheme::Light, BasePalette::Dark),
+        (ColorScheme::Light, BasePalette::Index(3)),
+    ] {
+        let layout = build_layout(&mut env, color_scheme, base.into());
+        assert_eq!(
+            run_palettes(&layout),
+            [(0, vec![])],
+            "{base:?} in a {color_scheme:?} color scheme should select the first palette"
+        );
+    }
+}
+
+#[test]
+fn palette_overrides_are_sorted_and_deduplicated() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let overrides = [
+        PaletteOverride::new(3, RED),
+        PaletteOverride::new(1, BLUE),
+        PaletteOverride::new(3, GREEN),
+    ];
+    let layout = build_layout(
+        &mut env,
+        ColorScheme::Light,
+        FontPalette::new(BasePalette::Normal, &overrides[..]),
+    );
+    assert_eq!(
+        run_palettes(&layout),
+        [(
+            0,
+            vec![
+                PaletteOverride::new(1, BLUE),
+                PaletteOverride::new(3, GREEN)
+            ]
+        )],
+        "the last override of an entry should apply"
+    );
+}
+
+#[test]
+fn palette_change_splits_runs() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let overrides = [PaletteOverride::new(0, RED)];
+    let mut builder = env.ranged_builder(TEXT);
+    builder.push_default(StyleProperty::FontFamily(FontFamily::named(
+        "Noto Color Emoji",
+    )));
+    builder.push_default(StyleProperty::FontSize(24.0));
+    builder.push(
+        FontPalette::new(BasePalette::Normal, &overrides[..]),
+        '\u{2705}'.len_utf8()..,
+    );
+    let mut layout = builder.build(TEXT);
+    layout.break_all_lines(None);
+
+    assert_eq!(
+        run_palettes(&layout),
+        [(0, vec![]), (0, overrides.to_vec())]
+    );
+}
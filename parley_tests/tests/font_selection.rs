@@ -0,0 +1,300 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for custom font selection and font diagnostics.
+
+use core::cell::RefCell;
+use core::ops::Range;
+use std::sync::Arc;
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::fontique::{Query, QueryFont};
+use parley::{
+    CharCluster, Coverage, DefaultFontSelector, FontFamily, FontInstance, FontSelectionStyle,
+    FontSelector, GenericFamily, Layout, StyleProperty, select_font_by_coverage,
+};
+
+/// Selects `family` for digits, and uses the default policy for everything else.
+struct DigitFont {
+    family: &'static str,
+    /// The font sizes of the styles seen by the selector.
+    font_sizes: RefCell<Vec<f32>>,
+}
+
+impl FontSelector for DigitFont {
+    fn select_font(
+        &self,
+        cluster: &mut CharCluster,
+        style: &FontSelectionStyle<'_>,
+        query: &mut Query<'_>,
+    ) -> Option<QueryFont> {
+        self.font_sizes.borrow_mut().push(style.font_size);
+        if cluster.chars().iter().all(|c| c.ch.is_ascii_digit()) {
+            query.set_families([self.family]);
+        }
+        select_font_by_coverage(cluster, style, query)
+    }
+}
+
+fn run_fonts(layout: &Layout<ColorBrush>) -> Vec<(Range<usize>, FontInstance)> {
+    layout
+        .lines()
+        .flat_map(|line| line.runs())
+        .map(|run| (run.text_range(), run.font().clone()))
+        .collect()
+}
+
+#[test]
+fn font_selection_custom_selector() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "abc123def";
+
+    let mut builder = env.ranged_builder(text);
+    builder.push_default(StyleProperty::FontFamily(FontFamily::named("Arimo")));
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+    let arimo = run_fonts(&layout)[0].1.clone();
+
+    let selector = DigitFont {
+        family: "Arimo",
+        font_sizes: RefCell::new(Vec::new()),
+    };
+    let mut builder = env.ranged_builder(text);
+    builder.push_default(StyleProperty::FontSize(20.));
+    builder.set_font_selector(Some(&selector));
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+
+    let fonts = run_fonts(&layout);
+    assert_eq!(fonts.len(), 3, "the digits should be in a run of their own");
+    assert_ne!(fonts[0].1, arimo);
+    assert_eq!(fonts[1].0, 3..6);
+    assert_eq!(fonts[1].1, arimo);
+    assert_eq!(
+        fonts[2].1, fonts[0].1,
+        "the query should be set up again after the selector changed it"
+    );
+    assert!(
+        selector.font_sizes.borrow().iter().all(|&size| size == 20.),
+        "the selector should see the resolved style"
+    );
+}
+
+#[test]
+fn font_selection_default_selector() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "Hello مرحبا 👋";
+
+    let builder = env.ranged_builder(text);
+    let mut reference = builder.build(text);
+    reference.break_all_lines(None);
+
+    let mut builder = env.ranged_builder(text);
+    builder.set_font_selector(Some(&DefaultFontSelector));
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+
+    assert_eq!(run_fonts(&layout), run_fonts(&reference));
+}
+
+/// Selects `.0` for all clusters.
+struct AlwaysFamily(&'static str);
+
+impl FontSelector for AlwaysFamily {
+    fn select_font(
+        &self,
+        cluster: &mut CharCluster,
+        style: &FontSelectionStyle<'_>,
+        query: &mut Query<'_>,
+    ) -> Option<QueryFont> {
+        query.set_families([self.0]);
+        select_font_by_coverage(cluster, style, query)
+    }
+}
+
+#[test]
+fn font_selection_context_selector() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "abc 123";
+
+    let builder = env.ranged_builder(text);
+    let mut reference = builder.build(text);
+    reference.break_all_lines(None);
+    let mut builder = env.ranged_builder(text);
+    builder.set_font_selector(Some(&AlwaysFamily("Arimo")));
+    let mut arimo = builder.build(text);
+    arimo.break_all_lines(None);
+    assert_ne!(run_fonts(&arimo), run_fonts(&reference));
+
+    env.layout_context()
+        .set_font_selector(Some(Arc::new(AlwaysFamily("Arimo"))));
+    let builder = env.ranged_builder(text);
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+    assert_eq!(run_fonts(&layout), run_fonts(&arimo));
+
+    // The builder's selector takes precedence over the context's.
+    let mut builder = env.ranged_builder(text);
+    builder.set_font_selector(Some(&DefaultFontSelector));
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+    assert_eq!(run_fonts(&layout), run_fonts(&reference));
+
+    env.layout_context().set_font_selector(None);
+    let builder = env.ranged_builder(text);
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+    assert_eq!(run_fonts(&layout), run_fonts(&reference));
+}
+
+/// Never selects a font, so that the last resort font is used.
+struct NoFont;
+
+impl FontSelector for NoFont {
+    fn select_font(
+        &self,
+        _cluster: &mut CharCluster,
+        _style: &FontSelectionStyle<'_>,
+        _query: &mut Query<'_>,
+    ) -> Option<QueryFont> {
+        None
+    }
+}
+
+#[test]
+fn font_diagnostics_missing_glyphs() {
+    let mut env = TestEnv::new(test_name!(), None);
+
+    // None of the test fonts cover CJK ideographs, but diagnostics are opt-in.
+    let text = "Hi 漢字!";
+    let builder = env.ranged_builder(text);
+    let layout = builder.build(text);
+    assert!(layout.font_diagnostics().is_empty());
+
+    env.layout_context().set_font_diagnostics(true);
+    let text = "Hello";
+    let builder = env.ranged_builder(text);
+    let layout = builder.build(text);
+    assert!(layout.font_diagnostics().is_empty());
+
+    let text = "Hi 漢字!";
+    let builder = env.ranged_builder(text);
+    let layout = builder.build(text);
+    let diagnostics = layout.font_diagnostics();
+    let missing = diagnostics.missing_glyphs().collect::<Vec<_>>();
+    assert_eq!(missing.len(), 1, "adjacent clusters should be merged");
+    assert_eq!(missing[0].text_range, 3..9);
+    assert_eq!(missing[0].coverage, Coverage::NONE);
+    assert!(!missing[0].requested_families.is_empty());
+    assert_eq!(diagnostics.last_resort().count(), 0);
+}
+
+#[test]
+fn font_diagnostics_fallback_and_last_resort() {
+    let mut env = TestEnv::new(test_name!(), None);
+    env.layout_context().set_font_diagnostics(true);
+    let text = "12";
+
+    let selector = DigitFont {
+        family: "Arimo",
+        font_sizes: RefCell::new(Vec::new()),
+    };
+    let mut builder = env.ranged_builder(text);
+    builder.set_font_selector(Some(&selector));
+    let layout = builder.build(text);
+    let fallbacks = layout.font_diagnostics().fallbacks().collect::<Vec<_>>();
+    assert_eq!(fallbacks.len(), 1);
+    let fallback = fallbacks[0];
+    assert_eq!(fallback.text_range, 0..2);
+    assert!(
+        fallback
+            .family
+            .is_some_and(|family| !fallback.requested_families.contains(&family))
+    );
+    assert!(!fallback.has_missing_glyphs());
+    assert!(!fallback.is_last_resort);
+
+    let mut builder = env.ranged_builder(text);
+    builder.set_font_selector(Some(&NoFont));
+    let layout = builder.build(text);
+    let last_resort = layout.font_diagnostics().last_resort().collect::<Vec<_>>();
+    assert_eq!(last_resort.len(), 1);
+    assert_eq!(last_resort[0].text_range, 0..2);
+}
+
+#[test]
+fn font_diagnostics_emoji_family() {
+    let mut env = TestEnv::new(test_name!(), None);
+    env.layout_context().set_font_diagnostics(true);
+    let emoji_family = env.collection().family_id("Noto Color Emoji").unwrap();
+    env.collection()
+        .set_generic_families(GenericFamily::Emoji, [emoji_family].into_iter());
+
+    let text = "ab ✌\u{FE0F}";
+    let mut builder = env.ranged_builder(text);
+    builder.push_default(StyleProperty::FontFamily(FontFamily::named("Arimo")));
+    let layout = builder.build(text);
+    assert!(
+        layout.font_diagnostics().is_empty(),
+        "the implicit emoji family shouldn't be reported as a fallback"
+    );
+}
+
+#[test]
+fn font_selection_localized_generic_family() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let arimo = env.collection().family_id("Arimo").unwrap();
+    let roboto = env.collection().family_id("Roboto").unwrap();
+    env.collection()
+        .set_generic_families(GenericFamily::SansSerif, [arimo].into_iter());
+    env.collection()
+        .set_generic_families((GenericFamily::SansSerif, "ja"), [roboto].into_iter());
+
+    let font_for = |env: &mut TestEnv, family: FontFamily<'_>, locale: Option<&str>| {
+        let text = "abc";
+        let mut builder = env.ranged_builder(text);
+        builder.push_default(StyleProperty::FontFamily(family));
+        builder.push_default(StyleProperty::Locale(
+            locale.map(|locale| locale.parse().unwrap()),
+        ));
+        let mut layout = builder.build(text);
+        layout.break_all_lines(None);
+        run_fonts(&layout)[0].1.clone()
+    };
+    let sans_serif = FontFamily::from(GenericFamily::SansSerif);
+    let arimo_font = font_for(&mut env, FontFamily::named("Arimo"), None);
+    let roboto_font = font_for(&mut env, FontFamily::named("Roboto"), None);
+
+    assert_eq!(font_for(&mut env, sans_serif.clone(), None), arimo_font);
+    assert_eq!(
+        font_for(&mut env, sans_serif.clone(), Some("de")),
+        arimo_font
+    );
+    assert_eq!(
+        font_for(&mut env, sans_serif.clone(), Some("ja-JP")),
+        roboto_font
+    );
+}
+
+#[test]
+fn font_generation_tracks_collection_changes() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "abc";
+    let build = |env: &mut TestEnv| {
+        let mut builder = env.ranged_builder(text);
+        builder.push_default(StyleProperty::FontFamily(GenericFamily::SansSerif.into()));
+        builder.build(text)
+    };
+
+    let layout = build(&mut env);
+    assert_eq!(layout.font_generation(), env.collection().generation());
+
+    let roboto = env.collection().family_id("Roboto").unwrap();
+    env.collection()
+        .set_generic_families(GenericFamily::SansSerif, [roboto].into_iter());
+    assert_ne!(layout.font_generation(), env.collection().generation());
+
+    let layout = build(&mut env);
+    assert_eq!(layout.font_generation(), env.collection().generation());
+}
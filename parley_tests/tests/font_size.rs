@@ -0,0 +1,150 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for optical sizing and font size adjustment.
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv, env::create_font_context};
+use parley::setting::Tag;
+use parley::{
+    FontFamily, FontOpticalSizing, FontSizeAdjust, FontVariation, Layout, LayoutContext, Run,
+    StyleProperty,
+};
+
+fn build_layout(
+    env: &mut TestEnv,
+    text: &str,
+    font_size: f32,
+    properties: &[StyleProperty<'_, ColorBrush>],
+) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(text);
+    builder.push_default(StyleProperty::FontSize(font_size));
+    for property in properties {
+        builder.push_default(property.clone());
+    }
+    let mut layout = builder.build(text);
+    layout.break_all_lines(None);
+    layout
+}
+
+fn first_run_coords(layout: &Layout<ColorBrush>) -> Vec<i16> {
+    let run = layout.lines().next().unwrap().runs().next().unwrap();
+    run.normalized_coords()
+        .iter()
+        .map(|coord| coord.to_bits())
+        .collect()
+}
+
+fn metric(run: &Run<'_, ColorBrush>, cap_height: bool) -> f32 {
+    let metrics = run.font_metrics();
+    if cap_height {
+        metrics.cap_height.unwrap()
+    } else {
+        metrics.x_height.unwrap()
+    }
+}
+
+#[test]
+fn optical_sizing_follows_font_size() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let family = StyleProperty::FontFamily(FontFamily::named("Roboto Flex"));
+
+    let small = build_layout(&mut env, "Hamburg", 9.0, &[family.clone()]);
+    let large = build_layout(&mut env, "Hamburg", 72.0, &[family.clone()]);
+    assert_ne!(
+        first_run_coords(&small),
+        first_run_coords(&large),
+        "text at different sizes should use different optical sizes"
+    );
+
+    let none = StyleProperty::FontOpticalSizing(FontOpticalSizing::None);
+    let small = build_layout(&mut env, "Hamburg", 9.0, &[family.clone(), none.clone()]);
+    let large = build_layout(&mut env, "Hamburg", 72.0, &[family, none]);
+    assert_eq!(first_run_coords(&small), first_run_coords(&large));
+}
+
+#[test]
+fn optical_sizing_ignores_display_scale() {
+    let (mut lcx, mut fcx) = (LayoutContext::new(), create_font_context());
+    let mut coords = |scale: f32| {
+        let text = "Hamburg";
+        let mut builder = lcx.ranged_builder(&mut fcx, text, scale, true);
+        builder.push_default(StyleProperty::FontFamily(FontFamily::named("Roboto Flex")));
+        builder.push_default(StyleProperty::FontSize(12.0));
+        let mut layout: Layout<ColorBrush> = builder.build(text);
+        layout.break_all_lines(None);
+        first_run_coords(&layout)
+    };
+    assert_eq!(
+        coords(1.0),
+        coords(2.0),
+        "the optical size should be the same at every display scale"
+    );
+}
+
+#[test]
+fn optical_sizing_yields_to_variations() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let variations = [FontVariation::new(Tag::new(b"opsz"), 30.0)];
+    let properties = [
+        StyleProperty::FontFamily(FontFamily::named("Roboto Flex")),
+        StyleProperty::FontVariations((&variations).into()),
+    ];
+
+    let small = build_layout(&mut env, "Hamburg", 9.0, &properties);
+    let large = build_layout(&mut env, "Hamburg", 72.0, &properties);
+    assert_eq!(
+        first_run_coords(&small),
+        first_run_coords(&large),
+        "an explicit `opsz` variation should override optical sizing"
+    );
+}
+
+#[test]
+fn font_size_adjust_to_ratio() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let layout = build_layout(
+        &mut env,
+        "Hamburg",
+        20.0,
+        &[StyleProperty::FontSizeAdjust(FontSizeAdjust::ExHeight(0.6))],
+    );
+    let run = layout.lines().next().unwrap().runs().next().unwrap();
+    let x_height = run.font_metrics().x_height.unwrap();
+    assert!(
+        (x_height - 12.0).abs() < 0.01,
+        "x-height {x_height} should be 0.6 times the font size"
+    );
+    assert_ne!(run.font_size(), 20.0);
+}
+
+#[test]
+fn font_size_adjust_from_font_matches_fallback() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "Hamburg بيروت";
+    for (adjust, cap_height) in [
+        (FontSizeAdjust::ExHeightFromFont, false),
+        (FontSizeAdjust::CapHeightFromFont, true),
+    ] {
+        let layout = build_layout(
+            &mut env,
+            text,
+            20.0,
+            &[StyleProperty::FontSizeAdjust(adjust)],
+        );
+        let runs: Vec<_> = layout.lines().flat_map(|line| line.runs()).collect();
+        let first = runs.first().unwrap();
+        let last = runs.last().unwrap();
+        assert_ne!(first.font().font, last.font().font);
+        assert_eq!(
+            first.font_size(),
+            20.0,
+            "the first available font shouldn't be adjusted"
+        );
+        let (expected, actual) = (metric(first, cap_height), metric(last, cap_height));
+        assert!(
+            (expected - actual).abs() < 0.01,
+            "{adjust:?}: fallback metric {actual} should match {expected}"
+        );
+    }
+}
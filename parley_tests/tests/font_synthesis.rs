@@ -0,0 +1,104 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for `font-synthesis` control and faux bold advances.
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::{
+    FontFamily, FontStyle, FontSynthesis, FontWeight, Layout, PositionedLayoutItem, StyleProperty,
+};
+
+const TEXT: &str = "Hamburg";
+
+fn build_layout(
+    env: &mut TestEnv,
+    properties: &[StyleProperty<'_, ColorBrush>],
+) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(TEXT);
+    builder.push_default(StyleProperty::FontFamily(FontFamily::named("Roboto")));
+    builder.push_default(StyleProperty::FontSize(24.0));
+    for property in properties {
+        builder.push_default(property.clone());
+    }
+    let mut layout = builder.build(TEXT);
+    layout.break_all_lines(None);
+    layout
+}
+
+fn advance(layout: &Layout<ColorBrush>) -> f32 {
+    layout.lines().map(|line| line.metrics().advance).sum()
+}
+
+#[test]
+fn faux_bold_widens_advances() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let regular = build_layout(&mut env, &[]);
+    let bold = build_layout(&mut env, &[StyleProperty::FontWeight(FontWeight::BOLD)]);
+
+    let line = bold.lines().next().unwrap();
+    let run = line.runs().next().unwrap();
+    assert!(run.synthesis().embolden());
+    let strength = run.embolden_strength();
+    assert_eq!(
+        strength, 1.0,
+        "the strength should be a 24th of the font size"
+    );
+
+    let glyph_count = run
+        .clusters()
+        .map(|cluster| cluster.glyphs().count())
+        .sum::<usize>();
+    let expected = advance(&regular) + strength * glyph_count as f32;
+    assert!(
+        (advance(&bold) - expected).abs() < 0.01,
+        "each glyph should be widened by the embolden strength"
+    );
+
+    for item in line.items() {
+        let PositionedLayoutItem::GlyphRun(glyph_run) = item else {
+            continue;
+        };
+        assert_eq!(glyph_run.synthesis(), run.synthesis());
+        assert_eq!(glyph_run.embolden_strength(), strength);
+    }
+}
+
+#[test]
+fn font_synthesis_none_disables_faux_styles() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let regular = build_layout(&mut env, &[]);
+    let styles = [
+        StyleProperty::FontWeight(FontWeight::BOLD),
+        StyleProperty::FontStyle(FontStyle::Italic),
+    ];
+
+    let synthesized = build_layout(&mut env, &styles);
+    let synthesis = synthesized
+        .lines()
+        .next()
+        .unwrap()
+        .runs()
+        .next()
+        .unwrap()
+        .synthesis();
+    assert!(synthesis.embolden());
+    assert!(synthesis.skew().is_some());
+
+    let mut properties = styles.to_vec();
+    properties.push(StyleProperty::FontSynthesis(FontSynthesis {
+        weight: false,
+        ..FontSynthesis::ALL
+    }));
+    let layout = build_layout(&mut env, &properties);
+    let run = layout.lines().next().unwrap().runs().next().unwrap();
+    assert!(!run.synthesis().embolden());
+    assert!(run.synthesis().skew().is_some());
+    assert_eq!(run.embolden_strength(), 0.0);
+    assert_eq!(advance(&layout), advance(&regular));
+
+    properties.push(StyleProperty::FontSynthesis(FontSynthesis::NONE));
+    let layout = build_layout(&mut env, &properties);
+    let run = layout.lines().next().unwrap().runs().next().unwrap();
+    assert!(!run.synthesis().any());
+}
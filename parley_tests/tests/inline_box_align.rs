@@ -0,0 +1,161 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::{
+    test_name,
+    util::{ColorBrush, TestEnv},
+};
+use parley::{
+    InlineBox, InlineBoxKind, Layout, LineMetrics, PositionedInlineBox, PositionedLayoutItem,
+    StyleProperty, VerticalAlign,
+};
+
+const TEXT: &str = "Hello world";
+
+fn layout_with_box(
+    env: &mut TestEnv,
+    height: f32,
+    baseline: Option<f32>,
+    vertical_align: VerticalAlign,
+) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(TEXT);
+    builder.push_default(StyleProperty::FontSize(20.0));
+    builder.push_inline_box(InlineBox {
+        id: 0,
+        kind: InlineBoxKind::InFlow,
+        index: 5,
+        width: 10.0,
+        height,
+        baseline,
+        vertical_align,
+    });
+    let mut layout = builder.build(TEXT);
+    layout.break_all_lines(None);
+    layout
+}
+
+fn positioned_box(layout: &Layout<ColorBrush>) -> (PositionedInlineBox, LineMetrics) {
+    let line = layout.lines().next().unwrap();
+    let inline_box = line
+        .items()
+        .find_map(|item| match item {
+            PositionedLayoutItem::InlineBox(inline_box) => Some(inline_box),
+            PositionedLayoutItem::GlyphRun(_) => None,
+        })
+        .unwrap();
+    (inline_box, *line.metrics())
+}
+
+#[test]
+fn inline_box_align_baseline() {
+    let mut env = TestEnv::new(test_name!(), None);
+
+    let layout = layout_with_box(&mut env, 10.0, None, VerticalAlign::Baseline);
+    let (inline_box, metrics) = positioned_box(&layout);
+    assert_eq!(inline_box.y + inline_box.height, metrics.baseline);
+
+    let layout = layout_with_box(&mut env, 10.0, Some(6.0), VerticalAlign::Baseline);
+    let (inline_box, metrics) = positioned_box(&layout);
+    assert_eq!(inline_box.y + 6.0, metrics.baseline);
+}
+
+#[test]
+fn inline_box_align_length() {
+    let mut env = TestEnv::new(test_name!(), None);
+
+    let layout = layout_with_box(&mut env, 10.0, None, VerticalAlign::Length(4.0));
+    let (inline_box, metrics) = positioned_box(&layout);
+    assert_eq!(inline_box.y + inline_box.height, metrics.baseline - 4.0);
+
+    let layout = layout_with_box(&mut env, 10.0, None, VerticalAlign::Length(-4.0));
+    let (inline_box, metrics) = positioned_box(&layout);
+    assert_eq!(inline_box.y + inline_box.height, metrics.baseline + 4.0);
+}
+
+#[test]
+fn inline_box_align_sub_super() {
+    let mut env = TestEnv::new(test_name!(), None);
+
+    let (baseline_box, _) = positioned_box(&layout_with_box(
+        &mut env,
+        10.0,
+        None,
+        VerticalAlign::Baseline,
+    ));
+    let (sub_box, _) = positioned_box(&layout_with_box(&mut env, 10.0, None, VerticalAlign::Sub));
+    let (super_box, _) =
+        positioned_box(&layout_with_box(&mut env, 10.0, None, VerticalAlign::Super));
+
+    assert!(
+        sub_box.y > baseline_box.y,
+        "subscript boxes should be lowered"
+    );
+    assert!(
+        super_box.y < baseline_box.y,
+        "superscript boxes should be raised"
+    );
+}
+
+#[test]
+fn inline_box_align_text_top_and_bottom() {
+    let mut env = TestEnv::new(test_name!(), None);
+
+    let layout = layout_with_box(&mut env, 4.0, None, VerticalAlign::TextTop);
+    let (text_top, metrics) = positioned_box(&layout);
+    assert_eq!(text_top.y, metrics.content_block_min_coord);
+
+    let layout = layout_with_box(&mut env, 4.0, None, VerticalAlign::TextBottom);
+    let (text_bottom, metrics) = positioned_box(&layout);
+    assert_eq!(
+        text_bottom.y + text_bottom.height,
+        metrics.content_block_max_coord
+    );
+}
+
+#[test]
+fn inline_box_align_middle() {
+    let mut env = TestEnv::new(test_name!(), None);
+
+    let layout = layout_with_box(&mut env, 10.0, None, VerticalAlign::Middle);
+    let (inline_box, metrics) = positioned_box(&layout);
+    let center = inline_box.y + inline_box.height / 2.0;
+    assert!(
+        center < metrics.baseline,
+        "a middle-aligned box should be centered above the baseline"
+    );
+    assert!(
+        center > metrics.content_block_min_coord,
+        "a middle-aligned box should be centered below the top of the text"
+    );
+}
+
+#[test]
+fn inline_box_align_top_and_bottom() {
+    let mut env = TestEnv::new(test_name!(), None);
+
+    let layout = layout_with_box(&mut env, 10.0, None, VerticalAlign::Top);
+    let (inline_box, metrics) = positioned_box(&layout);
+    assert_eq!(inline_box.y, metrics.block_min_coord);
+
+    let layout = layout_with_box(&mut env, 10.0, None, VerticalAlign::Bottom);
+    let (inline_box, metrics) = positioned_box(&layout);
+    assert_eq!(inline_box.y + inline_box.height, metrics.block_max_coord);
+}
+
+#[test]
+fn inline_box_align_tall_top_box_grows_line() {
+    let mut env = TestEnv::new(test_name!(), None);
+
+    let reference = layout_with_box(&mut env, 1.0, None, VerticalAlign::Top);
+    let (_, reference_metrics) = positioned_box(&reference);
+
+    let layout = layout_with_box(&mut env, 100.0, None, VerticalAlign::Top);
+    let (inline_box, metrics) = positioned_box(&layout);
+
+    assert_eq!(metrics.line_height, 100.0);
+    assert_eq!(layout.height(), 100.0);
+    // The box hangs down from the top of the line, so the text baseline does not move.
+    assert_eq!(metrics.baseline, reference_metrics.baseline);
+    assert_eq!(inline_box.y, metrics.block_min_coord);
+    assert_eq!(inline_box.y + inline_box.height, metrics.block_max_coord);
+}
@@ -10,7 +10,7 @@ use crate::test_name;
 use crate::util::{ColorBrush, TestEnv};
 use parley::{
     Affinity, Alignment, AlignmentOptions, BoundingBox, Brush, Cursor, InlineBox, InlineBoxKind,
-    Layout, Line, LineHeight, Selection, StyleProperty,
+    Layout, Line, LineHeight, Selection, StyleProperty, VerticalAlign,
 };
 use peniko::kurbo::Size;
 
@@ -141,6 +141,7 @@ fn build_layout<A: Into<Option<f32>>>(
         width: 50.0,
         height: 5.0,
         baseline: None,
+        vertical_align: VerticalAlign::Baseline,
     });
     builder.push_inline_box(InlineBox {
         id: 1,
@@ -149,6 +150,7 @@ fn build_layout<A: Into<Option<f32>>>(
         width: 50.0,
         height: 3.0,
         baseline: None,
+        vertical_align: VerticalAlign::Baseline,
     });
 
     let mut layout = builder.build(TEXT);
@@ -527,6 +529,7 @@ fn lines_negative_leading_inline_box_grows_line_box() {
         width: 12.0,
         height: box_height,
         baseline: None,
+        vertical_align: VerticalAlign::Baseline,
     });
     let mut layout: Layout<ColorBrush> = builder.build(text);
     layout.break_all_lines(None);
@@ -22,18 +22,30 @@
 #![allow(missing_docs, reason = "we don't need docs for testing")]
 #![allow(clippy::cast_possible_truncation, reason = "not critical for testing")]
 
+mod baseline_grid;
 mod basic;
+mod cjk_typography;
 mod cursor;
 mod draw;
 mod editor;
+mod emoji;
 mod exclusions;
 mod floats;
+mod font_face;
+mod font_palette;
+mod font_selection;
+mod font_size;
+mod font_synthesis;
+mod inline_box_align;
 mod issues;
 mod line_break;
 mod linebreaking_matches_chrome;
 mod lines;
+mod named_instances;
 mod out_of_flow_boxes;
+mod paragraph_style;
 mod styles;
+mod text_box_trim;
 mod text_indent;
 mod wrap;
 #[macro_use]
@@ -0,0 +1,45 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for selecting named instances of variable fonts.
+
+use crate::test_name;
+use crate::util::TestEnv;
+use parley::{FontFamily, FontStyle, FontWeight, TextStyle};
+
+#[test]
+fn named_instance_to_text_style() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let font_cx = env.font_context();
+    let family = font_cx.collection.family_by_name("Roboto Flex").unwrap();
+    let instances = family.named_instances(Some(&mut font_cx.source_cache));
+    let (_, instance) = instances
+        .iter()
+        .find(|(_, instance)| instance.name() == Some("SemiBold Italic"))
+        .expect("Roboto Flex has a semibold italic instance");
+
+    let mut style = TextStyle {
+        font_family: FontFamily::named("Roboto Flex"),
+        font_size: 24.0,
+        ..TextStyle::default()
+    };
+    style.set_named_instance(instance);
+    assert_eq!(style.font_weight, FontWeight::SEMI_BOLD);
+    assert_eq!(style.font_style, FontStyle::Oblique(Some(10.0)));
+
+    let mut builder = env.tree_builder();
+    builder.push_style_span(style);
+    builder.push_text("Hamburg");
+    let (mut layout, _) = builder.build();
+    layout.break_all_lines(None);
+
+    let line = layout.lines().next().unwrap();
+    let run = line.runs().next().unwrap();
+    assert_eq!(run.font_attrs().weight, FontWeight::SEMI_BOLD);
+    assert!(
+        run.normalized_coords()
+            .iter()
+            .any(|coord| coord.to_bits() != 0),
+        "the instance's coordinates should be applied"
+    );
+}
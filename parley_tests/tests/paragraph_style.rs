@@ -0,0 +1,273 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Paragraph style tests.
+
+use core::ops::Range;
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::{Alignment, AlignmentOptions, Layout, Line, ParagraphStyle, WhiteSpaceCollapse};
+
+const FIRST: &str = "The quick brown fox jumps over the lazy dog and keeps on running far away.\n";
+const SECOND: &str = "A second paragraph that also wraps over more than one line of text.";
+const WIDTH: f32 = 200.0;
+
+fn build_layout(
+    env: &mut TestEnv,
+    styles: &[(ParagraphStyle, Range<usize>)],
+) -> Layout<ColorBrush> {
+    let text = [FIRST, SECOND].concat();
+    let mut builder = env.ranged_builder(&text);
+    for (style, range) in styles {
+        builder.push_paragraph_style(*style, range.clone());
+    }
+    let mut layout = builder.build(&text);
+    layout.break_all_lines(Some(WIDTH));
+    layout.align(Alignment::Start, AlignmentOptions::default());
+    layout
+}
+
+fn in_second_paragraph(line: &Line<'_, ColorBrush>) -> bool {
+    line.text_range().start >= FIRST.len()
+}
+
+fn first_paragraph() -> Range<usize> {
+    0..FIRST.len()
+}
+
+fn second_paragraph() -> Range<usize> {
+    FIRST.len()..FIRST.len() + SECOND.len()
+}
+
+#[test]
+fn paragraph_style_start_and_first_line_indent() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let style = ParagraphStyle {
+        start_indent: 20.0,
+        first_line_indent: 10.0,
+        ..Default::default()
+    };
+    let layout = build_layout(&mut env, &[(style, first_paragraph())]);
+
+    let mut is_first_line = true;
+    for line in layout.lines() {
+        let metrics = line.metrics();
+        let expected_offset = if in_second_paragraph(&line) {
+            0.0
+        } else if is_first_line {
+            30.0
+        } else {
+            20.0
+        };
+        is_first_line = false;
+        assert_eq!(metrics.offset, expected_offset);
+        assert!(
+            metrics.offset + metrics.advance - metrics.trailing_whitespace <= WIDTH,
+            "indented lines should be broken within the remaining width"
+        );
+    }
+}
+
+#[test]
+fn paragraph_style_alignment_and_end_indent() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let style = ParagraphStyle {
+        alignment: Some(Alignment::End),
+        end_indent: 15.0,
+        ..Default::default()
+    };
+    let layout = build_layout(&mut env, &[(style, second_paragraph())]);
+
+    let mut second_paragraph_lines = 0;
+    for line in layout.lines() {
+        let metrics = line.metrics();
+        if in_second_paragraph(&line) {
+            second_paragraph_lines += 1;
+            let end = metrics.offset + metrics.advance - metrics.trailing_whitespace;
+            assert!(
+                (end - (WIDTH - 15.0)).abs() < 0.01,
+                "end-aligned lines should end at the end indent, but ended at {end}"
+            );
+        } else {
+            assert_eq!(metrics.offset, 0.0);
+        }
+    }
+    assert!(
+        second_paragraph_lines > 1,
+        "the second paragraph should wrap"
+    );
+}
+
+#[test]
+fn paragraph_style_spacing() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let reference = build_layout(&mut env, &[]);
+    let first = ParagraphStyle {
+        // Not applied to the first paragraph of the layout.
+        space_before: 100.0,
+        space_after: 10.0,
+        ..Default::default()
+    };
+    let second = ParagraphStyle {
+        space_before: 5.0,
+        // Not applied to the last paragraph of the layout.
+        space_after: 100.0,
+        ..Default::default()
+    };
+    let layout = build_layout(
+        &mut env,
+        &[(first, first_paragraph()), (second, second_paragraph())],
+    );
+
+    assert_eq!(reference.len(), layout.len());
+    for (reference_line, line) in reference.lines().zip(layout.lines()) {
+        let spacing = if in_second_paragraph(&line) {
+            15.0
+        } else {
+            0.0
+        };
+        assert_eq!(
+            line.metrics().block_min_coord - reference_line.metrics().block_min_coord,
+            spacing
+        );
+        assert_eq!(
+            line.metrics().baseline - reference_line.metrics().baseline,
+            spacing
+        );
+    }
+    assert_eq!(layout.height() - reference.height(), 15.0);
+}
+
+#[test]
+fn paragraph_style_justify_is_undone_when_rebreaking() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let reference = build_layout(&mut env, &[]);
+    let style = ParagraphStyle {
+        alignment: Some(Alignment::Justify),
+        ..Default::default()
+    };
+    let mut layout = build_layout(&mut env, &[(style, second_paragraph())]);
+
+    // Justification widens clusters, which must be reverted before breaking lines again.
+    layout.break_all_lines(Some(WIDTH));
+    layout.align(Alignment::Start, AlignmentOptions::default());
+
+    assert_eq!(reference.len(), layout.len());
+    for (reference_line, line) in reference.lines().zip(layout.lines()) {
+        assert_eq!(line.text_range(), reference_line.text_range());
+        assert_eq!(line.metrics().advance, reference_line.metrics().advance);
+    }
+}
+
+#[test]
+fn paragraph_style_tree_builder() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let first = ParagraphStyle {
+        start_indent: 20.0,
+        ..Default::default()
+    };
+    let second = ParagraphStyle {
+        alignment: Some(Alignment::Center),
+        space_before: 8.0,
+        ..Default::default()
+    };
+    let ranged = build_layout(
+        &mut env,
+        &[(first, first_paragraph()), (second, second_paragraph())],
+    );
+
+    let mut builder = env.tree_builder();
+    builder.set_white_space_mode(WhiteSpaceCollapse::Preserve);
+    builder.set_paragraph_style(first);
+    builder.push_text(FIRST);
+    builder.set_paragraph_style(second);
+    builder.push_text(SECOND);
+    let (mut tree, _) = builder.build();
+    tree.break_all_lines(Some(WIDTH));
+    tree.align(Alignment::Start, AlignmentOptions::default());
+
+    assert_eq!(ranged.len(), tree.len());
+    for (ranged_line, tree_line) in ranged.lines().zip(tree.lines()) {
+        assert_eq!(ranged_line.metrics(), tree_line.metrics());
+    }
+}
+
+/// Builds a single paragraph with enough lines to split across fragments.
+fn build_long_paragraph(env: &mut TestEnv, style: ParagraphStyle) -> Layout<ColorBrush> {
+    let text = FIRST.trim_end().repeat(3);
+    let mut builder = env.ranged_builder(&text);
+    builder.push_paragraph_style(style, ..);
+    let mut layout = builder.build(&text);
+    layout.break_all_lines(Some(WIDTH));
+    layout
+}
+
+#[test]
+fn paragraph_style_fragment_widows_and_orphans() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let layout = build_long_paragraph(&mut env, ParagraphStyle::default());
+    let len = layout.len();
+    assert!(len >= 5, "the paragraph should span at least five lines");
+
+    // Leave exactly one line for the next fragment.
+    let block_size = layout.get(len - 2).unwrap().metrics().block_max_coord;
+    assert_eq!(layout.fragment_end(0, block_size), len - 1);
+    assert_eq!(layout.fragment_end(0, layout.height()), len);
+    assert_eq!(layout.fragment_end(len, block_size), len);
+
+    let widows = build_long_paragraph(
+        &mut env,
+        ParagraphStyle {
+            widows: 2,
+            ..Default::default()
+        },
+    );
+    assert_eq!(widows.fragment_end(0, block_size), len - 2);
+
+    // Continuing the paragraph in a fragment that can hold only a single line leaves one orphan
+    // behind unless at least two are required.
+    let line_size = layout.get(0).unwrap().metrics().line_height;
+    assert_eq!(layout.fragment_end(1, line_size), 2);
+    let orphans = build_long_paragraph(
+        &mut env,
+        ParagraphStyle {
+            orphans: 2,
+            ..Default::default()
+        },
+    );
+    // The constraint can't be satisfied, so the fragment is filled as far as possible.
+    assert_eq!(orphans.fragment_end(1, line_size), 2);
+    assert_eq!(orphans.fragment_end(0, block_size), len - 1);
+    let two_lines = orphans.get(2).unwrap().metrics().block_max_coord
+        - orphans.get(1).unwrap().metrics().block_min_coord;
+    assert_eq!(orphans.fragment_end(1, two_lines), 3);
+}
+
+#[test]
+fn paragraph_style_fragment_keep_with_next() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = "Intro\nHeading\nBody";
+    let build = |env: &mut TestEnv, keep_with_next: bool| {
+        let mut builder = env.ranged_builder(text);
+        builder.push_paragraph_style(
+            ParagraphStyle {
+                keep_with_next,
+                ..Default::default()
+            },
+            6..13,
+        );
+        let mut layout = builder.build(text);
+        layout.break_all_lines(Some(WIDTH));
+        layout
+    };
+
+    let layout = build(&mut env, false);
+    assert_eq!(layout.len(), 3);
+    let block_size = layout.get(1).unwrap().metrics().block_max_coord;
+    assert_eq!(layout.fragment_end(0, block_size), 2);
+
+    // The heading moves to the next fragment together with the body.
+    let layout = build(&mut env, true);
+    assert_eq!(layout.fragment_end(0, block_size), 1);
+}
@@ -0,0 +1,162 @@
+// Copyright 2026 the Parley Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Leading trim tests.
+
+use crate::test_name;
+use crate::util::{ColorBrush, TestEnv};
+use parley::{
+    Layout, LineHeight, StyleProperty, TextBoxEdge, TextBoxOverEdge, TextBoxTrim, TextBoxUnderEdge,
+};
+
+const TEXT: &str = "The quick brown fox jumps over the lazy dog and keeps on running far away.";
+
+fn build_trimmed_layout(
+    env: &mut TestEnv,
+    trim: TextBoxTrim,
+    edge: TextBoxEdge,
+) -> Layout<ColorBrush> {
+    let mut builder = env.ranged_builder(TEXT);
+    builder.push_default(StyleProperty::FontSize(20.0));
+    // A generous line height, such that there is leading to trim.
+    builder.push_default(LineHeight::FontSizeRelative(2.0));
+    let mut layout = builder.build(TEXT);
+    layout.set_text_box_trim(trim, edge);
+    layout.break_all_lines(Some(200.0));
+    layout
+}
+
+#[test]
+fn text_box_trim_none() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let reference = build_trimmed_layout(&mut env, TextBoxTrim::None, TextBoxEdge::default());
+    let first_line = reference.get(0).unwrap();
+    assert!(
+        first_line.metrics().content_block_min_coord > 0.,
+        "untrimmed leading should be above the first line's text"
+    );
+}
+
+#[test]
+fn text_box_trim_start() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let reference = build_trimmed_layout(&mut env, TextBoxTrim::None, TextBoxEdge::default());
+    let layout = build_trimmed_layout(&mut env, TextBoxTrim::TrimStart, TextBoxEdge::default());
+
+    assert_eq!(reference.len(), layout.len());
+    let first_line = layout.get(0).unwrap();
+    assert_eq!(first_line.metrics().block_min_coord, 0.);
+    assert_eq!(first_line.metrics().content_block_min_coord, 0.);
+
+    let trimmed = reference.first_baseline().unwrap() - layout.first_baseline().unwrap();
+    assert!(trimmed > 0., "the first line's leading should be trimmed");
+    assert_eq!(reference.height() - layout.height(), trimmed);
+
+    // All following lines move up by the trimmed amount.
+    for (reference_line, line) in reference.lines().zip(layout.lines()).skip(1) {
+        assert_eq!(
+            reference_line.metrics().baseline - line.metrics().baseline,
+            trimmed
+        );
+    }
+}
+
+#[test]
+fn text_box_trim_start_cap_height() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let text = build_trimmed_layout(&mut env, TextBoxTrim::TrimStart, TextBoxEdge::default());
+    let cap = build_trimmed_layout(
+        &mut env,
+        TextBoxTrim::TrimStart,
+        TextBoxEdge {
+            over: TextBoxOverEdge::Cap,
+            ..TextBoxEdge::default()
+        },
+    );
+    let ex = build_trimmed_layout(
+        &mut env,
+        TextBoxTrim::TrimStart,
+        TextBoxEdge {
+            over: TextBoxOverEdge::Ex,
+            ..TextBoxEdge::default()
+        },
+    );
+
+    let text_baseline = text.first_baseline().unwrap();
+    let cap_baseline = cap.first_baseline().unwrap();
+    let ex_baseline = ex.first_baseline().unwrap();
+    assert!(
+        cap_baseline < text_baseline,
+        "the cap height should be below the ascent"
+    );
+    assert!(
+        ex_baseline < cap_baseline,
+        "the x-height should be below the cap height"
+    );
+    assert_eq!(text.height() - cap.height(), text_baseline - cap_baseline);
+}
+
+#[test]
+fn text_box_trim_end_alphabetic() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let reference = build_trimmed_layout(&mut env, TextBoxTrim::None, TextBoxEdge::default());
+    let layout = build_trimmed_layout(
+        &mut env,
+        TextBoxTrim::TrimEnd,
+        TextBoxEdge {
+            under: TextBoxUnderEdge::Alphabetic,
+            ..TextBoxEdge::default()
+        },
+    );
+
+    assert_eq!(reference.first_baseline(), layout.first_baseline());
+    let last_line = layout.lines().next_back().unwrap();
+    assert_eq!(
+        last_line.metrics().block_max_coord,
+        last_line.metrics().baseline
+    );
+    assert_eq!(layout.height(), layout.last_baseline().unwrap());
+    assert!(
+        layout.height() < reference.height(),
+        "the last line's leading and descent should be trimmed"
+    );
+}
+
+#[test]
+fn text_box_trim_both() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let layout = build_trimmed_layout(&mut env, TextBoxTrim::TrimBoth, TextBoxEdge::default());
+
+    let first_line = layout.get(0).unwrap();
+    let last_line = layout.lines().next_back().unwrap();
+    assert_eq!(first_line.metrics().content_block_min_coord, 0.);
+    assert_eq!(
+        last_line.metrics().block_max_coord,
+        last_line.metrics().content_block_max_coord
+    );
+    assert_eq!(layout.height(), last_line.metrics().block_max_coord);
+}
+
+#[test]
+fn text_box_trim_end_partial_break() {
+    let mut env = TestEnv::new(test_name!(), None);
+    let reference = build_trimmed_layout(&mut env, TextBoxTrim::None, TextBoxEdge::default());
+    let mut layout = build_trimmed_layout(&mut env, TextBoxTrim::TrimEnd, TextBoxEdge::default());
+
+    // Stop breaking after the first line, before the end of the text is reached.
+    let mut breaker = layout.break_lines();
+    breaker.state_mut().set_layout_max_advance(200.0);
+    breaker.state_mut().set_line_max_advance(200.0);
+    breaker.break_next();
+    assert!(!breaker.is_done());
+    breaker.finish();
+
+    assert_eq!(layout.len(), 1);
+    let line = layout.get(0).unwrap();
+    let reference_line = reference.get(0).unwrap();
+    assert_eq!(
+        line.metrics().block_max_coord,
+        reference_line.metrics().block_max_coord,
+        "a line that doesn't end the text shouldn't be trimmed"
+    );
+}
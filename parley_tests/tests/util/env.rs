@@ -102,6 +102,7 @@ pub(crate) fn create_font_context() -> FontContext {
     let mut collection = Collection::new(CollectionOptions {
         shared: false,
         system_fonts: false,
+        ..CollectionOptions::default()
     });
     load_fonts(&mut collection, parley_dev::font_dirs()).unwrap();
     for font in FONT_FAMILY_LIST {
@@ -165,6 +166,10 @@ impl TestEnv {
         &mut self.font_cx
     }
 
+    pub(crate) fn layout_context(&mut self) -> &mut LayoutContext<ColorBrush> {
+        &mut self.layout_cx
+    }
+
     pub(crate) fn collection(&mut self) -> &mut Collection {
         &mut self.font_cx.collection
     }